@@ -18,7 +18,7 @@ fn main() {
         }
     }
     // Reads 462 byte from DB100
-    println!("");
+    println!();
     println!("Attempt to read 462 byte from DB100");
     let mut read_buffer = vec![0u8; 462];
     match client.read_db(db_number, 0, &mut read_buffer) {
@@ -27,19 +27,13 @@ fn main() {
             println!("Job time (ms) : {:.3}", client.last_time);
             println!("Chunks        : {}", client.chunks);           
             println!("Data read:");
-            for (i, chunk) in read_buffer.chunks(32).enumerate() {
-                print!("{:04X}: ", i * 32); // Hexc Offset
-                for byte in chunk {
-                    print!("{:02X} ", byte);
-                }
-                println!();
-            }
+            print!("{}", rust7::format_hex(&read_buffer, 0));
         },
         Err(e) => eprintln!("Read failed: {}", e),
     }
 
     // Writes 1024 byte to DB100 
-    println!("");
+    println!();
     println!("Attempt to write 1024 byte to DB100");
     let mut write_data = [0u8; 1024];
 
@@ -57,7 +51,7 @@ fn main() {
     }
 
     // Read a bit 
-    println!("");
+    println!();
     println!("Attempt to read DB100.DBX45.5");
     match client.read_bit(client::S7_AREA_DB,  db_number, 47, 5) {
         Ok(value) => {
@@ -70,7 +64,7 @@ fn main() {
     }
 
     // Write a bit 
-    println!("");
+    println!();
     println!("Attempt to write 'false' into DB100.DBX16.0");
     match client.write_bit(client::S7_AREA_DB, db_number, 16, 0, false) {
         Ok(_) => {
@@ -82,6 +76,6 @@ fn main() {
     }
 
     client.disconnect();
-    println!("");
+    println!();
     println!("Disconnected");
 }
\ No newline at end of file
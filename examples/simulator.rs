@@ -0,0 +1,51 @@
+use std::time::Duration;
+use rust7::{S7Client, S7DataType, S7Value, S7_AREA_DB, S7_AREA_MK};
+use rust7::sim::S7Simulator;
+
+fn main() {
+    // Simulated PLC: DB100 (Speed REAL at 0, Counter INT at 4) and 16 byte of Merkers
+    let mut sim = S7Simulator::new();
+    sim.register_db(100, vec![0u8; 64]);
+    sim.register_area(S7_AREA_MK, vec![0u8; 16]);
+
+    sim.set_value(S7_AREA_DB, 100, 0, S7DataType::Real, &S7Value::Real(12.5)).unwrap();
+
+    // The counter increments every 200 ms
+    sim.every(Duration::from_millis(200), |areas| {
+        let mut counter = [0u8; 2];
+        areas.read_area(S7_AREA_DB, 100, 4, &mut counter).unwrap();
+        let counter = i16::from_be_bytes(counter).wrapping_add(1);
+        areas.write_area(S7_AREA_DB, 100, 4, &counter.to_be_bytes()).unwrap();
+    });
+    // The speed jumps after 500 ms
+    sim.schedule_write(Duration::from_millis(500), S7_AREA_DB, 100, 0, 99.0f32.to_be_bytes().to_vec());
+
+    if let Err(e) = sim.start("127.0.0.1:0") {
+        eprintln!("Simulator start failed: {}", e);
+        return;
+    }
+    let port = sim.local_address().unwrap().port();
+    println!("Simulator listening on port {}", port);
+
+    let mut client = S7Client::new();
+    client.set_connection_port(port).unwrap();
+    if let Err(e) = client.connect_s71200_1500("127.0.0.1") {
+        eprintln!("Connection failed: {}", e);
+        return;
+    }
+    println!("Connected, CPU status: {}", client.get_plc_status().unwrap());
+
+    for _ in 0..5 {
+        let speed = client.read_value(S7_AREA_DB, 100, 0, S7DataType::Real).unwrap();
+        let counter = client.read_value(S7_AREA_DB, 100, 4, S7DataType::Int).unwrap();
+        println!("Speed = {}, Counter = {}", speed, counter);
+        std::thread::sleep(Duration::from_millis(250));
+    }
+
+    // Client writes are visible in the simulated memory
+    client.write_bit(S7_AREA_MK, 0, 2, 3, true).unwrap();
+    println!("M2.3 = {}", sim.value(S7_AREA_MK, 0, 2 * 8 + 3, S7DataType::Bool).unwrap());
+
+    client.disconnect();
+    sim.stop();
+}
@@ -0,0 +1,367 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Typed addresses
+//!
+//! `read_area()` and its helpers take the start as a byte index, except for the bits where it is
+//! `byte * 8 + bit`: passing one where the other is expected reads the wrong variable without any
+//! error. The address types keep the two apart:
+//! - `AreaAddress`: byte of any area (`DbAddress` for a byte of a DB).
+//! - `BitAddress`: bit of any area.
+//!
+//! They are built from the STEP 7 notation (`"DB10.DBW4"`, `"M2.3"`...) or with the builders, and
+//! are accepted by `read_at()`, `write_at()`, `read_bit_at()`, `write_bit_at()`,
+//! `read_value_at()`, `write_value_at()` and by `Tag::at()`/`Tag::at_bit()`.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::{S7Client, S7DataType};
+//! use rust7::address::{AreaAddress, BitAddress, DbAddress};
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! let speed = client.read_value_at(DbAddress::new(10, 4), S7DataType::Real).unwrap();
+//! let running = client.read_bit_at("DB10.DBX8.1".parse::<BitAddress>().unwrap()).unwrap();
+//! client.write_bit_at(AreaAddress::merker(2).bit(3), true).unwrap();
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use crate::client::{S7Client, S7Error, S7_AREA_DB, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE, S7_WL_BYTE};
+use crate::value::{S7DataType, S7Value};
+
+/// Bytes addressable by the protocol (the bit address is 24 bit long)
+const MAX_AREA_BYTES: usize = 1 << 21;
+
+/// ### Byte of a DB
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DbAddress {
+    pub db_number: u16,
+    pub byte: u32,
+}
+
+/// ### Byte of an S7 memory area
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AreaAddress {
+    /// S7 memory area constant (e.g., `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_DB`, `S7_AREA_MK`).
+    pub area: u8,
+    /// DB number (0 for non-DB areas).
+    pub db_number: u16,
+    pub byte: u32,
+}
+
+/// ### Bit of an S7 memory area
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitAddress {
+    /// S7 memory area constant (e.g., `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_DB`, `S7_AREA_MK`).
+    pub area: u8,
+    /// DB number (0 for non-DB areas).
+    pub db_number: u16,
+    pub byte: u32,
+    /// Bit index inside the byte (0..7, checked by the operations)
+    pub bit: u8,
+}
+
+impl DbAddress {
+    /// ### Creates the address of a byte of a DB
+    ///
+    pub fn new(db_number: u16, byte: u32) -> Self {
+        DbAddress { db_number, byte }
+    }
+
+    /// ### Returns the address of a bit of this byte
+    ///
+    pub fn bit(self, bit: u8) -> BitAddress {
+        AreaAddress::from(self).bit(bit)
+    }
+}
+
+impl AreaAddress {
+    /// ### Creates the address of a byte of an area
+    ///
+    /// ### Parameters
+    /// - `area`: S7 memory area constant.
+    /// - `db_number`: DB number (ignored for non-DB areas, stored as 0).
+    /// - `byte`: Byte index.
+    ///
+    pub fn new(area: u8, db_number: u16, byte: u32) -> Self {
+        AreaAddress { area, db_number: if area == S7_AREA_DB { db_number } else { 0 }, byte }
+    }
+
+    /// ### Creates the address of a byte of a DB
+    ///
+    pub fn db(db_number: u16, byte: u32) -> Self {
+        AreaAddress::new(S7_AREA_DB, db_number, byte)
+    }
+
+    /// ### Creates the address of a byte of the process inputs
+    ///
+    pub fn input(byte: u32) -> Self {
+        AreaAddress::new(S7_AREA_PE, 0, byte)
+    }
+
+    /// ### Creates the address of a byte of the process outputs
+    ///
+    pub fn output(byte: u32) -> Self {
+        AreaAddress::new(S7_AREA_PA, 0, byte)
+    }
+
+    /// ### Creates the address of a byte of the merkers
+    ///
+    pub fn merker(byte: u32) -> Self {
+        AreaAddress::new(S7_AREA_MK, 0, byte)
+    }
+
+    /// ### Returns the address of a bit of this byte
+    ///
+    pub fn bit(self, bit: u8) -> BitAddress {
+        BitAddress { area: self.area, db_number: self.db_number, byte: self.byte, bit }
+    }
+
+    /// ### Returns the address moved by a number of bytes (e.g. the next field of a structure)
+    ///
+    pub fn offset(self, bytes: u32) -> Self {
+        AreaAddress { byte: self.byte.wrapping_add(bytes), ..self }
+    }
+}
+
+impl BitAddress {
+    /// ### Creates the address of a bit of an area
+    ///
+    pub fn new(area: u8, db_number: u16, byte: u32, bit: u8) -> Self {
+        AreaAddress::new(area, db_number, byte).bit(bit)
+    }
+
+    /// ### Returns the address of the byte containing the bit
+    ///
+    pub fn byte_address(self) -> AreaAddress {
+        AreaAddress { area: self.area, db_number: self.db_number, byte: self.byte }
+    }
+
+    /// ### Returns the start used by `read_area()` with `S7_WL_BIT`: `byte * 8 + bit`
+    ///
+    /// `None` if the bit is invalid or the byte is beyond the last one addressable (2 MB).
+    ///
+    pub fn bit_start(self) -> Option<u32> {
+        if self.bit > 7 || self.byte as usize >= MAX_AREA_BYTES {
+            return None;
+        }
+        Some(self.byte * 8 + self.bit as u32)
+    }
+}
+
+impl From<DbAddress> for AreaAddress {
+    fn from(address: DbAddress) -> Self {
+        AreaAddress::db(address.db_number, address.byte)
+    }
+}
+
+impl TryFrom<AreaAddress> for DbAddress {
+    type Error = S7Error;
+
+    fn try_from(address: AreaAddress) -> Result<Self, Self::Error> {
+        if address.area != S7_AREA_DB {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        Ok(DbAddress::new(address.db_number, address.byte))
+    }
+}
+
+/// Prefix of the STEP 7 notation: `DB10.DB`, `M`, `I`, `Q` or the area code
+fn prefix(area: u8, db_number: u16) -> String {
+    match area {
+        S7_AREA_DB => format!("DB{}.DB", db_number),
+        S7_AREA_PE => "I".to_string(),
+        S7_AREA_PA => "Q".to_string(),
+        S7_AREA_MK => "M".to_string(),
+        area => format!("0x{:02X}:", area),
+    }
+}
+
+impl fmt::Display for DbAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", AreaAddress::from(*self))
+    }
+}
+
+impl fmt::Display for AreaAddress {
+    /// `DB10.DBB4`, `MB2`, `IB0`, `QB1`, `0x1C:4` for the other areas
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.area {
+            S7_AREA_DB | S7_AREA_PE | S7_AREA_PA | S7_AREA_MK => write!(f, "{}B{}", prefix(self.area, self.db_number), self.byte),
+            _ => write!(f, "{}{}", prefix(self.area, self.db_number), self.byte),
+        }
+    }
+}
+
+impl fmt::Display for BitAddress {
+    /// `DB10.DBX4.3`, `M2.3`, `I0.1`, `Q1.0`, `0x1C:4.3` for the other areas
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marker = if self.area == S7_AREA_DB { "X" } else { "" };
+        write!(f, "{}{}{}.{}", prefix(self.area, self.db_number), marker, self.byte, self.bit)
+    }
+}
+
+/// Parses the STEP 7 notation: (area, db number, byte, bit)
+///
+/// `DB10.DBX4.3`, `DB10.DBB4`/`DBW`/`DBD`, `M2.3`, `MB2`/`MW`/`MD`, `I`/`E` (inputs) and `Q`/`A`
+/// (outputs) as `M`. Case insensitive.
+fn parse_address(text: &str) -> Result<(u8, u16, u32, Option<u8>), S7Error> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_uppercase();
+    let (area, db_number, rest) = if let Some(db) = text.strip_prefix("DB") {
+        let (number, rest) = db.split_once('.').ok_or(S7Error::InvalidFunParameter)?;
+        let rest = rest.strip_prefix("DB").ok_or(S7Error::InvalidFunParameter)?;
+        (S7_AREA_DB, number.parse::<u16>().map_err(|_| S7Error::InvalidFunParameter)?, rest)
+    } else {
+        let mut chars = text.chars();
+        let area = match chars.next() {
+            Some('M') => S7_AREA_MK,
+            Some('I') | Some('E') => S7_AREA_PE,
+            Some('Q') | Some('A') => S7_AREA_PA,
+            _ => return Err(S7Error::InvalidFunParameter),
+        };
+        (area, 0, chars.as_str())
+    };
+
+    let (width, offset) = match rest.chars().next() {
+        Some(c @ ('X' | 'B' | 'W' | 'D')) => (Some(c), &rest[1..]),
+        _ => (None, rest),
+    };
+    let number = |text: &str| text.parse::<u32>().map_err(|_| S7Error::InvalidFunParameter);
+    match (width, offset.split_once('.')) {
+        // Bit: DBX in a DB, no width in the other areas
+        (Some('X'), Some((byte, bit))) if area == S7_AREA_DB => Ok((area, db_number, number(byte)?, Some(bit.parse().map_err(|_| S7Error::InvalidFunParameter)?))),
+        (None, Some((byte, bit))) if area != S7_AREA_DB => Ok((area, db_number, number(byte)?, Some(bit.parse().map_err(|_| S7Error::InvalidFunParameter)?))),
+        (Some('B' | 'W' | 'D'), None) => Ok((area, db_number, number(offset)?, None)),
+        _ => Err(S7Error::InvalidFunParameter),
+    }
+}
+
+impl FromStr for AreaAddress {
+    type Err = S7Error;
+
+    /// ### Parses a byte, word or double word address: `DB10.DBW4`, `MB2`, `IW0`, `QD4`...
+    ///
+    /// The width only marks the address as a byte address, it is not stored.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Invalid address, or a bit address.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_address(s)? {
+            (area, db_number, byte, None) => Ok(AreaAddress::new(area, db_number, byte)),
+            _ => Err(S7Error::InvalidFunParameter),
+        }
+    }
+}
+
+impl FromStr for DbAddress {
+    type Err = S7Error;
+
+    /// ### Parses a byte address of a DB: `DB10.DBB4`, `DB10.DBW4`, `DB10.DBD4`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Invalid address, bit address or another area.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DbAddress::try_from(s.parse::<AreaAddress>()?)
+    }
+}
+
+impl FromStr for BitAddress {
+    type Err = S7Error;
+
+    /// ### Parses a bit address: `DB10.DBX4.3`, `M2.3`, `I0.1`, `Q1.0`...
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Invalid address, bit index > 7, or a byte address.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_address(s)? {
+            (area, db_number, byte, Some(bit)) if bit <= 7 => Ok(BitAddress::new(area, db_number, byte, bit)),
+            _ => Err(S7Error::InvalidFunParameter),
+        }
+    }
+}
+
+impl S7Client {
+    /// ### Reads bytes starting from an address
+    ///
+    /// Same as `read_area()` with wordlen = `S7_WL_BYTE`, the number of bytes read is the size
+    /// of the buffer.
+    ///
+    /// ### Errors
+    /// - Reported by `read_area()`
+    ///
+    pub fn read_at(&mut self, address: impl Into<AreaAddress>, buffer: &mut [u8]) -> Result<(), S7Error> {
+        let address = address.into();
+        self.read_area(address.area, address.db_number, address.byte, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Writes bytes starting from an address
+    ///
+    /// Same as `write_area()` with wordlen = `S7_WL_BYTE`.
+    ///
+    /// ### Errors
+    /// - Reported by `write_area()`
+    ///
+    pub fn write_at(&mut self, address: impl Into<AreaAddress>, buffer: &[u8]) -> Result<(), S7Error> {
+        let address = address.into();
+        self.write_area(address.area, address.db_number, address.byte, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Reads a bit
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Bit index > 7 or byte beyond 2 MB.
+    /// - Reported by `read_bit()`
+    ///
+    pub fn read_bit_at(&mut self, address: BitAddress) -> Result<bool, S7Error> {
+        address.bit_start().ok_or(S7Error::InvalidFunParameter)?;
+        self.read_bit(address.area, address.db_number, address.byte, address.bit)
+    }
+
+    /// ### Writes a bit, leaving the other bits of the byte unchanged
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Bit index > 7 or byte beyond 2 MB.
+    /// - Reported by `write_bit()`
+    ///
+    pub fn write_bit_at(&mut self, address: BitAddress, value: bool) -> Result<(), S7Error> {
+        address.bit_start().ok_or(S7Error::InvalidFunParameter)?;
+        self.write_bit(address.area, address.db_number, address.byte, address.bit, value)
+    }
+
+    /// ### Reads a typed value starting from an address
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: `S7DataType::Bool`, a bit is read with `read_bit_at()`.
+    /// - Reported by `read_value()`
+    ///
+    pub fn read_value_at(&mut self, address: impl Into<AreaAddress>, data_type: S7DataType) -> Result<S7Value, S7Error> {
+        if data_type == S7DataType::Bool {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let address = address.into();
+        self.read_value(address.area, address.db_number, address.byte, data_type)
+    }
+
+    /// ### Writes a typed value starting from an address
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: `S7DataType::Bool` (a bit is written with `write_bit_at()`)
+    ///   or the value doesn't match the data type.
+    /// - Reported by `write_value()`
+    ///
+    pub fn write_value_at(&mut self, address: impl Into<AreaAddress>, data_type: S7DataType, value: &S7Value) -> Result<(), S7Error> {
+        if data_type == S7DataType::Bool {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let address = address.into();
+        self.write_value(address.area, address.db_number, address.byte, data_type, value)
+    }
+}
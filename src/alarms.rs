@@ -0,0 +1,521 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Alarm subscription (S7-300/400)
+//!
+//! The blocks SFC 17-20 (ALARM_SQ, ALARM_S...) and SFB 33-37 (ALARM, ALARM_8...) of a 300/400 CPU
+//! generate messages, which the CPU sends spontaneously to the clients subscribed to them (as
+//! WinCC does), without polling alarm bits. `subscribe_alarms()` registers the client with the
+//! message service of the CPU and installs a callback that receives each message.
+//!
+//! The messages arrive while the client is idle or between the responses of other requests:
+//! they are delivered by `wait_alarms()` and by any operation of the client. A dedicated
+//! connection, looping on `wait_alarms()`, delivers them as soon as they're generated.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::S7Client;
+//! use rust7::alarms::{AlarmService, S7Alarm};
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s7300("192.168.0.100").unwrap();
+//!
+//! client.subscribe_alarms(AlarmService::AlarmS, Box::new(|alarm: &S7Alarm| {
+//!     println!("{:?} EV_ID {:08X} {}", alarm.timestamp, alarm.event_id,
+//!         if alarm.is_coming() { "coming" } else { "going" });
+//! })).unwrap();
+//!
+//! loop {
+//!     client.wait_alarms(Duration::from_secs(1)).unwrap();
+//! }
+//! ```
+
+use std::io;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::client::{S7Client, S7Error, S7ErrorCodes};
+use crate::frame::{split_userdata, S7UserDataParams, UD_GROUP_SZL, UD_SUBFN_ALARM8_IND, UD_SUBFN_ALARM_ACK_IND, UD_SUBFN_ALARM_QUERY, UD_SUBFN_ALARMSQ_IND,
+    UD_SUBFN_ALARMS_IND, UD_SUBFN_MESSAGE_SERVICE, UD_SUBFN_NOTIFY8_IND, UD_SUBFN_NOTIFY_IND, UD_METHOD_RESPONSE,
+    UD_TYPE_PUSH, UD_TYPE_REQUEST};
+use crate::value::date_time_from_bcd;
+
+const RES_SUCCESS: u8 = 0xFF;
+const RES_NOT_FOUND: u8 = 0x0A;
+const TS_OCTET_STRING: u8 = 0x09;
+const DATA_HEADER_LEN: usize = 4; // Return code + Transport size + Length
+/// Transport sizes whose length is expressed in bits
+const TS_RES_BYTE: u8 = 0x04;
+const TS_RES_INT: u8 = 0x05;
+/// Message service: subscription to the alarms (ALARM_S, ALARM_8...)
+const MSG_EVENT_ALARMS: u8 = 0x80;
+/// Message service: alarm types
+const ALARM_ABORT: u8 = 0x04;
+const ALARM_INITIATE: u8 = 0x05;
+const ALARM_S_ABORT: u8 = 0x08;
+const ALARM_S_INITIATE: u8 = 0x09;
+/// Name of the subscriber (8 characters)
+const SUBSCRIBER: &[u8; 8] = b"Rust7   ";
+/// Variable specification of the message objects
+const VAR_SPEC: u8 = 0x12;
+/// Syntax ID of the message objects
+const SYNTAX_ALARM_INDSET: u8 = 0x16;
+const SYNTAX_NOTIFY_INDSET: u8 = 0x1C;
+const SYNTAX_ALARM_ACKSET: u8 = 0x19;
+const SYNTAX_ALARM_QUERYREQSET: u8 = 0x1A;
+/// Alarm query: by alarm type, of the ALARM_S or ALARM_8 messages
+const QUERY_BY_ALARM_TYPE: u8 = 0x01;
+const QUERY_ALARM_8: u8 = 0x02;
+const QUERY_ALARM_S: u8 = 0x04;
+/// Size of the fixed part of a pending alarm dataset
+const PENDING_HEADER_LEN: usize = 11;
+/// Follow-up requests of a multi-part query, guards against a CPU that never ends it
+const MAX_QUERY_PARTS: usize = 1024;
+
+/// ### Message service to subscribe
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmService {
+    /// Messages of SFC 17-20 and 107-108 (ALARM_S, ALARM_SQ, ALARM_D, ALARM_DQ)
+    AlarmS,
+    /// Messages of SFB 31-37 (NOTIFY, NOTIFY_8P, ALARM, ALARM_8, ALARM_8P)
+    Alarm8,
+}
+
+/// ### Kind of a message received
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmKind {
+    AlarmS,
+    AlarmSq,
+    Alarm8,
+    Notify,
+    Notify8,
+    /// Acknowledgement of a message (by another station or by the program)
+    Ack,
+}
+
+/// ### Message generated by the CPU
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S7Alarm {
+    pub kind: AlarmKind,
+    /// Time of the event, by the CPU clock (the time is returned as if it were UTC)
+    pub timestamp: SystemTime,
+    /// Message number (EV_ID of the block)
+    pub event_id: u32,
+    /// Signal states, one bit per signal (bit 0 only for ALARM_S)
+    pub state: u8,
+    /// Signals changed by this event
+    pub event_state: u8,
+    /// Signals whose "going" state is acknowledged
+    pub ack_state_going: u8,
+    /// Signals whose "coming" state is acknowledged
+    pub ack_state_coming: u8,
+    /// Associated values (SD_1, SD_2...), raw
+    pub values: Vec<Vec<u8>>,
+}
+
+impl S7Alarm {
+    /// ### Returns true if the (first) signal is active, false if the message is going
+    ///
+    pub fn is_coming(&self) -> bool {
+        self.state & 0x01 != 0
+    }
+}
+
+/// ### Pending alarm returned by `query_alarms()`
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S7PendingAlarm {
+    /// Message number (EV_ID of the block)
+    pub event_id: u32,
+    /// Signal states, one bit per signal (bit 0 only for ALARM_S)
+    pub state: u8,
+    /// Signals whose "going" state is acknowledged
+    pub ack_state_going: u8,
+    /// Signals whose "coming" state is acknowledged
+    pub ack_state_coming: u8,
+    /// Time of the last "coming" event, by the CPU clock (as if it were UTC)
+    pub coming: Option<SystemTime>,
+    /// Associated value of the "coming" event, raw
+    pub coming_value: Vec<u8>,
+    /// Time of the last "going" event (`None` if the message didn't go yet)
+    pub going: Option<SystemTime>,
+    /// Associated value of the "going" event, raw
+    pub going_value: Vec<u8>,
+}
+
+impl S7PendingAlarm {
+    /// ### Returns true if the (first) signal is active
+    ///
+    pub fn is_coming(&self) -> bool {
+        self.state & 0x01 != 0
+    }
+
+    /// ### Returns true if the "coming" state of the (first) signal is acknowledged
+    ///
+    pub fn is_acknowledged(&self) -> bool {
+        self.ack_state_coming & 0x01 != 0
+    }
+}
+
+/// Callback receiving the alarm messages
+pub type AlarmListener = Box<dyn FnMut(&S7Alarm) + Send>;
+
+/// Maps the subfunction of a pushed telegram
+fn alarm_kind(subfunction: u8) -> Option<AlarmKind> {
+    match subfunction {
+        UD_SUBFN_ALARMS_IND => Some(AlarmKind::AlarmS),
+        UD_SUBFN_ALARMSQ_IND => Some(AlarmKind::AlarmSq),
+        UD_SUBFN_ALARM8_IND => Some(AlarmKind::Alarm8),
+        UD_SUBFN_NOTIFY_IND => Some(AlarmKind::Notify),
+        UD_SUBFN_NOTIFY8_IND => Some(AlarmKind::Notify8),
+        UD_SUBFN_ALARM_ACK_IND => Some(AlarmKind::Ack),
+        _ => None,
+    }
+}
+
+/// Returns true if the S7 telegram is a Userdata pushed by the CPU (not a response)
+pub(crate) fn is_pushed(telegram: &[u8]) -> bool {
+    split_userdata(telegram).is_ok_and(|(_, params, _)| params.type_group & 0xF0 == UD_TYPE_PUSH)
+}
+
+/// Returns the message service request data
+fn message_service_request(events: u8, alarm_type: u8) -> Vec<u8> {
+    let mut request = vec![RES_SUCCESS, TS_OCTET_STRING, 0x00, 0x0C, events, 0x00];
+    request.extend_from_slice(SUBSCRIBER);
+    request.extend_from_slice(&[alarm_type, 0x00]);
+    request
+}
+
+/// Decodes an associated value (Return code, Transport size, Length, data padded to even) at `offset`
+fn parse_value(payload: &[u8], offset: &mut usize) -> Result<Vec<u8>, S7Error> {
+    let header = payload.get(*offset..*offset + DATA_HEADER_LEN).ok_or(S7Error::IsoInvalidTelegram)?;
+    let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+    let size = match header[1] {
+        TS_RES_BYTE | TS_RES_INT => length.div_ceil(8),
+        _ => length,
+    };
+    let start = *offset + DATA_HEADER_LEN;
+    let value = payload.get(start..start + size).ok_or(S7Error::IsoInvalidTelegram)?;
+    *offset = start + size + size % 2;
+    Ok(value.to_vec())
+}
+
+/// ### Decodes the message objects of an indication
+///
+/// ```text
+/// [0] Timestamp (DATE_AND_TIME, 8)   [8] Function ID   [9] Number of objects
+/// For each object:
+/// [0] Var spec (0x12)   [1] Length   [2] Syntax ID   [3] Number of values   [4] EV_ID (4)
+/// [8] Event state   [9] State   [10] Ack state going   [11] Ack state coming   (Ack: only the last two)
+/// Then the associated values: Return code, Transport size, Length (2), data (padded to even)
+/// ```
+pub(crate) fn parse_alarms(kind: AlarmKind, payload: &[u8]) -> Result<Vec<S7Alarm>, S7Error> {
+    if payload.len() < 10 {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    let timestamp = date_time_from_bcd(&payload[..8]).unwrap_or(UNIX_EPOCH);
+    let count = payload[9] as usize;
+    let mut alarms = Vec::with_capacity(count);
+    let mut offset = 10;
+    for _ in 0..count {
+        let header = payload.get(offset..offset + 8).ok_or(S7Error::IsoInvalidTelegram)?;
+        if header[0] != VAR_SPEC {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        let syntax_id = header[2];
+        let value_count = header[3] as usize;
+        let mut alarm = S7Alarm {
+            kind,
+            timestamp,
+            event_id: u32::from_be_bytes([header[4], header[5], header[6], header[7]]),
+            state: 0,
+            event_state: 0,
+            ack_state_going: 0,
+            ack_state_coming: 0,
+            values: Vec::with_capacity(value_count),
+        };
+        offset += 8;
+        match syntax_id {
+            SYNTAX_ALARM_INDSET | SYNTAX_NOTIFY_INDSET => {
+                let states = payload.get(offset..offset + 4).ok_or(S7Error::IsoInvalidTelegram)?;
+                alarm.event_state = states[0];
+                alarm.state = states[1];
+                alarm.ack_state_going = states[2];
+                alarm.ack_state_coming = states[3];
+                offset += 4;
+            }
+            SYNTAX_ALARM_ACKSET => {
+                let states = payload.get(offset..offset + 2).ok_or(S7Error::IsoInvalidTelegram)?;
+                alarm.kind = AlarmKind::Ack;
+                alarm.ack_state_going = states[0];
+                alarm.ack_state_coming = states[1];
+                offset += 2;
+            }
+            _ => return Err(S7Error::IsoInvalidTelegram),
+        }
+        for _ in 0..value_count {
+            alarm.values.push(parse_value(payload, &mut offset)?);
+        }
+        alarms.push(alarm);
+    }
+    Ok(alarms)
+}
+
+/// ### Decodes a dataset of the alarm query response
+///
+/// ```text
+/// [0] Reserved (2)   [2] Alarm type   [3] EV_ID (4)   [7] Reserved   [8] State
+/// [9] Ack state going   [10] Ack state coming
+/// [11] Timestamp coming (8) + Associated value   then   Timestamp going (8) + Associated value
+/// ```
+fn parse_pending(dataset: &[u8]) -> Result<S7PendingAlarm, S7Error> {
+    if dataset.len() < PENDING_HEADER_LEN {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    let mut alarm = S7PendingAlarm {
+        event_id: u32::from_be_bytes([dataset[3], dataset[4], dataset[5], dataset[6]]),
+        state: dataset[8],
+        ack_state_going: dataset[9],
+        ack_state_coming: dataset[10],
+        coming: None,
+        coming_value: Vec::new(),
+        going: None,
+        going_value: Vec::new(),
+    };
+    let mut offset = PENDING_HEADER_LEN;
+    // The parts not sent are left empty
+    if let Some(raw) = dataset.get(offset..offset + 8) {
+        alarm.coming = date_time_from_bcd(raw);
+        offset += 8;
+        if offset < dataset.len() {
+            alarm.coming_value = parse_value(dataset, &mut offset)?;
+        }
+    }
+    if let Some(raw) = dataset.get(offset..offset + 8) {
+        alarm.going = date_time_from_bcd(raw);
+        offset += 8;
+        if offset < dataset.len() {
+            alarm.going_value = parse_value(dataset, &mut offset)?;
+        }
+    }
+    Ok(alarm)
+}
+
+/// ### Decodes the alarm query response (the data of all the parts)
+///
+/// ```text
+/// [0] Function ID   [1] Number of objects   [2] Return code   [3] Transport size   [4] Length (2)
+/// [6] Datasets: Length + Dataset
+/// ```
+fn parse_pending_alarms(payload: &[u8]) -> Result<Vec<S7PendingAlarm>, S7Error> {
+    if payload.len() < 6 {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    match payload[2] {
+        RES_SUCCESS => {}
+        // No pending alarms
+        RES_NOT_FOUND => return Ok(Vec::new()),
+        code => return Err(S7Error::S7Unspecified(S7ErrorCodes::item(code))),
+    }
+    let end = (6 + u16::from_be_bytes([payload[4], payload[5]]) as usize).min(payload.len());
+    let mut alarms = Vec::new();
+    let mut offset = 6;
+    while offset < end {
+        let length = payload[offset] as usize;
+        let dataset = payload.get(offset + 1..offset + 1 + length).ok_or(S7Error::IsoInvalidTelegram)?;
+        alarms.push(parse_pending(dataset)?);
+        offset += 1 + length;
+    }
+    Ok(alarms)
+}
+
+/// Returns the data of a query response part (without its header)
+fn query_part(data: &[u8]) -> Result<&[u8], S7Error> {
+    if data.len() < DATA_HEADER_LEN {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    match data[0] {
+        RES_SUCCESS => Ok(&data[DATA_HEADER_LEN..]),
+        RES_NOT_FOUND => Err(S7Error::S7NotFound),
+        code => Err(S7Error::S7Unspecified(S7ErrorCodes::item(code))),
+    }
+}
+
+impl S7Client {
+    /// ### Subscribes to the alarm messages of the CPU
+    ///
+    /// ### Parameters
+    /// - `service`: `AlarmService::AlarmS` (SFC ALARM_S, ALARM_SQ...) or `AlarmService::Alarm8`
+    ///   (SFB ALARM, ALARM_8...).
+    /// - `listener`: Callback receiving each message, on the thread using the client. It replaces
+    ///   the one of a previous subscription.
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::S7Unspecified`: The CPU refused the subscription (e.g. S7-1200/1500, which
+    ///   don't provide the message service through PUT/GET).
+    /// - `S7Error::IsoInvalidTelegram`: Inconsistent response.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    /// ### Notes
+    /// - The subscription ends with the connection: after a reconnection, subscribe again.
+    /// - Each connection is a subscriber of the CPU, whose number is limited (see the CPU data
+    ///   sheet, "stations that can log on for message functions").
+    ///
+    pub fn subscribe_alarms(&mut self, service: AlarmService, listener: AlarmListener) -> Result<(), S7Error> {
+        let alarm_type = match service {
+            AlarmService::AlarmS => ALARM_S_INITIATE,
+            AlarmService::Alarm8 => ALARM_INITIATE,
+        };
+        self.message_service(MSG_EVENT_ALARMS, alarm_type)?;
+        self.alarm_listener = Some(listener);
+        Ok(())
+    }
+
+    /// ### Ends the alarm subscription and removes the callback
+    ///
+    /// ### Errors
+    /// - Reported by `subscribe_alarms()`
+    ///
+    pub fn unsubscribe_alarms(&mut self, service: AlarmService) -> Result<(), S7Error> {
+        self.alarm_listener = None;
+        let alarm_type = match service {
+            AlarmService::AlarmS => ALARM_S_ABORT,
+            AlarmService::Alarm8 => ALARM_ABORT,
+        };
+        self.message_service(MSG_EVENT_ALARMS, alarm_type)
+    }
+
+    /// ### Reads the alarms pending in the CPU
+    ///
+    /// Returns the messages currently active or not yet acknowledged, e.g. to fill the alarm
+    /// list after a (re)connection: subscribe first, then query, so no message is missed.
+    ///
+    /// ### Parameters
+    /// - `service`: `AlarmService::AlarmS` or `AlarmService::Alarm8` (see `subscribe_alarms()`).
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<S7PendingAlarm>>)` (empty if there are no pending alarms) or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::S7Unspecified`: The CPU refused the query (e.g. S7-1200/1500).
+    /// - `S7Error::IsoInvalidTelegram`: Inconsistent response.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    /// ### Notes
+    /// Long lists span more PDUs: the following parts are requested until the CPU marks the last one.
+    ///
+    pub fn query_alarms(&mut self, service: AlarmService) -> Result<Vec<S7PendingAlarm>, S7Error> {
+        let alarm_type = match service {
+            AlarmService::AlarmS => QUERY_ALARM_S,
+            AlarmService::Alarm8 => QUERY_ALARM_8,
+        };
+        let params = S7UserDataParams::request(UD_GROUP_SZL, UD_SUBFN_ALARM_QUERY);
+        let request = [RES_SUCCESS, TS_OCTET_STRING, 0x00, 0x0C,
+            0x00, 0x01, VAR_SPEC, 0x08, SYNTAX_ALARM_QUERYREQSET, 0x00, QUERY_BY_ALARM_TYPE, 0x34, 0x00, 0x00, 0x00, alarm_type];
+        let (mut res_params, data) = self.userdata(&params, &request)?;
+        let mut payload = match query_part(&data) {
+            Ok(part) => part.to_vec(),
+            Err(S7Error::S7NotFound) => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        for _ in 0..MAX_QUERY_PARTS {
+            if !res_params.more_data() {
+                return parse_pending_alarms(&payload);
+            }
+            // Follow-up request of the next part
+            let follow_up = S7UserDataParams {
+                method: UD_METHOD_RESPONSE,
+                type_group: UD_TYPE_REQUEST | UD_GROUP_SZL,
+                subfunction: UD_SUBFN_ALARM_QUERY,
+                sequence: res_params.sequence,
+                data_unit_ref: 0,
+                last_data_unit: 0,
+                error_code: 0,
+                extended: true,
+            };
+            let (params, data) = self.userdata(&follow_up, &[RES_NOT_FOUND, 0x00, 0x00, 0x00])?;
+            payload.extend_from_slice(query_part(&data)?);
+            res_params = params;
+        }
+        Err(S7Error::IsoInvalidTelegram)
+    }
+
+    /// ### Waits for the alarm messages and delivers them to the callback
+    ///
+    /// ### Parameters
+    /// - `timeout`: Time to wait for the messages.
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)` number of messages delivered during the wait (0 = none), or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::ConnectionClosed`, `S7Error::Io` and the ISO errors: The connection is broken
+    ///   (reconnect and subscribe again).
+    ///
+    /// ### Notes
+    /// Other telegrams received meanwhile (late responses) are discarded.
+    ///
+    pub fn wait_alarms(&mut self, timeout: Duration) -> Result<usize, S7Error> {
+        let deadline = Instant::now() + timeout;
+        let mut delivered = 0;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(delivered);
+            }
+            self.set_read_timeout_once(remaining)?;
+            match self.recv_raw_pdu() {
+                Ok(pdu) => delivered += self.dispatch_alarms(&pdu).unwrap_or(0),
+                Err(S7Error::Io(e)) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => {
+                    return Ok(delivered);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// ### Delivers the alarms of a telegram pushed by the CPU
+    ///
+    /// ### Returns
+    /// `Some(<usize>)` number of messages delivered (0 without a callback) if the telegram is a
+    /// pushed one, which is consumed; `None` if it's a response to a request.
+    ///
+    pub(crate) fn dispatch_alarms(&mut self, pdu: &[u8]) -> Option<usize> {
+        if !is_pushed(pdu) {
+            return None;
+        }
+        let (_, params, data) = split_userdata(pdu).ok()?;
+        let alarms = match alarm_kind(params.subfunction) {
+            Some(kind) if params.group() == UD_GROUP_SZL && data.len() > DATA_HEADER_LEN => parse_alarms(kind, &data[DATA_HEADER_LEN..]).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        if alarms.is_empty() {
+            s7_debug!("pushed telegram discarded: subfunction 0x{:02X}", params.subfunction);
+        }
+        let Some(listener) = self.alarm_listener.as_mut() else {
+            return Some(0);
+        };
+        for alarm in alarms.iter() {
+            s7_debug!("alarm: EV_ID 0x{:08X}, state 0x{:02X}", alarm.event_id, alarm.state);
+            listener(alarm);
+        }
+        Some(alarms.len())
+    }
+
+    /// Sends a message service request (subscription or its end)
+    fn message_service(&mut self, events: u8, alarm_type: u8) -> Result<(), S7Error> {
+        let params = S7UserDataParams::request(UD_GROUP_SZL, UD_SUBFN_MESSAGE_SERVICE);
+        let (_, data) = self.userdata(&params, &message_service_request(events, alarm_type))?;
+        match data.first() {
+            Some(&RES_SUCCESS) => Ok(()),
+            Some(&code) => Err(S7Error::S7Unspecified(S7ErrorCodes::item(code))),
+            None => Err(S7Error::IsoInvalidTelegram),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Analog value conversion
+//!
+//! The S7 analog modules represent the nominal range of a channel (0..20 mA, 4..20 mA, 0..10 V...)
+//! as 0..27648, ±10 V and the other bipolar ranges as -27648..27648. Values beyond the nominal
+//! range are still measured up to the overshoot limit (32511), below it the module reports an
+//! overflow (32767) or an underflow (-32768), e.g. for a wire break of a 4..20 mA channel.
+//!
+//! | Raw value (unipolar) | Raw value (bipolar) | `AnalogStatus` |
+//! |----------------------|---------------------|----------------|
+//! | 32512..=32767 | 32512..=32767 | `Overflow` |
+//! | 27649..=32511 | 27649..=32511 | `Overrange` |
+//! | 0..=27648 | -27648..=27648 | `Nominal` |
+//! | -4864..=-1 (4..20 mA, 1..5 V) | -32512..=-27649 | `Underrange` |
+//! | -32768..=-4865 | -32768..=-32513 | `Underflow` |
+//!
+//! ### Example
+//! ```rust
+//! use rust7::analog::{analog_to_physical, physical_to_analog, AnalogStatus};
+//!
+//! // 4..20 mA pressure transmitter, 0..16 bar
+//! let pressure = analog_to_physical(13824, 0.0, 16.0);
+//! assert_eq!(pressure.value, 8.0);
+//! assert_eq!(pressure.status, AnalogStatus::Nominal);
+//! assert!(!analog_to_physical(-32768, 0.0, 16.0).is_valid()); // Wire break
+//!
+//! // 0..10 V valve positioner, 0..100 %
+//! assert_eq!(physical_to_analog(25.0, 0.0, 100.0), 6912);
+//! ```
+
+/// Upper limit of the nominal range
+pub const ANALOG_NOMINAL_HIGH: i16 = 27648;
+/// Lower limit of the nominal range of the bipolar channels
+pub const ANALOG_NOMINAL_LOW_BIPOLAR: i16 = -27648;
+/// Upper limit of the overshoot range
+pub const ANALOG_OVERSHOOT_HIGH: i16 = 32511;
+/// Lower limit of the undershoot range of the unipolar channels (4..20 mA, 1..5 V)
+pub const ANALOG_UNDERSHOOT_LOW: i16 = -4864;
+/// Lower limit of the undershoot range of the bipolar channels
+pub const ANALOG_UNDERSHOOT_LOW_BIPOLAR: i16 = -32512;
+/// Value reported by the module on overflow
+pub const ANALOG_OVERFLOW: i16 = 32767;
+/// Value reported by the module on underflow (e.g. wire break)
+pub const ANALOG_UNDERFLOW: i16 = -32768;
+
+/// ### Range of a raw analog value
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalogStatus {
+    /// Inside the nominal range
+    Nominal,
+    /// Above the nominal range, still measured
+    Overrange,
+    /// Below the nominal range, still measured
+    Underrange,
+    /// Above the measuring range: the value is not significant
+    Overflow,
+    /// Below the measuring range (e.g. wire break): the value is not significant
+    Underflow,
+}
+
+impl AnalogStatus {
+    /// ### Classifies a raw value of a unipolar channel (0..27648)
+    ///
+    pub fn unipolar(raw: i16) -> Self {
+        match raw {
+            r if r > ANALOG_OVERSHOOT_HIGH => AnalogStatus::Overflow,
+            r if r > ANALOG_NOMINAL_HIGH => AnalogStatus::Overrange,
+            r if r >= 0 => AnalogStatus::Nominal,
+            r if r >= ANALOG_UNDERSHOOT_LOW => AnalogStatus::Underrange,
+            _ => AnalogStatus::Underflow,
+        }
+    }
+
+    /// ### Classifies a raw value of a bipolar channel (-27648..27648)
+    ///
+    pub fn bipolar(raw: i16) -> Self {
+        match raw {
+            r if r > ANALOG_OVERSHOOT_HIGH => AnalogStatus::Overflow,
+            r if r > ANALOG_NOMINAL_HIGH => AnalogStatus::Overrange,
+            r if r >= ANALOG_NOMINAL_LOW_BIPOLAR => AnalogStatus::Nominal,
+            r if r >= ANALOG_UNDERSHOOT_LOW_BIPOLAR => AnalogStatus::Underrange,
+            _ => AnalogStatus::Underflow,
+        }
+    }
+
+    /// ### Returns true if the value is significant (not an overflow or an underflow)
+    ///
+    pub fn is_valid(self) -> bool {
+        !matches!(self, AnalogStatus::Overflow | AnalogStatus::Underflow)
+    }
+}
+
+/// ### Physical value of an analog input
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnalogValue {
+    /// Value in engineering units, extrapolated outside of the nominal range
+    pub value: f64,
+    pub status: AnalogStatus,
+}
+
+impl AnalogValue {
+    /// ### Returns true if the value is significant (not an overflow or an underflow)
+    ///
+    pub fn is_valid(&self) -> bool {
+        self.status.is_valid()
+    }
+}
+
+/// Linear interpolation of the raw value, `raw_low` -> `low`, 27648 -> `high`
+fn interpolate(raw: i16, raw_low: i16, low: f64, high: f64) -> f64 {
+    low + (raw as f64 - raw_low as f64) * (high - low) / (ANALOG_NOMINAL_HIGH as f64 - raw_low as f64)
+}
+
+/// Inverse of `interpolate()`, clamped to the nominal range
+fn deinterpolate(value: f64, raw_low: i16, low: f64, high: f64) -> i16 {
+    if high == low || value.is_nan() {
+        return raw_low;
+    }
+    let raw = raw_low as f64 + (value - low) * (ANALOG_NOMINAL_HIGH as f64 - raw_low as f64) / (high - low);
+    raw.round().clamp(raw_low as f64, ANALOG_NOMINAL_HIGH as f64) as i16
+}
+
+/// ### Converts the raw value of a unipolar analog input to engineering units
+///
+/// Unipolar channels: 0..20 mA, 4..20 mA, 0..10 V, 1..5 V...
+///
+/// ### Parameters
+/// - `raw`: Value read from the input (e.g. `IW256`).
+/// - `low`: Physical value at the start of the range (raw 0, i.e. 0 or 4 mA).
+/// - `high`: Physical value at the end of the range (raw 27648, i.e. 20 mA).
+///
+/// ### Returns
+/// The physical value and the status of the raw value. The value of an `Overflow` or of an
+/// `Underflow` is not significant: check `is_valid()` before using it.
+///
+pub fn analog_to_physical(raw: i16, low: f64, high: f64) -> AnalogValue {
+    AnalogValue {
+        value: interpolate(raw, 0, low, high),
+        status: AnalogStatus::unipolar(raw),
+    }
+}
+
+/// ### Converts a physical value to the raw value of a unipolar analog output
+///
+/// Inverse of `analog_to_physical()`.
+///
+/// ### Parameters
+/// - `value`: Physical value to output.
+/// - `low`: Physical value at the start of the range (raw 0).
+/// - `high`: Physical value at the end of the range (raw 27648).
+///
+/// ### Returns
+/// The raw value to write, clamped to the nominal range 0..27648 (0 if `low` == `high` or `value` is NaN).
+///
+pub fn physical_to_analog(value: f64, low: f64, high: f64) -> i16 {
+    deinterpolate(value, 0, low, high)
+}
+
+/// ### Converts the raw value of a bipolar analog input to engineering units
+///
+/// Bipolar channels: ±10 V, ±20 mA...
+///
+/// ### Parameters
+/// - `raw`: Value read from the input.
+/// - `low`: Physical value at the start of the range (raw -27648, e.g. -10 V).
+/// - `high`: Physical value at the end of the range (raw 27648, e.g. +10 V).
+///
+/// ### Returns
+/// The physical value and the status of the raw value (see `analog_to_physical()`).
+///
+pub fn analog_to_physical_bipolar(raw: i16, low: f64, high: f64) -> AnalogValue {
+    AnalogValue {
+        value: interpolate(raw, ANALOG_NOMINAL_LOW_BIPOLAR, low, high),
+        status: AnalogStatus::bipolar(raw),
+    }
+}
+
+/// ### Converts a physical value to the raw value of a bipolar analog output
+///
+/// Inverse of `analog_to_physical_bipolar()`.
+///
+/// ### Returns
+/// The raw value to write, clamped to the nominal range -27648..27648 (-27648 if `low` == `high`
+/// or `value` is NaN).
+///
+pub fn physical_to_analog_bipolar(value: f64, low: f64, high: f64) -> i16 {
+    deinterpolate(value, ANALOG_NOMINAL_LOW_BIPOLAR, low, high)
+}
@@ -0,0 +1,162 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Audit trail of the writes
+//!
+//! The regulated industries require a trail of the modifications made to a PLC by the
+//! supervisory systems. An audit hook installed on the client receives a record for every write
+//! (`write_area()`, the methods based on it, `write_multi_vars()`, `execute_force()` and
+//! `write_nck()`): time, address, value before the write (optional, it costs a read), value
+//! written, outcome and a context set by the caller (user, recipe, reason...).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::audit::AuditRecord;
+//!
+//! let mut client = S7Client::new();
+//! client.set_audit_hook(Some(Box::new(|record: &AuditRecord| {
+//!     println!("{:?} {} {} {:02X?} -> {:02X?}: {:?}", record.timestamp, record.context.unwrap_or("-"),
+//!         record.write.address(), record.old_value, record.write.data, record.result);
+//! })));
+//! client.set_audit_old_values(true);
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! client.set_audit_context(Some("recipe 12, operator jdoe"));
+//! client.write_db(10, 0, &[1, 2, 3, 4]).unwrap();
+//! ```
+
+use std::time::SystemTime;
+use crate::client::{S7Client, S7Error};
+use crate::multivar::S7DataItem;
+use crate::nck::NckAddress;
+use crate::observer::WriteRequest;
+
+/// ### Record of a write
+///
+#[derive(Debug, Clone, Copy)]
+pub struct AuditRecord<'a> {
+    /// Time of the write request
+    pub timestamp: SystemTime,
+    /// Operation, address and value written
+    pub write: WriteRequest<'a>,
+    /// Value before the write, `None` if not read (see `set_audit_old_values()`) or if the read failed
+    pub old_value: Option<&'a [u8]>,
+    /// Outcome of the write (of the variable for `write_multi_vars()`)
+    pub result: Result<(), &'a S7Error>,
+    /// Context set by the caller (see `set_audit_context()`)
+    pub context: Option<&'a str>,
+    /// True if the write was validated but not sent (see `set_dry_run()`)
+    pub dry_run: bool,
+}
+
+/// Callback receiving the audit records
+pub type AuditHook = Box<dyn FnMut(&AuditRecord) + Send>;
+
+/// Time and old values of the writes being audited
+pub(crate) struct PendingAudit {
+    timestamp: SystemTime,
+    old_values: Vec<Option<Vec<u8>>>,
+}
+
+impl S7Client {
+    /// ### Installs the audit hook
+    ///
+    /// ### Parameters
+    /// - `hook`: The callback receiving a record for every write, `None` removes it (Default).
+    ///
+    /// ### Notes
+    /// - The hook is called once per write after its outcome is known, the automatic retries
+    ///   (see `set_retry_policy()`) included; for `write_multi_vars()`, once per variable.
+    /// - It's called synchronously on the thread running the write: keep it short, or send the
+    ///   record to a queue.
+    ///
+    pub fn set_audit_hook(&mut self, hook: Option<AuditHook>) {
+        self.audit_hook = hook;
+    }
+
+    /// ### Sets the context reported in the audit records
+    ///
+    /// ### Parameters
+    /// - `context`: Free text (e.g. user, recipe, reason), reported by the following writes until
+    ///   changed; `None` to clear it (Default).
+    ///
+    pub fn set_audit_context(&mut self, context: Option<&str>) {
+        self.audit_context = context.map(str::to_string);
+    }
+
+    /// ### Reads the values before the writes for the audit records
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to read the old values, false (Default) to leave `old_value` empty.
+    ///
+    /// ### Notes
+    /// Each write is preceded by a read of the same range (a single `read_multi_vars()` for a
+    /// `write_multi_vars()`), served by the read cache if enabled. The read is done after the
+    /// write policy and the protection check accepted the write: a refused write has no old
+    /// value. A failed read doesn't prevent the write, the old value is reported as `None`.
+    ///
+    pub fn set_audit_old_values(&mut self, enabled: bool) {
+        self.audit_old_values = enabled;
+    }
+
+    /// Starts the audit of a write of `count` variables, `None` if there's no hook
+    pub(crate) fn audit_begin(&self, count: usize) -> Option<PendingAudit> {
+        self.audit_hook.as_ref()?;
+        Some(PendingAudit { timestamp: SystemTime::now(), old_values: vec![None; count] })
+    }
+
+    /// Reads the old value of a `write_area()` accepted for sending (see `set_audit_old_values()`)
+    pub(crate) fn audit_read_old_value(&mut self, pending: &mut Option<PendingAudit>, area: u8, db_number: u16, start: u32, wordlen: u8, size: usize) {
+        let Some(pending) = pending.as_mut().filter(|_| self.audit_old_values) else {
+            return;
+        };
+        if pending.old_values[0].is_some() {
+            return; // Already read by a previous attempt
+        }
+        let (last_time, chunks) = (self.last_time, self.chunks);
+        let mut old_value = vec![0u8; size];
+        pending.old_values[0] = self.read_area(area, db_number, start, wordlen, &mut old_value).ok().map(|_| old_value);
+        (self.last_time, self.chunks) = (last_time, chunks);
+    }
+
+    /// Reads the old values of a `write_multi_vars()` accepted for sending (see `set_audit_old_values()`)
+    pub(crate) fn audit_read_old_values(&mut self, pending: &mut Option<PendingAudit>, items: &[S7DataItem]) {
+        let Some(pending) = pending.as_mut().filter(|_| self.audit_old_values) else {
+            return;
+        };
+        let (last_time, chunks) = (self.last_time, self.chunks);
+        let mut reads: Vec<S7DataItem> = items.iter()
+            .map(|item| S7DataItem::new(item.area, item.wordlen, item.db, item.start, item.amount))
+            .collect();
+        if self.read_multi_vars(&mut reads).is_ok() {
+            pending.old_values = reads.into_iter().map(|read| read.result.ok().map(|_| read.data)).collect();
+        }
+        (self.last_time, self.chunks) = (last_time, chunks);
+    }
+
+    /// Reads the old value of a `write_nck()` accepted for sending (see `set_audit_old_values()`)
+    pub(crate) fn audit_read_old_nck(&mut self, pending: &mut Option<PendingAudit>, address: &NckAddress) {
+        let Some(pending) = pending.as_mut().filter(|_| self.audit_old_values) else {
+            return;
+        };
+        let (last_time, chunks) = (self.last_time, self.chunks);
+        pending.old_values[0] = self.read_nck(address).ok();
+        (self.last_time, self.chunks) = (last_time, chunks);
+    }
+
+    /// Reports the outcome of the `index`-th write of `pending` to the hook
+    pub(crate) fn audit(&mut self, pending: &PendingAudit, index: usize, write: WriteRequest, result: Result<(), &S7Error>) {
+        let dry_run = self.dry_run();
+        if let Some(hook) = self.audit_hook.as_mut() {
+            hook(&AuditRecord {
+                timestamp: pending.timestamp,
+                write,
+                old_value: pending.old_values.get(index).and_then(Option::as_deref),
+                result,
+                context: self.audit_context.as_deref(),
+                dry_run,
+            });
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Authorization of the writes
+//!
+//! A write policy installed on the client is consulted before any write is sent
+//! (`write_area()`, the methods based on it, `write_multi_vars()`, `execute_force()` and
+//! `write_nck()`): a denied write fails with
+//! `S7Error::WriteDenied` and nothing reaches the PLC. The enforcement is centralized, instead of
+//! trusting every call site of the application.
+//!
+//! The policy is any closure returning a `WriteDecision`; `WriteAllowList` covers the common case
+//! of the areas, DBs and DB ranges that can be written.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::{S7Client, S7_AREA_MK};
+//! use rust7::authorize::WriteAllowList;
+//!
+//! let mut client = S7Client::new();
+//! client.set_write_policy(Some(WriteAllowList::new()
+//!     .with_area(S7_AREA_MK)     // All the merkers
+//!     .with_db(20)               // The whole DB20
+//!     .with_db_range(10, 0, 64)  // DB10, byte 0..64
+//!     .into_policy()));
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! client.write_db(10, 0, &[1, 2, 3, 4]).unwrap();
+//! assert!(client.write_db(10, 100, &[1]).is_err()); // WriteDenied
+//! ```
+
+use std::ops::Range;
+use crate::client::{S7Client, S7Error, S7_AREA_DB, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE, S7_WL_BIT};
+use crate::observer::WriteRequest;
+
+/// ### Outcome of the write policy
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteDecision {
+    /// The write can be sent
+    Allow,
+    /// The write is refused, with the reason reported by `S7Error::WriteDenied`
+    Deny(String),
+}
+
+/// Callback deciding if a write can be sent
+pub type WritePolicy = Box<dyn FnMut(&WriteRequest) -> WriteDecision + Send>;
+
+/// ### Allow-list of the writable areas, DBs and DB ranges
+///
+/// Everything not listed is denied.
+///
+#[derive(Debug, Clone, Default)]
+pub struct WriteAllowList {
+    areas: Vec<u8>,
+    dbs: Vec<(u16, Range<usize>)>,
+    nck_areas: Vec<u8>,
+}
+
+impl WriteAllowList {
+    /// ### Creates an empty list (all the writes are denied)
+    ///
+    pub fn new() -> Self {
+        WriteAllowList::default()
+    }
+
+    /// ### Allows a whole area (`S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_MK`, `S7_AREA_DB` = all the DBs)
+    ///
+    /// ### Notes
+    /// `ForceJob::UnforceAll` is allowed only if the three forceable areas (`S7_AREA_PE`,
+    /// `S7_AREA_PA`, `S7_AREA_MK`) are allowed.
+    ///
+    pub fn with_area(mut self, area: u8) -> Self {
+        self.areas.push(area);
+        self
+    }
+
+    /// ### Allows a whole DB
+    ///
+    pub fn with_db(self, db_number: u16) -> Self {
+        self.with_db_range(db_number, 0, usize::MAX)
+    }
+
+    /// ### Allows the bytes `start..end` of a DB
+    ///
+    /// ### Notes
+    /// A write is allowed only if all its bytes are inside one of the ranges; a bit write is
+    /// checked against the byte containing the bit.
+    ///
+    pub fn with_db_range(mut self, db_number: u16, start: usize, end: usize) -> Self {
+        self.dbs.push((db_number, start..end));
+        self
+    }
+
+    /// ### Allows the variables of an NCK area (`NCK_AREA_N`, `NCK_AREA_C`...) for `write_nck()`
+    ///
+    pub fn with_nck_area(mut self, area: u8) -> Self {
+        self.nck_areas.push(area);
+        self
+    }
+
+    /// ### Checks a write against the list
+    ///
+    pub fn check(&self, write: &WriteRequest) -> WriteDecision {
+        if let Some(nck) = &write.nck {
+            if self.nck_areas.contains(&nck.area) {
+                return WriteDecision::Allow;
+            }
+            return WriteDecision::Deny("not in the allow-list".to_string());
+        }
+        if write.operation == "force" && write.data.is_empty() {
+            // ForceJob::UnforceAll acts on all the forceable areas
+            if [S7_AREA_PE, S7_AREA_PA, S7_AREA_MK].iter().all(|area| self.areas.contains(area)) {
+                return WriteDecision::Allow;
+            }
+            return WriteDecision::Deny("not in the allow-list".to_string());
+        }
+        if self.areas.contains(&write.area) {
+            return WriteDecision::Allow;
+        }
+        if write.area == S7_AREA_DB {
+            let (first, last) = if write.wordlen == S7_WL_BIT {
+                let byte = write.start as usize >> 3;
+                (byte, byte + 1)
+            } else {
+                (write.start as usize, write.start as usize + write.data.len())
+            };
+            let allowed = self.dbs.iter()
+                .any(|(db_number, range)| *db_number == write.db_number && range.start <= first && last <= range.end);
+            if allowed {
+                return WriteDecision::Allow;
+            }
+        }
+        WriteDecision::Deny("not in the allow-list".to_string())
+    }
+
+    /// ### Returns the list as a policy for `set_write_policy()`
+    ///
+    pub fn into_policy(self) -> WritePolicy {
+        Box::new(move |write: &WriteRequest| self.check(write))
+    }
+}
+
+impl S7Client {
+    /// ### Installs the write policy
+    ///
+    /// ### Parameters
+    /// - `policy`: The callback consulted before any write is sent, `None` removes it (Default,
+    ///   all the writes are allowed).
+    ///
+    /// ### Notes
+    /// - A denied write fails with `S7Error::WriteDenied`, before the protection check (see
+    ///   `set_check_write_protection()`) and the dry-run (see `set_dry_run()`).
+    /// - `write_multi_vars()` consults the policy for each variable and sends nothing if any of
+    ///   them is denied.
+    ///
+    pub fn set_write_policy(&mut self, policy: Option<WritePolicy>) {
+        self.write_policy = policy;
+    }
+
+    /// Consults the write policy, `Err(S7Error::WriteDenied)` if the write is refused
+    pub(crate) fn authorize_write(&mut self, write: &WriteRequest) -> Result<(), S7Error> {
+        let Some(policy) = self.write_policy.as_mut() else {
+            return Ok(());
+        };
+        match policy(write) {
+            WriteDecision::Allow => Ok(()),
+            WriteDecision::Deny(reason) => Err(S7Error::WriteDenied { address: write.address(), reason }),
+        }
+    }
+}
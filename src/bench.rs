@@ -0,0 +1,270 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Throughput benchmark
+//!
+//! `S7Client::benchmark()` runs a standardized pattern of reads and writes (areas x sizes x
+//! operations) on a connected PLC, or on the simulator, and reports throughput and latency
+//! percentiles of each combination. The numbers allow choosing chunk sizes, PDU lengths and the
+//! number of connections objectively.
+//!
+//! Writes are safe for the process: each write puts back the values read just before, so the
+//! memory keeps its content (unless the PLC program changes it in the meantime).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::bench::BenchmarkProfile;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! let report = client.benchmark(&BenchmarkProfile::standard(10)).unwrap();
+//! for result in &report.results {
+//!     println!("{:?} {} byte: {:.0} byte/s, p99 {:?}", result.operation, result.size, result.throughput, result.p99);
+//! }
+//! ```
+
+use std::time::{Duration, Instant};
+use crate::client::{S7Client, S7Error, S7_AREA_DB, S7_AREA_MK, S7_WL_BYTE};
+
+/// ### Operation measured by the benchmark
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchmarkOperation {
+    Read,
+    /// Writes back the values read before the measure
+    Write,
+}
+
+/// ### Memory block used by the benchmark
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkTarget {
+    pub area: u8,
+    /// DB number (DB area only)
+    pub db_number: u16,
+    /// First byte of the block
+    pub start: u32,
+}
+
+/// ### Pattern of the benchmark
+///
+/// Each target is measured with each size and each operation. The block from `start` to
+/// `start + max(sizes)` must exist in every target.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchmarkProfile {
+    pub targets: Vec<BenchmarkTarget>,
+    /// Transfer sizes in byte
+    pub sizes: Vec<usize>,
+    pub operations: Vec<BenchmarkOperation>,
+    /// Measured transfers for each combination
+    pub iterations: u32,
+    /// Transfers executed before the measure of each combination (not reported)
+    pub warmup: u32,
+}
+
+impl BenchmarkProfile {
+    /// ### Short profile: reads of 1 byte to 1 KB of a DB
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB used, at least 1024 byte long.
+    ///
+    pub fn quick(db_number: u16) -> Self {
+        BenchmarkProfile {
+            targets: vec![BenchmarkTarget { area: S7_AREA_DB, db_number, start: 0 }],
+            sizes: vec![1, 64, 222, 1024],
+            operations: vec![BenchmarkOperation::Read],
+            iterations: 20,
+            warmup: 2,
+        }
+    }
+
+    /// ### Standard profile: reads and writes of 1 byte to 8 KB of a DB and of the Merkers
+    ///
+    /// The sizes include one PDU of the S7-300/400 (222 byte) and of the S7-1200/1500 (462 byte),
+    /// and transfers split into several PDUs.
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB used, at least 8192 byte long. The Merkers used are MB0..MB63.
+    ///
+    pub fn standard(db_number: u16) -> Self {
+        BenchmarkProfile {
+            targets: vec![
+                BenchmarkTarget { area: S7_AREA_DB, db_number, start: 0 },
+                BenchmarkTarget { area: S7_AREA_MK, db_number: 0, start: 0 },
+            ],
+            sizes: vec![1, 16, 64, 222, 462, 1024, 4096, 8192],
+            operations: vec![BenchmarkOperation::Read, BenchmarkOperation::Write],
+            iterations: 50,
+            warmup: 5,
+        }
+    }
+}
+
+/// ### Measures of a combination (target, size, operation)
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchmarkResult {
+    pub target: BenchmarkTarget,
+    pub size: usize,
+    pub operation: BenchmarkOperation,
+    /// Successful transfers
+    pub iterations: u32,
+    /// Failed transfers (not included in the times)
+    pub errors: u32,
+    /// Request telegrams sent for each transfer (> 1 if the transfer is split into PDUs)
+    pub requests_per_transfer: f64,
+    /// Byte per second
+    pub throughput: f64,
+    pub min: Duration,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// ### Result of `benchmark()`
+///
+#[derive(Debug)]
+pub struct BenchmarkReport {
+    /// Negotiated PDU length
+    pub pdu_length: u16,
+    /// One result for each combination measured, in the order of the profile
+    pub results: Vec<BenchmarkResult>,
+    /// Combinations skipped because the target is too small or not accessible for the size
+    pub skipped: Vec<(BenchmarkTarget, usize, S7Error)>,
+    /// Duration of the whole benchmark
+    pub elapsed: Duration,
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl S7Client {
+    /// ### Runs a throughput benchmark
+    ///
+    /// For each target and size, the block is read once (to check it exists and to get the values
+    /// written back), then each operation is executed `warmup` + `iterations` times.
+    ///
+    /// ### Parameters
+    /// - `profile`: The pattern to run, e.g. `BenchmarkProfile::standard()`.
+    ///
+    /// ### Returns
+    /// `Ok(<BenchmarkReport>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::InvalidFunParameter`: Empty profile, zero iterations or a size of 0 byte.
+    /// - `S7Error::Io` and the ISO errors: The connection failed during the benchmark.
+    /// - Reported by `read_area()` and `write_area()` during the warmup.
+    ///
+    /// ### Notes
+    /// A block the PLC refuses (e.g. a DB too short) is reported in `skipped`, not as an error;
+    /// the errors reported by the PLC during the measure are counted in `errors`.
+    /// The transfers use the byte word length, through `read_area()` and `write_area()`.
+    ///
+    pub fn benchmark(&mut self, profile: &BenchmarkProfile) -> Result<BenchmarkReport, S7Error> {
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+        if profile.targets.is_empty() || profile.sizes.is_empty() || profile.operations.is_empty()
+            || profile.iterations == 0 || profile.sizes.contains(&0) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let begin = Instant::now();
+        let mut report = BenchmarkReport {
+            pdu_length: self.pdu_length,
+            results: Vec::new(),
+            skipped: Vec::new(),
+            elapsed: Duration::ZERO,
+        };
+
+        for target in &profile.targets {
+            for &size in &profile.sizes {
+                let mut buffer = vec![0u8; size];
+                let link_errors = self.traffic_stats().errors;
+                if let Err(error) = self.read_area(target.area, target.db_number, target.start, S7_WL_BYTE, &mut buffer) {
+                    if self.traffic_stats().errors > link_errors {
+                        return Err(error);
+                    }
+                    report.skipped.push((*target, size, error));
+                    continue;
+                }
+                for &operation in &profile.operations {
+                    report.results.push(self.benchmark_one(profile, *target, operation, &mut buffer)?);
+                }
+            }
+        }
+        report.elapsed = begin.elapsed();
+        Ok(report)
+    }
+
+    fn benchmark_transfer(&mut self, target: BenchmarkTarget, operation: BenchmarkOperation, buffer: &mut [u8]) -> Result<(), S7Error> {
+        match operation {
+            BenchmarkOperation::Read => self.read_area(target.area, target.db_number, target.start, S7_WL_BYTE, buffer),
+            BenchmarkOperation::Write => self.write_area(target.area, target.db_number, target.start, S7_WL_BYTE, buffer),
+        }
+    }
+
+    fn benchmark_one(&mut self, profile: &BenchmarkProfile, target: BenchmarkTarget, operation: BenchmarkOperation, buffer: &mut [u8]) -> Result<BenchmarkResult, S7Error> {
+        let link_errors = self.traffic_stats().errors;
+        for _ in 0..profile.warmup {
+            self.benchmark_transfer(target, operation, buffer)?;
+        }
+
+        let frames_before = self.traffic_stats().frames_sent;
+        let mut samples = Vec::with_capacity(profile.iterations as usize);
+        let mut errors = 0;
+        for _ in 0..profile.iterations {
+            let start = Instant::now();
+            match self.benchmark_transfer(target, operation, buffer) {
+                Ok(()) => samples.push(start.elapsed()),
+                Err(error) => {
+                    // Transport and protocol errors abort, the errors reported by the PLC are counted
+                    if self.traffic_stats().errors > link_errors {
+                        return Err(error);
+                    }
+                    errors += 1;
+                }
+            }
+        }
+        let frames = self.traffic_stats().frames_sent - frames_before;
+
+        samples.sort();
+        let total: Duration = samples.iter().sum();
+        let count = samples.len();
+        let (min, mean, p50, p90, p99, max) = if count > 0 {
+            (
+                samples[0],
+                total / count as u32,
+                percentile(&samples, 50),
+                percentile(&samples, 90),
+                percentile(&samples, 99),
+                samples[count - 1],
+            )
+        } else {
+            Default::default()
+        };
+        let throughput = if total.is_zero() { 0.0 } else { (count * buffer.len()) as f64 / total.as_secs_f64() };
+        Ok(BenchmarkResult {
+            target,
+            size: buffer.len(),
+            operation,
+            iterations: count as u32,
+            errors,
+            requests_per_transfer: frames as f64 / profile.iterations as f64,
+            throughput,
+            min,
+            mean,
+            p50,
+            p90,
+            p99,
+            max,
+        })
+    }
+}
@@ -0,0 +1,391 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### s7cli - Command line tool (feature `cli`)
+//!
+//! ```text
+//! s7cli monitor --tags <file.toml> [--csv]
+//! s7cli scan <network/prefix> [--rack <n> --slot <n>] [--timeout <ms>]
+//! ```
+//!
+//! `monitor` connects to the PLC of a configuration file (the format of `S7Client::from_config_file()`)
+//! and polls its tags at the rate of their poll group, showing a live table of the values with
+//! their quality and timing; with `--csv` a line per tag read is printed instead, to be redirected
+//! to a file.
+//!
+//! `scan` probes the port 102 of each address of a network, attempts an S7 connection to the
+//! responders and prints the inventory of the devices found (order number, firmware, operating mode).
+
+use std::env;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use rust7::{S7Client, S7DataType, S7Error, S7Value, S7_AREA_DB, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE};
+use rust7::config::ClientConfig;
+use rust7::szl::PlcStatus;
+use rust7::tag::Tag;
+
+const USAGE: &str = "\
+Usage:
+  s7cli monitor --tags <file.toml> [--csv]
+  s7cli scan <network/prefix> [--rack <n> --slot <n>] [--timeout <ms>]
+
+Commands:
+  monitor   Polls the tags of a configuration file and shows their values live
+            --tags <file.toml>  Connection and poll groups (see S7Client::from_config_file)
+            --csv               Prints a CSV line per tag read instead of the table
+  scan      Finds the S7 devices of a network (e.g. 192.168.0.0/24)
+            --rack, --slot      CPU position (Default: tries 0/0, 0/2 and 0/3)
+            --timeout <ms>      Connection and response timeout (Default: 500)";
+
+// ANSI sequence: clear screen and cursor home
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+const ISO_TCP_PORT: u16 = 102;
+// Rack/slot tried by the scan: S7-1200/1500, S7-300, S7-400
+const SCAN_RACK_SLOTS: [(u16, u16); 3] = [(0, 0), (0, 2), (0, 3)];
+const SCAN_TIMEOUT_MS: u64 = 500;
+const SCAN_THREADS: usize = 64;
+// Largest network scanned (a /16)
+const SCAN_MAX_HOSTS: u64 = 65536;
+
+/// Live state of a monitored tag
+struct TagState {
+    tag: Tag,
+    group: usize,
+    value: Option<S7Value>,
+    /// None until the first read, `Ok` or the error text of the last read
+    quality: Option<Result<(), String>>,
+    /// Duration of the last read (ms)
+    time_ms: f64,
+    /// Time of the last successful read
+    updated: Option<Instant>,
+}
+
+struct MonitorOptions {
+    tags: String,
+    csv: bool,
+}
+
+fn parse_monitor_options(args: &[String]) -> Result<MonitorOptions, S7Error> {
+    let mut tags = None;
+    let mut csv = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tags" => tags = args.next().cloned(),
+            "--csv" => csv = true,
+            _ => return Err(S7Error::Other(format!("unknown option \"{}\"", arg))),
+        }
+    }
+    let tags = tags.ok_or_else(|| S7Error::Other("missing --tags <file.toml>".to_string()))?;
+    Ok(MonitorOptions { tags, csv })
+}
+
+/// Address of a tag in the STEP 7 notation (e.g. `DB10.DBW4`, `M2.3`)
+fn address(tag: &Tag) -> String {
+    let prefix = match tag.area {
+        S7_AREA_DB => format!("DB{}.DB", tag.db_number),
+        S7_AREA_PE => "I".to_string(),
+        S7_AREA_PA => "Q".to_string(),
+        S7_AREA_MK => "M".to_string(),
+        area => format!("0x{:02X}:", area),
+    };
+    match (tag.data_type, tag.data_type.size()) {
+        (S7DataType::Bool, _) if tag.area == S7_AREA_DB => format!("{}X{}.{}", prefix, tag.start / 8, tag.start % 8),
+        (S7DataType::Bool, _) => format!("{}{}.{}", prefix, tag.start / 8, tag.start % 8),
+        (_, 2) => format!("{}W{}", prefix, tag.start),
+        (_, 4) => format!("{}D{}", prefix, tag.start),
+        (_, 1) => format!("{}B{}", prefix, tag.start),
+        (_, size) => format!("{}B{} ({} byte)", prefix, tag.start, size),
+    }
+}
+
+fn quality(state: &TagState, stale_after: Option<Duration>) -> String {
+    match &state.quality {
+        None => "-".to_string(),
+        Some(Err(error)) => format!("BAD: {}", error),
+        Some(Ok(())) if stale_after.zip(state.updated).is_some_and(|(limit, time)| time.elapsed() > limit) => "STALE".to_string(),
+        Some(Ok(())) => "GOOD".to_string(),
+    }
+}
+
+/// Formats a table, columns left aligned
+fn table<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().zip(widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        cells.join("  ").trim_end().to_string() + "\n"
+    };
+    let mut text = line(&header.map(str::to_string));
+    text.push_str(&line(&widths.map(|width| "-".repeat(width))));
+    for row in rows {
+        text.push_str(&line(row));
+    }
+    text
+}
+
+/// Quotes a CSV field if needed
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn render_table(config: &ClientConfig, states: &[TagState], stale_after: &[Option<Duration>], client: &S7Client) {
+    let rows: Vec<[String; 6]> = states.iter().map(|state| [
+        state.tag.name.clone(),
+        address(&state.tag),
+        state.value.as_ref().map(S7Value::to_string).unwrap_or_default(),
+        quality(state, stale_after[state.group]),
+        format!("{:.1}", state.time_ms),
+        state.updated.map(|time| time.elapsed().as_millis().to_string()).unwrap_or_default(),
+    ]).collect();
+    let status = if client.connected { format!("connected, PDU {} byte", client.pdu_length) } else { "not connected".to_string() };
+    print!("{}s7cli monitor - {} ({}) - Ctrl+C to exit\n\n{}", CLEAR_SCREEN, config.connection.address, status,
+        table(["Name", "Address", "Value", "Quality", "Time (ms)", "Age (ms)"], &rows));
+}
+
+fn monitor(args: &[String]) -> Result<(), S7Error> {
+    let options = parse_monitor_options(args)?;
+    let config = ClientConfig::load(&options.tags)?;
+    if config.poll_groups.iter().all(|group| group.tags.is_empty()) {
+        return Err(S7Error::Other(format!("no tags in {}", options.tags)));
+    }
+
+    let mut client = S7Client::new();
+    config.connection.configure(&mut client)?;
+    config.connection.connect(&mut client)?;
+
+    let intervals: Vec<Duration> = config.poll_groups.iter().map(|group| Duration::from_millis(group.interval_ms)).collect();
+    let stale_after: Vec<Option<Duration>> = config.poll_groups.iter().zip(&intervals)
+        .map(|(group, interval)| group.stale_factor.map(|factor| interval.mul_f64(factor)))
+        .collect();
+    let mut states: Vec<TagState> = config.poll_groups.iter().enumerate()
+        .flat_map(|(group, config)| config.tags.iter().map(move |tag| TagState {
+            tag: tag.clone(),
+            group,
+            value: None,
+            quality: None,
+            time_ms: 0.0,
+            updated: None,
+        }))
+        .collect();
+    let mut next_polls = vec![Instant::now(); intervals.len()];
+
+    if options.csv {
+        println!("timestamp_ms,name,address,value,quality,time_ms");
+    }
+    loop {
+        for (group, next_poll) in next_polls.iter_mut().enumerate() {
+            if Instant::now() < *next_poll {
+                continue;
+            }
+            *next_poll = Instant::now() + intervals[group];
+            if !client.connected {
+                // Reconnection attempted once per scan, the tags of the group keep the error
+                if let Err(error) = client.reconnect() {
+                    for state in states.iter_mut().filter(|state| state.group == group) {
+                        state.quality = Some(Err(error.to_string()));
+                    }
+                    continue;
+                }
+            }
+            for state in states.iter_mut().filter(|state| state.group == group) {
+                let result = state.tag.read(&mut client);
+                state.time_ms = client.last_time;
+                match result {
+                    Ok(value) => {
+                        state.value = Some(value);
+                        state.quality = Some(Ok(()));
+                        state.updated = Some(Instant::now());
+                    }
+                    Err(error) => {
+                        state.quality = Some(Err(error.to_string()));
+                        if error.is_transport() {
+                            client.disconnect();
+                        }
+                    }
+                }
+                if options.csv {
+                    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+                    let value = if matches!(state.quality, Some(Ok(()))) { state.value.as_ref().map(S7Value::to_string) } else { None };
+                    println!("{},{},{},{},{},{:.3}",
+                        timestamp,
+                        csv_field(&state.tag.name),
+                        csv_field(&address(&state.tag)),
+                        csv_field(&value.unwrap_or_default()),
+                        csv_field(&quality(state, stale_after[group])),
+                        state.time_ms);
+                }
+            }
+        }
+        if !options.csv {
+            render_table(&config, &states, &stale_after, &client);
+        }
+        let next_poll = next_polls.iter().min().copied().unwrap_or_else(Instant::now);
+        thread::sleep(next_poll.saturating_duration_since(Instant::now()));
+    }
+}
+
+struct ScanOptions {
+    first: u32,
+    hosts: u32,
+    rack_slots: Vec<(u16, u16)>,
+    timeout: Duration,
+}
+
+/// Device answering on the port 102
+struct Device {
+    ip: Ipv4Addr,
+    /// Rack/slot accepted and negotiated PDU, or the error of the S7 connection
+    connection: Result<(u16, u16, u16), S7Error>,
+    order_number: Option<String>,
+    firmware: Option<String>,
+    status: Option<PlcStatus>,
+}
+
+/// Parses "a.b.c.d/prefix" (or a single address) into the first address and the number of hosts
+fn parse_network(network: &str) -> Result<(u32, u32), S7Error> {
+    let invalid = || S7Error::Other(format!("invalid network \"{}\"", network));
+    let (address, prefix) = match network.split_once('/') {
+        Some((address, prefix)) => (address, prefix.parse::<u32>().map_err(|_| invalid())?),
+        None => (network, 32),
+    };
+    let address: Ipv4Addr = address.parse().map_err(|_| invalid())?;
+    if prefix > 32 {
+        return Err(invalid());
+    }
+    let size = 1u64 << (32 - prefix);
+    if size > SCAN_MAX_HOSTS {
+        return Err(S7Error::Other(format!("network too large, max /{}", 32 - SCAN_MAX_HOSTS.trailing_zeros())));
+    }
+    let base = u32::from(address) & !((size - 1) as u32);
+    // The network and broadcast addresses are skipped, except for /31 and /32
+    if size > 2 { Ok((base + 1, (size - 2) as u32)) } else { Ok((base, size as u32)) }
+}
+
+fn parse_scan_options(args: &[String]) -> Result<ScanOptions, S7Error> {
+    let mut network = None;
+    let (mut rack, mut slot) = (None, None);
+    let mut timeout_ms = SCAN_TIMEOUT_MS;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut number = |name: &str| -> Result<u64, S7Error> {
+            args.next().and_then(|value| value.parse().ok())
+                .ok_or_else(|| S7Error::Other(format!("{} requires a number", name)))
+        };
+        match arg.as_str() {
+            "--rack" => rack = Some(number("--rack")? as u16),
+            "--slot" => slot = Some(number("--slot")? as u16),
+            "--timeout" => timeout_ms = number("--timeout")?.max(1),
+            _ if arg.starts_with("--") => return Err(S7Error::Other(format!("unknown option \"{}\"", arg))),
+            _ => network = Some(arg.clone()),
+        }
+    }
+    let network = network.ok_or_else(|| S7Error::Other("missing the network to scan (e.g. 192.168.0.0/24)".to_string()))?;
+    let (first, hosts) = parse_network(&network)?;
+    let rack_slots = match (rack, slot) {
+        (None, None) => SCAN_RACK_SLOTS.to_vec(),
+        (rack, slot) => vec![(rack.unwrap_or(0), slot.unwrap_or(0))],
+    };
+    Ok(ScanOptions { first, hosts, rack_slots, timeout: Duration::from_millis(timeout_ms) })
+}
+
+/// Probes an address, `None` if the port 102 is closed
+fn probe(ip: Ipv4Addr, options: &ScanOptions) -> Option<Device> {
+    TcpStream::connect_timeout(&SocketAddr::from((ip, ISO_TCP_PORT)), options.timeout).ok()?;
+
+    let timeout_ms = options.timeout.as_millis() as u64;
+    let mut client = S7Client::new();
+    let mut connection = Err(S7Error::NotConnected);
+    for &(rack, slot) in &options.rack_slots {
+        let _ = client.set_timeout(timeout_ms, timeout_ms, timeout_ms);
+        connection = client.connect_rack_slot(&ip.to_string(), rack, slot).map(|_| (rack, slot, client.pdu_length));
+        if connection.is_ok() {
+            break;
+        }
+    }
+    let mut device = Device { ip, connection, order_number: None, firmware: None, status: None };
+    if device.connection.is_ok() {
+        if let Ok(ident) = client.get_module_ident() {
+            device.order_number = Some(ident.order_number);
+            device.firmware = ident.firmware_version;
+        }
+        device.status = client.get_plc_status().ok();
+        client.disconnect();
+    }
+    Some(device)
+}
+
+fn scan(args: &[String]) -> Result<(), S7Error> {
+    let options = parse_scan_options(args)?;
+    let next = AtomicUsize::new(0);
+    let devices = Mutex::new(Vec::new());
+    eprintln!("Scanning {} address(es)...", options.hosts);
+
+    thread::scope(|scope| {
+        for _ in 0..SCAN_THREADS.min(options.hosts as usize) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= options.hosts as usize {
+                    break;
+                }
+                let ip = Ipv4Addr::from(options.first + index as u32);
+                if let Some(device) = probe(ip, &options) {
+                    devices.lock().unwrap().push(device);
+                }
+            });
+        }
+    });
+
+    let mut devices = devices.into_inner().unwrap();
+    devices.sort_by_key(|device| device.ip);
+    let rows: Vec<[String; 6]> = devices.iter().map(|device| {
+        let text = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        match &device.connection {
+            Ok((rack, slot, pdu_length)) => [
+                device.ip.to_string(),
+                format!("{}/{}", rack, slot),
+                pdu_length.to_string(),
+                text(&device.order_number),
+                text(&device.firmware),
+                text(&device.status.map(|status| status.to_string())),
+            ],
+            Err(error) => [device.ip.to_string(), "-".to_string(), "-".to_string(), format!("No S7 connection: {}", error), "-".to_string(), "-".to_string()],
+        }
+    }).collect();
+    print!("{}", table(["Address", "Rack/Slot", "PDU", "Order number", "Firmware", "Status"], &rows));
+    let s7_devices = devices.iter().filter(|device| device.connection.is_ok()).count();
+    println!("\n{} address(es) scanned, {} responding on port {}, {} S7 device(s)", options.hosts, devices.len(), ISO_TCP_PORT, s7_devices);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("monitor") => monitor(&args[1..]),
+        Some("scan") => scan(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("s7cli: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,598 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Block functions
+//!
+//! Directory of the blocks loaded in the CPU (S7-300/400, WinAC, S7-1200/1500 with PUT/GET only for
+//! non-optimized blocks): list of the blocks of a type and header information of a block.
+//! `browse_dbs()` combines them to list the DBs with their size and modification time, e.g. to let
+//! the user select a DB to monitor without the TIA project. `parse_mc7_header()` decodes the header
+//! of an uploaded block.
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::client::{S7Client, S7Error, S7ErrorCodes};
+use crate::frame::{split_userdata, userdata_telegram, S7UserDataParams, UD_GROUP_BLOCK, UD_METHOD_RESPONSE,
+    UD_SUBFN_BLOCK_INFO, UD_SUBFN_LIST_BLOCKS_OF_TYPE, UD_TYPE_REQUEST};
+
+const PDU_REF: u16 = 0x0600;
+const RES_SUCCESS: u8 = 0xFF;
+const RES_NOT_FOUND: u8 = 0x0A;
+const TS_OCTET_STRING: u8 = 0x09;
+const DATA_HEADER_LEN: usize = 4; // Return code + Transport size + Length
+const LIST_ITEM_LEN: usize = 4;   // Number + Flags + Language
+const BLOCK_INFO_LEN: usize = 70;
+/// Userdata error: the block doesn't exist
+const UD_ERR_BLOCK_NOT_FOUND: u16 = 0xD209;
+/// Follow-up requests of a multi-part list, guards against a CPU that never ends it
+const MAX_LIST_PARTS: usize = 1024;
+/// 1984-01-01 00:00:00 UTC, origin of the S7 block timestamps
+const S7_EPOCH_UNIX_SECS: u64 = 441_763_200;
+const MC7_HEADER_LEN: usize = 36;
+const MC7_FOOTER_LEN: usize = 36;
+/// First byte of a block in the load memory format
+const MC7_BLOCK_ID: u8 = 0x70;
+const MC7_KNOW_HOW_PROTECTED: u32 = 3;
+
+/// Block type: Organization Block
+pub const BLOCK_OB: u8 = 0x38;
+/// Block type: Data Block
+pub const BLOCK_DB: u8 = 0x41;
+/// Block type: System Data Block
+pub const BLOCK_SDB: u8 = 0x42;
+/// Block type: Function
+pub const BLOCK_FC: u8 = 0x43;
+/// Block type: System Function
+pub const BLOCK_SFC: u8 = 0x44;
+/// Block type: Function Block
+pub const BLOCK_FB: u8 = 0x45;
+/// Block type: System Function Block
+pub const BLOCK_SFB: u8 = 0x46;
+
+/// Block flag: the DB is write-protected in the CPU (attribute "DB is write-protected in the AS")
+pub const BLOCK_FLAG_WRITE_PROTECTED: u8 = 0x02;
+
+/// ### Block type
+///
+/// Converts from/to the `BLOCK_XXX` protocol codes: the block functions accept both.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BlockType {
+    Ob,
+    Db,
+    Sdb,
+    Fc,
+    Sfc,
+    Fb,
+    Sfb,
+}
+
+impl BlockType {
+    /// ### Maps a `BLOCK_XXX` protocol code, `None` if unknown
+    ///
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            BLOCK_OB => Some(BlockType::Ob),
+            BLOCK_DB => Some(BlockType::Db),
+            BLOCK_SDB => Some(BlockType::Sdb),
+            BLOCK_FC => Some(BlockType::Fc),
+            BLOCK_SFC => Some(BlockType::Sfc),
+            BLOCK_FB => Some(BlockType::Fb),
+            BLOCK_SFB => Some(BlockType::Sfb),
+            _ => None,
+        }
+    }
+
+    /// ### Returns the protocol code (`BLOCK_XXX`)
+    ///
+    pub fn code(&self) -> u8 {
+        match self {
+            BlockType::Ob => BLOCK_OB,
+            BlockType::Db => BLOCK_DB,
+            BlockType::Sdb => BLOCK_SDB,
+            BlockType::Fc => BLOCK_FC,
+            BlockType::Sfc => BLOCK_SFC,
+            BlockType::Fb => BLOCK_FB,
+            BlockType::Sfb => BLOCK_SFB,
+        }
+    }
+}
+
+impl From<BlockType> for u8 {
+    fn from(block_type: BlockType) -> u8 {
+        block_type.code()
+    }
+}
+
+impl TryFrom<u8> for BlockType {
+    type Error = S7Error;
+
+    fn try_from(code: u8) -> Result<Self, S7Error> {
+        BlockType::from_code(code).ok_or(S7Error::InvalidFunParameter)
+    }
+}
+
+impl fmt::Display for BlockType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BlockType::Ob => "OB",
+            BlockType::Db => "DB",
+            BlockType::Sdb => "SDB",
+            BlockType::Fc => "FC",
+            BlockType::Sfc => "SFC",
+            BlockType::Fb => "FB",
+            BlockType::Sfb => "SFB",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// ### Programming language of a block
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockLanguage {
+    /// Statement list (AWL)
+    Stl,
+    /// Ladder diagram (KOP)
+    Lad,
+    /// Function block diagram (FUP)
+    Fbd,
+    Scl,
+    /// Data block
+    Db,
+    Graph,
+    /// System data block
+    Sdb,
+    /// Data block created by the CPU
+    CpuDb,
+    /// Code not listed above
+    Other(u8),
+}
+
+impl BlockLanguage {
+    /// ### Maps the language code of the block list and block info
+    ///
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x01 => BlockLanguage::Stl,
+            0x02 => BlockLanguage::Lad,
+            0x03 => BlockLanguage::Fbd,
+            0x04 => BlockLanguage::Scl,
+            0x05 => BlockLanguage::Db,
+            0x06 => BlockLanguage::Graph,
+            0x07 => BlockLanguage::Sdb,
+            0x08 => BlockLanguage::CpuDb,
+            code => BlockLanguage::Other(code),
+        }
+    }
+
+    /// ### Returns the language code
+    ///
+    pub fn code(&self) -> u8 {
+        match self {
+            BlockLanguage::Stl => 0x01,
+            BlockLanguage::Lad => 0x02,
+            BlockLanguage::Fbd => 0x03,
+            BlockLanguage::Scl => 0x04,
+            BlockLanguage::Db => 0x05,
+            BlockLanguage::Graph => 0x06,
+            BlockLanguage::Sdb => 0x07,
+            BlockLanguage::CpuDb => 0x08,
+            BlockLanguage::Other(code) => *code,
+        }
+    }
+}
+
+impl From<u8> for BlockLanguage {
+    fn from(code: u8) -> Self {
+        BlockLanguage::from_code(code)
+    }
+}
+
+impl fmt::Display for BlockLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockLanguage::Stl => write!(f, "STL"),
+            BlockLanguage::Lad => write!(f, "LAD"),
+            BlockLanguage::Fbd => write!(f, "FBD"),
+            BlockLanguage::Scl => write!(f, "SCL"),
+            BlockLanguage::Db => write!(f, "DB"),
+            BlockLanguage::Graph => write!(f, "GRAPH"),
+            BlockLanguage::Sdb => write!(f, "SDB"),
+            BlockLanguage::CpuDb => write!(f, "CPU-DB"),
+            BlockLanguage::Other(code) => write!(f, "0x{:02X}", code),
+        }
+    }
+}
+
+/// ### Entry of a block list
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7BlockEntry {
+    pub number: u16,
+    /// Block flags (as reported by the CPU)
+    pub flags: u8,
+    /// Language code (typed by `block_language()`)
+    pub language: u8,
+}
+
+impl S7BlockEntry {
+    /// ### Language of the block
+    ///
+    pub fn block_language(&self) -> BlockLanguage {
+        BlockLanguage::from_code(self.language)
+    }
+}
+
+/// ### Header information of a block
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S7BlockInfo {
+    /// Block type (`BLOCK_OB`, `BLOCK_DB`..., typed by `block_kind()`)
+    pub block_type: u8,
+    pub number: u16,
+    /// Language code (typed by `block_language()`)
+    pub language: u8,
+    pub flags: u8,
+    /// Size in the load memory
+    pub load_size: u32,
+    /// Size of the MC7 code, for a DB the size of its data
+    pub mc7_size: u16,
+    /// Local data (temp) size
+    pub local_data: u16,
+    /// Segment table (SBB) size
+    pub sbb_length: u16,
+    pub checksum: u16,
+    pub version: u8,
+    /// Last modification of the code (of the data for a DB)
+    pub code_time: SystemTime,
+    /// Last modification of the interface
+    pub interface_time: SystemTime,
+    pub author: String,
+    pub family: String,
+    /// Name of the block (header field "Name")
+    pub header: String,
+}
+
+impl S7BlockInfo {
+    /// ### Type of the block, `None` for an unknown code
+    ///
+    pub fn block_kind(&self) -> Option<BlockType> {
+        BlockType::from_code(self.block_type)
+    }
+
+    /// ### True if the block is write-protected in the CPU (flag `BLOCK_FLAG_WRITE_PROTECTED`)
+    ///
+    pub fn write_protected(&self) -> bool {
+        self.flags & BLOCK_FLAG_WRITE_PROTECTED != 0
+    }
+
+    /// ### Language of the block
+    ///
+    pub fn block_language(&self) -> BlockLanguage {
+        BlockLanguage::from_code(self.language)
+    }
+}
+
+/// ### Header of an MC7 block
+///
+/// Decoded by `parse_mc7_header()` from a block in the upload format (the layout of the load
+/// memory): 36 byte header, MC7 code, interface and segment tables, 36 byte footer.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mc7Header {
+    /// Block type (`BLOCK_OB`, `BLOCK_DB`..., typed by `block_kind()`)
+    pub block_type: u8,
+    pub number: u16,
+    /// Language code (typed by `block_language()`)
+    pub language: u8,
+    pub flags: u8,
+    /// MC7 version
+    pub mc7_version: u8,
+    /// Size in the load memory
+    pub load_size: u32,
+    /// Know-how protection active
+    pub know_how_protected: bool,
+    /// Last modification of the code (of the data for a DB)
+    pub code_time: SystemTime,
+    /// Last modification of the interface
+    pub interface_time: SystemTime,
+    /// Segment table (SBB) size
+    pub sbb_length: u16,
+    /// Additional data size
+    pub add_length: u16,
+    /// Local data (temp) size
+    pub local_data: u16,
+    /// Size of the MC7 code, for a DB the size of its data
+    pub mc7_size: u16,
+    pub author: String,
+    pub family: String,
+    /// Name of the block (header field "Name")
+    pub header: String,
+    /// Block version (high nibble major, low nibble minor)
+    pub version: u8,
+    pub checksum: u16,
+}
+
+impl Mc7Header {
+    /// ### Type of the block, `None` for an unknown code
+    ///
+    pub fn block_kind(&self) -> Option<BlockType> {
+        BlockType::from_code(self.block_type)
+    }
+
+    /// ### Language of the block
+    ///
+    pub fn block_language(&self) -> BlockLanguage {
+        BlockLanguage::from_code(self.language)
+    }
+
+    /// ### Returns the MC7 code (the initial data for a DB) of the block the header was parsed from
+    ///
+    pub fn code<'a>(&self, block: &'a [u8]) -> Option<&'a [u8]> {
+        block.get(MC7_HEADER_LEN..MC7_HEADER_LEN + self.mc7_size as usize)
+    }
+}
+
+/// ### DB found by `browse_dbs()`
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S7DbEntry {
+    pub db_number: u16,
+    /// Size of the data, in byte
+    pub size: usize,
+    /// Last modification
+    pub timestamp: SystemTime,
+}
+
+/// Decodes an S7 block timestamp: ms since midnight (4 byte) + days since 1984-01-01 (2 byte)
+fn block_timestamp(raw: &[u8]) -> SystemTime {
+    let ms = u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]) as u64;
+    let days = u16::from_be_bytes([raw[4], raw[5]]) as u64;
+    UNIX_EPOCH + Duration::from_secs(S7_EPOCH_UNIX_SECS + days * 86_400) + Duration::from_millis(ms)
+}
+
+/// Maps the block type of the MC7 header to the `BLOCK_XXX` code
+fn mc7_block_type(code: u8) -> Option<u8> {
+    match code {
+        0x08 => Some(BLOCK_OB),
+        0x0A => Some(BLOCK_DB),
+        0x0B => Some(BLOCK_SDB),
+        0x0C => Some(BLOCK_FC),
+        0x0D => Some(BLOCK_SFC),
+        0x0E => Some(BLOCK_FB),
+        0x0F => Some(BLOCK_SFB),
+        _ => None,
+    }
+}
+
+fn block_text(raw: &[u8]) -> String {
+    String::from_utf8_lossy(raw).trim_end_matches(['\0', ' ']).to_string()
+}
+
+/// ### Decodes the header and the footer of an MC7 block
+///
+/// ```text
+/// Header (36 byte)                          Footer (last 36 byte)
+/// [0]  0x70 (block in load memory)          [0]  Author (8)
+/// [1]  Flags                                [8]  Family (8)
+/// [2]  MC7 version                          [16] Name (8)
+/// [4]  Language                             [24] Version
+/// [5]  Block type (0x08 OB, 0x0A DB...)     [26] Checksum (2)
+/// [6]  Number (2)
+/// [8]  Load memory length (4)
+/// [12] Security (4, 3 = know-how protected)
+/// [16] Code timestamp (6)
+/// [22] Interface timestamp (6)
+/// [28] SBB length, [30] Add length, [32] Local data, [34] MC7 length
+/// ```
+///
+/// ### Parameters
+/// - `block`: The whole block as uploaded from the CPU (or read from a file).
+///
+/// ### Returns
+/// `Ok(<Mc7Header>)` or `Err(<S7Error>)`
+///
+/// ### Errors
+/// - `S7Error::Other`: Not an MC7 block, unknown block type, or shorter than the lengths of its header.
+///
+pub fn parse_mc7_header(block: &[u8]) -> Result<Mc7Header, S7Error> {
+    if block.len() < MC7_HEADER_LEN + MC7_FOOTER_LEN || block[0] != MC7_BLOCK_ID {
+        return Err(S7Error::Other("mc7: not an MC7 block".to_string()));
+    }
+    let block_type = mc7_block_type(block[5])
+        .ok_or_else(|| S7Error::Other(format!("mc7: unknown block type 0x{:02X}", block[5])))?;
+    let u16_at = |offset: usize| u16::from_be_bytes([block[offset], block[offset + 1]]);
+    let mc7_size = u16_at(34);
+    if block.len() < MC7_HEADER_LEN + mc7_size as usize + MC7_FOOTER_LEN {
+        return Err(S7Error::Other(format!("mc7: {} byte, the code alone is {} byte", block.len(), mc7_size)));
+    }
+    let footer = &block[block.len() - MC7_FOOTER_LEN..];
+    Ok(Mc7Header {
+        block_type,
+        number: u16_at(6),
+        language: block[4],
+        flags: block[1],
+        mc7_version: block[2],
+        load_size: u32::from_be_bytes([block[8], block[9], block[10], block[11]]),
+        know_how_protected: u32::from_be_bytes([block[12], block[13], block[14], block[15]]) == MC7_KNOW_HOW_PROTECTED,
+        code_time: block_timestamp(&block[16..22]),
+        interface_time: block_timestamp(&block[22..28]),
+        sbb_length: u16_at(28),
+        add_length: u16_at(30),
+        local_data: u16_at(32),
+        mc7_size,
+        author: block_text(&footer[0..8]),
+        family: block_text(&footer[8..16]),
+        header: block_text(&footer[16..24]),
+        version: footer[24],
+        checksum: u16::from_be_bytes([footer[26], footer[27]]),
+    })
+}
+
+/// Maps the return code of the data part
+fn check_return_code(data: &[u8]) -> Result<(), S7Error> {
+    if data.len() < DATA_HEADER_LEN {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    match data[0] {
+        RES_SUCCESS => Ok(()),
+        RES_NOT_FOUND => Err(S7Error::S7NotFound),
+        code => Err(S7Error::S7Unspecified(S7ErrorCodes::item(code))),
+    }
+}
+
+impl S7Client {
+    /// Sends a block function request and returns the parameters and the data of the response
+    fn block_function(&mut self, params: &S7UserDataParams, data: &[u8]) -> Result<(S7UserDataParams, Vec<u8>), S7Error> {
+        self.observe("userdata", data.len(), |client| {
+            let pdu = client.exchange(&userdata_telegram(PDU_REF, params, data))?;
+            let (_, res_params, res_data) = split_userdata(&pdu)?;
+            match res_params.error_code {
+                0 => {}
+                UD_ERR_BLOCK_NOT_FOUND => return Err(S7Error::S7NotFound),
+                code => return Err(S7Error::S7Unspecified(S7ErrorCodes::userdata(code))),
+            }
+            check_return_code(res_data)?;
+            Ok((res_params, res_data.to_vec()))
+        })
+    }
+
+    /// ### Lists the blocks of a type loaded in the CPU
+    ///
+    /// ### Parameters
+    /// - `block_type`: `BlockType` or `BLOCK_OB`, `BLOCK_DB`, `BLOCK_SDB`, `BLOCK_FC`, `BLOCK_SFC`, `BLOCK_FB`, `BLOCK_SFB`.
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<S7BlockEntry>>)` (empty if there are no blocks of the type) or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::IsoInvalidTelegram`: Inconsistent response.
+    /// - `S7Error::S7Unspecified`: The CPU refused the request.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    /// ### Notes
+    /// Long lists span more PDUs: the following parts are requested until the CPU marks the last one.
+    ///
+    pub fn list_blocks_of_type(&mut self, block_type: impl Into<u8>) -> Result<Vec<S7BlockEntry>, S7Error> {
+        let block_type = block_type.into();
+        let params = S7UserDataParams::request(UD_GROUP_BLOCK, UD_SUBFN_LIST_BLOCKS_OF_TYPE);
+        let request = [RES_SUCCESS, TS_OCTET_STRING, 0x00, 0x02, 0x30, block_type];
+        let mut result = self.block_function(&params, &request);
+
+        let mut items = Vec::new();
+        for _ in 0..MAX_LIST_PARTS {
+            let (res_params, data) = match result {
+                Ok(response) => response,
+                // No blocks of this type
+                Err(S7Error::S7NotFound) => return Ok(items),
+                Err(error) => return Err(error),
+            };
+            let length = (u16::from_be_bytes([data[2], data[3]]) as usize).min(data.len() - DATA_HEADER_LEN);
+            for item in data[DATA_HEADER_LEN..DATA_HEADER_LEN + length].chunks_exact(LIST_ITEM_LEN) {
+                items.push(S7BlockEntry {
+                    number: u16::from_be_bytes([item[0], item[1]]),
+                    flags: item[2],
+                    language: item[3],
+                });
+            }
+            if !res_params.more_data() {
+                return Ok(items);
+            }
+
+            // Follow-up request of the next part
+            let follow_up = S7UserDataParams {
+                method: UD_METHOD_RESPONSE,
+                type_group: UD_TYPE_REQUEST | UD_GROUP_BLOCK,
+                subfunction: UD_SUBFN_LIST_BLOCKS_OF_TYPE,
+                sequence: res_params.sequence,
+                data_unit_ref: 0,
+                last_data_unit: 0,
+                error_code: 0,
+                extended: true,
+            };
+            result = self.block_function(&follow_up, &[RES_NOT_FOUND, 0x00, 0x00, 0x00]);
+        }
+        Err(S7Error::IsoInvalidTelegram)
+    }
+
+    /// ### Returns the header information of a block
+    ///
+    /// ### Parameters
+    /// - `block_type`: `BlockType` or `BLOCK_OB`, `BLOCK_DB`... (see `list_blocks_of_type()`).
+    /// - `number`: Block number (0..=65535, sent as 5 ASCII digits).
+    ///
+    /// ### Returns
+    /// `Ok(<S7BlockInfo>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::S7NotFound`: The block doesn't exist.
+    /// - `S7Error::IsoInvalidTelegram`: Inconsistent response.
+    /// - `S7Error::S7Unspecified`: The CPU refused the request.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    pub fn get_block_info(&mut self, block_type: impl Into<u8>, number: u16) -> Result<S7BlockInfo, S7Error> {
+        let block_type = block_type.into();
+        let params = S7UserDataParams::request(UD_GROUP_BLOCK, UD_SUBFN_BLOCK_INFO);
+        let mut request = vec![RES_SUCCESS, TS_OCTET_STRING, 0x00, 0x08, 0x30, block_type];
+        request.extend_from_slice(format!("{:05}", number).as_bytes());
+        request.push(b'A'); // Destination filesystem: active (online) blocks
+
+        let (_, data) = self.block_function(&params, &request)?;
+        let info = data.get(DATA_HEADER_LEN..DATA_HEADER_LEN + BLOCK_INFO_LEN).ok_or(S7Error::IsoInvalidTelegram)?;
+
+        let u16_at = |offset: usize| u16::from_be_bytes([info[offset], info[offset + 1]]);
+        Ok(S7BlockInfo {
+            block_type: info[11],
+            number: u16_at(12),
+            language: info[10],
+            flags: info[9],
+            load_size: u32::from_be_bytes([info[14], info[15], info[16], info[17]]),
+            mc7_size: u16_at(40),
+            local_data: u16_at(38),
+            sbb_length: u16_at(34),
+            checksum: u16_at(68),
+            version: info[66],
+            code_time: block_timestamp(&info[22..28]),
+            interface_time: block_timestamp(&info[28..34]),
+            author: block_text(&info[42..50]),
+            family: block_text(&info[50..58]),
+            header: block_text(&info[58..66]),
+        })
+    }
+
+    /// ### Lists the DBs of the CPU with their size and modification time
+    ///
+    /// Combines `list_blocks_of_type(BLOCK_DB)` and `get_block_info()` for each DB.
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<S7DbEntry>>)`, sorted by DB number, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `list_blocks_of_type()` and `get_block_info()`
+    ///
+    /// ### Notes
+    /// A DB deleted between the list and its info request is skipped. With many DBs this takes one
+    /// request per DB: call it on demand (e.g. when the user opens the selection), not cyclically.
+    ///
+    pub fn browse_dbs(&mut self) -> Result<Vec<S7DbEntry>, S7Error> {
+        let mut blocks = self.list_blocks_of_type(BLOCK_DB)?;
+        blocks.sort_by_key(|block| block.number);
+
+        let mut dbs = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            match self.get_block_info(BLOCK_DB, block.number) {
+                Ok(info) => dbs.push(S7DbEntry {
+                    db_number: block.number,
+                    size: info.mc7_size as usize,
+                    timestamp: info.code_time,
+                }),
+                Err(S7Error::S7NotFound) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(dbs)
+    }
+}
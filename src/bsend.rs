@@ -0,0 +1,252 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### BSEND/BRCV (programmed block communication)
+//!
+//! BSEND/BRCV transfer a block of up to 64 KB between two partners. The block is split into
+//! fragments that fit the negotiated PDU, each fragment is acknowledged by the receiver.
+//! Sender and receiver are paired by the R_ID configured in the SFB call of the CPU.
+//!
+//! ```text
+//! Fragment (Userdata, group 0x06, subfunction 0x01)
+//!   Parameters:  ... [Sequence] [ID Sequence] [Last data unit: 0x00 last, 0x01 more] [Error]
+//!   Data:        0xFF 0x09 [Length] 0x12 [Spec length] 0x13 0x00 [R_ID (4)] {[Total length (2)]} [Payload]
+//! ```
+//!
+//! The total length is present only in the first fragment (spec length 0x08 instead of 0x06).
+//!
+//! ### Notes
+//! The layout follows the one used by Snap7; it is verified between Rust7 client and `S7Partner`.
+
+use std::time::Duration;
+use crate::client::{S7Client, S7Error};
+use crate::frame::{
+    S7UserDataParams, split_userdata, userdata_telegram, UD_GROUP_BSEND, UD_SUBFN_BSEND,
+    UD_METHOD_RESPONSE, UD_TYPE_RESPONSE,
+};
+
+/// Max size of a block
+pub const BSEND_MAX_SIZE: usize = 65535;
+
+const RES_SUCCESS: u8 = 0xFF;
+const RES_NO_DATA: u8 = 0x0A;
+const TS_OCTET_STRING: u8 = 0x09;
+const SPEC_ID: u8 = 0x12;
+const SYNTAX_ID_PBC: u8 = 0x13;
+const SPEC_LEN: u8 = 0x06;       // Syntax ID + Reserved + R_ID
+const SPEC_LEN_FIRST: u8 = 0x08; // ... + Total length
+// S7 Header + Parameters + Data header + Spec (first fragment)
+const FRAGMENT_OVERHEAD: usize = 10 + 12 + 4 + 2 + SPEC_LEN_FIRST as usize;
+
+/// ### Fragment of a BSEND block
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlockFragment {
+    pub r_id: u32,
+    /// Present only in the first fragment
+    pub total_len: Option<u16>,
+    pub payload: Vec<u8>,
+}
+
+impl BlockFragment {
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        let spec_len = if self.total_len.is_some() { SPEC_LEN_FIRST } else { SPEC_LEN };
+        let length = 2 + spec_len as usize + self.payload.len();
+        buffer.push(RES_SUCCESS);
+        buffer.push(TS_OCTET_STRING);
+        buffer.extend_from_slice(&(length as u16).to_be_bytes());
+        buffer.extend_from_slice(&[SPEC_ID, spec_len, SYNTAX_ID_PBC, 0x00]);
+        buffer.extend_from_slice(&self.r_id.to_be_bytes());
+        if let Some(total_len) = self.total_len {
+            buffer.extend_from_slice(&total_len.to_be_bytes());
+        }
+        buffer.extend_from_slice(&self.payload);
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Short buffer or wrong spec.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < 12 || buffer[4] != SPEC_ID || buffer[6] != SYNTAX_ID_PBC {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        let r_id = u32::from_be_bytes([buffer[8], buffer[9], buffer[10], buffer[11]]);
+        let (total_len, payload_offset) = match buffer[5] {
+            SPEC_LEN => (None, 12),
+            SPEC_LEN_FIRST if buffer.len() >= 14 => (Some(u16::from_be_bytes([buffer[12], buffer[13]])), 14),
+            _ => return Err(S7Error::IsoInvalidTelegram),
+        };
+        let length = u16::from_be_bytes([buffer[2], buffer[3]]) as usize;
+        let payload_end = (4 + length).min(buffer.len());
+        if payload_end < payload_offset {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        Ok(BlockFragment {
+            r_id,
+            total_len,
+            payload: buffer[payload_offset..payload_end].to_vec(),
+        })
+    }
+}
+
+/// ### Parameters of the acknowledge of a fragment
+///
+pub(crate) fn fragment_ack(request: &S7UserDataParams, id_sequence: u8) -> S7UserDataParams {
+    S7UserDataParams {
+        method: UD_METHOD_RESPONSE,
+        type_group: UD_TYPE_RESPONSE | UD_GROUP_BSEND,
+        subfunction: UD_SUBFN_BSEND,
+        sequence: request.sequence,
+        data_unit_ref: id_sequence,
+        last_data_unit: 0x00,
+        error_code: 0x0000,
+        extended: true,
+    }
+}
+
+/// ### Data of the acknowledge of a fragment
+///
+pub(crate) const FRAGMENT_ACK_DATA: [u8; 4] = [RES_NO_DATA, 0x00, 0x00, 0x00];
+
+/// ### Reassembles the fragments of a block
+///
+#[derive(Debug, Default)]
+pub(crate) struct BlockReceiver {
+    r_id: u32,
+    data: Vec<u8>,
+    expected: usize,
+}
+
+impl BlockReceiver {
+    /// ### Adds a fragment
+    ///
+    /// ### Returns
+    /// `Ok(Some(<block>))` when the last fragment is received, `Ok(None)` if more data follows.
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Fragment out of sequence or inconsistent total length.
+    ///
+    pub fn push(&mut self, fragment: BlockFragment, last: bool) -> Result<Option<(u32, Vec<u8>)>, S7Error> {
+        match fragment.total_len {
+            Some(total_len) => {
+                self.r_id = fragment.r_id;
+                self.expected = total_len as usize;
+                self.data.clear();
+            }
+            None if fragment.r_id != self.r_id || self.expected == 0 => {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+            None => {}
+        }
+        self.data.extend_from_slice(&fragment.payload);
+
+        if !last {
+            return Ok(None);
+        }
+        let complete = self.data.len() == self.expected;
+        let block = std::mem::take(&mut self.data);
+        self.expected = 0;
+        if !complete {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        Ok(Some((self.r_id, block)))
+    }
+}
+
+impl S7Client {
+    /// ### Sends a block to the BRCV of the partner CPU
+    ///
+    /// ### Parameters
+    /// - `r_id`: R_ID of the BRCV instance in the CPU.
+    /// - `data`: Block to send (max `BSEND_MAX_SIZE` bytes).
+    ///
+    /// ### Returns
+    /// `Ok(())` once every fragment has been acknowledged.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Empty or too large block.
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::S7Unspecified`: The partner refused a fragment (e.g. no BRCV with that R_ID).
+    /// - `S7Error::IsoInvalidTelegram`: Inconsistent acknowledge.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    pub fn bsend(&mut self, r_id: u32, data: &[u8]) -> Result<(), S7Error> {
+        if data.is_empty() || data.len() > BSEND_MAX_SIZE {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+
+        let fragment_size = (self.pdu_length as usize).saturating_sub(FRAGMENT_OVERHEAD).max(1);
+        let mut params = S7UserDataParams::request(UD_GROUP_BSEND, UD_SUBFN_BSEND);
+        params.extended = true;
+
+        let mut chunks = data.chunks(fragment_size).peekable();
+        let mut first = true;
+        while let Some(chunk) = chunks.next() {
+            params.last_data_unit = if chunks.peek().is_some() { 0x01 } else { 0x00 };
+
+            let mut fragment = Vec::new();
+            BlockFragment {
+                r_id,
+                total_len: if first { Some(data.len() as u16) } else { None },
+                payload: chunk.to_vec(),
+            }.encode(&mut fragment);
+
+            let (ack, _) = self.userdata(&params, &fragment)?;
+            if ack.group() != UD_GROUP_BSEND {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+
+            // The receiver assigns the ID sequence with the first acknowledge
+            params.data_unit_ref = ack.data_unit_ref;
+            params.sequence = params.sequence.wrapping_add(1);
+            first = false;
+        }
+        Ok(())
+    }
+
+    /// ### Waits for a block sent by the BSEND of the partner CPU
+    ///
+    /// ### Parameters
+    /// - `r_id`: R_ID of the BSEND instance in the CPU.
+    /// - `timeout`: Max time to wait for each fragment.
+    ///
+    /// ### Returns
+    /// `Ok(<block>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::IsoInvalidTelegram`: Unexpected telegram or inconsistent fragments.
+    /// - `S7Error::Io`: network I/O error (also on timeout).
+    ///
+    /// ### Notes
+    /// Telegrams of other R_IDs are acknowledged and discarded.
+    ///
+    pub fn brecv(&mut self, r_id: u32, timeout: Duration) -> Result<Vec<u8>, S7Error> {
+        self.set_read_timeout_once(timeout)?;
+
+        let mut receiver = BlockReceiver::default();
+        let id_sequence = 0x01;
+        loop {
+            let pdu = self.recv_pdu()?;
+            let (header, params, data) = split_userdata(&pdu)?;
+            if params.group() != UD_GROUP_BSEND || params.subfunction != UD_SUBFN_BSEND {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+            let fragment = BlockFragment::decode(data)?;
+            let fragment_r_id = fragment.r_id;
+            let block = receiver.push(fragment, !params.more_data())?;
+
+            let ack = fragment_ack(&params, id_sequence);
+            self.send_telegram(&userdata_telegram(header.pdu_ref, &ack, &FRAGMENT_ACK_DATA))?;
+
+            if let Some((_, block)) = block {
+                if fragment_r_id == r_id {
+                    return Ok(block);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Read cache
+//!
+//! With `S7Client::set_read_cache()` the reads are served from memory if the same address (or a
+//! block containing it) was read within the TTL, so many independent components requesting the
+//! same status word cost a single telegram per TTL. The writes done through the client invalidate
+//! the cached bytes they overlap; changes made by the PLC program are seen only when the TTL expires
+//! or after an explicit invalidation.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::S7Client;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! client.set_read_cache(Some(Duration::from_millis(100)));
+//!
+//! let mut status = [0u8; 2];
+//! for _ in 0..1000 {
+//!     client.read_db(10, 0, &mut status).unwrap(); // One telegram each 100 ms
+//! }
+//! println!("{:?}", client.read_cache_stats());
+//! ```
+
+use std::time::{Duration, Instant};
+use crate::client::{S7Client, S7_AREA_DB, S7_WL_BIT};
+
+/// Max cached blocks, the oldest are evicted first
+const MAX_ENTRIES: usize = 256;
+
+/// ### Counters of the read cache
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadCacheStats {
+    /// Reads served from memory
+    pub hits: u64,
+    /// Reads sent to the PLC
+    pub misses: u64,
+    /// Blocks currently cached (expired ones included until purged)
+    pub entries: usize,
+}
+
+struct CacheEntry {
+    area: u8,
+    db_number: u16,
+    wordlen: u8,
+    /// Byte index, bit index for bit entries
+    start: u32,
+    data: Vec<u8>,
+    time: Instant,
+}
+
+impl CacheEntry {
+    /// Bytes covered by the entry
+    fn byte_range(&self) -> (u32, u32) {
+        if self.wordlen == S7_WL_BIT {
+            (self.start / 8, self.start / 8 + 1)
+        } else {
+            (self.start, self.start + self.data.len() as u32)
+        }
+    }
+}
+
+fn db_of(area: u8, db_number: u16) -> u16 {
+    if area == S7_AREA_DB { db_number } else { 0 }
+}
+
+/// Cache of the blocks read by `read_area()`
+pub(crate) struct ReadCache {
+    ttl: Duration,
+    entries: Vec<CacheEntry>,
+    stats: ReadCacheStats,
+}
+
+impl ReadCache {
+    fn new(ttl: Duration) -> Self {
+        ReadCache { ttl, entries: Vec::new(), stats: ReadCacheStats::default() }
+    }
+
+    /// Time to live of the cached blocks
+    pub(crate) fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns `size` elements from a fresh entry, `None` on a miss
+    pub(crate) fn lookup(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, size: usize) -> Option<Vec<u8>> {
+        let db_number = db_of(area, db_number);
+        let ttl = self.ttl;
+        let found = self.entries.iter().rev()
+            .filter(|entry| entry.area == area && entry.db_number == db_number && entry.time.elapsed() < ttl)
+            .find_map(|entry| {
+                match (wordlen == S7_WL_BIT, entry.wordlen == S7_WL_BIT) {
+                    // Bit from a bit entry
+                    (true, true) if entry.start == start => Some(vec![entry.data[0]]),
+                    // Bit from a byte entry
+                    (true, false) => {
+                        let (first, end) = entry.byte_range();
+                        (first..end).contains(&(start / 8))
+                            .then(|| vec![(entry.data[(start / 8 - first) as usize] >> (start % 8)) & 0x01])
+                    }
+                    // Bytes from a byte entry
+                    (false, false) => {
+                        let (first, end) = entry.byte_range();
+                        (start >= first && start + size as u32 <= end)
+                            .then(|| entry.data[(start - first) as usize..(start - first) as usize + size].to_vec())
+                    }
+                    _ => None,
+                }
+            });
+        match found {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        found
+    }
+
+    /// Stores the result of a read
+    pub(crate) fn insert(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, data: &[u8]) {
+        let entry = CacheEntry {
+            area,
+            db_number: db_of(area, db_number),
+            wordlen,
+            start,
+            data: data.to_vec(),
+            time: Instant::now(),
+        };
+        let (first, end) = entry.byte_range();
+        let ttl = self.ttl;
+        // Expired entries and the ones superseded by the new block are dropped
+        self.entries.retain(|old| {
+            let (old_first, old_end) = old.byte_range();
+            let superseded = old.area == entry.area && old.db_number == entry.db_number && if entry.wordlen == S7_WL_BIT {
+                old.wordlen == S7_WL_BIT && old.start == entry.start
+            } else {
+                old_first >= first && old_end <= end
+            };
+            old.time.elapsed() < ttl && !superseded
+        });
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Drops the entries overlapping a block of bytes
+    pub(crate) fn invalidate(&mut self, area: u8, db_number: u16, first: u32, end: u32) {
+        let db_number = db_of(area, db_number);
+        self.entries.retain(|entry| {
+            let (entry_first, entry_end) = entry.byte_range();
+            entry.area != area || entry.db_number != db_number || entry_end <= first || entry_first >= end
+        });
+    }
+
+    /// Drops the entries overlapping a write of `size` elements
+    pub(crate) fn invalidate_write(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, size: usize) {
+        let (first, end) = if wordlen == S7_WL_BIT {
+            (start / 8, start / 8 + 1)
+        } else {
+            (start, start + size as u32)
+        };
+        self.invalidate(area, db_number, first, end);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl S7Client {
+    /// ### Enables or disables the read cache
+    ///
+    /// ### Parameters
+    /// - `ttl`: Max age of the cached data, `None` disables the cache (Default).
+    ///
+    /// ### Notes
+    /// - `read_area()` and the methods based on it (`read_db()`, `read_bit()`, `read_value()`...)
+    ///   use the cache; `read_bits()` and `read_multi_vars()` always read from the PLC.
+    /// - A read is served if a block read within the TTL contains it; a cache hit doesn't update
+    ///   `last_time` and `chunks` (both 0).
+    /// - The writes done through this client (`write_area()`, `write_multi_vars()` and the methods
+    ///   based on them) invalidate the cached bytes they overlap. The cache is emptied on disconnect.
+    /// - Changing the TTL empties the cache and resets its counters.
+    ///
+    pub fn set_read_cache(&mut self, ttl: Option<Duration>) {
+        self.read_cache = ttl.map(ReadCache::new);
+    }
+
+    /// ### Empties the read cache
+    ///
+    pub fn invalidate_read_cache(&mut self) {
+        if let Some(cache) = self.read_cache.as_mut() {
+            cache.clear();
+        }
+    }
+
+    /// ### Removes a block of bytes from the read cache
+    ///
+    /// Use it when the application knows that the PLC changed the block (e.g. after a command
+    /// that the PLC acknowledges by updating it).
+    ///
+    /// ### Parameters
+    /// - `area`, `db_number`: Memory area and DB number (ignored for non-DB areas).
+    /// - `start`: First byte.
+    /// - `size`: Number of bytes.
+    ///
+    pub fn invalidate_read_cache_area(&mut self, area: u8, db_number: u16, start: u32, size: usize) {
+        if let Some(cache) = self.read_cache.as_mut() {
+            cache.invalidate(area, db_number, start, start + size as u32);
+        }
+    }
+
+    /// ### Returns the counters of the read cache
+    ///
+    /// `None` if the cache is disabled.
+    ///
+    pub fn read_cache_stats(&self) -> Option<ReadCacheStats> {
+        self.read_cache.as_ref().map(|cache| ReadCacheStats { entries: cache.entries.len(), ..cache.stats })
+    }
+}
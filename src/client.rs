@@ -1,13 +1,37 @@
 // Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
 // Copyright 2025 - Davide Nardella
 
-use std::net::{TcpStream, ToSocketAddrs};
+use std::net::{Ipv4Addr, TcpStream, ToSocketAddrs};
 use std::net::Shutdown;
 use std::time::Duration;
 use std::fmt;
 use std::io;
 use std::io::{Read, Write};
+use std::mem::MaybeUninit;
 use std::time::Instant;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use socket2::{Domain, Protocol, Socket, SockRef, Type};
+use crate::value::{S7Value, S7DataType};
+use crate::szl::PlcStatus;
+use crate::blocks::BlockType;
+use crate::transport::{Transport, TransportWrapper};
+use crate::iso_tcp::{self, CotpRequestHook, IsoConnectParams};
+use crate::socks::{socks5_connect, ProxyAuth, Socks5Proxy};
+use crate::stats::{TrafficStats, TransferReport, FrameCounters, CountingTransport};
+use crate::cache::ReadCache;
+use crate::retry::RetryPolicy;
+use crate::observer::{S7Observer, WriteRequest};
+use crate::alarms::{is_pushed, AlarmListener};
+use crate::audit::{AuditHook, PendingAudit};
+use crate::authorize::WritePolicy;
+use crate::frame::{
+    TpktHeader, CotpData, S7Header, S7SetupComm, S7ItemSpec, DataItemHeader,
+    S7UserDataParams, userdata_telegram, split_userdata,
+    TPKT_LEN, TPKT_ISO_LEN, COTP_CC, S7_ID, S7_ACK_HEADER_LEN, ITEM_SPEC_LEN, DATA_ITEM_HEADER_LEN,
+    FN_SETUP_COMM, FN_READ_VAR, FN_WRITE_VAR, ParseDiagnostic, StrictParser,
+};
 
 
 // Connection types
@@ -28,26 +52,26 @@ pub const S7_WL_BYTE: u8 = 0x02;
 // Transport
 const TS_RES_BIT: u8 = 0x03;
 const TS_RES_BYTE: u8 = 0x04;
+const TS_RES_INT: u8 = 0x05;
 
 // PDU related
-const TPKT_ISO_LEN: usize   = 7; // ISO Header length
 const PDU_LEN_REQ: u16      = 480; // PDU Length requested for negotiation
-const ISO_CR_LEN: usize     = 22;   // Connection request telegram size 
-const ISO_CONN_REQ: u8      = 0xE0; // ISO connection requesr
-const ISO_CONN_OK: u8       = 0xD0; // ISO connection accepted
+const DRAIN_QUIET_MS: u64   = 100; // Quiet time that ends a socket drain
+const PDU_LEN_FALLBACK: [u16; 2] = [PDU_LEN_REQ, 240]; // PDU Lengths tried in sequence if the CPU rejects the negotiation
+const PDU_REF: u16          = 0x0500; // First PDU Reference, then incremented at each request
+const MAX_AREA_BYTES: usize = 1 << 21; // Bytes addressable by a Read/Write item (24 bit address)
+const ROUTING_TSAP_LEN: usize = 28; // Routing TSAP size (see S7Routing)
 const ISO_PN_REQ_LEN: usize = 25;   // PDU negotiation request telegram size 
 const ISO_PN_RES_LEN: usize = 27;   // PDU negotiation response telegram size 
-const ISO_ID: u8            = 0x03; // RFC 1006 ID
-const S7_ID: u8             = 0x32; // S7 Protocol ID
 
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1); // Identity of the next connection (see S7Client::connection_id)
 
 const READ_REQ_LEN: usize   = 31; // TKPT + ISO + S7 headers
+const WRITE_REQ_LEN: usize  = 35; // TKPT + ISO + S7 headers + Data item header
+const RW_PARAMS_LEN: u16    = 2 + ITEM_SPEC_LEN as u16; // Function + Items count + Item
 const READ_RES_LEN: usize   = 18; // Read job response header length
 const WRITE_RES_LEN: usize  = 15; // Write job response header length
 
-const EOT: u8               = 0x80; // ISO End of Trasmission
-const RW_RES_OFFSET: usize  = 14;
-
 /// Operation successful
 const RES_SUCCESS: u8         = 0xFF; 
 /// Invalid Address requested
@@ -57,24 +81,55 @@ const RES_INVALID_ADDRESS: u8 = 0x05;
 /// Resource not found
 /// - The DB doesn't exists in the CPU
 const RES_NOT_FOUND: u8       = 0x0A; 
-
-// Macros
-macro_rules! hi_part {
-    ($x:expr) => {
-        (($x >> 8) & 0xFF) as u8
-    };
+/// Access to the object not allowed
+/// - The DB is write-protected or the area is not accessible
+const RES_ACCESS_DENIED: u8   = 0x03;
+/// Data type not supported (transport size refused)
+const RES_DATA_TYPE: u8       = 0x06;
+/// Data type inconsistent: the data length doesn't match the request
+const RES_DATA_SIZE: u8       = 0x07;
+
+/// Error class/code of an Ack: the service cannot be executed in the current state of the CPU
+const ERR_OBJECT_STATE: (u8, u8) = (0x84, 0x02);
+
+/// ### Raw codes of an error reported by the PLC
+///
+/// The fields not carried by the failed telegram are 0.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct S7ErrorCodes {
+    /// Return code of the data item (e.g. 0x0A object not found)
+    pub return_code: u8,
+    /// Error class of the Ack header (e.g. 0x84 protocol/state error)
+    pub error_class: u8,
+    /// Error code of the Ack header, or of the Userdata parameters (e.g. 0xD209)
+    pub error_code: u16,
 }
 
-macro_rules! lo_part {
-    ($x:expr) => {
-        ($x & 0xFF) as u8
-    };
+impl S7ErrorCodes {
+    /// ### Codes of a data item return code
+    ///
+    pub fn item(return_code: u8) -> Self {
+        S7ErrorCodes { return_code, ..Default::default() }
+    }
+
+    /// ### Codes of the error class/code of an Ack header
+    ///
+    pub fn header(error_class: u8, error_code: u8) -> Self {
+        S7ErrorCodes { error_class, error_code: error_code as u16, ..Default::default() }
+    }
+
+    /// ### Codes of the error code of the Userdata parameters
+    ///
+    pub fn userdata(error_code: u16) -> Self {
+        S7ErrorCodes { error_code, ..Default::default() }
+    }
 }
 
-macro_rules! make_u16 {
-    ($hi:expr, $lo:expr) => {
-        ((($hi as u16) << 8) | ($lo as u16))
-    };
+impl fmt::Display for S7ErrorCodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "return code 0x{:02X}, error class 0x{:02X}, error code 0x{:04X}", self.return_code, self.error_class, self.error_code)
+    }
 }
 
 #[derive(Debug)]
@@ -91,8 +146,101 @@ pub enum S7Error {
     InvalidFunParameter,
     S7NotFound,
     S7InvalidAddress,
-    S7Unspecified,
+    /// Error not mapped to a specific variant, with the codes received
+    S7Unspecified(S7ErrorCodes),
+    /// The CPU refused the job because of its operating mode (STOP or transition), with the
+    /// codes received
+    CpuStopped(S7ErrorCodes),
+    /// Access to the object not allowed (e.g. write-protected DB), with the reason found by the
+    /// protection check before the write (see `set_check_write_protection()`), `None` when the
+    /// CPU refused the access
+    AccessDenied(Option<String>),
+    /// The CPU doesn't support the data type (transport size) of the request
+    DataTypeMismatch,
+    /// The length of the data doesn't match the request
+    WrongDataSize,
+    /// The size of the buffer doesn't fit the request, detected before sending it: `size` byte
+    /// instead of `expected` (the maximum for the block transfers)
+    SizeMismatch { size: usize, expected: usize },
+    /// A received telegram failed the strict-parse validation (see `set_strict_parse()`)
+    MalformedTelegram(Box<ParseDiagnostic>),
+    /// The address is inside the DB but was refused: the DB likely has the optimized access, which
+    /// doesn't allow the absolute addressing (inferred, the block info doesn't report the
+    /// attribute, see `set_diagnose_address_errors()`)
+    OptimizedDb(u16),
+    /// The address exceeds the DB: `end` byte requested, the DB is `length` byte long
+    /// (see `set_diagnose_address_errors()`)
+    BeyondDbLength { db_number: u16, end: usize, length: usize },
+    /// Write refused by the write policy of the client, nothing was sent (see `set_write_policy()`)
+    WriteDenied { address: String, reason: String },
     Other(String),
+    /// An error with the operation and the address that caused it (see `set_error_context()`)
+    WithContext(Box<ErrorContext>),
+}
+
+/// `io::Error` isn't `Clone`: the copy of `Io` keeps the kind and the message
+impl Clone for S7Error {
+    fn clone(&self) -> Self {
+        match self {
+            S7Error::Io(e) => S7Error::Io(io::Error::new(e.kind(), e.to_string())),
+            S7Error::NotConnected => S7Error::NotConnected,
+            S7Error::TcpConnectionFailed => S7Error::TcpConnectionFailed,
+            S7Error::ConnectionClosed => S7Error::ConnectionClosed,
+            S7Error::IsoConnectionFailed => S7Error::IsoConnectionFailed,
+            S7Error::IsoFragmentedPacket => S7Error::IsoFragmentedPacket,
+            S7Error::IsoInvalidHeader => S7Error::IsoInvalidHeader,
+            S7Error::IsoInvalidTelegram => S7Error::IsoInvalidTelegram,
+            S7Error::PduNegotiationFailed => S7Error::PduNegotiationFailed,
+            S7Error::InvalidFunParameter => S7Error::InvalidFunParameter,
+            S7Error::S7NotFound => S7Error::S7NotFound,
+            S7Error::S7InvalidAddress => S7Error::S7InvalidAddress,
+            S7Error::S7Unspecified(codes) => S7Error::S7Unspecified(*codes),
+            S7Error::CpuStopped(codes) => S7Error::CpuStopped(*codes),
+            S7Error::AccessDenied(reason) => S7Error::AccessDenied(reason.clone()),
+            S7Error::DataTypeMismatch => S7Error::DataTypeMismatch,
+            S7Error::WrongDataSize => S7Error::WrongDataSize,
+            S7Error::SizeMismatch { size, expected } => S7Error::SizeMismatch { size: *size, expected: *expected },
+            S7Error::MalformedTelegram(diagnostic) => S7Error::MalformedTelegram(diagnostic.clone()),
+            S7Error::OptimizedDb(db_number) => S7Error::OptimizedDb(*db_number),
+            S7Error::BeyondDbLength { db_number, end, length } => S7Error::BeyondDbLength { db_number: *db_number, end: *end, length: *length },
+            S7Error::WriteDenied { address, reason } => S7Error::WriteDenied { address: address.clone(), reason: reason.clone() },
+            S7Error::Other(message) => S7Error::Other(message.clone()),
+            S7Error::WithContext(context) => S7Error::WithContext(context.clone()),
+        }
+    }
+}
+
+/// ### Operation and address of a failed Read/Write
+///
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    /// Method that failed (`read_area`, `write_area` or `read_bits`)
+    pub operation: &'static str,
+    pub area: u8,
+    /// DB number (0 for non-DB areas)
+    pub db_number: u16,
+    /// First element (byte index, bit index for bit access)
+    pub start: u32,
+    pub wordlen: u8,
+    /// Number of elements
+    pub size: usize,
+    /// The error
+    pub error: S7Error,
+}
+
+impl ErrorContext {
+    /// ### Returns the address in the STEP 7 notation (e.g. `DB10.DBB4`, `M2.3`)
+    ///
+    pub fn address(&self) -> String {
+        step7_address(self.area, self.db_number, self.start, self.wordlen)
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = if self.wordlen == S7_WL_BIT { "bit" } else { "byte" };
+        write!(f, "{} {} ({} {}): {}", self.operation, self.address(), self.size, unit, self.error)
+    }
 }
 
 impl fmt::Display for S7Error {
@@ -110,8 +258,137 @@ impl fmt::Display for S7Error {
             S7Error::InvalidFunParameter => write!(f, "Invalid parameter supplied to the function"),
             S7Error::S7NotFound => write!(f, "S7 Resource not found in the CPU"),
             S7Error::S7InvalidAddress => write!(f, "S7 Invalid address"),
-            S7Error::S7Unspecified => write!(f, "S7 unspecified error"),
+            S7Error::S7Unspecified(codes) => write!(f, "S7 unspecified error ({})", codes),
+            S7Error::CpuStopped(codes) => write!(f, "S7 CPU in STOP ({})", codes),
+            S7Error::AccessDenied(None) => write!(f, "S7 Access to the object denied"),
+            S7Error::AccessDenied(Some(reason)) => write!(f, "S7 Access to the object denied: {}", reason),
+            S7Error::DataTypeMismatch => write!(f, "S7 Data type not supported"),
+            S7Error::WrongDataSize => write!(f, "S7 Wrong data size"),
+            S7Error::SizeMismatch { size, expected } => write!(f, "Buffer size mismatch: {} byte, expected {}", size, expected),
+            S7Error::MalformedTelegram(diag) => write!(f, "Malformed telegram at {}", diag),
+            S7Error::OptimizedDb(db_number) => write!(f, "S7 Address inside DB{} refused: likely optimized access (absolute addressing not allowed)", db_number),
+            S7Error::BeyondDbLength { db_number, end, length } => write!(f, "S7 Address beyond DB{} length: up to byte {} requested, the DB is {} byte long", db_number, end, length),
+            S7Error::WriteDenied { address, reason } => write!(f, "Write to {} denied by the write policy: {}", address, reason),
             S7Error::Other(msg) => write!(f, "{}", msg),
+            S7Error::WithContext(context) => write!(f, "{}", context),
+        }
+    }
+}
+
+/// ### Category of an `S7Error`, to choose the recovery strategy
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum S7ErrorClass {
+    /// The connection is lost or can't be established: reconnect
+    Transport,
+    /// Timeout or malformed/desynchronized telegram: resync (done by the client) and retry
+    Protocol,
+    /// Refused by the PLC (wrong address, missing DB, CPU in STOP...): retrying won't help
+    Plc,
+    /// Invalid call (parameters, not connected...): fix the application
+    Client,
+}
+
+impl S7Error {
+    /// ### Returns the category of the error
+    ///
+    /// | Class | Errors |
+    /// |---|---|
+    /// | `Transport` | `Io` (but timeouts), `ConnectionClosed`, `TcpConnectionFailed`, `IsoConnectionFailed`, `PduNegotiationFailed` |
+    /// | `Protocol` | `Io` timeouts, `IsoFragmentedPacket`, `IsoInvalidHeader`, `IsoInvalidTelegram`, `MalformedTelegram` |
+    /// | `Plc` | `S7NotFound`, `S7InvalidAddress`, `AccessDenied`, `DataTypeMismatch`, `WrongDataSize`, `S7Unspecified`, `CpuStopped`, `OptimizedDb`, `BeyondDbLength` |
+    /// | `Client` | `NotConnected`, `InvalidFunParameter`, `SizeMismatch`, `WriteDenied`, `Other` |
+    ///
+    /// The class of `WithContext` is the one of the wrapped error.
+    ///
+    pub fn class(&self) -> S7ErrorClass {
+        match self.root() {
+            S7Error::Io(e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => S7ErrorClass::Protocol,
+            S7Error::Io(_)
+            | S7Error::ConnectionClosed
+            | S7Error::TcpConnectionFailed
+            | S7Error::IsoConnectionFailed
+            | S7Error::PduNegotiationFailed => S7ErrorClass::Transport,
+            S7Error::IsoFragmentedPacket
+            | S7Error::IsoInvalidHeader
+            | S7Error::IsoInvalidTelegram
+            | S7Error::MalformedTelegram(_) => S7ErrorClass::Protocol,
+            S7Error::S7NotFound
+            | S7Error::S7InvalidAddress
+            | S7Error::AccessDenied(_)
+            | S7Error::DataTypeMismatch
+            | S7Error::WrongDataSize
+            | S7Error::S7Unspecified(_)
+            | S7Error::CpuStopped(_)
+            | S7Error::OptimizedDb(_)
+            | S7Error::BeyondDbLength { .. } => S7ErrorClass::Plc,
+            S7Error::NotConnected
+            | S7Error::InvalidFunParameter
+            | S7Error::SizeMismatch { .. }
+            | S7Error::WriteDenied { .. }
+            | S7Error::Other(_) => S7ErrorClass::Client,
+            S7Error::WithContext(_) => unreachable!("root() unwraps the context"),
+        }
+    }
+
+    /// ### Returns true for the transport errors: the connection should be re-established
+    ///
+    pub fn is_transport(&self) -> bool {
+        self.class() == S7ErrorClass::Transport
+    }
+
+    /// ### Returns true for the protocol errors: the same connection can be retried after a resync
+    ///
+    pub fn is_protocol(&self) -> bool {
+        self.class() == S7ErrorClass::Protocol
+    }
+
+    /// ### Returns true for the errors reported by the PLC (address or configuration problem)
+    ///
+    pub fn is_plc_side(&self) -> bool {
+        self.class() == S7ErrorClass::Plc
+    }
+
+    /// ### Returns true if retrying the operation (after a reconnection for the transport errors) may succeed
+    ///
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self.class(), S7ErrorClass::Transport | S7ErrorClass::Protocol)
+    }
+
+    /// ### Returns the error without its context
+    ///
+    /// The wrapped error for `WithContext`, the error itself otherwise: match it to handle the
+    /// errors the same way with or without the context.
+    ///
+    pub fn root(&self) -> &S7Error {
+        match self {
+            S7Error::WithContext(context) => context.error.root(),
+            error => error,
+        }
+    }
+
+    /// ### Returns the operation and the address of the failed Read/Write, if attached
+    ///
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            S7Error::WithContext(context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// ### Returns the raw codes of an error reported by the PLC
+    ///
+    /// `None` for the errors detected by the client (I/O, ISO, parameters...).
+    ///
+    pub fn codes(&self) -> Option<S7ErrorCodes> {
+        match self.root() {
+            S7Error::S7NotFound => Some(S7ErrorCodes::item(RES_NOT_FOUND)),
+            S7Error::S7InvalidAddress | S7Error::OptimizedDb(_) | S7Error::BeyondDbLength { .. } => Some(S7ErrorCodes::item(RES_INVALID_ADDRESS)),
+            S7Error::AccessDenied(_) => Some(S7ErrorCodes::item(RES_ACCESS_DENIED)),
+            S7Error::DataTypeMismatch => Some(S7ErrorCodes::item(RES_DATA_TYPE)),
+            S7Error::WrongDataSize => Some(S7ErrorCodes::item(RES_DATA_SIZE)),
+            S7Error::S7Unspecified(codes) | S7Error::CpuStopped(codes) => Some(*codes),
+            _ => None,
         }
     }
 }
@@ -121,15 +398,247 @@ impl From<io::Error> for S7Error {
         S7Error::Io(err)
     }
 }
+/// ### Connection negotiation results
+///
+/// Filled by the connection methods with the values returned by the PLC: the COTP connection
+/// confirm and the S7 PDU negotiation response. Useful for diagnosing connection-resource
+/// problems on busy CPUs.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NegotiationInfo {
+    /// COTP reference of the client (echoed by the PLC)
+    pub cotp_local_ref: u16,
+    /// COTP reference assigned by the PLC
+    pub cotp_remote_ref: u16,
+    /// Negotiated COTP TPDU size (bytes)
+    pub tpdu_size: usize,
+    /// Negotiated S7 PDU length (bytes)
+    pub pdu_length: u16,
+    /// S7 PDU length requested in the accepted negotiation (less than 480 if the CPU rejected the larger sizes)
+    pub pdu_length_requested: u16,
+    /// Max parallel jobs the client may issue (AmQ calling)
+    pub max_amq_calling: u16,
+    /// Max parallel jobs the PLC accepts (AmQ called)
+    pub max_amq_called: u16,
+}
+
+/// ### Result of `health_check()`
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Round-trip time of the probe transaction
+    pub rtt: Duration,
+    /// Operating mode of the CPU
+    pub status: PlcStatus,
+}
+
+/// ### S7 routing parameters
+///
+/// Destination of a connection routed by a gateway station (CPU or CP with routing capability,
+/// e.g. CP 443-1, IE/PB Link) to a CPU that is reachable only through another subnet.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S7Routing {
+    /// S7 subnet ID of the destination subnet, as shown by NetPro/TIA: "0011 - 0005" = `(0x0011, 0x0005)`
+    pub subnet_id: (u16, u16),
+    /// Address of the destination CPU in its subnet: MPI/PROFIBUS address (1 byte) or IPv4 address (4 byte)
+    pub address: Vec<u8>,
+    /// Rack of the destination CPU
+    pub rack: u16,
+    /// Slot of the destination CPU
+    pub slot: u16,
+}
+
+impl S7Routing {
+    /// ### Destination CPU on an MPI/PROFIBUS subnet
+    ///
+    pub fn mpi(subnet_id: (u16, u16), address: u8, rack: u16, slot: u16) -> Self {
+        S7Routing { subnet_id, address: vec![address], rack, slot }
+    }
+
+    /// ### Destination CPU on an Industrial Ethernet subnet
+    ///
+    pub fn ip(subnet_id: (u16, u16), address: Ipv4Addr, rack: u16, slot: u16) -> Self {
+        S7Routing { subnet_id, address: address.octets().to_vec(), rack, slot }
+    }
+
+    /// ### Builds the routing TSAPs of the COTP Connection Request
+    ///
+    /// ```text
+    /// Local TSAP (28 byte)            Remote TSAP (28 byte)
+    /// [0]     0x01 (one block)        [0]      0x01
+    /// [1]     Subnet ID length (0)    [1]      Subnet ID length (6)
+    /// [2]     Address length (0)      [2]      Address length (1 or 4)
+    /// [3]     Fn/Rack/Slot length (2) [3]      Fn/Rack/Slot length (2)
+    /// [4..26] 0x00                    [4..10]  Subnet ID
+    ///                                 [10..14] Address (left aligned)
+    ///                                 [14..26] 0x00
+    /// [26]    Connection type         [26]     Connection type
+    /// [27]    Gateway Rack/Slot       [27]     Destination Rack/Slot
+    /// ```
+    pub(crate) fn tsaps(&self, conn_type: u16, rack: u16, slot: u16) -> Result<(Vec<u8>, Vec<u8>), S7Error> {
+        if (self.address.len() != 1 && self.address.len() != 4) || rack > 7 || slot > 31 || self.rack > 7 || self.slot > 31 {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let mut local = vec![0u8; ROUTING_TSAP_LEN];
+        local[..4].copy_from_slice(&[0x01, 0x00, 0x00, 0x02]);
+        local[26] = conn_type as u8;
+        local[27] = (rack * 0x20 + slot) as u8;
+
+        let mut remote = vec![0u8; ROUTING_TSAP_LEN];
+        remote[..4].copy_from_slice(&[0x01, 0x06, self.address.len() as u8, 0x02]);
+        remote[4..6].copy_from_slice(&self.subnet_id.0.to_be_bytes());
+        remote[8..10].copy_from_slice(&self.subnet_id.1.to_be_bytes());
+        remote[10..10 + self.address.len()].copy_from_slice(&self.address);
+        remote[26] = conn_type as u8;
+        remote[27] = (self.rack * 0x20 + self.slot) as u8;
+        Ok((local, remote))
+    }
+}
+
+/// ### Behavior of `Drop` on a connected client
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DropBehavior {
+    /// Same as `disconnect()`: COTP DR and graceful close as configured (Default)
+    #[default]
+    Disconnect,
+    /// Immediate shutdown of the socket, without COTP DR and without waiting for the PLC
+    Abort,
+    /// COTP DR, then waits at most the given time for the PLC to close its side
+    Graceful(Duration),
+}
+
+/// ### Settings of a client
+///
+/// Snapshot of the configuration of an `S7Client` taken by `S7Client::config()`: endpoint of the
+/// last connection, timeouts, socket and COTP options, chunking, pacing, read cache TTL and observer.
+/// `S7Client::from_config()` creates any number of identically configured clients from it, e.g. to
+/// spread a large transfer on parallel connections.
+///
+/// ### Notes
+/// The boxed hooks (retry policy, transport wrapper, COTP request hook, audit hook, write policy)
+/// and the alarm listener cannot be cloned and are not part of the configuration, install them again on the new
+/// client (the audit settings included).
+/// The observer is shared.
+///
+#[derive(Clone)]
+pub struct S7ClientConfig {
+    endpoint: Option<(String, Vec<u8>, Vec<u8>)>,
+    proxy: Option<Socks5Proxy>,
+    port: u16,
+    co_timeout_ms: u64,
+    rd_timeout_ms: u64,
+    wr_timeout_ms: u64,
+    rd_timeout_per_kb_ms: u64,
+    nodelay: bool,
+    rcv_buf_size: usize,
+    snd_buf_size: usize,
+    linger: Option<Duration>,
+    close_timeout_ms: u64,
+    send_cotp_dr: bool,
+    drop_behavior: DropBehavior,
+    cotp_local_ref: u16,
+    tpdu_size_code: u8,
+    heartbeat: Option<(u8, u16, u32)>,
+    min_request_gap: Duration,
+    conn_type: u16,
+    max_chunk: u16,
+    chunk_alignment: u16,
+    chunk_retries: u32,
+    verify_cpu_stop: bool,
+    diagnose_address: bool,
+    check_write_protection: bool,
+    dry_run: bool,
+    error_context: bool,
+    strict_parse: bool,
+    transfer_reports: bool,
+    read_cache_ttl: Option<Duration>,
+    observer: Option<Arc<dyn S7Observer>>,
+}
+
+impl S7ClientConfig {
+    /// ### Address of the PLC of the last TCP connection
+    ///
+    /// `None` if the client never connected, or connected by `connect_transport()`.
+    ///
+    pub fn address(&self) -> Option<&str> {
+        self.endpoint.as_ref().map(|(ip, _, _)| ip.as_str())
+    }
+
+    /// ### TCP port
+    ///
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// ### S7 connection type (`CT_PG`, `CT_OP` or `CT_S7`)
+    ///
+    pub fn connection_type(&self) -> u16 {
+        self.conn_type
+    }
+}
+
+impl Default for S7ClientConfig {
+    fn default() -> Self {
+        S7Client::new().config()
+    }
+}
+
+impl fmt::Debug for S7ClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S7ClientConfig")
+            .field("address", &self.address())
+            .field("port", &self.port)
+            .field("conn_type", &self.conn_type)
+            .field("co_timeout_ms", &self.co_timeout_ms)
+            .field("rd_timeout_ms", &self.rd_timeout_ms)
+            .field("wr_timeout_ms", &self.wr_timeout_ms)
+            .field("tpdu_size_code", &self.tpdu_size_code)
+            .field("max_chunk", &self.max_chunk)
+            .field("read_cache_ttl", &self.read_cache_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
 pub struct S7Client {
-    stream: Option<TcpStream>,
+    stream: Option<Box<dyn Transport>>,
+    transport_wrapper: Option<TransportWrapper>,
+    pub(crate) endpoint: Option<(String, Vec<u8>, Vec<u8>)>, // Address and TSAPs of the last TCP connection
+    pub(crate) connection_id: u64, // Identity of the current connection, unique in the process (0 = never connected)
+    cotp_request_hook: Option<CotpRequestHook>,
+    proxy: Option<Socks5Proxy>,
     port: u16,
     co_timeout_ms: u64,
     rd_timeout_ms: u64,
     wr_timeout_ms: u64,
+    rd_timeout_per_kb_ms: u64, // Adaptive read timeout factor (0 = disabled)
+    eff_rd_timeout_ms: u64,    // Read timeout currently set on the socket
+    nodelay: bool,             // TCP_NODELAY
+    rcv_buf_size: usize,       // SO_RCVBUF (0 = OS default)
+    snd_buf_size: usize,       // SO_SNDBUF (0 = OS default)
+    linger: Option<Duration>,  // SO_LINGER (None = OS default)
+    close_timeout_ms: u64,     // Graceful close wait (0 = immediate shutdown)
+    send_cotp_dr: bool,        // Send a COTP Disconnect Request on disconnect
+    drop_behavior: DropBehavior,
+    cotp_local_ref: u16,       // COTP reference of the client
+    tpdu_size_code: u8,        // COTP TPDU size requested: 2^code byte
+    heartbeat: Option<(u8, u16, u32)>, // Address (area, db, byte) read by health_check()
+    min_request_gap: Duration,    // Minimum time between two requests (0 = no pacing)
+    last_request: Option<Instant>,
     conn_type: u16,
     max_rd_pdu_data: u16, // Max Read PDU Payload
     max_wr_pdu_data: u16, // Max Write PDU Payload
+    max_chunk: u16,       // User cap of the chunk size (0 = PDU payload)
+    chunk_alignment: u16, // Chunk sizes multiple of this (0, 1 = no alignment)
+    chunk_retries: u32,   // Retries of a chunk that timed out
+    verify_cpu_stop: bool, // Query the CPU status when a job is refused
+    diagnose_address: bool, // Query the DB info when an address is refused
+    check_write_protection: bool, // Query the protection level before each write
+    dry_run: bool,         // Validate the writes without sending them
+    error_context: bool,   // Wrap the Read/Write errors into WithContext
+    pdu_ref: u16,          // PDU Reference of the last request
+    stale_responses: bool, // A late response to an abandoned request may still arrive
     /// PDU length negotiated by the CPU
     pub pdu_length: u16,  
     /// Client connected
@@ -139,8 +648,24 @@ pub struct S7Client {
     /// If an error occurred the value will be 0
     pub last_time: f64,
     /// ### Indicates how many pieces the data to be read or written in the last operation was divided into
-    /// Maybe you need to know it only for extreme tuning
+    /// Maybe you need to know it only for extreme tuning (see `set_transfer_report()` for the detail of each piece)
     pub chunks:  usize,
+    /// ### Results of the last connection negotiation (COTP + S7)
+    pub negotiation: NegotiationInfo,
+    strict_parse: bool,
+    frame_counters: Arc<FrameCounters>,  // Updated by the CountingTransport
+    traffic_errors: u64,
+    area_requests: BTreeMap<u8, u64>,
+    pub(crate) read_cache: Option<ReadCache>, // None = disabled
+    transfer_reports: bool,
+    transfer_report: Option<TransferReport>,
+    pub(crate) retry_policy: Option<Box<dyn RetryPolicy>>, // None = no automatic retries
+    pub(crate) observer: Option<Arc<dyn S7Observer>>,
+    pub(crate) alarm_listener: Option<AlarmListener>,
+    pub(crate) audit_hook: Option<AuditHook>,
+    pub(crate) audit_context: Option<String>,
+    pub(crate) audit_old_values: bool,
+    pub(crate) write_policy: Option<WritePolicy>, // None = all the writes allowed
 }
 
     /// ### Checks the incoming ISO Packet coherence
@@ -148,7 +673,7 @@ pub struct S7Client {
     /// Typically, a PLC never sends incorrect values, but we may find data in the buffer 
     /// from a fragmented transmission, so it is good practice to check.
     /// 
-    fn check_iso_packet(pdu_length: u16, iso_packet: &mut [u8; TPKT_ISO_LEN]) -> Result<usize, S7Error> {
+    fn check_iso_packet(pdu_length: u16, iso_packet: &[u8; TPKT_ISO_LEN]) -> Result<usize, S7Error> {
         //
         //  TPKT + ISO Header
         // 
@@ -163,16 +688,14 @@ pub struct S7Client {
         //      [6]    EOT                  0x80
 
         // Check Telegram validity
-        
-        if iso_packet[0] != ISO_ID || iso_packet[4] != 0x02 || iso_packet[5] != 0xF0 {
-            return Err(S7Error::IsoInvalidHeader);
-        }
-        
-        if iso_packet[6] != EOT {
+        let tpkt = TpktHeader::decode(iso_packet)?;
+        let cotp = CotpData::decode(&iso_packet[TPKT_LEN..])?;
+
+        if !cotp.eot {
             return Err(S7Error::IsoFragmentedPacket);
         }
         
-        let telegram_length: usize = make_u16!(iso_packet[2], iso_packet[3]) as usize;
+        let telegram_length: usize = tpkt.length as usize;
         
         if telegram_length < TPKT_ISO_LEN || 
            telegram_length - TPKT_ISO_LEN > pdu_length as usize || 
@@ -184,6 +707,324 @@ pub struct S7Client {
         Ok(telegram_length - TPKT_ISO_LEN)
     }
 
+    /// ### Strict-parse validation of a Read/Write Var response
+    ///
+    /// `telegram` is the whole response (TPKT included), `pdu_ref` the reference of the request,
+    /// `amount` the elements requested.
+    /// Error return codes are not checked here, they are mapped by the caller.
+    ///
+    fn strict_check_rw_response(telegram: &[u8], pdu_ref: u16, function: u8, wordlen: u8, amount: u16) -> Result<(), S7Error> {
+        let parser = StrictParser::new(telegram);
+        let data = parser.ack_data(pdu_ref, function)?;
+        parser.byte(TPKT_ISO_LEN + S7_ACK_HEADER_LEN + 1, "S7 items count", 0x01)?;
+
+        if function == FN_WRITE_VAR {
+            parser.min_len(data + 1, "S7 return code")?;
+            if telegram.len() != data + 1 {
+                return Err(parser.fail(data, "S7 data length", 1, telegram.len() - data));
+            }
+            return Ok(());
+        }
+
+        parser.min_len(data + DATA_ITEM_HEADER_LEN, "S7 data item header")?;
+        if telegram[data] != RES_SUCCESS {
+            return Ok(());
+        }
+
+        let (transport, length) = if wordlen == S7_WL_BIT {
+            (TS_RES_BIT, amount)
+        } else {
+            (TS_RES_BYTE, amount << 3)
+        };
+        parser.byte(data + 1, "S7 transport size", transport)?;
+        parser.word(data + 2, "S7 data item length", length)?;
+        parser.min_len(data + DATA_ITEM_HEADER_LEN + amount as usize, "S7 payload")?;
+        Ok(())
+    }
+
+    /// ### Checks that a read response carries exactly the bytes requested
+    ///
+    /// The length of the data item (bits for the byte transport sizes) must match the chunk size,
+    /// and the telegram must contain the whole payload: a short response (e.g. truncated by a
+    /// gateway) would otherwise leave stale bytes in the buffer.
+    ///
+    /// `S7Error::MalformedTelegram` with the detail of the mismatch otherwise.
+    ///
+    fn check_read_payload(iso_packet: &[u8], pdu: &[u8], item_offset: usize, item: &DataItemHeader, chunk_size: usize) -> Result<(), S7Error> {
+        let length = match item.transport_size {
+            TS_RES_BYTE | TS_RES_INT => item.length as usize >> 3,
+            _ => item.length as usize,
+        };
+        let data_offset = item_offset + DATA_ITEM_HEADER_LEN;
+        let available = pdu.len().saturating_sub(data_offset);
+        if length == chunk_size && available >= length {
+            return Ok(());
+        }
+        let telegram = [iso_packet, pdu].concat();
+        let parser = StrictParser::new(&telegram);
+        if length != chunk_size {
+            Err(parser.fail(TPKT_ISO_LEN + item_offset + 2, "S7 data item length (byte)", chunk_size, length))
+        } else {
+            Err(parser.fail(TPKT_ISO_LEN + data_offset, "S7 payload (byte)", length, available))
+        }
+    }
+
+    /// ### Half-closes the stream and waits for the peer to close its side
+    ///
+    /// Late data is discarded. Fails if the peer doesn't close within `timeout`.
+    ///
+    fn graceful_close(stream: &mut dyn Transport, timeout: Duration) -> Result<(), S7Error> {
+        stream.flush()?;
+        stream.shutdown(Shutdown::Write)?;
+
+        let deadline = Instant::now() + timeout;
+        let mut discard = [0u8; 256];
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(S7Error::Io(io::Error::new(io::ErrorKind::TimedOut, "the PLC didn't close the connection")));
+            }
+            stream.set_read_timeout(Some(deadline - now))?;
+            match stream.read(&mut discard) {
+                Ok(0) => return Ok(()), // Closed by the peer
+                Ok(_) => {}             // Late data, discard it
+                Err(error) if matches!(error.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => {} // Deadline checked above
+                Err(error) => return Err(S7Error::Io(error)),
+            }
+        }
+    }
+
+    /// ### Waits until `min_gap` has elapsed since the previous request, then marks a new one
+    ///
+    fn pace(min_gap: Duration, last_request: &mut Option<Instant>) {
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < min_gap {
+                std::thread::sleep(min_gap - elapsed);
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+
+    /// ### Receives the response to a Read/Write request
+    ///
+    /// Returns the ISO header and the size of the S7 telegram stored into `response`, that must
+    /// be at least `min_size` byte. The alarm messages pushed by the CPU before the response are
+    /// stored into `pushed`.
+    ///
+    /// `stale`: `Some(<pdu_ref>)` discards the responses carrying another PDU Reference (late
+    /// responses to the requests abandoned after a timeout).
+    ///
+    fn receive_chunk(stream: &mut dyn Transport, pdu_length: u16, min_size: usize, response: &mut [u8], pushed: &mut Vec<Vec<u8>>, stale: Option<u16>) -> Result<([u8; TPKT_ISO_LEN], usize), S7Error> {
+        loop {
+            // Read and check ISO header
+            let mut iso_packet = [0u8; TPKT_ISO_LEN];
+            stream.read_exact(&mut iso_packet)?;
+
+            let s7_comm_size = check_iso_packet(pdu_length, &iso_packet)?;
+
+            // Read and check S7 Telegram
+            if s7_comm_size > response.len() {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+            stream.read_exact(&mut response[..s7_comm_size])?;
+            let size_resp = s7_comm_size;
+
+            if is_pushed(&response[..size_resp]) {
+                pushed.push(response[..size_resp].to_vec());
+                continue;
+            }
+
+            let header = S7Header::decode(&response[..size_resp])?;
+            if let Some(pdu_ref) = stale.filter(|&pdu_ref| pdu_ref != header.pdu_ref) {
+                s7_debug!("stale response discarded: PDU reference 0x{:04X}, expected 0x{:04X}", header.pdu_ref, pdu_ref);
+                continue;
+            }
+
+            // A refused job is acknowledged by a short telegram carrying the error
+            check_ack_error(&header)?;
+
+            if s7_comm_size < min_size {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+            return Ok((iso_packet, size_resp));
+        }
+    }
+
+    /// ### Formats an address in the STEP 7 notation (e.g. `DB10.DBB4`, `M2.3`)
+    ///
+    pub(crate) fn step7_address(area: u8, db_number: u16, start: u32, wordlen: u8) -> String {
+        let (byte, bit) = if wordlen == S7_WL_BIT { (start / 8, Some(start % 8)) } else { (start, None) };
+        let prefix = match area {
+            S7_AREA_DB => format!("DB{}.DB", db_number),
+            S7_AREA_PE => "I".to_string(),
+            S7_AREA_PA => "Q".to_string(),
+            S7_AREA_MK => "M".to_string(),
+            area => format!("0x{:02X}:", area),
+        };
+        match (bit, area) {
+            (Some(bit), S7_AREA_DB) => format!("{}X{}.{}", prefix, byte, bit),
+            (Some(bit), _) => format!("{}{}.{}", prefix, byte, bit),
+            (None, _) => format!("{}B{}", prefix, byte),
+        }
+    }
+
+    /// ### Maps the return code of a Read/Write item
+    ///
+    pub(crate) fn item_result(return_code: u8) -> Result<(), S7Error> {
+        match return_code {
+            RES_SUCCESS => Ok(()),
+            RES_NOT_FOUND => Err(S7Error::S7NotFound),
+            RES_INVALID_ADDRESS => Err(S7Error::S7InvalidAddress),
+            RES_ACCESS_DENIED => Err(S7Error::AccessDenied(None)),
+            RES_DATA_TYPE => Err(S7Error::DataTypeMismatch),
+            RES_DATA_SIZE => Err(S7Error::WrongDataSize),
+            _ => Err(S7Error::S7Unspecified(S7ErrorCodes::item(return_code))),
+        }
+    }
+
+    /// ### Maps the error class/code of an Ack header
+    ///
+    pub(crate) fn check_ack_error(header: &S7Header) -> Result<(), S7Error> {
+        let codes = S7ErrorCodes::header(header.error_class, header.error_code);
+        match (header.error_class, header.error_code) {
+            (0x00, _) => Ok(()),
+            ERR_OBJECT_STATE => Err(S7Error::CpuStopped(codes)),
+            _ => Err(S7Error::S7Unspecified(codes)),
+        }
+    }
+
+    /// ### Returns true for a read/write timeout
+    ///
+    fn is_timeout(error: &S7Error) -> bool {
+        matches!(error, S7Error::Io(e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock))
+    }
+
+    /// ### Checks the buffer of a Read/Write before sending anything
+    ///
+    /// A bit access transfers exactly 1 byte, a byte access any size up to the last byte
+    /// addressable by the protocol (the bit address is 24 bit long).
+    ///
+    fn check_buffer_size(wordlen: u8, start: u32, size: usize) -> Result<(), S7Error> {
+        let first_byte = if wordlen == S7_WL_BIT { start as usize / 8 } else { start as usize };
+        if first_byte >= MAX_AREA_BYTES {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let room = MAX_AREA_BYTES - first_byte;
+        match wordlen {
+            S7_WL_BIT if size != 1 => Err(S7Error::SizeMismatch { size, expected: 1 }),
+            S7_WL_BYTE if size > room => Err(S7Error::SizeMismatch { size, expected: room }),
+            _ => Ok(()),
+        }
+    }
+
+    /// ### Destination of a read
+    ///
+    /// Lets the read path fill an initialized slice, an uninitialized slice or the end of a `Vec`
+    /// without zeroing it first.
+    ///
+    pub(crate) trait ReadTarget {
+        /// Number of elements to read
+        fn size(&self) -> usize;
+        /// Stores the data received at `offset`
+        fn put(&mut self, offset: usize, data: &[u8]);
+        /// Discards the data stored by a failed attempt
+        fn reset(&mut self) {}
+        /// Data stored, `None` if it can't be read back (uninitialized memory)
+        fn contents(&self) -> Option<&[u8]>;
+    }
+
+    impl ReadTarget for [u8] {
+        fn size(&self) -> usize {
+            self.len()
+        }
+
+        fn put(&mut self, offset: usize, data: &[u8]) {
+            self[offset..offset + data.len()].copy_from_slice(data);
+        }
+
+        fn contents(&self) -> Option<&[u8]> {
+            Some(self)
+        }
+    }
+
+    impl ReadTarget for [MaybeUninit<u8>] {
+        fn size(&self) -> usize {
+            self.len()
+        }
+
+        fn put(&mut self, offset: usize, data: &[u8]) {
+            for (slot, &byte) in self[offset..offset + data.len()].iter_mut().zip(data) {
+                slot.write(byte);
+            }
+        }
+
+        fn contents(&self) -> Option<&[u8]> {
+            None
+        }
+    }
+
+    /// `size` elements appended to a `Vec`
+    struct AppendTarget<'a> {
+        vec: &'a mut Vec<u8>,
+        base: usize,
+        size: usize,
+    }
+
+    impl ReadTarget for AppendTarget<'_> {
+        fn size(&self) -> usize {
+            self.size
+        }
+
+        fn put(&mut self, offset: usize, data: &[u8]) {
+            // The chunks arrive in order, a chunk retried replaces the previous attempt
+            self.vec.truncate(self.base + offset);
+            self.vec.extend_from_slice(data);
+        }
+
+        fn reset(&mut self) {
+            self.vec.truncate(self.base);
+        }
+
+        fn contents(&self) -> Option<&[u8]> {
+            self.vec.get(self.base..self.base + self.size)
+        }
+    }
+
+    /// ### Returns the start of a bit access: `byte_num * 8 + bit_idx`
+    ///
+    /// `S7Error::InvalidFunParameter` if the bit index is > 7 or the byte is beyond the last one addressable (2 MB).
+    ///
+    fn bit_start(byte_num: u32, bit_idx: u8) -> Result<u32, S7Error> {
+        if bit_idx > 7 || byte_num as usize >= MAX_AREA_BYTES {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        Ok(byte_num * 8 + bit_idx as u32)
+    }
+
+    /// ### Discards the data pending in the socket, e.g. the late response to a timed out request
+    ///
+    /// Reads until the socket stays quiet for `DRAIN_QUIET_MS`, then restores the read timeout.
+    /// Returns the number of bytes discarded.
+    ///
+    fn drain(stream: &mut dyn Transport, read_timeout_ms: u64) -> Result<usize, S7Error> {
+        stream.set_read_timeout(Some(Duration::from_millis(DRAIN_QUIET_MS)))?;
+        let mut discard = [0u8; 256];
+        let mut discarded = 0;
+        let result = loop {
+            match stream.read(&mut discard) {
+                Ok(0) => break Err(S7Error::ConnectionClosed),
+                Ok(size) => discarded += size, // Late data, discard it
+                Err(e) => {
+                    let error = S7Error::Io(e);
+                    break if is_timeout(&error) { Ok(discarded) } else { Err(error) };
+                }
+            }
+        };
+        stream.set_read_timeout((read_timeout_ms > 0).then(|| Duration::from_millis(read_timeout_ms)))?;
+        result
+    }
+
 impl S7Client {
     /// ### Creates a new `S7Client` instance with default settings.
     ///
@@ -195,17 +1036,61 @@ impl S7Client {
     pub fn new() -> Self {
         S7Client {
             stream: None,
+            transport_wrapper: None,
+            endpoint: None,
+            connection_id: 0,
+            cotp_request_hook: None,
+            proxy: None,
             port: 102,
             co_timeout_ms: 3000,
             rd_timeout_ms: 1000,
             wr_timeout_ms: 500,
+            rd_timeout_per_kb_ms: 0,
+            eff_rd_timeout_ms: 0,
+            nodelay: true,
+            rcv_buf_size: 0,
+            snd_buf_size: 0,
+            linger: None,
+            close_timeout_ms: 0,
+            send_cotp_dr: false,
+            drop_behavior: DropBehavior::Disconnect,
+            cotp_local_ref: 0x0001,
+            tpdu_size_code: 0x0A, // 1024 byte
+            heartbeat: None,
+            min_request_gap: Duration::ZERO,
+            last_request: None,
             conn_type: CT_PG,
             max_rd_pdu_data: 0, 
             max_wr_pdu_data: 0, 
+            max_chunk: 0,
+            chunk_alignment: 0,
+            chunk_retries: 0,
+            verify_cpu_stop: false,
+            diagnose_address: false,
+            check_write_protection: false,
+            dry_run: false,
+            error_context: false,
+            pdu_ref: PDU_REF,
+            stale_responses: false,
             pdu_length: 0x0000,
             connected: false,
             last_time: 0.0,
             chunks:0,
+            negotiation: NegotiationInfo::default(),
+            strict_parse: false,
+            frame_counters: Arc::new(FrameCounters::default()),
+            traffic_errors: 0,
+            area_requests: BTreeMap::new(),
+            read_cache: None,
+            transfer_reports: false,
+            transfer_report: None,
+            retry_policy: None,
+            observer: None,
+            alarm_listener: None,
+            audit_hook: None,
+            audit_context: None,
+            audit_old_values: false,
+            write_policy: None,
         }
     }
 
@@ -250,7 +1135,7 @@ impl S7Client {
     ///    
     pub fn set_connection_type(&mut self, connection_type: u16) -> Result<(), S7Error> {
         
-        if connection_type < CT_PG || connection_type > CT_S7 {
+        if !(CT_PG..=CT_S7).contains(&connection_type) {
             return Err(S7Error::InvalidFunParameter);
         }
         
@@ -289,27 +1174,848 @@ impl S7Client {
         Ok(())
     }
 
-    /// ### Sets the TCP Connection Port
-    /// 
-    /// The default S7 Port is 102, but if you need NAT the addresses you can use this method to change the default value.
-    /// 
+    /// ### Enables or disables TCP_NODELAY
+    ///
+    /// By default the Nagle algorithm is disabled (TCP_NODELAY on), since S7 is a request/response
+    /// protocol made of small telegrams and any coalescing delay directly adds to the job time.
+    ///
+    /// Enabling Nagle can reduce the number of segments on high-latency links (satellite, VPN)
+    /// shared with many parallel clients.
+    ///
     /// ### Parameters
-    /// - `port`: TCP Connection port (1..65535)
-    /// 
-    /// ### Returns
-    /// `Ok(())` on success, or an `S7Error` on failure.
+    /// - `nodelay`: true to disable the Nagle algorithm (Default = true)
     ///
-    /// ### Errors
-    /// - `S7Error::InvalidFunParam`: Invalid parameter supplied to the function.
-    /// 
     /// ### Notes
-    /// 1. Value must be > 0, otherwise it is ignored
-    /// 2. The client must not be connected (that is, call this method before connecting).
-    /// 
-    pub fn set_connection_port(&mut self, port: u16)  -> Result<(), S7Error> {
+    /// The client must not be connected (that is, call this method before connecting).
+    ///
+    pub fn set_nodelay(&mut self, nodelay: bool) {
+        self.nodelay = nodelay;
+    }
 
-        if port == 0 {
-            return Err(S7Error::InvalidFunParameter);
+    /// ### Sets the socket buffer sizes (SO_RCVBUF / SO_SNDBUF)
+    ///
+    /// Larger buffers may improve the throughput on links with a high bandwidth-delay product,
+    /// smaller ones reduce the memory footprint when many clients run in parallel.
+    ///
+    /// ### Parameters
+    /// - `rcv_buf_size` : Receive buffer size (bytes), 0 = OS default (Default = 0)
+    /// - `snd_buf_size` : Send buffer size (bytes), 0 = OS default (Default = 0)
+    ///
+    /// ### Notes
+    /// 1. The OS may round or clamp the values (e.g. Linux doubles them).
+    /// 2. The client must not be connected (that is, call this method before connecting).
+    ///
+    pub fn set_buffer_sizes(&mut self, rcv_buf_size: usize, snd_buf_size: usize) {
+        self.rcv_buf_size = rcv_buf_size;
+        self.snd_buf_size = snd_buf_size;
+    }
+
+    /// ### Sets the SO_LINGER socket option
+    ///
+    /// Controls what happens to unsent data when the socket is closed:
+    /// - `None`: OS default, the close returns immediately and pending data is sent in background.
+    /// - `Some(Duration::ZERO)`: abortive close, pending data is discarded and an RST is sent.
+    /// - `Some(timeout)`: the close waits up to `timeout` for pending data to be acknowledged.
+    ///
+    /// ### Parameters
+    /// - `linger`: Linger behavior (Default = None)
+    ///
+    /// ### Notes
+    /// The client must not be connected (that is, call this method before connecting).
+    ///
+    pub fn set_linger(&mut self, linger: Option<Duration>) {
+        self.linger = linger;
+    }
+
+    /// ### Enables the graceful close
+    ///
+    /// By default `disconnect()` shuts the socket down immediately. Abrupt closes occasionally leave
+    /// CPs holding stale connection resources.
+    ///
+    /// When enabled, `disconnect()` first flushes pending data and sends a FIN, then waits (at most
+    /// `timeout_ms`) for the PLC to close its side, discarding any late data, and finally shuts the socket down.
+    ///
+    /// ### Parameters
+    /// - `timeout_ms` : Max wait (ms) for the peer to close, 0 disables the graceful close (Default = 0)
+    ///
+    pub fn set_graceful_close(&mut self, timeout_ms: u64) {
+        self.close_timeout_ms = timeout_ms;
+    }
+
+    /// ### Sets the COTP TPDU size
+    ///
+    /// The TPDU size is the "PDU Max Length" parameter of the COTP connection request, that is the
+    /// maximum size of the ISO data units. The default (1024 byte) is accepted by all Siemens CPUs,
+    /// but some older CPs and third-party ISO-on-TCP stacks reject or mishandle it.
+    ///
+    /// ### Parameters
+    /// - `size`: TPDU size (bytes), must be a power of 2 between 128 and 8192 (Default = 1024)
+    ///
+    /// ### Returns
+    /// `Ok(())` on success, or an `S7Error` on failure.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParam`: Invalid parameter supplied to the function.
+    ///
+    /// ### Notes
+    /// 1. The S7 PDU negotiated later must fit into the TPDU, so don't go below 512 unless the device requires it.
+    /// 2. The client must not be connected (that is, call this method before connecting).
+    ///
+    pub fn set_tpdu_size(&mut self, size: u16) -> Result<(), S7Error> {
+
+        if !size.is_power_of_two() || !(128..=8192).contains(&size) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        self.tpdu_size_code = size.trailing_zeros() as u8;
+
+        Ok(())
+    }
+
+    /// ### Customizes the COTP Connection Request
+    ///
+    /// Some third-party devices and gateways expect non-standard connection requests: another
+    /// class/options byte, additional parameters (e.g. checksum, version, additional option
+    /// selection) or different values of the standard ones. The hook receives the request built
+    /// by the client, just before it's sent, and can change any field.
+    ///
+    /// ### Parameters
+    /// - `hook`: The customizing function, or `None` to remove it.
+    ///
+    /// ### Notes
+    /// 1. An entry of `extra_params` with the code of a standard parameter (0xC0 TPDU size,
+    ///    0xC1 Src TSAP, 0xC2 Dst TSAP) replaces it.
+    /// 2. The hook is called at each connection. The whole header can't exceed 255 byte,
+    ///    otherwise the connection fails with `S7Error::InvalidFunParameter`.
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::S7Client;
+    ///
+    /// let mut client = S7Client::new();
+    /// client.set_cotp_request_hook(Some(Box::new(|request| {
+    ///     request.class = 0x02; // Class 0, extended formats
+    ///     request.extra_params.push((0xC6, vec![0x01])); // Additional option selection
+    /// })));
+    /// ```
+    ///
+    pub fn set_cotp_request_hook(&mut self, hook: Option<CotpRequestHook>) {
+        self.cotp_request_hook = hook;
+    }
+
+    /// ### Enables the COTP Disconnect Request
+    ///
+    /// When enabled, `disconnect()` sends a COTP DR telegram before closing the TCP socket.
+    ///
+    /// Some CPs keep the S7 connection allocated for a long time after a bare TCP shutdown,
+    /// exhausting their connection resources, the DR releases it immediately.
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to send the DR (Default = false)
+    ///
+    pub fn set_cotp_disconnect(&mut self, enabled: bool) {
+        self.send_cotp_dr = enabled;
+    }
+
+    /// ### Sets how a connected client is closed when dropped
+    ///
+    /// ### Parameters
+    /// - `behavior`: (Default = `DropBehavior::Disconnect`)
+    ///
+    /// ### Values
+    /// - `DropBehavior::Disconnect`: same as `disconnect()`, COTP DR and graceful close as set by
+    ///   `set_cotp_disconnect()` and `set_graceful_close()`.
+    /// - `DropBehavior::Abort`: immediate shutdown, the drop never waits.
+    /// - `DropBehavior::Graceful(timeout)`: COTP DR, then waits at most `timeout` for the PLC to close.
+    ///
+    /// ### Notes
+    /// `Drop` can't report errors: they are only logged (feature `log`). Call `close()` before
+    /// dropping the client to check them.
+    ///
+    pub fn set_drop_behavior(&mut self, behavior: DropBehavior) {
+        self.drop_behavior = behavior;
+    }
+
+    /// ### Enables the adaptive read timeout
+    ///
+    /// With a flat read timeout, large DB uploads over slow links (VPN, cellular) require globally
+    /// inflating `rd_timeout_ms`, which delays the failure detection for small reads.
+    ///
+    /// When enabled, the read timeout of each operation is scaled with its size:
+    ///
+    /// `effective timeout = rd_timeout_ms + per_kb_ms * transfer size (KB, rounded up)`
+    ///
+    /// Since large transfers are split into chunks, the same value also covers the time the CPU needs
+    /// to serve a long sequence of requests.
+    ///
+    /// ### Parameters
+    /// - `per_kb_ms` : Additional timeout (ms) per KB of data to transfer, 0 disables the feature (Default = 0)
+    ///
+    /// ### Notes
+    /// The base value `rd_timeout_ms` is the one supplied to `set_timeout()`.
+    ///
+    pub fn set_adaptive_timeout(&mut self, per_kb_ms: u64) {
+        self.rd_timeout_per_kb_ms = per_kb_ms;
+    }
+
+    /// ### Sets the minimum time between two requests
+    ///
+    /// Old CPUs and CPs (e.g. CP343-1 Lean, S7-300 with heavy communication load) degrade when polled
+    /// back-to-back. With a minimum gap, each request (each chunk of a large transfer included) is
+    /// delayed until the gap since the previous one has elapsed; no `sleep` is needed in the application.
+    ///
+    /// ### Parameters
+    /// - `gap`: Minimum time between the start of two consecutive requests, `Duration::ZERO` disables the pacing (Default)
+    ///
+    /// ### Notes
+    /// The gap is measured from the sending of the previous request, so the time spent waiting
+    /// for the response (and by the application between two calls) counts towards it.
+    ///
+    pub fn set_request_gap(&mut self, gap: Duration) {
+        self.min_request_gap = gap;
+    }
+
+    /// ### Caps the size of the chunks of the Read/Write transfers
+    ///
+    /// By default a large transfer is split into chunks as large as the negotiated PDU allows.
+    /// Some CPs behave better with smaller jobs, and smaller chunks bound the time the CPU spends
+    /// on each request, so the other clients sharing it are served in between.
+    ///
+    /// ### Parameters
+    /// - `bytes`: Max payload of a Read/Write request (elements for bit access), 0 removes the cap (Default)
+    ///
+    /// ### Notes
+    /// - The cap only lowers the chunk size: a value above the PDU payload has no effect.
+    /// - The multi-variable requests are not split, so they are not affected.
+    ///
+    pub fn set_max_chunk(&mut self, bytes: u16) {
+        self.max_chunk = bytes;
+    }
+
+    /// ### Aligns the chunk splits to an element size
+    ///
+    /// A large transfer is split into chunks exchanged in different request/response cycles, so a
+    /// value crossing a chunk boundary may have its halves read in different PLC scan cycles.
+    /// With an alignment, the size of each chunk (but the last) is a multiple of `element_size`:
+    /// when the buffer starts at a value boundary, no value is torn.
+    ///
+    /// ### Parameters
+    /// - `element_size`: Size of the values (e.g. 4 for REAL/DINT, 8 for LREAL), 0 or 1 disables the alignment (Default)
+    ///
+    /// ### Notes
+    /// - The boundaries are relative to the start of the transfer, not to the start of the area.
+    /// - Only the byte transfers are aligned. If `element_size` exceeds the chunk size (see
+    ///   `set_max_chunk()`), the chunks are not aligned.
+    ///
+    pub fn set_chunk_alignment(&mut self, element_size: u16) {
+        self.chunk_alignment = element_size;
+    }
+
+    /// ### Retries a chunk that timed out instead of failing the whole transfer
+    ///
+    /// By default a timeout fails the `read_area()`/`write_area()`, so a large transfer over a
+    /// lossy link restarts from the first chunk on any hiccup. With retries, the chunk whose
+    /// response didn't arrive is sent again, after discarding any late data pending in the socket.
+    ///
+    /// ### Parameters
+    /// - `retries`: Max retries of each chunk, 0 disables the feature (Default)
+    ///
+    /// ### Notes
+    /// - Only the timeouts waiting for the response are retried; any other error fails the transfer.
+    /// - Before a retry the socket is drained until it stays quiet for 100 ms.
+    /// - A retried write is sent twice if only its response was lost: the PLC writes the same data again.
+    /// - The retries are counted in the transfer report (see `set_transfer_report()`).
+    ///
+    pub fn set_chunk_retries(&mut self, retries: u32) {
+        self.chunk_retries = retries;
+    }
+
+    /// ### Verifies the CPU status when a Read/Write job is refused
+    ///
+    /// The CPUs in STOP (or in a transition) refuse some jobs with a specific error, reported as
+    /// `S7Error::CpuStopped`, but others answer with a generic error. When enabled, a
+    /// `read_area()`/`write_area()` failed with `S7Error::S7Unspecified` is followed by a status
+    /// query (`get_plc_status()`): if the CPU is not in RUN, the error becomes `S7Error::CpuStopped`.
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to verify, false (Default) to report the errors as received
+    ///
+    /// ### Notes
+    /// The query costs a round-trip, only on the refused jobs; its own errors are ignored.
+    ///
+    pub fn set_verify_cpu_stop(&mut self, enabled: bool) {
+        self.verify_cpu_stop = enabled;
+    }
+
+    /// ### Diagnoses the DB addresses refused by the CPU
+    ///
+    /// The CPU answers with the same error (`S7Error::S7InvalidAddress`) to an address beyond the
+    /// end of the DB and to any address of a DB with the optimized access (S7-1200/1500), which
+    /// need completely different fixes. When enabled, a DB access failed with `S7InvalidAddress`
+    /// is followed by a block info query (`get_block_info()`):
+    /// - the range exceeds the DB length: the error becomes `S7Error::BeyondDbLength`, with the
+    ///   length of the DB;
+    /// - the range is inside the DB: the error becomes `S7Error::OptimizedDb`, the likely cause
+    ///   being the optimized access (disable "Optimized block access" in the DB properties). The
+    ///   block info doesn't report the attribute: it is inferred from the refusal.
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to diagnose, false (Default) to report the errors as received
+    ///
+    /// ### Notes
+    /// The query costs a round-trip, only on the refused accesses. If the block info can't be
+    /// read (e.g. protected CPU) the error is reported as received.
+    ///
+    pub fn set_diagnose_address_errors(&mut self, enabled: bool) {
+        self.diagnose_address = enabled;
+    }
+
+    /// ### Checks the protection of the CPU and of the DBs before each write
+    ///
+    /// A write refused by a protected CPU (key switch in RUN, protection level assigned in the
+    /// hardware configuration) or to a write-protected DB fails with a generic error. When
+    /// enabled, `write_area()`, the methods based on it and `write_multi_vars()` first read the
+    /// protection level (`get_protection()`) and, for the DB targets, the block info of the DB
+    /// (`get_block_info()`, flag `BLOCK_FLAG_WRITE_PROTECTED`): if the write is not allowed,
+    /// nothing is sent and the error is `S7Error::AccessDenied` with the reason (the protection
+    /// level and the position of the mode selector, or the protected DB).
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to check, false (Default) to send the writes directly
+    ///
+    /// ### Notes
+    /// - The queries cost a round-trip per write, plus one per DB written.
+    /// - CPUs without the SZL 0x0232 or refusing the block info (the error reported by the CPU is
+    ///   ignored) and a protection lifted by a password are checked by the write itself.
+    ///
+    pub fn set_check_write_protection(&mut self, enabled: bool) {
+        self.check_write_protection = enabled;
+    }
+
+    /// ### Enables the dry-run mode of the writes
+    ///
+    /// In dry-run mode `write_area()`, the methods based on it, `write_multi_vars()`,
+    /// `execute_force()` and `write_nck()` validate the request (connection, area, word length,
+    /// size and, if enabled, the protection of the CPU), notify it to the observer (`S7Observer::on_dry_run()`) and return success without
+    /// sending anything: a recipe download sequence can be rehearsed against a live PLC.
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to validate the writes without sending them, false (Default) to send them
+    ///
+    /// ### Notes
+    /// - The reads are sent as usual, so they return the values currently in the PLC, not the
+    ///   ones "written"; the read cache is not invalidated.
+    /// - The other requests changing the PLC (clock, CPU control, block functions, BSEND) are
+    ///   not affected.
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use std::sync::Arc;
+    /// use rust7::S7Client;
+    /// use rust7::observer::{S7Observer, WriteRequest};
+    ///
+    /// struct Printer;
+    ///
+    /// impl S7Observer for Printer {
+    ///     fn on_dry_run(&self, write: &WriteRequest) {
+    ///         println!("{} <- {:02X?}", write.address(), write.data);
+    ///     }
+    /// }
+    ///
+    /// let mut client = S7Client::new();
+    /// client.set_observer(Some(Arc::new(Printer)));
+    /// client.set_dry_run(true);
+    /// client.connect_s71200_1500("192.168.0.100").unwrap();
+    /// client.write_db(10, 0, &[1, 2, 3, 4]).unwrap(); // Printed, not sent
+    /// ```
+    ///
+    pub fn set_dry_run(&mut self, enabled: bool) {
+        self.dry_run = enabled;
+    }
+
+    /// ### Returns true if the writes are validated without sending them (see `set_dry_run()`)
+    ///
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// ### Attaches the operation and the address to the Read/Write errors
+    ///
+    /// When enabled, the errors of `read_area()`, `write_area()`, `read_bits()` and of the methods
+    /// based on them are returned as `S7Error::WithContext`, whose message tells which address
+    /// failed (e.g. `read_area DB100.DBB12 (4 byte): S7 Invalid address`).
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to attach the context, false (Default) to return the errors as they are
+    ///
+    /// ### Notes
+    /// Match `error.root()` to handle the errors regardless of the context, `error.context()`
+    /// returns the details.
+    ///
+    pub fn set_error_context(&mut self, enabled: bool) {
+        self.error_context = enabled;
+    }
+
+    /// Wraps the error of a Read/Write into `WithContext`, if enabled
+    fn with_context<T, F>(&self, result: Result<T, S7Error>, context: F) -> Result<T, S7Error>
+    where
+        F: FnOnce(S7Error) -> ErrorContext,
+    {
+        match result {
+            Err(error) if self.error_context && !matches!(error, S7Error::WithContext(_)) => {
+                let mut context = context(error);
+                if context.area != S7_AREA_DB {
+                    context.db_number = 0;
+                }
+                Err(S7Error::WithContext(Box::new(context)))
+            }
+            result => result,
+        }
+    }
+
+    /// Turns a generic refusal into `CpuStopped` if the CPU is not in RUN (see `set_verify_cpu_stop()`)
+    fn verify_cpu_stop<T>(&mut self, result: Result<T, S7Error>) -> Result<T, S7Error> {
+        match result {
+            Err(S7Error::S7Unspecified(codes)) if self.verify_cpu_stop => {
+                let (last_time, chunks) = (self.last_time, self.chunks);
+                let status = self.get_plc_status();
+                (self.last_time, self.chunks) = (last_time, chunks);
+                match status {
+                    Ok(PlcStatus::Stop | PlcStatus::Startup) => Err(S7Error::CpuStopped(codes)),
+                    _ => Err(S7Error::S7Unspecified(codes)),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// True if `read_area()` applies more than the bare read (cache, retries, error context, CPU
+    /// stop verification, address diagnosis), which `read_multi_vars()` doesn't
+    pub(crate) fn read_area_extended(&self) -> bool {
+        self.read_cache.is_some() || self.retry_policy.is_some() || self.error_context || self.verify_cpu_stop || self.diagnose_address
+    }
+
+    /// Refuses a write to a write-protected CPU or DB (see `set_check_write_protection()`)
+    ///
+    /// `dbs`: DBs written, each one checked once.
+    pub(crate) fn check_write_protection(&mut self, dbs: &[u16]) -> Result<(), S7Error> {
+        if !self.check_write_protection {
+            return Ok(());
+        }
+        let (last_time, chunks) = (self.last_time, self.chunks);
+        let result = self.query_write_protection(dbs);
+        (self.last_time, self.chunks) = (last_time, chunks);
+        result
+    }
+
+    fn query_write_protection(&mut self, dbs: &[u16]) -> Result<(), S7Error> {
+        match self.get_protection() {
+            Ok(protection) if protection.write_protected() => {
+                return Err(S7Error::AccessDenied(Some(format!("CPU write-protected ({})", protection))));
+            }
+            Ok(_) => {}
+            Err(error) if error.class() == S7ErrorClass::Plc => {}
+            Err(error) => return Err(error),
+        }
+        for (index, db_number) in dbs.iter().enumerate() {
+            if dbs[..index].contains(db_number) {
+                continue;
+            }
+            match self.get_block_info(BlockType::Db, *db_number) {
+                Ok(info) if info.write_protected() => {
+                    return Err(S7Error::AccessDenied(Some(format!("DB{} is write-protected in the CPU", db_number))));
+                }
+                Ok(_) => {}
+                Err(error) if error.class() == S7ErrorClass::Plc => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates a write and notifies it to the observer instead of sending it (see `set_dry_run()`)
+    pub(crate) fn dry_run_write(&mut self, write: &WriteRequest) -> Result<(), S7Error> {
+        self.last_time = 0.0;
+        self.chunks = 0;
+        if write.nck.is_none() {
+            self.check_area(write.area)?;
+        }
+        if write.wordlen != S7_WL_BIT && write.wordlen != S7_WL_BYTE {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+        s7_debug!("dry run: {} byte to {} not sent", write.data.len(), write.address());
+        if let Some(observer) = self.observer.as_ref() {
+            observer.on_dry_run(write);
+        }
+        Ok(())
+    }
+
+    /// Turns `S7InvalidAddress` on a DB into `OptimizedDb` or `BeyondDbLength` (see `set_diagnose_address_errors()`)
+    fn diagnose_address<T>(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, size: usize, result: Result<T, S7Error>) -> Result<T, S7Error> {
+        match result {
+            Err(S7Error::S7InvalidAddress) if self.diagnose_address && area == S7_AREA_DB => {
+                let end = if wordlen == S7_WL_BIT { (start as usize + size).div_ceil(8) } else { start as usize + size };
+                let (last_time, chunks) = (self.last_time, self.chunks);
+                let info = self.get_block_info(BlockType::Db, db_number);
+                (self.last_time, self.chunks) = (last_time, chunks);
+                match info {
+                    Ok(info) if end > info.mc7_size as usize => Err(S7Error::BeyondDbLength { db_number, end, length: info.mc7_size as usize }),
+                    Ok(_) => Err(S7Error::OptimizedDb(db_number)),
+                    Err(_) => Err(S7Error::S7InvalidAddress),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Max payload of a chunk, `pdu_data` capped by `set_max_chunk()` and aligned for byte transfers
+    fn chunk_limit(&self, pdu_data: u16, wordlen: u8) -> u16 {
+        let limit = if self.max_chunk > 0 { pdu_data.min(self.max_chunk) } else { pdu_data };
+        if wordlen == S7_WL_BYTE && self.chunk_alignment > 1 && limit >= self.chunk_alignment {
+            limit - limit % self.chunk_alignment
+        } else {
+            limit
+        }
+    }
+
+    /// ### Sets the socket read timeout for a transfer of `size` bytes
+    ///
+    fn apply_read_timeout(&mut self, size: usize) -> Result<(), S7Error> {
+        let kbytes = size.div_ceil(1024) as u64;
+        let timeout_ms = self.rd_timeout_ms + self.rd_timeout_per_kb_ms * kbytes;
+
+        // Avoid a syscall per operation when nothing changes
+        if timeout_ms != self.eff_rd_timeout_ms {
+            if let Some(stream) = self.stream.as_mut() {
+                stream.set_read_timeout(Some(Duration::from_millis(timeout_ms)))?;
+                self.eff_rd_timeout_ms = timeout_ms;
+            }
+        }
+        Ok(())
+    }
+
+    /// ### Sets the socket read timeout for the next receive only
+    ///
+    /// The regular read timeout is restored by the next operation.
+    ///
+    pub(crate) fn set_read_timeout_once(&mut self, timeout: Duration) -> Result<(), S7Error> {
+        if let Some(stream) = self.stream.as_mut() {
+            stream.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+            self.eff_rd_timeout_ms = u64::MAX; // Forces apply_read_timeout()
+        }
+        Ok(())
+    }
+
+    /// ### Sends a telegram and receives the whole response
+    ///
+    /// `request` is the complete telegram (TPKT included), the S7 PDU of the response
+    /// (TPKT and COTP stripped) is returned.
+    ///
+    /// The PDU Reference of the request is replaced by the next one of the client: after a
+    /// timeout, the responses carrying another reference are discarded (see `resync()`).
+    ///
+    pub(crate) fn exchange(&mut self, request: &[u8]) -> Result<Vec<u8>, S7Error> {
+        self.pdu_ref = self.pdu_ref.wrapping_add(1);
+        let pdu_ref = self.pdu_ref;
+        let mut request = request.to_vec();
+        if request.len() >= TPKT_ISO_LEN + 6 && request[TPKT_ISO_LEN] == S7_ID {
+            request[TPKT_ISO_LEN + 4..TPKT_ISO_LEN + 6].copy_from_slice(&pdu_ref.to_be_bytes());
+        }
+        let result = self.send_telegram(&request)
+            .and_then(|_| self.apply_read_timeout(0))
+            .and_then(|_| self.recv_response(pdu_ref));
+        self.count_result(&result);
+        result
+    }
+
+    /// ### Receives the response to the request `pdu_ref`
+    ///
+    /// The late responses to abandoned requests are discarded.
+    ///
+    fn recv_response(&mut self, pdu_ref: u16) -> Result<Vec<u8>, S7Error> {
+        loop {
+            let pdu = self.recv_pdu()?;
+            if self.stale_responses {
+                let received = S7Header::decode(&pdu)?.pdu_ref;
+                if received != pdu_ref {
+                    s7_debug!("stale response discarded: PDU reference 0x{:04X}, expected 0x{:04X}", received, pdu_ref);
+                    continue;
+                }
+                self.stale_responses = false;
+            }
+            return Ok(pdu);
+        }
+    }
+
+    /// ### Sends a complete telegram (TPKT included)
+    ///
+    pub(crate) fn send_telegram(&mut self, telegram: &[u8]) -> Result<(), S7Error> {
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+        let stream = self.stream.as_mut().ok_or(S7Error::NotConnected)?;
+        pace(self.min_request_gap, &mut self.last_request);
+        stream.write_all(telegram)?;
+        Ok(())
+    }
+
+    /// ### Receives a telegram and returns its S7 PDU (TPKT and COTP stripped)
+    ///
+    /// Uses the read timeout currently set on the socket. The alarm messages pushed by the CPU
+    /// are delivered (see `subscribe_alarms()`) and skipped.
+    ///
+    pub(crate) fn recv_pdu(&mut self) -> Result<Vec<u8>, S7Error> {
+        loop {
+            let pdu = self.recv_raw_pdu()?;
+            if self.dispatch_alarms(&pdu).is_none() {
+                return Ok(pdu);
+            }
+        }
+    }
+
+    /// ### Receives the next telegram, pushed ones included
+    ///
+    pub(crate) fn recv_raw_pdu(&mut self) -> Result<Vec<u8>, S7Error> {
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+        let pdu_length = self.pdu_length;
+        let stream = self.stream.as_mut().ok_or(S7Error::NotConnected)?;
+
+        let mut iso_packet = [0u8; TPKT_ISO_LEN];
+        stream.read_exact(&mut iso_packet)?;
+        let size = check_iso_packet(pdu_length, &iso_packet)?;
+
+        let mut pdu = vec![0u8; size];
+        stream.read_exact(&mut pdu)?;
+        Ok(pdu)
+    }
+
+    /// ### Performs a Userdata transaction
+    ///
+    /// ### Returns
+    /// `Ok((<params>, <data>))` parameters and data part of the response, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: The response is not a Userdata telegram.
+    /// - `S7Error::S7Unspecified`: The PLC reported an error in the parameters.
+    /// - Other reported by `exchange()`
+    ///
+    pub(crate) fn userdata(&mut self, params: &S7UserDataParams, data: &[u8]) -> Result<(S7UserDataParams, Vec<u8>), S7Error> {
+        self.observe("userdata", data.len(), |client| {
+            let pdu = client.exchange(&userdata_telegram(PDU_REF, params, data))?;
+
+            let (_, res_params, res_data) = split_userdata(&pdu)?;
+            if res_params.error_code != 0 {
+                return Err(S7Error::S7Unspecified(S7ErrorCodes::userdata(res_params.error_code)));
+            }
+            Ok((res_params, res_data.to_vec()))
+        })
+    }
+
+    /// ### Counts the failed operations requiring a retry or a reconnection
+    ///
+    /// After a timeout or a protocol error the stream is resynchronized (see `resync()`).
+    ///
+    fn count_result<T>(&mut self, result: &Result<T, S7Error>) {
+        if let Err(
+            S7Error::Io(_)
+            | S7Error::ConnectionClosed
+            | S7Error::IsoFragmentedPacket
+            | S7Error::IsoInvalidHeader
+            | S7Error::IsoInvalidTelegram
+            | S7Error::MalformedTelegram(_),
+        ) = result {
+            self.traffic_errors += 1;
+        }
+        if let Err(error) = result {
+            s7_debug!("operation failed: {}", error);
+            if is_timeout(error) {
+                self.stale_responses = true;
+            }
+            if error.is_protocol() {
+                let _ = self.resync();
+            }
+        }
+    }
+
+    /// ### Discards the pending bytes to resynchronize the telegram stream
+    ///
+    /// After a timeout or a malformed telegram, the rest of a response (or a late one) may still be
+    /// in the socket, and would be taken as the response to the next request. `resync()` reads and
+    /// discards the incoming data until the socket stays quiet for 100 ms, so the connection can
+    /// be used again without a reconnection (that costs a new handshake and a CP connection resource).
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)` number of bytes discarded, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::ConnectionClosed`: The PLC closed the connection (reconnect).
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    /// ### Notes
+    /// The client calls it after any operation failed with a timeout, `S7Error::IsoInvalidHeader`,
+    /// `S7Error::IsoInvalidTelegram`, `S7Error::IsoFragmentedPacket` or `S7Error::MalformedTelegram`.
+    /// A response arriving later than the drain is recognized by its PDU Reference (every request
+    /// carries a new one) and discarded by the next operation.
+    ///
+    pub fn resync(&mut self) -> Result<usize, S7Error> {
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+        let stream = self.stream.as_mut().ok_or(S7Error::NotConnected)?;
+        let result = drain(stream.as_mut(), self.rd_timeout_ms);
+        self.eff_rd_timeout_ms = u64::MAX; // Forces apply_read_timeout()
+        if let Ok(discarded) = result {
+            s7_debug!("resync: {} byte discarded", discarded);
+        }
+        result
+    }
+
+    /// ### Counts a Read/Write request sent to `area`
+    ///
+    pub(crate) fn count_area_request(&mut self, area: u8) {
+        *self.area_requests.entry(area).or_insert(0) += 1;
+    }
+
+    /// ### Returns the traffic counters
+    ///
+    /// ### Returns
+    /// A snapshot of the counters: telegrams and bytes sent/received (connection handshakes included),
+    /// failed operations requiring a retry or a reconnection (I/O and ISO/telegram errors, not the
+    /// errors reported by the PLC, such as `S7NotFound`) and Read/Write requests sent to each area.
+    ///
+    /// ### Notes
+    /// The counters are cumulative across reconnections, use `reset_traffic_stats()` to zero them.
+    ///
+    pub fn traffic_stats(&self) -> TrafficStats {
+        let mut stats = self.frame_counters.snapshot();
+        stats.errors = self.traffic_errors;
+        stats.area_requests = self.area_requests.clone();
+        stats
+    }
+
+    /// ### Zeroes the traffic counters
+    ///
+    pub fn reset_traffic_stats(&mut self) {
+        self.frame_counters.reset();
+        self.traffic_errors = 0;
+        self.area_requests.clear();
+    }
+
+    /// ### Enables or disables the per-chunk report of the transfers
+    ///
+    /// When enabled, `read_area()`, `write_area()` and the methods based on them record the
+    /// offset, the size and the duration of each chunk, see `last_transfer_report()`.
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to record the reports, false (Default) to disable and discard them.
+    ///
+    pub fn set_transfer_report(&mut self, enabled: bool) {
+        self.transfer_reports = enabled;
+        self.transfer_report = None;
+    }
+
+    /// ### Returns the per-chunk report of the last `read_area()`/`write_area()`
+    ///
+    /// ### Returns
+    /// `None` if the reports are disabled (see `set_transfer_report()`) or no transfer was done yet.
+    ///
+    /// ### Notes
+    /// - On error the report is kept: `completed` is false and the last chunk is the failed one, its
+    ///   time measured up to the error (e.g. the read timeout).
+    /// - A read served by the read cache gives a completed report without chunks.
+    /// - `chunks` and `last_time` are still updated, the report is their detailed counterpart.
+    ///
+    pub fn last_transfer_report(&self) -> Option<&TransferReport> {
+        self.transfer_report.as_ref()
+    }
+
+    /// Starts the report of a transfer, if enabled
+    fn begin_transfer_report(&mut self) {
+        self.transfer_report = self.transfer_reports.then(TransferReport::default);
+    }
+
+    /// Closes the report of a transfer
+    fn finish_transfer_report(&mut self, completed: bool) {
+        if let Some(report) = self.transfer_report.as_mut() {
+            report.finish(completed);
+        }
+    }
+
+    /// ### Sets the address read by `health_check()`
+    ///
+    /// By default `health_check()` measures the round-trip of the CPU status query. With a heartbeat
+    /// address, the round-trip is measured on a 1-byte read of that address, i.e. on the same path
+    /// of the process data.
+    ///
+    /// ### Parameters
+    /// - `address`: `Some((area, db_number, byte))`, or `None` to remove it.
+    ///
+    pub fn set_heartbeat_address(&mut self, address: Option<(u8, u16, u32)>) {
+        self.heartbeat = address;
+    }
+
+    /// ### Checks the connection health
+    ///
+    /// Performs a minimal transaction (the CPU status query, preceded by a 1-byte read if a heartbeat
+    /// address is set) and reports the round-trip time and the operating mode of the CPU.
+    ///
+    /// ### Returns
+    /// `Ok(<HealthReport>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - Other reported by `read_area()` or `get_plc_status()`
+    ///
+    /// ### Notes
+    /// A failure here usually means the connection is no longer usable: disconnect and reconnect.
+    ///
+    pub fn health_check(&mut self) -> Result<HealthReport, S7Error> {
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+
+        let mut rtt = None;
+        if let Some((area, db_number, start)) = self.heartbeat {
+            let mut buffer = [0u8; 1];
+            let probe = Instant::now();
+            self.read_area(area, db_number, start, S7_WL_BYTE, &mut buffer)?;
+            rtt = Some(probe.elapsed());
+        }
+
+        let probe = Instant::now();
+        let status = self.get_plc_status()?;
+        let rtt = rtt.unwrap_or_else(|| probe.elapsed());
+
+        Ok(HealthReport { rtt, status })
+    }
+
+    /// ### Sets the TCP Connection Port
+    /// 
+    /// The default S7 Port is 102, but if you need NAT the addresses you can use this method to change the default value.
+    /// 
+    /// ### Parameters
+    /// - `port`: TCP Connection port (1..65535)
+    /// 
+    /// ### Returns
+    /// `Ok(())` on success, or an `S7Error` on failure.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParam`: Invalid parameter supplied to the function.
+    /// 
+    /// ### Notes
+    /// 1. Value must be > 0, otherwise it is ignored
+    /// 2. The client must not be connected (that is, call this method before connecting).
+    /// 
+    pub fn set_connection_port(&mut self, port: u16)  -> Result<(), S7Error> {
+
+        if port == 0 {
+            return Err(S7Error::InvalidFunParameter);
         }
 
         self.port = port;
@@ -317,6 +2023,23 @@ impl S7Client {
         Ok(())
     }
 
+    /// ### Enables the strict-parse diagnostics mode
+    ///
+    /// When enabled, every received telegram is validated field-by-field (TPKT, COTP, S7 header,
+    /// PDU reference, lengths, function, transport size...) and the first inconsistency is reported as
+    /// `S7Error::MalformedTelegram`, containing the offset, the field name, the expected and received
+    /// values and an hex dump of the telegram.
+    ///
+    /// It's disabled by default: Siemens CPUs are consistent, but it's essential when talking to
+    /// semi-compatible devices (LOGO!, third-party S7 slaves) to understand why a telegram is rejected.
+    ///
+    /// ### Parameters
+    /// - `enabled`: true to enable the strict validation.
+    ///
+    pub fn set_strict_parse(&mut self, enabled: bool) {
+        self.strict_parse = enabled;
+    }
+
     /// ### Connects to the S71200 or S71500 families
     ///
     /// This helper method is same as `connect_rack_slot()` with rack=0 and slot=0
@@ -362,11 +2085,28 @@ impl S7Client {
     /// 
     pub fn connect_rack_slot(&mut self, ip: &str, rack: u16, slot: u16) -> Result<(), S7Error> {
 
-        let local_tsap: u16 = 0x0100;
-        let remote_tsap: u16 = (self.conn_type << 8) + (rack * 0x20) + slot;        
+        let (local_tsap, remote_tsap) = self.rack_slot_tsaps(rack, slot);
         self.connect_tsap(ip, local_tsap, remote_tsap)
     }
 
+    /// ### Returns the (local, remote) TSAPs addressing the CPU in `rack`/`slot`
+    ///
+    pub(crate) fn rack_slot_tsaps(&self, rack: u16, slot: u16) -> (u16, u16) {
+        (0x0100, (self.conn_type << 8) + (rack * 0x20) + slot)
+    }
+
+    /// ### Returns the TCP port used by the connection methods
+    ///
+    pub(crate) fn connection_port(&self) -> u16 {
+        self.port
+    }
+
+    /// ### Returns the connection timeout
+    ///
+    pub(crate) fn connection_timeout(&self) -> Duration {
+        Duration::from_millis(self.co_timeout_ms)
+    }
+
     /// ### Connects to a Siemens ISO-Hardware using TSAP records
     ///
     /// This is the deepest connection method, you will need it only to connect to LOGO! or S7200.
@@ -378,125 +2118,525 @@ impl S7Client {
     /// - `remote_tsap` : Server TSAP (PLC).
     /// 
     /// ### Notes
-    ///     The connection port used is 102 (S7Protocol Port) unless you
-    ///     changed it via set_connection_port()
+    /// The connection port used is 102 (S7Protocol Port) unless you
+    /// changed it via set_connection_port()
+    ///
+    /// The client requests a 480 byte PDU, if the CPU rejects it a 240 byte PDU is requested.
+    ///
+    /// ### Returns
+    /// `Ok(())` on success, or an `S7Error` on failure.
+    ///
+    /// ### Errors
+    /// - `S7Error::TcpConnectionFailed`: TCP connection could not be established.
+    /// - `S7Error::IsoConnectionFailed`: ISO connection failed
+    /// - `S7Error::PduNegotiationFailed`: PDU negotiation failed (with all the requested sizes).
+    /// - `S7Error::Io`: network I/O error.
+    /// 
+    pub fn connect_tsap(&mut self, ip: &str, local_tsap: u16, remote_tsap: u16) -> Result<(), S7Error> {
+        self.connect_tsap_raw(ip, &local_tsap.to_be_bytes(), &remote_tsap.to_be_bytes())
+    }
+
+    /// ### Connects to a CPU through an S7 router
+    ///
+    /// The client connects to the gateway station (a CPU or CP with routing capability, reachable
+    /// via TCP/IP) which routes the connection to the destination CPU in another subnet
+    /// (PROFIBUS, MPI or another Industrial Ethernet).
+    ///
+    /// ### Parameters
+    /// - `ip` : IPV4 address of the gateway.
+    /// - `rack` : Rack of the gateway CPU/CP.
+    /// - `slot` : Slot of the gateway CPU/CP.
+    /// - `routing` : Destination subnet and CPU.
+    ///
+    /// ### Notes
+    /// - Routing must be enabled in the hardware configuration of the gateway (NetPro/TIA), and the
+    ///   subnet ID must match the one of the destination subnet.
+    /// - The connection type (`set_connection_type()`) applies to both ends.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Address length other than 1 or 4, rack > 7 or slot > 31.
+    /// - `S7Error::IsoConnectionFailed`: The gateway refused the connection (e.g. routing not configured).
+    /// - Other reported by `connect_tsap()`
+    ///
+    pub fn connect_routed(&mut self, ip: &str, rack: u16, slot: u16, routing: &S7Routing) -> Result<(), S7Error> {
+        let (local_tsap, remote_tsap) = routing.tsaps(self.conn_type, rack, slot)?;
+        self.connect_tsap_raw(ip, &local_tsap, &remote_tsap)
+    }
+
+    /// ### Connects using TSAPs of any length
+    ///
+    /// Same as `connect_tsap()`, but the TSAPs are byte strings: some third-party ISO-on-TCP devices
+    /// and simulators require TSAPs longer than 2 byte or ASCII TSAPs, e.g. `b"SIMATIC-ROOT-HMI"`.
+    ///
+    /// ### Parameters
+    /// - `ip` : PLC IPV4 address.
+    /// - `local_tsap` : Client TSAP (1..255 byte).
+    /// - `remote_tsap` : Server TSAP (1..255 byte).
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Empty TSAP, or the TSAPs don't fit into the Connection Request
+    ///   (max 241 byte together).
+    /// - Other reported by `connect_tsap()`
+    ///
+    pub fn connect_tsap_raw(&mut self, ip: &str, local_tsap: &[u8], remote_tsap: &[u8]) -> Result<(), S7Error> {
+        if local_tsap.is_empty() || remote_tsap.is_empty() {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        self.endpoint = Some((ip.to_string(), local_tsap.to_vec(), remote_tsap.to_vec()));
+        s7_debug!("connecting to {}:{}", ip, self.connection_port());
+        let result = self.open_connection(ip, local_tsap, remote_tsap);
+        if let Err(error) = &result {
+            s7_debug!("connection to {} failed: {}", ip, error);
+        }
+        result
+    }
+
+    /// ### TCP connection (through the proxy if set) followed by the ISO handshake
+    ///
+    fn open_connection(&mut self, ip: &str, local_tsap: &[u8], remote_tsap: &[u8]) -> Result<(), S7Error> {
+        self.connected = false;
+        self.last_time = 0.0;
+        self.negotiation = NegotiationInfo::default();
+        let start_time = Instant::now();      
+        
+        let stream = match self.proxy.clone() {
+            Some(proxy) => {
+                let mut stream = self.tcp_connect(&proxy.address_with_port())?;
+                // The handshake is bounded by the connection timeout
+                stream.set_read_timeout(Some(self.connection_timeout()))?;
+                stream.set_write_timeout(Some(self.connection_timeout()))?;
+                socks5_connect(&mut stream, ip, self.connection_port(), proxy.auth.as_ref())?;
+                stream
+            }
+            None => self.tcp_connect(&format!("{}:{}", ip, self.connection_port()))?,
+        };
+        stream.set_nodelay(self.nodelay)?;
+        if self.linger.is_some() {
+            SockRef::from(&stream).set_linger(self.linger)?;
+        }
+
+        let mut transport: Box<dyn Transport> = Box::new(stream);
+        if let Some(wrapper) = self.transport_wrapper.as_mut() {
+            transport = wrapper(transport)?;
+        }
+
+        self.iso_connect(transport, local_tsap, remote_tsap, start_time)
+    }
+
+    /// ### Opens the TCP connection to `addr` ("host:port") with the socket options set
+    ///
+    fn tcp_connect(&self, addr: &str) -> Result<TcpStream, S7Error> {
+        let co_timeout = self.connection_timeout();
+
+        let sock_addr = addr.to_socket_addrs()?.next().ok_or(S7Error::TcpConnectionFailed)?;
+        let socket = Socket::new(Domain::for_address(sock_addr), Type::STREAM, Some(Protocol::TCP))?;
+
+        // Buffer sizes must be set before connecting to be considered in the TCP window negotiation
+        if self.rcv_buf_size > 0 {
+            socket.set_recv_buffer_size(self.rcv_buf_size)?;
+        }
+        if self.snd_buf_size > 0 {
+            socket.set_send_buffer_size(self.snd_buf_size)?;
+        }
+
+        socket.connect_timeout(&sock_addr.into(), co_timeout)?;
+        Ok(socket.into())
+    }
+
+    /// ### Connects to a PLC through a user supplied transport
+    ///
+    /// Same as `connect_tsap()`, but the ISO-on-TCP telegrams go through `transport` instead of a
+    /// TCP connection created by the client (e.g. a tunnel, a proxy, a recording...).
+    ///
+    /// ### Parameters
+    /// - `transport` : An already open byte stream to the PLC.
+    /// - `local_tsap` : Client TSAP.
+    /// - `remote_tsap` : Server TSAP (PLC).
+    ///
+    /// ### Returns
+    /// `Ok(())` on success, or an `S7Error` on failure.
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoConnectionFailed`: ISO connection failed
+    /// - `S7Error::PduNegotiationFailed`: PDU negotiation failed.
+    /// - `S7Error::Io`: I/O error of the transport.
+    ///
+    pub fn connect_transport(&mut self, transport: Box<dyn Transport>, local_tsap: u16, remote_tsap: u16) -> Result<(), S7Error> {
+        self.endpoint = None; // A user transport can't be reopened
+        self.connected = false;
+        self.last_time = 0.0;
+        self.negotiation = NegotiationInfo::default();
+        let start_time = Instant::now();
+
+        s7_debug!("connecting through a user transport");
+        let result = self.iso_connect(transport, &local_tsap.to_be_bytes(), &remote_tsap.to_be_bytes(), start_time);
+        if let Err(error) = &result {
+            s7_debug!("connection through the user transport failed: {}", error);
+        }
+        result
+    }
+
+    /// ### Routes the connections through a SOCKS5 proxy
+    ///
+    /// The client dials the proxy, which opens the TCP connection to the PLC; the S7 layers are
+    /// unaffected.
+    ///
+    /// ### Parameters
+    /// - `addr`: Proxy address, "host:port" or "[IPv6]:port" (the port defaults to 1080).
+    /// - `auth`: Username/password, or `None` if the proxy doesn't require authentication.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Empty address.
+    ///
+    /// ### Notes
+    /// 1. The PLC address passed to the connection methods is resolved by the proxy if it's a
+    ///    host name, so names known only to the jump host can be used.
+    /// 2. The socket options (buffer sizes, TCP_NODELAY, linger...) apply to the connection to
+    ///    the proxy, the connection timeout bounds the proxy handshake too.
+    /// 3. `connect_transport()` doesn't use the proxy.
+    ///
+    pub fn set_proxy(&mut self, addr: &str, auth: Option<ProxyAuth>) -> Result<(), S7Error> {
+        if addr.is_empty() {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        self.proxy = Some(Socks5Proxy {
+            address: addr.to_string(),
+            auth,
+        });
+        Ok(())
+    }
+
+    /// ### Removes the proxy set by `set_proxy()`: the next connections are direct
+    ///
+    pub fn clear_proxy(&mut self) {
+        self.proxy = None;
+    }
+
+    /// ### Decorates the transport created by the connection methods
+    ///
+    /// The wrapper receives the TCP stream (already connected and configured) and returns the
+    /// transport the client will use, e.g. `RecordingTransport` to capture the traffic.
     ///
-    /// ### Returns
-    /// `Ok(())` on success, or an `S7Error` on failure.
+    /// ### Parameters
+    /// - `wrapper` : The decorating function, or `None` to remove it.
     ///
-    /// ### Errors
-    /// - `S7Error::TcpConnectionFailed`: TCP connection could not be established.
-    /// - `S7Error::IsoConnectionFailed`: ISO connection failed
-    /// - `S7Error::PduNegotiationFailed`: PDU negotiation failed.
-    /// - `S7Error::Io`: network I/O error.
-    /// 
-    pub fn connect_tsap(&mut self, ip: &str, local_tsap: u16, remote_tsap: u16) -> Result<(), S7Error> {
-   
-        self.connected = false;
-        self.last_time = 0.0;
-        let start_time = Instant::now();      
-        
-        let addr = format!("{}:{}", ip, self.port);
-        let co_timeout = Duration::from_millis(self.co_timeout_ms);
-        let rd_timeout = Duration::from_millis(self.rd_timeout_ms);
-        let wr_timeout = Duration::from_millis(self.wr_timeout_ms);
+    /// ### Notes
+    /// The wrapper is called at each connection.
+    ///
+    pub fn set_transport_wrapper(&mut self, wrapper: Option<TransportWrapper>) {
+        self.transport_wrapper = wrapper;
+    }
 
-        let mut stream = TcpStream::connect_timeout(&addr.to_socket_addrs()?.next().ok_or(S7Error::TcpConnectionFailed)?, co_timeout)?;
-        
-        stream.set_read_timeout(Some(rd_timeout))?;
-        stream.set_write_timeout(Some(wr_timeout))?;
-        stream.set_nodelay(true)?;
-        
+    /// ### ISO-on-TCP handshake and S7 PDU negotiation over an open transport
+    ///
+    fn iso_connect(&mut self, stream: Box<dyn Transport>, local_tsap: &[u8], remote_tsap: &[u8], start_time: Instant) -> Result<(), S7Error> {
+        let mut stream: Box<dyn Transport> = Box::new(CountingTransport::new(stream, self.frame_counters.clone()));
+        stream.set_read_timeout(Some(Duration::from_millis(self.rd_timeout_ms)))?;
+        self.eff_rd_timeout_ms = self.rd_timeout_ms;
+        stream.set_write_timeout(Some(Duration::from_millis(self.wr_timeout_ms)))?;
 
         // ISO-on-TCP handshake
-        let iso_cr: [u8; ISO_CR_LEN] = [
-		    // TPKT (RFC1006 Header)
-            ISO_ID, // RFC 1006 ID (3) 
-            0x00,   // Reserved, always 0
-            hi_part!(ISO_CR_LEN), // High part of packet lenght (entire frame, payload and TPDU included)
-            lo_part!(ISO_CR_LEN), // Low part of packet lenght (entire frame, payload and TPDU included)
-            // COTP (ISO 8073 Header)
-            0x11, // PDU Size Length
-            ISO_CONN_REQ, // CR - Connection Request ID
-            0x00, // Dst Reference HI
-            0x00, // Dst Reference LO
-            0x00, // Src Reference HI
-            0x01, // Src Reference LO
-            0x00, // Class + Options Flags
-            0xC0, // PDU Max Length ID
-            0x01, // PDU Max Length HI
-            0x0A, // PDU Max Length LO
-            0xC1, // Src TSAP Identifier
-            0x02, // Src TSAP Length (2 bytes)
-            hi_part!(local_tsap), // Loc TSAP HI 
-            lo_part!(local_tsap), // Loc TSAP LO 
-            0xC2, // Rem TSAP Identifier
-            0x02, // Rem TSAP Length (2 bytes)
-            hi_part!(remote_tsap), // Rem TSAP HI 
-            lo_part!(remote_tsap)  // Rem TSAP LO 
-        ];
-        
-        stream.write_all(&iso_cr)?;
-
-        let mut iso_resp = [0u8; ISO_CR_LEN];
+        let mut params = IsoConnectParams::new(local_tsap.to_vec(), remote_tsap.to_vec());
+        params.local_ref = self.cotp_local_ref;
+        params.tpdu_size_code = self.tpdu_size_code;
+        let mut request = params.connection_request();
+        if let Some(hook) = self.cotp_request_hook.as_mut() {
+            hook(&mut request);
+        }
+        iso_tcp::send_connect_request(stream.as_mut(), &request)?;
 
-        let size_resp = stream.read(&mut iso_resp)?;
+        let iso_resp = iso_tcp::read_packet(stream.as_mut())?;
 
-        if size_resp < ISO_CR_LEN || iso_resp[5] != ISO_CONN_OK {
-            return Err(S7Error::IsoConnectionFailed);
+        if self.strict_parse {
+            let parser = StrictParser::new(&iso_resp);
+            parser.min_len(TPKT_LEN + 7, "COTP connection confirm")?;
+            parser.byte(4, "COTP length indicator", (iso_resp.len() - TPKT_LEN - 1) as u8)?;
+            parser.byte(5, "COTP PDU type", COTP_CC)?;
         }
 
-        // S7 PDU Negotiation Telegram (contains also ISO Header and COTP Header)
-        let s7_pn: [u8; ISO_PN_REQ_LEN] = [
-            ISO_ID, 
-            0x00, 
-            0x00, 0x19, 
-            0x02, 0xf0, 0x80, 
-            S7_ID, 0x01, 0x00, 0x00, 0x04, 0x00, 0x00, 0x08, 0x00, 
-            0x00, 0xf0, 0x00, 0x00, 0x01, 0x00, 0x01, 
-            hi_part!(PDU_LEN_REQ),
-            lo_part!(PDU_LEN_REQ)
-        ];
-        stream.write_all(&s7_pn)?;
-        let mut pn_resp = [0u8; ISO_PN_RES_LEN];
-        
-        let size_pn = stream.read(&mut pn_resp)?;
-        
-        if size_pn < ISO_PN_RES_LEN || pn_resp[0] != ISO_ID || pn_resp[7] != S7_ID || pn_resp[17] != 0x00 {
-            return Err(S7Error::PduNegotiationFailed);
-        }
+        let iso_cc = iso_tcp::parse_connect_confirm(&iso_resp)?;
 
-        self.pdu_length = make_u16!(pn_resp[25], pn_resp[26]);
-       
-        if self.pdu_length == 0 {
-            return Err(S7Error::PduNegotiationFailed);
+        self.negotiation.cotp_local_ref = iso_cc.dst_ref;
+        self.negotiation.cotp_remote_ref = iso_cc.src_ref;
+        // The TPDU size parameter is optional into the CC, if missing the requested one applies
+        let tpdu_code = if iso_cc.tpdu_size != 0 { iso_cc.tpdu_size } else { self.tpdu_size_code };
+        self.negotiation.tpdu_size = 1usize.checked_shl(tpdu_code as u32).unwrap_or(0);
+
+        // S7 PDU Negotiation: very old CPUs reject 480 byte, the smaller sizes are tried in sequence
+        let mut negotiation = Err(S7Error::PduNegotiationFailed);
+        for pdu_length in PDU_LEN_FALLBACK {
+            negotiation = self.negotiate_pdu(stream.as_mut(), pdu_length);
+            if !matches!(negotiation, Err(S7Error::PduNegotiationFailed)) {
+                break;
+            }
+            s7_debug!("PDU of {} byte rejected by the CPU", pdu_length);
         }
+        let setup = negotiation?;
+
+        self.pdu_length = setup.pdu_length;
+        self.negotiation.pdu_length = setup.pdu_length;
+        self.negotiation.max_amq_calling = setup.max_amq_calling;
+        self.negotiation.max_amq_called = setup.max_amq_called;
         self.max_rd_pdu_data = self.pdu_length - 18; // 18 = S7 Response frame header
         self.max_wr_pdu_data = self.pdu_length - 28; // 28 = S7 Request frame header
 
         self.stream = Some(stream);
         self.connected = true;
+        self.connection_id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+        self.stale_responses = false;
         self.last_time = start_time.elapsed().as_secs_f64() * 1000.0;
+        s7_debug!("connected in {:.1} ms: PDU {} byte (requested {}), TPDU {} byte, max AMQ {}/{}",
+            self.last_time, self.pdu_length, self.negotiation.pdu_length_requested, self.negotiation.tpdu_size,
+            setup.max_amq_calling, setup.max_amq_called);
 
         Ok(())
     }
 
+    /// ### S7 PDU negotiation (Setup Communication) requesting `pdu_length` byte
+    ///
+    /// ### Errors
+    /// - `S7Error::PduNegotiationFailed`: The CPU rejected the request (error class or null PDU length)
+    ///   or the response is malformed.
+    /// - Transport errors, which end the negotiation.
+    ///
+    fn negotiate_pdu(&mut self, stream: &mut dyn Transport, pdu_length: u16) -> Result<S7SetupComm, S7Error> {
+        // S7 PDU Negotiation Telegram (contains also ISO Header and COTP Header)
+        let mut s7_pn = Vec::with_capacity(ISO_PN_REQ_LEN);
+        TpktHeader { length: ISO_PN_REQ_LEN as u16 }.encode(&mut s7_pn);
+        CotpData { eot: true }.encode(&mut s7_pn);
+        S7Header::job(0x0400, S7SetupComm::LEN as u16, 0).encode(&mut s7_pn);
+        S7SetupComm {
+            max_amq_calling: 1,
+            max_amq_called: 1,
+            pdu_length,
+        }.encode(&mut s7_pn);
+
+        stream.write_all(&s7_pn)?;
+        let pn_resp = iso_tcp::read_packet(stream)?;
+        let size_pn = pn_resp.len();
+
+        if size_pn < ISO_PN_RES_LEN || TpktHeader::decode(&pn_resp).is_err() {
+            return Err(S7Error::PduNegotiationFailed);
+        }
+
+        let pn_header = S7Header::decode(&pn_resp[TPKT_ISO_LEN..]).map_err(|_| S7Error::PduNegotiationFailed)?;
+
+        if pn_header.error_class != 0x00 {
+            return Err(S7Error::PduNegotiationFailed);
+        }
+
+        if self.strict_parse {
+            let parser = StrictParser::new(&pn_resp);
+            parser.ack_data(0x0400, FN_SETUP_COMM)?;
+            parser.word(TPKT_ISO_LEN + 6, "S7 parameters length", S7SetupComm::LEN as u16)?;
+        }
+
+        let setup = S7SetupComm::decode(&pn_resp[TPKT_ISO_LEN + S7_ACK_HEADER_LEN..])?;
+        if setup.pdu_length == 0 {
+            return Err(S7Error::PduNegotiationFailed);
+        }
+        self.negotiation.pdu_length_requested = pdu_length;
+        Ok(setup)
+    }
+
     /// ### Closes the connection.
     ///
     /// Safe to call even if the client is not currently connected.
     /// After disconnection, calls to read/write will return `S7Error::NotConnected`.
     /// 
     /// ### Notes
-    ///     A Client should be disconnected on low-level error (see read_area() and write_area() suggestion)
+    /// A Client should be disconnected on low-level error (see read_area() and write_area() suggestion)
     /// 
     pub fn disconnect(&mut self) {
-        if self.connected {
-            // If we are disconnecting on a low-level error it's better to flush the socket
-            let stream = self.stream.as_mut().unwrap();
-            let _ = stream.shutdown(Shutdown::Both);
-            self.stream = None;
-            self.connected = false;
+        let close_timeout = Duration::from_millis(self.close_timeout_ms);
+        if let Err(error) = self.release(self.send_cotp_dr, close_timeout) {
+            s7_debug!("disconnect: {}", error);
+        }
+    }
+
+    /// ### Closes the connection reporting the errors
+    ///
+    /// Same as `disconnect()` (COTP DR and graceful close as configured), for the callers that
+    /// need to know whether the connection was closed cleanly.
+    ///
+    /// ### Returns
+    /// `Ok(())` if the connection was closed cleanly (or the client was not connected), otherwise
+    /// the first error occurred.
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The COTP DR or the shutdown failed, or the PLC didn't close its side within
+    ///   the graceful close timeout (`TimedOut`).
+    ///
+    /// ### Notes
+    /// The client is disconnected in any case.
+    ///
+    pub fn close(&mut self) -> Result<(), S7Error> {
+        let close_timeout = Duration::from_millis(self.close_timeout_ms);
+        self.release(self.send_cotp_dr, close_timeout)
+    }
+
+    /// ### Releases the connection
+    ///
+    /// Every step is performed also after an error, the first one is returned.
+    ///
+    fn release(&mut self, send_cotp_dr: bool, close_timeout: Duration) -> Result<(), S7Error> {
+        let mut result = Ok(());
+        if let Some(mut stream) = self.stream.take().filter(|_| self.connected) {
+            if send_cotp_dr {
+                result = iso_tcp::send_disconnect(stream.as_mut(), self.negotiation.cotp_remote_ref, self.cotp_local_ref);
+            }
+            if !close_timeout.is_zero() {
+                result = result.and(graceful_close(stream.as_mut(), close_timeout));
+            }
+            match stream.shutdown(Shutdown::Both) {
+                // Already closed by the graceful close
+                Err(error) if error.kind() == io::ErrorKind::NotConnected => {}
+                shutdown => result = result.and(shutdown.map_err(S7Error::Io)),
+            }
+            s7_debug!("disconnected");
+        }
+        self.stream = None;
+        self.connected = false;
+        self.invalidate_read_cache();
+        result
+    }
+
+    /// ### Closes the connection and connects again to the same PLC
+    ///
+    /// The address and the TSAPs are the ones of the last `connect_XXX()` call; the current
+    /// settings (timeouts, PDU size, proxy, transport wrapper...) are applied.
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: No previous connection, or the last one was made by `connect_transport()`.
+    /// - Reported by `connect_tsap_raw()`
+    ///
+    pub fn reconnect(&mut self) -> Result<(), S7Error> {
+        let (ip, local_tsap, remote_tsap) = self.endpoint.clone()
+            .ok_or_else(|| S7Error::Other("reconnect: no TCP connection to restore".to_string()))?;
+        s7_debug!("reconnecting to {}", ip);
+        self.disconnect();
+        let result = self.connect_tsap_raw(&ip, &local_tsap, &remote_tsap);
+        if let Some(observer) = self.observer.as_ref() {
+            observer.on_reconnect(&ip, result.as_ref().copied());
+        }
+        result
+    }
+
+    /// ### Returns the configuration of the client
+    ///
+    /// The snapshot includes the endpoint of the last TCP connection, so a client created by
+    /// `from_config()` reaches the same PLC with `reconnect()`.
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::S7Client;
+    ///
+    /// let mut client = S7Client::new();
+    /// client.set_timeout(3000, 2000, 500).unwrap();
+    /// client.connect_s71200_1500("192.168.0.100").unwrap();
+    ///
+    /// let config = client.config();
+    /// let mut second = S7Client::from_config(&config);
+    /// second.reconnect().unwrap(); // Same PLC, same settings
+    /// ```
+    ///
+    pub fn config(&self) -> S7ClientConfig {
+        S7ClientConfig {
+            endpoint: self.endpoint.clone(),
+            proxy: self.proxy.clone(),
+            port: self.port,
+            co_timeout_ms: self.co_timeout_ms,
+            rd_timeout_ms: self.rd_timeout_ms,
+            wr_timeout_ms: self.wr_timeout_ms,
+            rd_timeout_per_kb_ms: self.rd_timeout_per_kb_ms,
+            nodelay: self.nodelay,
+            rcv_buf_size: self.rcv_buf_size,
+            snd_buf_size: self.snd_buf_size,
+            linger: self.linger,
+            close_timeout_ms: self.close_timeout_ms,
+            send_cotp_dr: self.send_cotp_dr,
+            drop_behavior: self.drop_behavior,
+            cotp_local_ref: self.cotp_local_ref,
+            tpdu_size_code: self.tpdu_size_code,
+            heartbeat: self.heartbeat,
+            min_request_gap: self.min_request_gap,
+            conn_type: self.conn_type,
+            max_chunk: self.max_chunk,
+            chunk_alignment: self.chunk_alignment,
+            chunk_retries: self.chunk_retries,
+            verify_cpu_stop: self.verify_cpu_stop,
+            diagnose_address: self.diagnose_address,
+            check_write_protection: self.check_write_protection,
+            dry_run: self.dry_run,
+            error_context: self.error_context,
+            strict_parse: self.strict_parse,
+            transfer_reports: self.transfer_reports,
+            read_cache_ttl: self.read_cache.as_ref().map(|cache| cache.ttl()),
+            observer: self.observer.clone(),
+        }
+    }
+
+    /// ### Creates a new client with the given configuration
+    ///
+    /// The client starts disconnected: use `reconnect()` to connect to the endpoint stored in the
+    /// configuration, or any `connect_XXX()` method to reach another PLC with the same settings.
+    ///
+    /// ### Parameters
+    /// - `config`: Configuration returned by `config()`.
+    ///
+    /// ### Notes
+    /// Counters, statistics and the content of the read cache are not copied.
+    ///
+    pub fn from_config(config: &S7ClientConfig) -> S7Client {
+        let mut client = S7Client::new();
+        client.endpoint = config.endpoint.clone();
+        client.proxy = config.proxy.clone();
+        client.port = config.port;
+        client.co_timeout_ms = config.co_timeout_ms;
+        client.rd_timeout_ms = config.rd_timeout_ms;
+        client.wr_timeout_ms = config.wr_timeout_ms;
+        client.rd_timeout_per_kb_ms = config.rd_timeout_per_kb_ms;
+        client.nodelay = config.nodelay;
+        client.rcv_buf_size = config.rcv_buf_size;
+        client.snd_buf_size = config.snd_buf_size;
+        client.linger = config.linger;
+        client.close_timeout_ms = config.close_timeout_ms;
+        client.send_cotp_dr = config.send_cotp_dr;
+        client.drop_behavior = config.drop_behavior;
+        client.cotp_local_ref = config.cotp_local_ref;
+        client.tpdu_size_code = config.tpdu_size_code;
+        client.heartbeat = config.heartbeat;
+        client.min_request_gap = config.min_request_gap;
+        client.conn_type = config.conn_type;
+        client.max_chunk = config.max_chunk;
+        client.chunk_alignment = config.chunk_alignment;
+        client.chunk_retries = config.chunk_retries;
+        client.verify_cpu_stop = config.verify_cpu_stop;
+        client.diagnose_address = config.diagnose_address;
+        client.check_write_protection = config.check_write_protection;
+        client.dry_run = config.dry_run;
+        client.error_context = config.error_context;
+        client.strict_parse = config.strict_parse;
+        client.transfer_reports = config.transfer_reports;
+        client.set_read_cache(config.read_cache_ttl);
+        client.observer = config.observer.clone();
+        client
+    }
+
+    /// ### Creates a new client with the same configuration, connected to the same PLC
+    ///
+    /// Shortcut for `from_config(&self.config())` followed by `reconnect()` when this client is
+    /// connected by TCP; otherwise the new client is returned disconnected.
+    ///
+    /// ### Errors
+    /// - Reported by `reconnect()`
+    ///
+    pub fn duplicate(&self) -> Result<S7Client, S7Error> {
+        let mut client = S7Client::from_config(&self.config());
+        if self.connected && self.endpoint.is_some() {
+            client.reconnect()?;
         }
+        Ok(client)
     }
 
     /// ### Reads a block of data from a specific S7 memory area.
@@ -520,7 +2660,8 @@ impl S7Client {
     /// #### Bit access notes
     /// 1. The start must be expressed in bits.
     ///    For example, if you want to access bit `DBX 45.3`, the start value would be 45 * 8 + 3 = 363.
-    /// 2. Whatever buffer is passed, only the first byte will be used, which is considered true if !=0 or false if ==0
+    /// 2. The buffer must be 1 byte long, the bit is considered true if !=0 or false if ==0
+    ///    (use `read_bits()` to read several consecutive bits)
     /// 
     /// ### Returns
     /// `Ok(())` Operation succeeded.
@@ -531,7 +2672,13 @@ impl S7Client {
     /// - `S7Error::IsoInvalidHeader`: Invalid ISO Header
     /// - `S7Error::IsoInvalidTelegram`: Inconsistent expected telegram length.
     /// - `S7Error::IsoFragmentedPacket`: ISO Packet fragmented.
+    /// - `S7Error::MalformedTelegram`: The response doesn't carry exactly the bytes requested.
     /// - `S7Error::S7Unspecified`: Unknown S7 Error.
+    /// - `S7Error::AccessDenied`: Access to the object not allowed (e.g. write-protected DB).
+    /// - `S7Error::DataTypeMismatch`: The CPU doesn't support the transport size requested.
+    /// - `S7Error::WrongDataSize`: The data length doesn't match the request.
+    /// - `S7Error::CpuStopped`: The CPU refused the job because it's in STOP (see `set_verify_cpu_stop()`).
+    /// - `S7Error::OptimizedDb`, `S7Error::BeyondDbLength`: Diagnosis of `S7InvalidAddress` (see `set_diagnose_address_errors()`).
     /// - `S7Error::Io`: network I/O error.
     ///
     /// #### Suggestion
@@ -539,6 +2686,7 @@ impl S7Client {
     /// 
     /// #### High level
     /// - `S7Error::InvalidFunParam`: Invalid parameter supplied to the function.
+    /// - `S7Error::SizeMismatch`: Buffer beyond the last byte addressable (2 MB), or not 1 byte long for a bit access.
     /// - `S7Error::NotFound`: The resource was not found (e.g. Inexistent DB).
     /// - `S7Error::S7InvalidAddress`:
     /// 1. Attempt to read beyond the limits.
@@ -549,13 +2697,109 @@ impl S7Client {
     /// - Large blocks are automatically split into chunks based on the negotiated PDU size.
     /// - In case of error the buffer contents will be inconsistent and should not be considered.
     /// 
-    pub fn read_area(&mut self, area: u8, db_number: u16, start: u16, wordlen: u8, buffer: &mut [u8]) -> Result<(), S7Error> {
+    pub fn read_area(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &mut [u8]) -> Result<(), S7Error> {
+        let size = buffer.len();
+        let result = self.with_retry(|client| client.read_area_once(area, db_number, start, wordlen, buffer));
+        self.with_context(result, |error| ErrorContext { operation: "read_area", area, db_number, start, wordlen, size, error })
+    }
+
+    /// ### Reads a block of data into an uninitialized buffer
+    ///
+    /// Same as `read_area()`, for buffers allocated without zeroing (e.g. arenas reused at each
+    /// read): the memset of large buffers before each read is avoided.
+    ///
+    /// ### Parameters
+    /// - `buffer`: Destination, `buffer.len()` elements are read.
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)`: number of bytes initialized from the start of the buffer (`buffer.len()`).
+    ///
+    /// ### Errors
+    /// - See `read_area()`. After an error the buffer must be considered uninitialized.
+    ///
+    /// ### Notes
+    /// The read cache serves these reads but is not filled by them.
+    ///
+    pub fn read_area_uninit(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &mut [MaybeUninit<u8>]) -> Result<usize, S7Error> {
+        let size = buffer.len();
+        let result = self.with_retry(|client| client.read_area_once(area, db_number, start, wordlen, buffer));
+        self.with_context(result, |error| ErrorContext { operation: "read_area", area, db_number, start, wordlen, size, error })?;
+        Ok(size)
+    }
+
+    /// ### Reads a block of data appending it to a `Vec`
+    ///
+    /// Same as `read_area()`, the data is appended to `buffer` as it arrives, so a `Vec` reused
+    /// with `clear()` is neither reallocated nor zeroed.
+    ///
+    /// ### Parameters
+    /// - `size`: Number of elements to read.
+    /// - `buffer`: Destination, `size` bytes are appended.
+    ///
+    /// ### Errors
+    /// - See `read_area()`. After an error `buffer` is left as it was before the call.
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::{S7Client, S7_AREA_DB, S7_WL_BYTE};
+    ///
+    /// let mut client = S7Client::new();
+    /// client.connect_s71200_1500("192.168.0.100").unwrap();
+    /// let mut image = Vec::with_capacity(65535);
+    /// loop {
+    ///     image.clear();
+    ///     client.read_area_append(S7_AREA_DB, 100, 0, S7_WL_BYTE, 60000, &mut image).unwrap();
+    ///     // ... use image ...
+    /// }
+    /// ```
+    ///
+    pub fn read_area_append(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, size: usize, buffer: &mut Vec<u8>) -> Result<(), S7Error> {
+        let base = buffer.len();
+        let mut target = AppendTarget { vec: buffer, base, size };
+        let result = self.with_retry(|client| client.read_area_once(area, db_number, start, wordlen, &mut target));
+        if result.is_err() {
+            target.reset();
+        }
+        self.with_context(result, |error| ErrorContext { operation: "read_area", area, db_number, start, wordlen, size, error })
+    }
+
+    fn read_area_once<B: ReadTarget + ?Sized>(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &mut B) -> Result<(), S7Error> {
+        check_buffer_size(wordlen, start, buffer.size())?;
+        let size = buffer.size();
+        buffer.reset();
+        self.begin_transfer_report();
+        if let Some(cache) = self.read_cache.as_mut().filter(|_| size > 0) {
+            if let Some(data) = cache.lookup(area, db_number, start, wordlen, size) {
+                buffer.put(0, &data);
+                self.last_time = 0.0;
+                self.chunks = 0;
+                self.finish_transfer_report(true);
+                return Ok(());
+            }
+        }
+        let result = self.observe("read_area", size, |client| {
+            let result = client.read_area_job(area, db_number, start, wordlen, buffer);
+            client.count_result(&result);
+            let result = client.verify_cpu_stop(result);
+            client.diagnose_address(area, db_number, start, wordlen, size, result)
+        });
+        self.finish_transfer_report(result.is_ok());
+        if let (Ok(()), Some(cache), Some(data)) = (&result, self.read_cache.as_mut(), buffer.contents()) {
+            if size > 0 {
+                cache.insert(area, db_number, start, wordlen, data);
+            }
+        }
+        result
+    }
+
+    /// Reads `buffer.size()` elements (a byte per bit for bit access)
+    fn read_area_job<B: ReadTarget + ?Sized>(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &mut B) -> Result<(), S7Error> {
 
         self.last_time = 0.0;
         self.chunks = 0;
 
         // Check Area
-        let _ = self.check_area(area)?;
+        self.check_area(area)?;
 
         // Check Word Length
         if wordlen != S7_WL_BIT && wordlen != S7_WL_BYTE {
@@ -569,46 +2813,27 @@ impl S7Client {
       
         let start_time = Instant::now();
 
-        let datasize: u16 = if wordlen == S7_WL_BYTE {
-            buffer.len().min(u16::MAX as usize) as u16
-        } else {
-            1 // Only 1 element allowed for bit operations
-        };
+        let datasize = buffer.size();
+
+        self.apply_read_timeout(datasize)?;
 
+        let max_chunk = self.chunk_limit(self.max_rd_pdu_data, wordlen) as usize;
+        s7_trace!("read of {} element(s) from area 0x{:02X}: {} chunk(s) of max {}",
+            datasize, area, datasize.div_ceil(max_chunk.max(1)), max_chunk);
         let stream = self.stream.as_mut().unwrap();      
        
+        let mut pushed = Vec::new();
         let mut offset = 0;
-        let mut long_start: u32 = start as u32;
+        let mut long_start: u32 = start;
 
         while offset < datasize {
             let remaining = datasize - offset;
-            let chunk_size = remaining.min(self.max_rd_pdu_data);
+            let chunk_size = remaining.min(max_chunk);
             self.chunks+=1;
-
-            // Read Request Header
-            let mut request: [u8; READ_REQ_LEN] = [ 
-                ISO_ID, 0x00,         // RFC 1006 ID (constant)                   0
-                0x00, 0x1f,           // Telegram Length (31)                     2
-                0x02, 0xf0, 0x80,     // COPT (constant)                          4
-                S7_ID,                // S7 Protocol ID                           7
-                0x01,                 // Job Type (Data)                          8
-                0x00, 0x00,           // Redundancy identification                9
-                0x05, 0x00,           // PDU Reference                            11  
-                0x00, 0x0e,           // Parameters Length (HI,LO) = 14           13 
-                0x00, 0x00,           // No write Payload here : 0                15
-                0x04,                 // Function: 4 Read Var, 5 Write Var        17
-                0x01,                 // Items count (used for multivar R/W)      18
-                0x12,                 // Var spec.                                19
-                0x0a,                 // constant 0x0a                            20
-                0x10,                 // Syntax ID                                21
-                wordlen,              // WordLen                                  22 
-                hi_part!(chunk_size), // HI (Read Payload Size)                   23
-                lo_part!(chunk_size), // LO (Read Payload Size)                   24
-                hi_part!(db_number),  // HI DB Number                             25
-                lo_part!(db_number),  // LO DB Number                             26
-                area,                 // Area                                     27 
-                0x00, 0x00, 0x00      // 24 bit Address (see below)               28
-            ];
+            *self.area_requests.entry(area).or_insert(0) += 1;
+            if let Some(report) = self.transfer_report.as_mut() {
+                report.begin_chunk(offset, chunk_size);
+            }
 
             let address = if wordlen == S7_WL_BIT { 
                 long_start 
@@ -616,46 +2841,73 @@ impl S7Client {
                 long_start << 3 
             };
 
-            request[28] = ((address >> 16) & 0xFF) as u8;
-            request[29] = ((address >> 8) & 0xFF) as u8;
-            request[30] = (address & 0xFF) as u8;
-
-            stream.write_all(&request)?;
-            
-            // Read and check ISO header
-            let mut iso_packet = [0u8; TPKT_ISO_LEN];
-            stream.read_exact(&mut iso_packet)?;
-
-            let s7_comm_size = check_iso_packet(self.pdu_length, &mut iso_packet)?;
-
-            if s7_comm_size < READ_RES_LEN {
-                return Err(S7Error::IsoInvalidTelegram);
-            }
+            // Read Request Telegram
+            self.pdu_ref = self.pdu_ref.wrapping_add(1);
+            let pdu_ref = self.pdu_ref;
+            let mut request = Vec::with_capacity(READ_REQ_LEN);
+            TpktHeader { length: READ_REQ_LEN as u16 }.encode(&mut request);
+            CotpData { eot: true }.encode(&mut request);
+            S7Header::job(pdu_ref, RW_PARAMS_LEN, 0).encode(&mut request);
+            request.push(FN_READ_VAR);
+            request.push(0x01); // Items count (used for multivar R/W)
+            S7ItemSpec {
+                wordlen,
+                amount: chunk_size as u16,
+                db_number,
+                area,
+                address,
+            }.encode(&mut request);
 
-            // Read and check S7 Telegram
             let mut response = [0u8; PDU_LEN_REQ as usize];
-            let size_resp = stream.read(&mut response)?;
+            let mut retries = 0;
+            let (iso_packet, size_resp) = loop {
+                pace(self.min_request_gap, &mut self.last_request);
+                stream.write_all(&request)?;
+
+                match receive_chunk(stream.as_mut(), self.pdu_length, READ_RES_LEN, &mut response, &mut pushed, self.stale_responses.then_some(pdu_ref)) {
+                    // Only the timeouts after a complete request are retried
+                    Err(error) if is_timeout(&error) && retries < self.chunk_retries => {
+                        retries += 1;
+                        s7_debug!("chunk at offset {} timed out, retry {} of {}", offset, retries, self.chunk_retries);
+                        if let Some(report) = self.transfer_report.as_mut() {
+                            report.retry();
+                        }
+                        // The response to the first attempt may still arrive after the retry's one
+                        self.stale_responses = true;
+                        drain(stream.as_mut(), self.eff_rd_timeout_ms)?;
+                    }
+                    result => break result?,
+                }
+            };
+            self.stale_responses = retries > 0;
 
-            if size_resp < s7_comm_size {
-                return Err(S7Error::IsoInvalidTelegram);
+            if self.strict_parse {
+                let telegram = [&iso_packet[..], &response[..size_resp]].concat();
+                strict_check_rw_response(&telegram, pdu_ref, FN_READ_VAR, wordlen, chunk_size as u16)?;
             }
 
-            if response[RW_RES_OFFSET] != RES_SUCCESS {
-                match response[RW_RES_OFFSET] {
-                    RES_NOT_FOUND => return Err(S7Error::S7NotFound),
-                    RES_INVALID_ADDRESS => return Err(S7Error::S7InvalidAddress),
-                    _ => return Err(S7Error::S7Unspecified)
-                }
-            }
+            // S7 Header + Function + Items count
+            let header = S7Header::decode(&response[..size_resp])?;
+            let item_offset = header.encoded_len() + 2;
+            let item = DataItemHeader::decode(&response[item_offset..size_resp])?;
+
+            item_result(item.return_code)?;
           
-            // Copy payload
-            let payload = &response[READ_RES_LEN..READ_RES_LEN + (size_resp - READ_RES_LEN).min(chunk_size as usize)];
-            buffer[offset as usize..offset as usize + payload.len()].copy_from_slice(payload);
+            // Copy payload, exactly the size requested
+            let data_offset = item_offset + DATA_ITEM_HEADER_LEN;
+            check_read_payload(&iso_packet, &response[..size_resp], item_offset, &item, chunk_size)?;
+            buffer.put(offset, &response[data_offset..data_offset + chunk_size]);
+            if let Some(report) = self.transfer_report.as_mut() {
+                report.end_chunk();
+            }
 
             offset += chunk_size;
             long_start += chunk_size as u32;
         }
 
+        for pdu in pushed {
+            self.dispatch_alarms(&pdu);
+        }
         self.last_time = start_time.elapsed().as_secs_f64() * 1000.0;
 
         Ok(())     
@@ -682,7 +2934,7 @@ impl S7Client {
     /// #### Bit access notes
     /// 1. The start must be expressed in bits.
     ///    For example, if you want to access bit `DBX 45.3`, the start value would be 45 * 8 + 3 = 363.
-    /// 2. Whatever buffer is passed, only the first byte will be used, which is considered true if !=0 or false if ==0
+    /// 2. The buffer must be 1 byte long, containing 0x00 (false) or 0x01 (true)
     /// 3. Writing a bit affects **only that bit**, leaving adjacent bits in the byte unchanged. 
     /// 
     /// ### Returns
@@ -695,6 +2947,13 @@ impl S7Client {
     /// - `S7Error::IsoInvalidTelegram`: Inconsistent expected telegram length.
     /// - `S7Error::IsoFragmentedPacket`: ISO Packet fragmented.
     /// - `S7Error::S7Unspecified`: Unknown S7 Error.
+    /// - `S7Error::AccessDenied`: Access to the object not allowed (e.g. write-protected DB), with
+    ///   the reason if detected before sending the write (see `set_check_write_protection()`).
+    /// - `S7Error::DataTypeMismatch`: The CPU doesn't support the transport size requested.
+    /// - `S7Error::WrongDataSize`: The data length doesn't match the request.
+    /// - `S7Error::CpuStopped`: The CPU refused the job because it's in STOP (see `set_verify_cpu_stop()`).
+    /// - `S7Error::OptimizedDb`, `S7Error::BeyondDbLength`: Diagnosis of `S7InvalidAddress` (see `set_diagnose_address_errors()`).
+    /// - `S7Error::WriteDenied`: The write policy refused the write (see `set_write_policy()`).
     /// - `S7Error::Io`: network I/O error.
     ///
     /// #### Suggestion
@@ -702,6 +2961,7 @@ impl S7Client {
     /// 
     /// #### High level
     /// - `S7Error::InvalidFunParam`: Invalid parameter supplied to the function.
+    /// - `S7Error::SizeMismatch`: Buffer beyond the last byte addressable (2 MB), or not 1 byte long for a bit access.
     /// - `S7Error::NotFound`: The resource was not found (e.g. Inexistent DB).
     /// - `S7Error::S7InvalidAddress`:
     /// 1. Attempt to write beyond the limits.
@@ -711,15 +2971,51 @@ impl S7Client {
     /// - The number of bytes to write will be equal to the size of the buffer passed.
     /// - Large blocks are automatically split into chunks based on the negotiated PDU size.
     /// - Writing the output buffer (`S7_AREA_PA`) usually does not produce useful results, in fact the output process image 
-    /// will be rewritten by OB1 in the next round
+    ///   will be rewritten by OB1 in the next round
     /// 
-    pub fn write_area(&mut self, area: u8, db_number: u16, start: u16, wordlen: u8, buffer: &[u8]) -> Result<(), S7Error> {
+    pub fn write_area(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &[u8]) -> Result<(), S7Error> {
+        let mut pending = self.audit_begin(1);
+        let result = self.with_retry(|client| client.write_area_once(area, db_number, start, wordlen, buffer, &mut pending));
+        if let Some(pending) = pending {
+            let write = WriteRequest { operation: "write_area", area, db_number, start, wordlen, data: buffer, nck: None };
+            self.audit(&pending, 0, write, result.as_ref().map(|_| ()));
+        }
+        let size = buffer.len();
+        self.with_context(result, |error| ErrorContext { operation: "write_area", area, db_number, start, wordlen, size, error })
+    }
+
+    fn write_area_once(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &[u8], pending: &mut Option<PendingAudit>) -> Result<(), S7Error> {
+        check_buffer_size(wordlen, start, buffer.len())?;
+        let write = WriteRequest { operation: "write_area", area, db_number, start, wordlen, data: buffer, nck: None };
+        self.authorize_write(&write)?;
+        let dbs: &[u16] = if area == S7_AREA_DB { &[db_number] } else { &[] };
+        self.check_write_protection(dbs)?;
+        self.audit_read_old_value(pending, area, db_number, start, wordlen, buffer.len());
+        if self.dry_run {
+            return self.dry_run_write(&write);
+        }
+        self.begin_transfer_report();
+        let result = self.observe("write_area", buffer.len(), |client| {
+            let result = client.write_area_job(area, db_number, start, wordlen, buffer);
+            client.count_result(&result);
+            let result = client.verify_cpu_stop(result);
+            client.diagnose_address(area, db_number, start, wordlen, buffer.len(), result)
+        });
+        self.finish_transfer_report(result.is_ok());
+        // Also on error: the PLC may have written part of the block
+        if let Some(cache) = self.read_cache.as_mut() {
+            cache.invalidate_write(area, db_number, start, wordlen, buffer.len());
+        }
+        result
+    }
+
+    fn write_area_job(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &[u8]) -> Result<(), S7Error> {
 
         self.last_time = 0.0;
         self.chunks = 0;
 
         // Check Area
-        let _ = self.check_area(area)?;
+        self.check_area(area)?;
 
         // Check Word Length
         if wordlen != S7_WL_BIT && wordlen != S7_WL_BYTE {
@@ -732,100 +3028,101 @@ impl S7Client {
         }
 
         let start_time = Instant::now();
+
+        // Only 1 element allowed for bit operations
+        let datasize: usize = if wordlen == S7_WL_BYTE { buffer.len() } else { 1 };
+
+        self.apply_read_timeout(datasize)?;
+
+        let max_chunk = self.chunk_limit(self.max_wr_pdu_data, wordlen) as usize;
+        s7_trace!("write of {} element(s) to area 0x{:02X}: {} chunk(s) of max {}",
+            datasize, area, datasize.div_ceil(max_chunk.max(1)), max_chunk);
         let stream = self.stream.as_mut().unwrap();
+        let mut pushed = Vec::new();
         let mut offset = 0;
-        let mut long_start: u32 = start as u32;
-
-        let datasize: usize = if wordlen == S7_WL_BYTE {
-            buffer.len().min(u16::MAX as usize)
-        } else {
-            1 // Only 1 element allowed for bit operations
-        };
+        let mut long_start: u32 = start;
         
         let transport: u8 = if wordlen == S7_WL_BIT { TS_RES_BIT } else { TS_RES_BYTE };
 
         while offset < datasize{
             self.chunks+=1;
-            let chunk_size = (datasize - offset).min(self.max_wr_pdu_data as usize);
+            *self.area_requests.entry(area).or_insert(0) += 1;
+            let chunk_size = (datasize - offset).min(max_chunk);
             let chunk = &buffer[offset..offset + chunk_size];
+            if let Some(report) = self.transfer_report.as_mut() {
+                report.begin_chunk(offset, chunk_size);
+            }
 
             let bits_payload: u16 = if wordlen == S7_WL_BIT { 1 } else { (chunk_size << 3) as u16 };
 
-            // 35 byte Write Request Header
-            let mut request = vec![ 
-                ISO_ID, 0x00,            // RFC 1006 ID (constant)
-                0x00, 0x00,              // Telegram Length (HI,LO) = Payload Size + 35
-                0x02, 0xf0, 0x80,        // COPT (constant)
-                S7_ID,                   // S7 Protocol ID 
-                0x01,                    // Job Type (Data)
-                0x00, 0x00,              // Redundancy identification 
-                0x05, 0x00,              // PDU Reference
-                0x00, 0x0e,              // Parameters Length (HI,LO) = 14
-                hi_part!(chunk_size + 4),// HI (Payload Size + 4) 
-                lo_part!(chunk_size + 4),// LO (Payload Size + 4)
-                0x05,                    // Function: 4 Read Var, 5 Write Var 
-                0x01,                    // Items count (used for multivar R/W)
-                0x12,                    // Var spec.
-                0x0a,                    // constant 0x0a
-                0x10,                    // Syntax ID 
-                wordlen,
-                hi_part!(chunk_size),    // HI Payload size
-                lo_part!(chunk_size),    // LO Payload size               
-                hi_part!(db_number),     // HI DB Number 
-                lo_part!(db_number),     // LO DB Number               
-                area,                    // Area ID
-                0x00, 0x00, 0x00,        // 24 bit Address (see below)
-                0x00,                    // Reserved
-                transport,               // TS_RES_BIT or TS_RES_BYTE
-                hi_part!(bits_payload),  // HI Payload size (bits) 
-                lo_part!(bits_payload)   // LO Payload size (bits)
-            ];
-
-            request.extend_from_slice(chunk); // Append the Payload to the Header
-
-            let total_len = request.len();
-            
-            // Set Telegram length
-            request[2] = hi_part!(total_len);
-            request[3] = lo_part!(total_len);
-
-            // Set Start Address (bits) inside the area
+            // Start Address (bits) inside the area
             let address = if wordlen == S7_WL_BIT { 
                 long_start 
             } else { 
                 long_start << 3 
             };
 
-            request[28] = ((address >> 16) & 0xFF) as u8;
-            request[29] = ((address >> 8) & 0xFF) as u8;
-            request[30] = (address & 0xFF) as u8;
-
-            stream.write_all(&request)?;
+            // Write Request Telegram
+            self.pdu_ref = self.pdu_ref.wrapping_add(1);
+            let pdu_ref = self.pdu_ref;
+            let total_len = WRITE_REQ_LEN + chunk_size;
+            let mut request = Vec::with_capacity(total_len);
+            TpktHeader { length: total_len as u16 }.encode(&mut request);
+            CotpData { eot: true }.encode(&mut request);
+            S7Header::job(pdu_ref, RW_PARAMS_LEN, (chunk_size + DATA_ITEM_HEADER_LEN) as u16).encode(&mut request);
+            request.push(FN_WRITE_VAR);
+            request.push(0x01); // Items count (used for multivar R/W)
+            S7ItemSpec {
+                wordlen,
+                amount: chunk_size as u16,
+                db_number,
+                area,
+                address,
+            }.encode(&mut request);
+            DataItemHeader {
+                return_code: 0x00,
+                transport_size: transport, // TS_RES_BIT or TS_RES_BYTE
+                length: bits_payload,
+            }.encode(&mut request);
 
-            // Read and check ISO header
-            let mut iso_packet = [0u8; TPKT_ISO_LEN];
-            stream.read_exact(&mut iso_packet)?;
+            request.extend_from_slice(chunk); // Append the Payload to the Header
 
-            let s7_comm_size = check_iso_packet(self.pdu_length, &mut iso_packet)?;
+            let mut response = [0u8; PDU_LEN_REQ as usize];
+            let mut retries = 0;
+            let (iso_packet, size_resp) = loop {
+                pace(self.min_request_gap, &mut self.last_request);
+                stream.write_all(&request)?;
+
+                match receive_chunk(stream.as_mut(), self.pdu_length, WRITE_RES_LEN, &mut response, &mut pushed, self.stale_responses.then_some(pdu_ref)) {
+                    // Only the timeouts after a complete request are retried
+                    Err(error) if is_timeout(&error) && retries < self.chunk_retries => {
+                        retries += 1;
+                        s7_debug!("chunk at offset {} timed out, retry {} of {}", offset, retries, self.chunk_retries);
+                        if let Some(report) = self.transfer_report.as_mut() {
+                            report.retry();
+                        }
+                        // The response to the first attempt may still arrive after the retry's one
+                        self.stale_responses = true;
+                        drain(stream.as_mut(), self.eff_rd_timeout_ms)?;
+                    }
+                    result => break result?,
+                }
+            };
+            self.stale_responses = retries > 0;
 
-            if s7_comm_size < WRITE_RES_LEN {
-                return Err(S7Error::IsoInvalidTelegram);
+            if self.strict_parse {
+                let telegram = [&iso_packet[..], &response[..size_resp]].concat();
+                strict_check_rw_response(&telegram, pdu_ref, FN_WRITE_VAR, wordlen, chunk_size as u16)?;
             }
 
-            // Read and check S7 Telegram
-            let mut response = [0u8; PDU_LEN_REQ as usize];
-            let size_resp = stream.read(&mut response)?;
+            // S7 Header + Function + Items count, then one return code per item
+            let header = S7Header::decode(&response[..size_resp])?;
+            let return_code = response[header.encoded_len() + 2];
 
-            if size_resp < s7_comm_size {
-                return Err(S7Error::IsoInvalidTelegram);
-            }
+            item_result(return_code)?;
 
-            if response[RW_RES_OFFSET] != RES_SUCCESS {
-                match response[RW_RES_OFFSET] {
-                    RES_NOT_FOUND => return Err(S7Error::S7NotFound),
-                    RES_INVALID_ADDRESS => return Err(S7Error::S7InvalidAddress),
-                    _ => return Err(S7Error::S7Unspecified)
-                }
+            if let Some(report) = self.transfer_report.as_mut() {
+                report.end_chunk();
             }
 
             // Next Chunk
@@ -833,6 +3130,9 @@ impl S7Client {
             long_start += chunk_size as u32;
         }
 
+        for pdu in pushed {
+            self.dispatch_alarms(&pdu);
+        }
         self.last_time = start_time.elapsed().as_secs_f64() * 1000.0;
 
         Ok(())     
@@ -854,7 +3154,7 @@ impl S7Client {
     /// ---
     /// For further info, please refer to `read_area()`
     /// 
-    pub fn read_db(&mut self, db_number: u16, start: u16, buffer: &mut [u8]) -> Result<(), S7Error> {
+    pub fn read_db(&mut self, db_number: u16, start: u32, buffer: &mut [u8]) -> Result<(), S7Error> {
         self.read_area(S7_AREA_DB, db_number, start, S7_WL_BYTE, buffer)
     }
 
@@ -883,21 +3183,20 @@ impl S7Client {
     /// - Other reported by read_area()
     /// 
     /// ### Suggestion
-    ///   
-    ///     Even reading a single bit requires an entire telegram.
-    ///     Since reading is non-invasive, if you need to read multiple bits 
-    ///     (more or less adjacent in the same area), I recommend reading blocks 
-    ///     of bytes and then unpacking them.
+    /// Even reading a single bit requires an entire telegram.
+    /// Since reading is non-invasive, if you need to read multiple bits 
+    /// (more or less adjacent in the same area), I recommend reading blocks 
+    /// of bytes and then unpacking them.
     /// ---
     /// For further info, please refer to `read_area()`
     /// 
-    pub fn read_bit(&mut self, area: u8, db_number: u16, byte_num: u16, bit_idx: u8) -> Result<bool, S7Error> {
+    pub fn read_bit(&mut self, area: u8, db_number: u16, byte_num: u32, bit_idx: u8) -> Result<bool, S7Error> {
 
         if bit_idx > 7 { 
             return Err(S7Error::InvalidFunParameter); 
         }
   
-        let start: u16 = byte_num * 8 + bit_idx as u16;
+        let start = bit_start(byte_num, bit_idx)?;
         let mut buffer = [0u8; 1];
         
         self.read_area(area, db_number, start, S7_WL_BIT, &mut buffer)?;
@@ -905,6 +3204,63 @@ impl S7Client {
         Ok(buffer[0] != 0)
     }
 
+    /// ### Reads consecutive bits from a specific S7 memory area
+    ///
+    /// All the bits are requested in a single bit item (`S7_WL_BIT` with count > 1), so they can
+    /// straddle byte boundaries without reading and masking whole bytes.
+    ///
+    /// ### Parameters
+    /// - `area`: S7 memory area constant (e.g., `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_DB`, `S7_AREA_MK`).
+    /// - `db_number`: DB number (ignored for non-DB areas).
+    /// - `byte_num`: Byte Number of the first bit.
+    /// - `bit_idx`: Index of the first bit inside the byte (0..7).
+    /// - `count`: Number of bits to read.
+    ///
+    /// ### Example
+    /// To read DB10.DBX71.6 .. DB10.DBX72.1 use:
+    ///
+    /// ```my_bits = read_bits(S7_AREA_DB, 10, 71, 6, 4);```
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<bool>>)`, the element 0 is the first bit, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParam`: Invalid parameter supplied to the function (e.g. `count` = 0).
+    /// - Other reported by read_area()
+    ///
+    /// ### Notes
+    /// The PLC returns a byte per bit, so a telegram carries up to the PDU data size bits; longer
+    /// sequences are split into chunks. Some CPUs accept only one bit per item and reject the
+    /// request with an S7 error: in that case read the bytes and unpack them.
+    /// ---
+    /// For further info, please refer to `read_area()`
+    ///
+    pub fn read_bits(&mut self, area: u8, db_number: u16, byte_num: u32, bit_idx: u8, count: u16) -> Result<Vec<bool>, S7Error> {
+
+        if bit_idx > 7 || count == 0 {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        let start = bit_start(byte_num, bit_idx)?;
+        let mut buffer = vec![0u8; count as usize];
+
+        let result = self.with_retry(|client| {
+            client.begin_transfer_report();
+            let result = client.observe("read_bits", count as usize, |client| {
+                let result = client.read_area_job(area, db_number, start, S7_WL_BIT, buffer.as_mut_slice());
+                client.count_result(&result);
+                let result = client.verify_cpu_stop(result);
+                client.diagnose_address(area, db_number, start, S7_WL_BIT, count as usize, result)
+            });
+            client.finish_transfer_report(result.is_ok());
+            result
+        });
+        let size = count as usize;
+        self.with_context(result, |error| ErrorContext { operation: "read_bits", area, db_number, start, wordlen: S7_WL_BIT, size, error })?;
+
+        Ok(buffer.into_iter().map(|bit| bit != 0).collect())
+    }
+
     /// ### Writes a block of byte to a specific Data Block (DB)
     ///
     /// This helper method is same as `write_area()` with:
@@ -921,7 +3277,7 @@ impl S7Client {
     /// ---
     /// For further info, please refer to `write_area()`
     /// 
-    pub fn write_db(&mut self, db_number: u16, start: u16, buffer: &[u8]) -> Result<(), S7Error> {
+    pub fn write_db(&mut self, db_number: u16, start: u32, buffer: &[u8]) -> Result<(), S7Error> {
         self.write_area(S7_AREA_DB, db_number, start, S7_WL_BYTE, buffer)
     }
 
@@ -951,26 +3307,91 @@ impl S7Client {
     /// - Other reported by read_area()
     /// 
     /// ### Notes
-    ///     Writing a bit affects only that bit, leaving adjacent bits in the byte unchanged. 
+    /// Writing a bit affects only that bit, leaving adjacent bits in the byte unchanged. 
     /// ---
     /// For further info, please refer to `write_area()`
     /// 
-   pub fn write_bit(&mut self, area: u8, db_number: u16, byte_num: u16, bit_idx: u8, value: bool) -> Result<(), S7Error> {
+   pub fn write_bit(&mut self, area: u8, db_number: u16, byte_num: u32, bit_idx: u8, value: bool) -> Result<(), S7Error> {
         
         if bit_idx > 7 { 
             return Err(S7Error::InvalidFunParameter); 
         }
   
-        let start: u16 = byte_num * 8 + bit_idx as u16;
-        let mut data = [0u8; 1];
-        data[0] = value as u8;        
+        let start = bit_start(byte_num, bit_idx)?;
+        let data = [value as u8];
               
-        self.write_area(area, db_number, start, S7_WL_BIT, &mut data)
+        self.write_area(area, db_number, start, S7_WL_BIT, &data)
+    }
+
+    /// ### Reads a typed value from a specific S7 memory area
+    ///
+    /// The data type is known only at runtime (e.g. from a configuration file), the value
+    /// is returned as a dynamic `S7Value`.
+    ///
+    /// ### Parameters
+    /// - `area`: S7 memory area constant (e.g., `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_DB`, `S7_AREA_MK`).
+    /// - `db_number`: DB number (ignored for non-DB areas).
+    /// - `start`: Starting byte index (bit index, i.e. `byte_num * 8 + bit_idx`, for `S7DataType::Bool`).
+    /// - `data_type`: Type of the variable.
+    ///
+    /// ### Returns
+    /// `Ok(<S7Value>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by read_area()
+    /// ---
+    /// For further info, please refer to `read_area()`
+    ///
+    pub fn read_value(&mut self, area: u8, db_number: u16, start: u32, data_type: S7DataType) -> Result<S7Value, S7Error> {
+        let wordlen = if data_type == S7DataType::Bool { S7_WL_BIT } else { S7_WL_BYTE };
+        let mut buffer = vec![0u8; data_type.size()];
+
+        self.read_area(area, db_number, start, wordlen, &mut buffer)?;
+
+        S7Value::decode(data_type, &buffer)
+    }
+
+    /// ### Writes a typed value to a specific S7 memory area
+    ///
+    /// ### Parameters
+    /// - `area`: S7 memory area constant (e.g., `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_DB`, `S7_AREA_MK`).
+    /// - `db_number`: DB number (ignored for non-DB areas).
+    /// - `start`: Starting byte index (bit index, i.e. `byte_num * 8 + bit_idx`, for `S7DataType::Bool`).
+    /// - `data_type`: Type of the variable.
+    /// - `value`: Value to write, it must match `data_type`.
+    ///
+    /// ### Returns
+    /// `Ok(())` Operation succeeded.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The value doesn't match the data type.
+    /// - Other reported by write_area()
+    /// ---
+    /// For further info, please refer to `write_area()`
+    ///
+    pub fn write_value(&mut self, area: u8, db_number: u16, start: u32, data_type: S7DataType, value: &S7Value) -> Result<(), S7Error> {
+        let wordlen = if data_type == S7DataType::Bool { S7_WL_BIT } else { S7_WL_BYTE };
+        let buffer = value.encode(data_type)?;
+
+        self.write_area(area, db_number, start, wordlen, &buffer)
+    }
+}
+
+impl Default for S7Client {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Drop for S7Client {
     fn drop(&mut self) {
-        self.disconnect();
+        let result = match self.drop_behavior {
+            DropBehavior::Disconnect => self.close(),
+            DropBehavior::Abort => self.release(false, Duration::ZERO),
+            DropBehavior::Graceful(timeout) => self.release(true, timeout),
+        };
+        if let Err(error) = result {
+            s7_debug!("close on drop: {}", error);
+        }
     }
 }
\ No newline at end of file
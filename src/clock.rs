@@ -0,0 +1,164 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### CPU clock
+//!
+//! Reading and setting the CPU clock, and its synchronization with the host clock. The CPU clock
+//! has no time zone: as everywhere in the crate, its time is handled as if it were UTC, so a CPU
+//! running on local time shows a drift equal to the UTC offset of its zone.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::S7Client;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_rack_slot("192.168.0.100", 0, 2).unwrap();
+//!
+//! // Nightly job: adjust only the CPUs drifted more than 2 s
+//! let sync = client.sync_plc_clock(Duration::from_secs(2)).unwrap();
+//! println!("drift {} ms, adjusted: {}", sync.drift.drift_ms, sync.adjusted);
+//! ```
+
+use std::time::{Duration, SystemTime};
+use crate::client::{S7Client, S7Error, S7ErrorCodes};
+use crate::frame::{S7UserDataParams, UD_GROUP_TIME, UD_SUBFN_READ_CLOCK, UD_SUBFN_SET_CLOCK};
+use crate::value::{date_time_from_bcd, date_time_to_bcd};
+
+const RES_SUCCESS: u8 = 0xFF;
+const RES_NOT_FOUND: u8 = 0x0A;
+const TS_OCTET_STRING: u8 = 0x09;
+const DATA_HEADER_LEN: usize = 4; // Return code + Transport size + Length
+/// Clock data: Reserved, Year (century, BCD), DATE_AND_TIME (8 byte)
+const CLOCK_DATA_LEN: usize = 10;
+
+/// ### Difference between the CPU clock and the host clock
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockDrift {
+    /// Time read from the CPU
+    pub plc_time: SystemTime,
+    /// Host time at the middle of the request, which compensates the transmission delay
+    pub host_time: SystemTime,
+    /// `plc_time - host_time` in ms: positive if the CPU clock is ahead
+    pub drift_ms: i64,
+}
+
+impl ClockDrift {
+    /// ### Absolute value of the drift
+    ///
+    pub fn magnitude(&self) -> Duration {
+        Duration::from_millis(self.drift_ms.unsigned_abs())
+    }
+}
+
+/// ### Result of `sync_plc_clock()`
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSync {
+    /// Drift measured before the synchronization
+    pub drift: ClockDrift,
+    /// The CPU clock was set to the host time
+    pub adjusted: bool,
+}
+
+    /// Signed difference `a - b` in ms
+    fn diff_ms(a: SystemTime, b: SystemTime) -> i64 {
+        match a.duration_since(b) {
+            Ok(ahead) => ahead.as_millis().min(i64::MAX as u128) as i64,
+            Err(behind) => -(behind.duration().as_millis().min(i64::MAX as u128) as i64),
+        }
+    }
+
+impl S7Client {
+    /// ### Reads the CPU clock
+    ///
+    /// ### Returns
+    /// `Ok(<SystemTime>)` (the CPU time as if it were UTC) or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::IsoInvalidTelegram`: Inconsistent response or invalid date.
+    /// - `S7Error::S7Unspecified`: The CPU refused the request.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    pub fn get_plc_time(&mut self) -> Result<SystemTime, S7Error> {
+        let params = S7UserDataParams::request(UD_GROUP_TIME, UD_SUBFN_READ_CLOCK);
+        let (_, data) = self.userdata(&params, &[RES_NOT_FOUND, 0x00, 0x00, 0x00])?;
+        if data.len() < DATA_HEADER_LEN {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        if data[0] != RES_SUCCESS {
+            return Err(S7Error::S7Unspecified(S7ErrorCodes::item(data[0])));
+        }
+        let clock = data.get(DATA_HEADER_LEN..DATA_HEADER_LEN + CLOCK_DATA_LEN).ok_or(S7Error::IsoInvalidTelegram)?;
+        date_time_from_bcd(&clock[2..]).ok_or(S7Error::IsoInvalidTelegram)
+    }
+
+    /// ### Sets the CPU clock
+    ///
+    /// ### Parameters
+    /// - `time`: New time, written as it is (UTC) since the CPU clock has no time zone.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: `time` outside 1990..=2089.
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::S7Unspecified`: The CPU refused the request (e.g. protection level).
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    pub fn set_plc_time(&mut self, time: SystemTime) -> Result<(), S7Error> {
+        let date_time = date_time_to_bcd(time).ok_or(S7Error::InvalidFunParameter)?;
+        let century = if date_time[0] >= 0x90 { 0x19 } else { 0x20 };
+        let mut request = vec![RES_SUCCESS, TS_OCTET_STRING, 0x00, CLOCK_DATA_LEN as u8, 0x00, century];
+        request.extend_from_slice(&date_time);
+
+        let params = S7UserDataParams::request(UD_GROUP_TIME, UD_SUBFN_SET_CLOCK);
+        self.userdata(&params, &request)?;
+        Ok(())
+    }
+
+    /// ### Sets the CPU clock to the host time
+    ///
+    /// ### Errors
+    /// - Reported by `set_plc_time()`
+    ///
+    pub fn set_plc_time_to_host(&mut self) -> Result<(), S7Error> {
+        self.set_plc_time(SystemTime::now())
+    }
+
+    /// ### Measures the drift of the CPU clock from the host clock
+    ///
+    /// The host time is taken at the middle of the request, the resolution is the one of the CPU
+    /// clock (1 ms on most CPUs, 10 ms on some S7-300) plus half of the round-trip time.
+    ///
+    /// ### Errors
+    /// - Reported by `get_plc_time()`
+    ///
+    pub fn measure_clock_drift(&mut self) -> Result<ClockDrift, S7Error> {
+        let before = SystemTime::now();
+        let plc_time = self.get_plc_time()?;
+        let after = SystemTime::now();
+        let host_time = before + after.duration_since(before).unwrap_or_default() / 2;
+        Ok(ClockDrift { plc_time, host_time, drift_ms: diff_ms(plc_time, host_time) })
+    }
+
+    /// ### Sets the CPU clock to the host time if it drifted more than `max_drift`
+    ///
+    /// ### Parameters
+    /// - `max_drift`: Drift tolerated; `Duration::ZERO` always sets the clock.
+    ///
+    /// ### Returns
+    /// `Ok(<ClockSync>)` with the drift measured and whether the clock was set, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `measure_clock_drift()` and `set_plc_time()`
+    ///
+    pub fn sync_plc_clock(&mut self, max_drift: Duration) -> Result<ClockSync, S7Error> {
+        let drift = self.measure_clock_drift()?;
+        let adjusted = drift.magnitude() > max_drift || max_drift.is_zero();
+        if adjusted {
+            self.set_plc_time_to_host()?;
+        }
+        Ok(ClockSync { drift, adjusted })
+    }
+}
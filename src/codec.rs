@@ -0,0 +1,196 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### tokio-util codecs for TPKT/COTP framing (feature `codec`)
+//!
+//! - `TpktCodec` frames the RFC 1006 packets out of a TCP byte stream: each item is the COTP TPDU
+//!   (COTP header included) of a complete TPKT packet, whatever the TCP segmentation was
+//!   (a packet split over several segments, several packets in one segment).
+//! - `IsoCodec` adds the COTP Data layer: sends each S7 PDU in a DT TPDU and reassembles the
+//!   incoming DT fragments (EOT = 0) into complete PDUs. Non-DT TPDUs (CC, DR, ER...) are returned
+//!   as they are.
+//!
+//! Both are meant to be used with `tokio_util::codec::Framed` and are the basis of an async client.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use futures::{SinkExt, StreamExt};
+//! use tokio::net::TcpStream;
+//! use tokio_util::codec::Framed;
+//! use rust7::codec::{IsoCodec, IsoPacket};
+//!
+//! # async fn run(s7_pdu: &[u8]) -> Result<(), rust7::S7Error> {
+//! let stream = TcpStream::connect("192.168.0.100:102").await?;
+//! let mut framed = Framed::new(stream, IsoCodec::new());
+//! // ... COTP connection ...
+//! framed.send(s7_pdu).await?;
+//! if let Some(IsoPacket::Data(pdu)) = framed.next().await.transpose()? {
+//!     println!("{} byte PDU", pdu.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use crate::client::S7Error;
+use crate::frame::{CotpData, TpktHeader, COTP_DT, COTP_DT_LEN, ISO_ID, TPKT_LEN};
+
+/// Default max length of a reassembled PDU
+const DEFAULT_MAX_PDU_LEN: usize = 65536;
+
+/// ### TPKT (RFC 1006) packet codec
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TpktCodec;
+
+impl TpktCodec {
+    /// ### Creates the codec
+    ///
+    pub fn new() -> Self {
+        TpktCodec
+    }
+}
+
+impl Decoder for TpktCodec {
+    type Item = BytesMut;
+    type Error = S7Error;
+
+    /// ### Returns the COTP TPDU of the next complete packet
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoInvalidHeader`: Wrong RFC 1006 ID or length shorter than the TPKT header.
+    ///   The stream is out of sync and should be closed.
+    ///
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>, S7Error> {
+        if src.len() < TPKT_LEN {
+            return Ok(None);
+        }
+        let length = TpktHeader::decode(src)?.length as usize;
+        if length <= TPKT_LEN {
+            return Err(S7Error::IsoInvalidHeader);
+        }
+        if src.len() < length {
+            src.reserve(length - src.len());
+            return Ok(None);
+        }
+        let mut packet = src.split_to(length);
+        packet.advance(TPKT_LEN);
+        Ok(Some(packet))
+    }
+}
+
+impl Encoder<&[u8]> for TpktCodec {
+    type Error = S7Error;
+
+    /// ### Writes a TPKT packet carrying the COTP TPDU `item`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The packet would exceed 65535 byte.
+    ///
+    fn encode(&mut self, item: &[u8], dst: &mut BytesMut) -> Result<(), S7Error> {
+        let length = TPKT_LEN + item.len();
+        if length > u16::MAX as usize {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        dst.reserve(length);
+        dst.put_u8(ISO_ID);
+        dst.put_u8(0x00);
+        dst.put_u16(length as u16);
+        dst.extend_from_slice(item);
+        Ok(())
+    }
+}
+
+/// ### Item decoded by `IsoCodec`
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IsoPacket {
+    /// Complete S7 PDU (COTP DT fragments reassembled, COTP header stripped)
+    Data(BytesMut),
+    /// Other COTP TPDU (CC, DR, ER...), COTP header included
+    Control(BytesMut),
+}
+
+/// ### ISO-on-TCP codec: TPKT + COTP Data
+///
+#[derive(Debug, Clone)]
+pub struct IsoCodec {
+    tpkt: TpktCodec,
+    /// Data of the DT fragments received so far
+    pending: BytesMut,
+    max_pdu_len: usize,
+}
+
+impl IsoCodec {
+    /// ### Creates the codec (max reassembled PDU 64 KB)
+    ///
+    pub fn new() -> Self {
+        Self::with_max_pdu_len(DEFAULT_MAX_PDU_LEN)
+    }
+
+    /// ### Creates the codec with a custom max length of the reassembled PDUs
+    ///
+    pub fn with_max_pdu_len(max_pdu_len: usize) -> Self {
+        IsoCodec {
+            tpkt: TpktCodec,
+            pending: BytesMut::new(),
+            max_pdu_len,
+        }
+    }
+}
+
+impl Default for IsoCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for IsoCodec {
+    type Item = IsoPacket;
+    type Error = S7Error;
+
+    /// ### Returns the next complete PDU or control TPDU
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoInvalidHeader`: Reported by `TpktCodec`, or malformed DT header.
+    /// - `S7Error::IsoFragmentedPacket`: The reassembled PDU exceeds the max length.
+    ///
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<IsoPacket>, S7Error> {
+        while let Some(mut tpdu) = self.tpkt.decode(src)? {
+            if tpdu.len() < 2 || tpdu[1] != COTP_DT {
+                return Ok(Some(IsoPacket::Control(tpdu)));
+            }
+            let dt = CotpData::decode(&tpdu)?;
+            tpdu.advance(COTP_DT_LEN);
+            if self.pending.len() + tpdu.len() > self.max_pdu_len {
+                self.pending.clear();
+                return Err(S7Error::IsoFragmentedPacket);
+            }
+            if dt.eot && self.pending.is_empty() {
+                return Ok(Some(IsoPacket::Data(tpdu)));
+            }
+            self.pending.unsplit(tpdu);
+            if dt.eot {
+                return Ok(Some(IsoPacket::Data(self.pending.split())));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Encoder<&[u8]> for IsoCodec {
+    type Error = S7Error;
+
+    /// ### Writes the S7 PDU `item` in a single COTP DT TPDU (EOT)
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The telegram would exceed 65535 byte.
+    ///
+    fn encode(&mut self, item: &[u8], dst: &mut BytesMut) -> Result<(), S7Error> {
+        let mut tpdu = Vec::with_capacity(COTP_DT_LEN + item.len());
+        CotpData { eot: true }.encode(&mut tpdu);
+        tpdu.extend_from_slice(item);
+        self.tpkt.encode(&tpdu, dst)
+    }
+}
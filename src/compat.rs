@@ -0,0 +1,6 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Compatibility layers for code written against other S7 libraries
+
+pub mod snap7;
@@ -0,0 +1,347 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Snap7 API shim
+//!
+//! `S7Client` of this module exposes the method names, the parameter order and the integer result
+//! codes of the Snap7 client (`Cli_DBRead`, `Cli_EBRead`, `Cli_ABWrite`, `Cli_ReadMultiVars`...),
+//! as do the Rust bindings of the C library. Code migrating away from it can switch by changing
+//! the `use` and dropping the `unsafe` blocks, then move to the native API at its own pace.
+//!
+//! Every method returns `0` on success or a Snap7 error code, see `error_text()`.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::compat::snap7::{error_text, S7Client};
+//!
+//! let mut client = S7Client::new();
+//! let result = client.connect_to("192.168.0.100", 0, 2);
+//! if result != 0 {
+//!     println!("{}", error_text(result));
+//! }
+//! let mut buffer = [0u8; 16];
+//! let result = client.db_read(100, 0, 16, &mut buffer);
+//! ```
+
+use std::io::ErrorKind;
+use crate::client::{self, S7Error, S7_AREA_DB, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE, S7_WL_BYTE};
+use crate::multivar::{S7DataItem, MAX_VARS};
+
+// TCP errors
+pub const ERR_TCP_CONNECTION_FAILED: i32 = 0x0003;
+pub const ERR_TCP_RECEIVE_TIMEOUT: i32 = 0x0004;
+pub const ERR_TCP_DATA_RECEIVE: i32 = 0x0005;
+pub const ERR_TCP_CONNECTION_RESET: i32 = 0x0008;
+pub const ERR_TCP_NOT_CONNECTED: i32 = 0x0009;
+
+// ISO errors
+pub const ERR_ISO_CONNECT: i32 = 0x0001_0000;
+pub const ERR_ISO_INVALID_PDU: i32 = 0x0003_0000;
+pub const ERR_ISO_INVALID_DATA_SIZE: i32 = 0x0004_0000;
+pub const ERR_ISO_TOO_MANY_FRAGMENTS: i32 = 0x0006_0000;
+
+// Client errors
+pub const ERR_NEGOTIATING_PDU: i32 = 0x0010_0000;
+pub const ERR_CLI_INVALID_PARAMS: i32 = 0x0020_0000;
+pub const ERR_CLI_TOO_MANY_ITEMS: i32 = 0x0040_0000;
+pub const ERR_CLI_INVALID_PLC_ANSWER: i32 = 0x0080_0000;
+pub const ERR_CLI_ADDRESS_OUT_OF_RANGE: i32 = 0x0090_0000;
+pub const ERR_CLI_INVALID_TRANSPORT_SIZE: i32 = 0x00A0_0000;
+pub const ERR_CLI_WRITE_DATA_SIZE_MISMATCH: i32 = 0x00B0_0000;
+pub const ERR_CLI_ITEM_NOT_AVAILABLE: i32 = 0x00C0_0000;
+pub const ERR_CLI_BUFFER_TOO_SMALL: i32 = 0x0220_0000;
+pub const ERR_CLI_FUNCTION_REFUSED: i32 = 0x0230_0000;
+
+/// ### Returns the Snap7 error code corresponding to an `S7Error`
+///
+pub fn error_code(error: &S7Error) -> i32 {
+    match error {
+        S7Error::Io(e) => match e.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => ERR_TCP_RECEIVE_TIMEOUT,
+            ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe | ErrorKind::UnexpectedEof => ERR_TCP_CONNECTION_RESET,
+            _ => ERR_TCP_DATA_RECEIVE,
+        },
+        S7Error::NotConnected => ERR_TCP_NOT_CONNECTED,
+        S7Error::TcpConnectionFailed => ERR_TCP_CONNECTION_FAILED,
+        S7Error::ConnectionClosed => ERR_TCP_CONNECTION_RESET,
+        S7Error::IsoConnectionFailed => ERR_ISO_CONNECT,
+        S7Error::IsoFragmentedPacket => ERR_ISO_TOO_MANY_FRAGMENTS,
+        S7Error::IsoInvalidHeader => ERR_ISO_INVALID_PDU,
+        S7Error::IsoInvalidTelegram => ERR_ISO_INVALID_DATA_SIZE,
+        S7Error::PduNegotiationFailed => ERR_NEGOTIATING_PDU,
+        S7Error::InvalidFunParameter => ERR_CLI_INVALID_PARAMS,
+        S7Error::S7NotFound => ERR_CLI_ITEM_NOT_AVAILABLE,
+        S7Error::S7InvalidAddress | S7Error::OptimizedDb(_) | S7Error::BeyondDbLength { .. } => ERR_CLI_ADDRESS_OUT_OF_RANGE,
+        S7Error::DataTypeMismatch => ERR_CLI_INVALID_TRANSPORT_SIZE,
+        S7Error::WrongDataSize => ERR_CLI_WRITE_DATA_SIZE_MISMATCH,
+        S7Error::SizeMismatch { .. } => ERR_CLI_INVALID_PARAMS,
+        S7Error::MalformedTelegram(_) => ERR_CLI_INVALID_PLC_ANSWER,
+        S7Error::S7Unspecified(_) | S7Error::CpuStopped(_) | S7Error::AccessDenied(_) | S7Error::WriteDenied { .. } | S7Error::Other(_) => ERR_CLI_FUNCTION_REFUSED,
+        S7Error::WithContext(context) => error_code(&context.error),
+    }
+}
+
+/// ### Returns the text of a Snap7 error code (`Cli_ErrorText`)
+///
+pub fn error_text(code: i32) -> String {
+    let text = match code {
+        0 => "OK",
+        ERR_TCP_CONNECTION_FAILED => "TCP : Connection failed",
+        ERR_TCP_RECEIVE_TIMEOUT => "TCP : Data receive Timeout",
+        ERR_TCP_DATA_RECEIVE => "TCP : Error receiving Data",
+        ERR_TCP_CONNECTION_RESET => "TCP : Connection reset by the peer",
+        ERR_TCP_NOT_CONNECTED => "TCP : Not connected",
+        ERR_ISO_CONNECT => "ISO : Connection error",
+        ERR_ISO_INVALID_PDU => "ISO : Bad format",
+        ERR_ISO_INVALID_DATA_SIZE => "ISO : Invalid Data size",
+        ERR_ISO_TOO_MANY_FRAGMENTS => "ISO : Too many packets fragments",
+        ERR_NEGOTIATING_PDU => "CPU : Error in PDU negotiation",
+        ERR_CLI_INVALID_PARAMS => "CLI : invalid param(s) supplied",
+        ERR_CLI_TOO_MANY_ITEMS => "CLI : Too many items (>20) in multi read/write",
+        ERR_CLI_INVALID_PLC_ANSWER => "CLI : Invalid CPU answer",
+        ERR_CLI_ADDRESS_OUT_OF_RANGE => "CPU : Address out of range",
+        ERR_CLI_INVALID_TRANSPORT_SIZE => "CPU : Invalid Transport size",
+        ERR_CLI_WRITE_DATA_SIZE_MISMATCH => "CPU : Data size mismatch",
+        ERR_CLI_ITEM_NOT_AVAILABLE => "CPU : Item not available",
+        ERR_CLI_BUFFER_TOO_SMALL => "CLI : Buffer too small",
+        ERR_CLI_FUNCTION_REFUSED => "CPU : Function refused by CPU (Unknown error)",
+        _ => return format!("Unknown error (0x{:08X})", code),
+    };
+    text.to_string()
+}
+
+/// ### Returns the Snap7 result code of a multi-variable item (`TS7DataItem.Result`)
+///
+pub fn item_result(item: &S7DataItem) -> i32 {
+    item.result.as_ref().err().map_or(0, error_code)
+}
+
+fn result_code(result: Result<(), S7Error>) -> i32 {
+    result.err().map_or(0, |e| error_code(&e))
+}
+
+/// Converts the Snap7 integer parameters of a byte access
+fn byte_range(start: i32, size: i32, buffer_len: usize) -> Result<(u32, usize), i32> {
+    let start = u32::try_from(start).map_err(|_| ERR_CLI_INVALID_PARAMS)?;
+    let size = usize::try_from(size).map_err(|_| ERR_CLI_INVALID_PARAMS)?;
+    if size > buffer_len {
+        return Err(ERR_CLI_BUFFER_TOO_SMALL);
+    }
+    Ok((start, size))
+}
+
+/// ### Snap7-style client
+///
+/// Wraps a native `rust7::S7Client`, reachable through `inner()` / `inner_mut()` for the features
+/// without a Snap7 counterpart.
+///
+#[derive(Default)]
+pub struct S7Client {
+    client: client::S7Client,
+    address: String,
+    local_tsap: u16,
+    remote_tsap: u16,
+}
+
+impl S7Client {
+    /// ### Creates the client (`Cli_Create`)
+    ///
+    pub fn new() -> Self {
+        S7Client::default()
+    }
+
+    /// ### Returns the native client
+    ///
+    pub fn inner(&self) -> &client::S7Client {
+        &self.client
+    }
+
+    /// ### Returns the native client
+    ///
+    pub fn inner_mut(&mut self) -> &mut client::S7Client {
+        &mut self.client
+    }
+
+    /// ### Consumes the shim and returns the native client
+    ///
+    pub fn into_inner(self) -> client::S7Client {
+        self.client
+    }
+
+    /// ### Sets the connection type (`Cli_SetConnectionType`): `CT_PG`, `CT_OP` or `CT_S7`
+    ///
+    pub fn set_connection_type(&mut self, connection_type: u16) -> i32 {
+        result_code(self.client.set_connection_type(connection_type))
+    }
+
+    /// ### Sets the address and the TSAPs used by `connect()` (`Cli_SetConnectionParams`)
+    ///
+    pub fn set_connection_params(&mut self, address: &str, local_tsap: u16, remote_tsap: u16) -> i32 {
+        self.address = address.to_string();
+        self.local_tsap = local_tsap;
+        self.remote_tsap = remote_tsap;
+        0
+    }
+
+    /// ### Connects with the parameters set by `set_connection_params()` (`Cli_Connect`)
+    ///
+    pub fn connect(&mut self) -> i32 {
+        if self.address.is_empty() {
+            return ERR_CLI_INVALID_PARAMS;
+        }
+        let address = self.address.clone();
+        result_code(self.client.connect_tsap(&address, self.local_tsap, self.remote_tsap))
+    }
+
+    /// ### Connects to a CPU given its rack and slot (`Cli_ConnectTo`)
+    ///
+    pub fn connect_to(&mut self, address: &str, rack: i32, slot: i32) -> i32 {
+        let (Ok(rack), Ok(slot)) = (u16::try_from(rack), u16::try_from(slot)) else {
+            return ERR_CLI_INVALID_PARAMS;
+        };
+        result_code(self.client.connect_rack_slot(address, rack, slot))
+    }
+
+    /// ### Disconnects (`Cli_Disconnect`)
+    ///
+    pub fn disconnect(&mut self) -> i32 {
+        self.client.disconnect();
+        0
+    }
+
+    /// ### Returns true if connected (`Cli_GetConnected`)
+    ///
+    pub fn get_connected(&self) -> bool {
+        self.client.connected
+    }
+
+    /// ### Returns the negotiated PDU length (`Cli_GetPduLength`)
+    ///
+    pub fn get_pdu_length(&self) -> i32 {
+        self.client.pdu_length as i32
+    }
+
+    /// ### Returns the time of the last job in ms (`Cli_GetExecTime`)
+    ///
+    pub fn get_exec_time(&self) -> i32 {
+        self.client.last_time.round() as i32
+    }
+
+    /// ### Reads the CPU status (`Cli_GetPlcStatus`): 0x08 RUN, 0x04 STOP, 0x00 unknown
+    ///
+    pub fn get_plc_status(&mut self, status: &mut i32) -> i32 {
+        match self.client.get_plc_status() {
+            Ok(plc_status) => {
+                *status = plc_status.mode() as i32;
+                0
+            }
+            Err(e) => error_code(&e),
+        }
+    }
+
+    /// ### Reads a data area (`Cli_ReadArea`)
+    ///
+    /// ### Parameters
+    /// - `area`, `db_number`, `start`, `amount`, `wordlen`: As Snap7, `S7_WL_BIT` or `S7_WL_BYTE` only.
+    /// - `buffer`: At least `amount` byte.
+    ///
+    pub fn read_area(&mut self, area: u8, db_number: i32, start: i32, amount: i32, wordlen: u8, buffer: &mut [u8]) -> i32 {
+        let Ok(db_number) = u16::try_from(db_number) else {
+            return ERR_CLI_INVALID_PARAMS;
+        };
+        match byte_range(start, amount, buffer.len()) {
+            Ok((start, size)) => result_code(self.client.read_area(area, db_number, start, wordlen, &mut buffer[..size])),
+            Err(code) => code,
+        }
+    }
+
+    /// ### Writes a data area (`Cli_WriteArea`)
+    ///
+    pub fn write_area(&mut self, area: u8, db_number: i32, start: i32, amount: i32, wordlen: u8, buffer: &[u8]) -> i32 {
+        let Ok(db_number) = u16::try_from(db_number) else {
+            return ERR_CLI_INVALID_PARAMS;
+        };
+        match byte_range(start, amount, buffer.len()) {
+            Ok((start, size)) => result_code(self.client.write_area(area, db_number, start, wordlen, &buffer[..size])),
+            Err(code) => code,
+        }
+    }
+
+    /// ### Reads `size` byte of a DB (`Cli_DBRead`)
+    ///
+    pub fn db_read(&mut self, db_number: i32, start: i32, size: i32, buffer: &mut [u8]) -> i32 {
+        self.read_area(S7_AREA_DB, db_number, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Writes `size` byte of a DB (`Cli_DBWrite`)
+    ///
+    pub fn db_write(&mut self, db_number: i32, start: i32, size: i32, buffer: &[u8]) -> i32 {
+        self.write_area(S7_AREA_DB, db_number, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Reads the Merkers (`Cli_MBRead`)
+    ///
+    pub fn mb_read(&mut self, start: i32, size: i32, buffer: &mut [u8]) -> i32 {
+        self.read_area(S7_AREA_MK, 0, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Writes the Merkers (`Cli_MBWrite`)
+    ///
+    pub fn mb_write(&mut self, start: i32, size: i32, buffer: &[u8]) -> i32 {
+        self.write_area(S7_AREA_MK, 0, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Reads the process inputs (`Cli_EBRead`)
+    ///
+    pub fn eb_read(&mut self, start: i32, size: i32, buffer: &mut [u8]) -> i32 {
+        self.read_area(S7_AREA_PE, 0, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Writes the process inputs (`Cli_EBWrite`)
+    ///
+    pub fn eb_write(&mut self, start: i32, size: i32, buffer: &[u8]) -> i32 {
+        self.write_area(S7_AREA_PE, 0, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Reads the process outputs (`Cli_ABRead`)
+    ///
+    pub fn ab_read(&mut self, start: i32, size: i32, buffer: &mut [u8]) -> i32 {
+        self.read_area(S7_AREA_PA, 0, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Writes the process outputs (`Cli_ABWrite`)
+    ///
+    pub fn ab_write(&mut self, start: i32, size: i32, buffer: &[u8]) -> i32 {
+        self.write_area(S7_AREA_PA, 0, start, size, S7_WL_BYTE, buffer)
+    }
+
+    /// ### Reads several variables (`Cli_ReadMultiVars`)
+    ///
+    /// ### Returns
+    /// The result of the telegram, the result of each variable is returned by `item_result()`.
+    ///
+    pub fn read_multi_vars(&mut self, items: &mut [S7DataItem]) -> i32 {
+        if items.len() > MAX_VARS {
+            return ERR_CLI_TOO_MANY_ITEMS;
+        }
+        result_code(self.client.read_multi_vars(items))
+    }
+
+    /// ### Writes several variables (`Cli_WriteMultiVars`)
+    ///
+    /// ### Returns
+    /// The result of the telegram, the result of each variable is returned by `item_result()`.
+    ///
+    pub fn write_multi_vars(&mut self, items: &mut [S7DataItem]) -> i32 {
+        if items.len() > MAX_VARS {
+            return ERR_CLI_TOO_MANY_ITEMS;
+        }
+        result_code(self.client.write_multi_vars(items))
+    }
+
+    /// ### Returns the text of an error code (`Cli_ErrorText`)
+    ///
+    pub fn error_text(&self, code: i32) -> String {
+        error_text(code)
+    }
+}
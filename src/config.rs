@@ -0,0 +1,203 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### TOML bootstrap of a client (feature `config`)
+//!
+//! `S7Client::from_config_file()` builds a connected client and its poll groups from a TOML file,
+//! so the operations staff can change the PLC address, the timeouts and the polling without
+//! recompiling the gateway.
+//!
+//! ```text
+//! [connection]
+//! address = "192.168.0.100"
+//! rack = 0
+//! slot = 1
+//! connection_type = "OP"        # PG (default), OP or S7
+//! read_timeout_ms = 2000
+//!
+//! [[poll_groups]]
+//! interval_ms = 500
+//! stale_factor = 3.0            # optional
+//! tags = [
+//!     { name = "Line1.Speed", area = "DB", db_number = 100, start = 0, data_type = "REAL" },
+//!     { name = "Line1.Running", area = "M", start = 80, data_type = "BOOL" },
+//! ]
+//! ```
+//!
+//! The tags are written as in the JSON tag configuration (see the `tag_config` module).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//!
+//! let mut gateway = S7Client::from_config_file("gateway.toml").unwrap();
+//! loop {
+//!     for subscription in gateway.subscriptions.iter_mut() {
+//!         for update in subscription.poll(&mut gateway.client).unwrap() {
+//!             println!("{} = {}", update.name, update.value);
+//!         }
+//!     }
+//!     std::thread::sleep(std::time::Duration::from_millis(100));
+//! }
+//! ```
+
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::client::{S7Client, S7Error, CT_OP, CT_PG, CT_S7};
+use crate::tag::Subscription;
+use crate::tag_config::{PollGroupConfig, TagConfig};
+
+// Default timeouts of the client (ms)
+const CO_TIMEOUT_MS: u64 = 3000;
+const RD_TIMEOUT_MS: u64 = 1000;
+const WR_TIMEOUT_MS: u64 = 500;
+
+/// ### Connection settings
+///
+/// The CPU is addressed by `local_tsap` + `remote_tsap` if both are set, otherwise by `rack` and
+/// `slot` (0, 0 as `connect_s71200_1500()` if missing).
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionConfig {
+    /// PLC address
+    pub address: String,
+    /// TCP port (Default = 102)
+    pub port: Option<u16>,
+    pub rack: Option<u16>,
+    pub slot: Option<u16>,
+    pub local_tsap: Option<u16>,
+    pub remote_tsap: Option<u16>,
+    /// `PG`, `OP` or `S7` (Default = PG)
+    pub connection_type: Option<String>,
+    pub connect_timeout_ms: Option<u64>,
+    pub read_timeout_ms: Option<u64>,
+    pub write_timeout_ms: Option<u64>,
+}
+
+impl ConnectionConfig {
+    /// ### Applies the settings to a client (not connected)
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: Unknown connection type.
+    /// - `S7Error::InvalidFunParameter`: Port or timeout of 0.
+    ///
+    pub fn configure(&self, client: &mut S7Client) -> Result<(), S7Error> {
+        if let Some(port) = self.port {
+            client.set_connection_port(port)?;
+        }
+        if let Some(connection_type) = &self.connection_type {
+            let connection_type = match connection_type.to_ascii_uppercase().as_str() {
+                "PG" => CT_PG,
+                "OP" => CT_OP,
+                "S7" => CT_S7,
+                _ => return Err(S7Error::Other(format!("config: unknown connection type \"{}\"", connection_type))),
+            };
+            client.set_connection_type(connection_type)?;
+        }
+        client.set_timeout(
+            self.connect_timeout_ms.unwrap_or(CO_TIMEOUT_MS),
+            self.read_timeout_ms.unwrap_or(RD_TIMEOUT_MS),
+            self.write_timeout_ms.unwrap_or(WR_TIMEOUT_MS),
+        )
+    }
+
+    /// ### Connects the client to the configured PLC
+    ///
+    /// ### Errors
+    /// - Reported by `connect_tsap()`
+    ///
+    pub fn connect(&self, client: &mut S7Client) -> Result<(), S7Error> {
+        match (self.local_tsap, self.remote_tsap) {
+            (Some(local_tsap), Some(remote_tsap)) => client.connect_tsap(&self.address, local_tsap, remote_tsap),
+            _ => client.connect_rack_slot(&self.address, self.rack.unwrap_or(0), self.slot.unwrap_or(0)),
+        }
+    }
+}
+
+/// ### Content of a client configuration file
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientConfig {
+    pub connection: ConnectionConfig,
+    #[serde(default)]
+    pub poll_groups: Vec<PollGroupConfig>,
+}
+
+impl ClientConfig {
+    /// ### Parses a TOML configuration
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: Malformed TOML, unknown field, area or data type, scan rate of 0 ms,
+    ///   invalid stale factor, duplicated tag name in a poll group, invalid scaling, edge detection
+    ///   of a non-BOOL tag or invalid deadband (the message contains the reason).
+    ///
+    pub fn from_toml(toml: &str) -> Result<Self, S7Error> {
+        let config: ClientConfig = toml::from_str(toml).map_err(|e| S7Error::Other(format!("config: {}", e)))?;
+        TagConfig { poll_groups: config.poll_groups.clone() }.validate()?;
+        Ok(config)
+    }
+
+    /// ### Serializes the configuration to TOML
+    ///
+    pub fn to_toml(&self) -> String {
+        // Serialization of plain structs with string keys can't fail
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    /// ### Loads the configuration from a TOML file
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The file cannot be read.
+    /// - Reported by `from_toml()`
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, S7Error> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// ### Creates the subscriptions of the poll groups
+    ///
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        self.poll_groups.iter().map(Subscription::from_config).collect()
+    }
+}
+
+/// ### Client and poll groups built by `S7Client::from_config_file()`
+///
+pub struct ConfiguredClient {
+    /// Connected client
+    pub client: S7Client,
+    pub subscriptions: Vec<Subscription>,
+    /// The configuration read, e.g. to reconnect with `connection.connect()`
+    pub config: ClientConfig,
+}
+
+impl S7Client {
+    /// ### Builds a connected client and its poll groups from a TOML file
+    ///
+    /// ### Parameters
+    /// - `path`: Configuration file (see the `config` module for the format).
+    ///
+    /// ### Returns
+    /// `Ok(<ConfiguredClient>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `ClientConfig::load()`, `ConnectionConfig::configure()` and `connect_tsap()`
+    ///
+    /// ### Notes
+    /// The connection is established before returning: a PLC not reachable at startup is an error.
+    /// Use `ClientConfig::load()` and `ConnectionConfig::connect()` to handle the connection yourself.
+    ///
+    pub fn from_config_file<P: AsRef<Path>>(path: P) -> Result<ConfiguredClient, S7Error> {
+        let config = ClientConfig::load(path)?;
+        let mut client = S7Client::new();
+        config.connection.configure(&mut client)?;
+        config.connection.connect(&mut client)?;
+        Ok(ConfiguredClient {
+            client,
+            subscriptions: config.subscriptions(),
+            config,
+        })
+    }
+}
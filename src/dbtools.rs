@@ -0,0 +1,545 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### DB tools
+//!
+//! Whole-DB utilities built on `read_db()`/`write_db()`:
+//! - `diff_db()` compares a DB with a reference image, e.g. to verify a recipe download or to
+//!   detect changes made on the PLC side.
+//! - `snapshot_db()` / `restore_db()` save a DB to a file and write it back, e.g. before and after
+//!   a maintenance.
+//! - `export_db_csv()` / `import_db_csv()` convert the fields of a DB region into CSV rows and
+//!   write back the edited values, e.g. to edit a recipe table with a spreadsheet.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::{S7Client, S7DataType};
+//! use rust7::dbtools::DbField;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! let mut reference = vec![0u8; 256];
+//! client.read_db(10, 0, &mut reference).unwrap();
+//! // ... later
+//! for range in client.diff_db(10, &reference).unwrap() {
+//!     println!("DBB{}..{}: {:02X?} -> {:02X?}", range.start, range.end(), range.reference, range.actual);
+//! }
+//!
+//! client.snapshot_db(10, "db10_before.s7db").unwrap();
+//! client.restore_db(10, "db10_before.s7db").unwrap();
+//!
+//! let layout = [
+//!     DbField::new("Speed", 0, S7DataType::Real),
+//!     DbField::new("Count", 4, S7DataType::DInt),
+//!     DbField::bit("Enable", 8, 0),
+//! ];
+//! std::fs::write("recipe.csv", client.export_db_csv(20, &layout).unwrap()).unwrap();
+//! // ... edited with a spreadsheet
+//! client.import_db_csv(20, &std::fs::read_to_string("recipe.csv").unwrap()).unwrap();
+//! ```
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::blocks::BLOCK_DB;
+use crate::client::{S7Client, S7Error, S7_AREA_DB};
+use crate::value::{S7DataType, S7Value};
+
+/// Signature of the snapshot files
+const SNAPSHOT_MAGIC: &[u8; 4] = b"S7DB";
+const SNAPSHOT_VERSION: u8 = 1;
+/// Magic + Version + DB number + Timestamp + Length + Checksum
+const SNAPSHOT_HEADER_LEN: usize = 23;
+const CSV_HEADER: &str = "name,offset,type,value\n";
+
+/// ### Range of bytes differing from the reference
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRange {
+    /// Offset of the first differing byte
+    pub start: usize,
+    /// Bytes of the reference
+    pub reference: Vec<u8>,
+    /// Bytes read from the PLC
+    pub actual: Vec<u8>,
+}
+
+impl DiffRange {
+    /// ### Returns the number of bytes of the range
+    ///
+    pub fn len(&self) -> usize {
+        self.actual.len()
+    }
+
+    /// ### Returns true if the range is empty (never the case for the ranges returned)
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.actual.is_empty()
+    }
+
+    /// ### Returns the offset following the last differing byte
+    ///
+    pub fn end(&self) -> usize {
+        self.start + self.actual.len()
+    }
+}
+
+/// ### Compares two buffers
+///
+/// ### Parameters
+/// - `actual`: Current data.
+/// - `reference`: Reference data, only the common length is compared.
+///
+/// ### Returns
+/// The ranges of consecutive differing bytes, in order of offset.
+///
+pub fn diff_buffers(actual: &[u8], reference: &[u8]) -> Vec<DiffRange> {
+    let mut ranges: Vec<DiffRange> = Vec::new();
+    for (offset, (&actual, &reference)) in actual.iter().zip(reference).enumerate() {
+        if actual == reference {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end() == offset => {
+                range.actual.push(actual);
+                range.reference.push(reference);
+            }
+            _ => ranges.push(DiffRange { start: offset, reference: vec![reference], actual: vec![actual] }),
+        }
+    }
+    ranges
+}
+
+impl S7Client {
+    /// ### Compares a DB with a reference image
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB number.
+    /// - `reference`: Reference image of the DB from byte 0 (e.g. a previous read or the recipe
+    ///   downloaded), its length is the number of bytes read and compared.
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<DiffRange>>)` the ranges of bytes that differ (empty if the DB matches), or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `read_db()` (e.g. `S7Error::S7InvalidAddress` if the DB is shorter than the reference)
+    ///
+    pub fn diff_db(&mut self, db_number: u16, reference: &[u8]) -> Result<Vec<DiffRange>, S7Error> {
+        let mut actual = vec![0u8; reference.len()];
+        self.read_db(db_number, 0, &mut actual)?;
+        Ok(diff_buffers(&actual, reference))
+    }
+}
+
+/// ### Image of a DB saved by `snapshot_db()`
+///
+/// File layout (little endian): "S7DB", version (1 byte), DB number (2 byte), timestamp (ms since
+/// 1970, 8 byte), length (4 byte), CRC-32 of the data (4 byte), data.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbSnapshot {
+    pub db_number: u16,
+    /// Time of the read
+    pub timestamp: SystemTime,
+    pub data: Vec<u8>,
+}
+
+/// CRC-32 (IEEE 802.3, as zip and png)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+impl DbSnapshot {
+    /// ### Encodes the snapshot in the file layout
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let ms = self.timestamp.duration_since(UNIX_EPOCH).map(|time| time.as_millis() as u64).unwrap_or(0);
+        let mut bytes = Vec::with_capacity(SNAPSHOT_HEADER_LEN + self.data.len());
+        bytes.extend_from_slice(SNAPSHOT_MAGIC);
+        bytes.push(SNAPSHOT_VERSION);
+        bytes.extend_from_slice(&self.db_number.to_le_bytes());
+        bytes.extend_from_slice(&ms.to_le_bytes());
+        bytes.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&crc32(&self.data).to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// ### Decodes a snapshot from the file layout
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: Not a snapshot, unsupported version, wrong length or checksum.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, S7Error> {
+        if bytes.len() < SNAPSHOT_HEADER_LEN || &bytes[..4] != SNAPSHOT_MAGIC {
+            return Err(S7Error::Other("snapshot: not a DB snapshot".to_string()));
+        }
+        if bytes[4] != SNAPSHOT_VERSION {
+            return Err(S7Error::Other(format!("snapshot: unsupported version {}", bytes[4])));
+        }
+        let u32_at = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+        let db_number = u16::from_le_bytes([bytes[5], bytes[6]]);
+        let ms = u64::from_le_bytes(bytes[7..15].try_into().unwrap_or_default());
+        let length = u32_at(15) as usize;
+        let data = &bytes[SNAPSHOT_HEADER_LEN..];
+        if data.len() != length {
+            return Err(S7Error::Other(format!("snapshot: {} byte of data, {} expected", data.len(), length)));
+        }
+        if crc32(data) != u32_at(19) {
+            return Err(S7Error::Other("snapshot: wrong checksum".to_string()));
+        }
+        Ok(DbSnapshot {
+            db_number,
+            timestamp: UNIX_EPOCH + Duration::from_millis(ms),
+            data: data.to_vec(),
+        })
+    }
+
+    /// ### Writes the snapshot to a file
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The file cannot be written.
+    ///
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), S7Error> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// ### Reads a snapshot from a file
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The file cannot be read.
+    /// - Reported by `from_bytes()`
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, S7Error> {
+        DbSnapshot::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl S7Client {
+    /// ### Reads a whole DB
+    ///
+    /// The size of the DB is obtained with `get_block_info()`.
+    ///
+    /// ### Returns
+    /// `Ok(<DbSnapshot>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `get_block_info()` (e.g. `S7Error::S7NotFound`) and `read_db()`
+    ///
+    pub fn read_db_snapshot(&mut self, db_number: u16) -> Result<DbSnapshot, S7Error> {
+        let size = self.get_block_info(BLOCK_DB, db_number)?.mc7_size as usize;
+        let mut data = vec![0u8; size];
+        self.read_db(db_number, 0, &mut data)?;
+        Ok(DbSnapshot { db_number, timestamp: SystemTime::now(), data })
+    }
+
+    /// ### Saves a whole DB to a file
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB number.
+    /// - `path`: File to write (see `DbSnapshot` for the layout).
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)` the size of the DB, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `read_db_snapshot()` and `DbSnapshot::save()`
+    ///
+    pub fn snapshot_db<P: AsRef<Path>>(&mut self, db_number: u16, path: P) -> Result<usize, S7Error> {
+        let snapshot = self.read_db_snapshot(db_number)?;
+        snapshot.save(path)?;
+        Ok(snapshot.data.len())
+    }
+
+    /// ### Writes back a DB saved by `snapshot_db()`
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB number, must be the one of the snapshot.
+    /// - `path`: Snapshot file.
+    ///
+    /// ### Errors
+    /// - Reported by `DbSnapshot::load()` (the file is verified before writing anything).
+    /// - `S7Error::InvalidFunParameter`: The snapshot is of another DB.
+    /// - `S7Error::WrongDataSize`: The size of the DB in the PLC differs from the snapshot (the DB
+    ///   was changed in the meantime), nothing is written.
+    /// - Reported by `get_block_info()` and `write_db()`
+    ///
+    /// ### Notes
+    /// A large DB is written in more telegrams: the write is not consistent for the PLC program.
+    /// To copy a DB into another one, use `DbSnapshot::load()` and `write_db()`.
+    ///
+    pub fn restore_db<P: AsRef<Path>>(&mut self, db_number: u16, path: P) -> Result<(), S7Error> {
+        let snapshot = DbSnapshot::load(path)?;
+        if snapshot.db_number != db_number {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        if self.get_block_info(BLOCK_DB, db_number)?.mc7_size as usize != snapshot.data.len() {
+            return Err(S7Error::WrongDataSize);
+        }
+        self.write_db(db_number, 0, &snapshot.data)
+    }
+}
+
+/// ### Field of a DB region, exported and imported as a CSV row
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbField {
+    pub name: String,
+    /// Byte offset in the DB
+    pub offset: u16,
+    /// Bit index (0..7), BOOL only
+    pub bit: u8,
+    pub data_type: S7DataType,
+}
+
+impl DbField {
+    /// ### Creates a field
+    ///
+    /// For a BOOL, the bit 0 of the byte (see `bit()`).
+    ///
+    pub fn new(name: &str, offset: u16, data_type: S7DataType) -> Self {
+        DbField { name: name.to_string(), offset, bit: 0, data_type }
+    }
+
+    /// ### Creates a BOOL field
+    ///
+    pub fn bit(name: &str, offset: u16, bit: u8) -> Self {
+        DbField { name: name.to_string(), offset, bit: bit & 0x07, data_type: S7DataType::Bool }
+    }
+
+    /// Address as written in the CSV: "12", "12.3" for a BOOL
+    fn address(&self) -> String {
+        if self.data_type == S7DataType::Bool {
+            format!("{}.{}", self.offset, self.bit)
+        } else {
+            self.offset.to_string()
+        }
+    }
+
+    fn end(&self) -> usize {
+        self.offset as usize + self.data_type.size()
+    }
+
+    /// Decodes the field from the image of the region starting at `start`
+    fn decode(&self, image: &[u8], start: usize) -> Result<S7Value, S7Error> {
+        let offset = self.offset as usize - start;
+        match self.data_type {
+            S7DataType::Bool => Ok(S7Value::Bool(image[offset] & (1 << self.bit) != 0)),
+            data_type => S7Value::decode(data_type, &image[offset..]),
+        }
+    }
+
+    /// Encodes the field into the image of the region starting at `start`
+    fn encode(&self, value: &S7Value, image: &mut [u8], start: usize) -> Result<(), S7Error> {
+        let offset = self.offset as usize - start;
+        match (self.data_type, value) {
+            (S7DataType::Bool, S7Value::Bool(level)) => {
+                if *level {
+                    image[offset] |= 1 << self.bit;
+                } else {
+                    image[offset] &= !(1 << self.bit);
+                }
+            }
+            (data_type, value) => {
+                let bytes = value.encode(data_type)?;
+                image[offset..offset + bytes.len()].copy_from_slice(&bytes);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the region covering all the fields: (start, length)
+fn region<'a>(fields: impl Iterator<Item = &'a DbField> + Clone) -> (usize, usize) {
+    let start = fields.clone().map(|field| field.offset as usize).min().unwrap_or(0);
+    let end = fields.map(DbField::end).max().unwrap_or(start);
+    (start, end - start)
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', ';', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Splits a CSV line, with quoted fields
+fn csv_split(line: &str, separator: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// ### Formats the fields of a DB region as CSV
+///
+/// ### Parameters
+/// - `image`: Content of the DB from the byte `start`.
+/// - `start`: Offset in the DB of the first byte of `image`.
+/// - `layout`: Fields, in the order of the rows.
+///
+/// ### Returns
+/// `Ok(<String>)` with the header `name,offset,type,value` and a row per field (the offset of a
+/// BOOL is `byte.bit`), or `Err(<S7Error>)`
+///
+/// ### Errors
+/// - `S7Error::InvalidFunParameter`: A field is outside the image.
+/// - Reported by `S7Value::decode()`
+///
+pub fn fields_to_csv(image: &[u8], start: usize, layout: &[DbField]) -> Result<String, S7Error> {
+    let mut csv = CSV_HEADER.to_string();
+    for field in layout {
+        if (field.offset as usize) < start || field.end() > start + image.len() {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let value = field.decode(image, start)?;
+        csv.push_str(&format!("{},{},{},{}\n",
+            csv_field(&field.name), field.address(), field.data_type, csv_field(&value.to_string())));
+    }
+    Ok(csv)
+}
+
+/// ### Parses the rows of a CSV written by `export_db_csv()`
+///
+/// The columns are found by the header (`offset`, `type` and `value` are required, `name` is
+/// optional), so they can be reordered; the separator can be `,` or `;` (as found in the header).
+/// Empty lines are skipped.
+///
+/// ### Returns
+/// `Ok(<Vec<(DbField, S7Value)>>)` or `Err(<S7Error>)`
+///
+/// ### Errors
+/// - `S7Error::Other`: Missing column, invalid offset, type or value (the message gives the line).
+///
+pub fn parse_csv_fields(csv: &str) -> Result<Vec<(DbField, S7Value)>, S7Error> {
+    let mut lines = csv.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let separator = if header.contains(';') && !header.contains(',') { ';' } else { ',' };
+    let columns: Vec<String> = csv_split(header.trim_start_matches('\u{FEFF}'), separator)
+        .iter().map(|column| column.trim().to_ascii_lowercase()).collect();
+    let column = |name: &str| columns.iter().position(|column| column == name);
+    let (Some(offset_col), Some(type_col), Some(value_col)) = (column("offset"), column("type"), column("value")) else {
+        return Err(S7Error::Other("csv: the columns offset, type and value are required".to_string()));
+    };
+    let name_col = column("name");
+
+    let mut fields = Vec::new();
+    for (index, line) in lines {
+        let error = |message: &str| S7Error::Other(format!("csv line {}: {}", index + 1, message));
+        let row = csv_split(line, separator);
+        let cell = |col: usize| row.get(col).map(String::as_str).ok_or_else(|| error("missing column"));
+        let data_type: S7DataType = cell(type_col)?.parse().map_err(|_| error("invalid type"))?;
+        let address = cell(offset_col)?.trim();
+        let (offset, bit) = match address.split_once('.') {
+            Some((byte, bit)) if data_type == S7DataType::Bool => (byte, bit.parse::<u8>().ok().filter(|bit| *bit < 8)),
+            None => (address, Some(0)),
+            Some(_) => (address, None),
+        };
+        let (Ok(offset), Some(bit)) = (offset.parse::<u16>(), bit) else {
+            return Err(error("invalid offset"));
+        };
+        let name = name_col.and_then(|col| row.get(col)).map(String::as_str).unwrap_or_default();
+        let value = S7Value::parse(cell(value_col)?, data_type).map_err(|_| error("invalid value"))?;
+        fields.push((DbField { name: name.to_string(), offset, bit, data_type }, value));
+    }
+    Ok(fields)
+}
+
+impl S7Client {
+    /// ### Exports the fields of a DB region as CSV
+    ///
+    /// The region covering all the fields is read with a single `read_db()`.
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB number.
+    /// - `layout`: Fields, in the order of the rows.
+    ///
+    /// ### Returns
+    /// `Ok(<String>)` (see `fields_to_csv()`) or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `read_db()` and `fields_to_csv()`
+    ///
+    pub fn export_db_csv(&mut self, db_number: u16, layout: &[DbField]) -> Result<String, S7Error> {
+        let (start, length) = region(layout.iter());
+        let mut image = vec![0u8; length];
+        if length > 0 {
+            self.read_db(db_number, start as u32, &mut image)?;
+        }
+        fields_to_csv(&image, start, layout)
+    }
+
+    /// ### Writes the values of a CSV exported by `export_db_csv()` (and edited) into a DB
+    ///
+    /// The region is read first and only the values that differ are written: the bytes changed
+    /// with `write_db()`, the bits with `write_bit()`, so the other bits of a byte are untouched.
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB number.
+    /// - `csv`: CSV text (see `parse_csv_fields()`).
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)` the number of values changed, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `parse_csv_fields()`: nothing is written if any row is invalid.
+    /// - `S7Error::Other`: A value doesn't fit into its type (e.g. a string too long).
+    /// - Reported by `read_db()`, `write_db()` and `write_bit()`
+    ///
+    pub fn import_db_csv(&mut self, db_number: u16, csv: &str) -> Result<usize, S7Error> {
+        let fields = parse_csv_fields(csv)?;
+        let (start, length) = region(fields.iter().map(|(field, _)| field));
+        if length == 0 {
+            return Ok(0);
+        }
+        let mut original = vec![0u8; length];
+        self.read_db(db_number, start as u32, &mut original)?;
+
+        let mut image = original.clone();
+        let mut bits = Vec::new();
+        let mut changed = 0;
+        for (field, value) in &fields {
+            if field.decode(&original, start)? == *value {
+                continue;
+            }
+            changed += 1;
+            match value {
+                S7Value::Bool(level) if field.data_type == S7DataType::Bool => bits.push((field.offset, field.bit, *level)),
+                _ => field.encode(value, &mut image, start)
+                    .map_err(|_| S7Error::Other(format!("csv: invalid value at offset {}", field.address())))?,
+            }
+        }
+        for range in diff_buffers(&image, &original) {
+            self.write_db(db_number, (start + range.start) as u32, &range.actual)?;
+        }
+        for (offset, bit, level) in bits {
+            self.write_bit(S7_AREA_DB, db_number, offset.into(), bit, level)?;
+        }
+        Ok(changed)
+    }
+}
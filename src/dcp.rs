@@ -0,0 +1,518 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### PROFINET DCP device discovery and configuration (feature `dcp`)
+//!
+//! The Discovery and Configuration Protocol works directly over Ethernet (EtherType 0x8892): an
+//! Identify request sent to the DCP multicast address is answered by every PROFINET device of the
+//! segment, even without an IP address or with one of another subnet. It's the way the engineering
+//! tools (PRONETA, TIA Portal "Accessible devices") find the devices to commission, assign them
+//! the IP address and the station name, and flash their LEDs to find them in the cabinet.
+//!
+//! The frames go through a `DcpLink`: `Dcp::open()` uses a raw socket of a network interface
+//! (`EthernetLink`), which requires administrator rights (root or CAP_NET_RAW on Linux, Npcap on
+//! Windows).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::net::Ipv4Addr;
+//! use std::time::Duration;
+//! use rust7::dcp::Dcp;
+//!
+//! let mut dcp = Dcp::open("eth0").unwrap();
+//! for device in dcp.identify_all(Duration::from_secs(2)).unwrap().iter().filter(|device| device.is_siemens()) {
+//!     println!("{:<24} {:<15} {} {}", device.station_name, device.ip, device.mac_string(), device.device_type);
+//! }
+//!
+//! // Commissioning of a new CPU
+//! let mac = [0x28, 0x63, 0x36, 0x11, 0x22, 0x33];
+//! dcp.signal(mac, Duration::from_secs(1)).unwrap(); // Is it the one in front of me?
+//! dcp.set_station_name(mac, "plc-line1", true, Duration::from_secs(1)).unwrap();
+//! dcp.set_ip(mac, Ipv4Addr::new(192, 168, 0, 10), Ipv4Addr::new(255, 255, 255, 0), Ipv4Addr::UNSPECIFIED, true, Duration::from_secs(1)).unwrap();
+//! ```
+
+use std::io;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use pnet_datalink::{Channel, Config, DataLinkReceiver, DataLinkSender};
+use crate::client::S7Error;
+
+/// EtherType of the PROFINET real-time frames
+pub const ETHERTYPE_PROFINET: u16 = 0x8892;
+/// Multicast address of the Identify requests
+pub const DCP_MULTICAST_MAC: [u8; 6] = [0x01, 0x0E, 0xCF, 0x00, 0x00, 0x00];
+/// PROFINET vendor ID of Siemens
+pub const VENDOR_ID_SIEMENS: u16 = 0x002A;
+
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETH_HEADER_LEN: usize = 14;
+const ETH_MIN_FRAME_LEN: usize = 60; // Without FCS
+const DCP_HEADER_LEN: usize = 12; // FrameID + ServiceID + ServiceType + Xid + ResponseDelay + DataLength
+const BLOCK_HEADER_LEN: usize = 4; // Option + Suboption + BlockLength
+const BLOCK_INFO_LEN: usize = 2;
+
+// Frame IDs
+const FRAME_ID_IDENTIFY_REQ: u16 = 0xFEFE;
+const FRAME_ID_IDENTIFY_RES: u16 = 0xFEFF;
+const FRAME_ID_GET_SET: u16 = 0xFEFD;
+
+// Services
+const SERVICE_SET: u8 = 0x04;
+const SERVICE_IDENTIFY: u8 = 0x05;
+const SERVICE_TYPE_REQUEST: u8 = 0x00;
+const SERVICE_TYPE_SUCCESS: u8 = 0x01;
+
+// Options / suboptions
+const OPT_IP: u8 = 0x01;
+const SUB_IP_MAC: u8 = 0x01;
+const SUB_IP_PARAMETER: u8 = 0x02;
+const OPT_DEVICE: u8 = 0x02;
+const SUB_DEVICE_TYPE: u8 = 0x01;
+const SUB_DEVICE_NAME: u8 = 0x02;
+const SUB_DEVICE_ID: u8 = 0x03;
+const SUB_DEVICE_ROLE: u8 = 0x04;
+const OPT_CONTROL: u8 = 0x05;
+const SUB_CONTROL_SIGNAL: u8 = 0x03;
+const SUB_CONTROL_RESPONSE: u8 = 0x04;
+const OPT_ALL: u8 = 0xFF;
+const SUB_ALL: u8 = 0xFF;
+
+/// Response delay factor of the Identify requests (the devices answer within factor * 10 ms)
+const RESPONSE_DELAY_FACTOR: u16 = 0x0001;
+
+// Block qualifiers of the Set requests
+const QUALIFIER_TEMPORARY: u16 = 0x0000;
+const QUALIFIER_PERMANENT: u16 = 0x0001;
+/// Signal value: flash once (the device flashes its LEDs for about 3 s)
+const SIGNAL_FLASH_ONCE: u16 = 0x0100;
+/// Max length of a name of station
+const MAX_STATION_NAME_LEN: usize = 240;
+
+// Max blocking time of a single receive of `EthernetLink`, the deadline is checked in between
+const POLL_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// ### Raw Ethernet access used by `Dcp`
+///
+pub trait DcpLink: Send {
+    /// ### Returns the MAC address of the interface
+    ///
+    fn mac_address(&self) -> [u8; 6];
+
+    /// ### Sends a complete Ethernet frame (FCS excluded)
+    ///
+    fn send(&mut self, frame: &[u8]) -> io::Result<()>;
+
+    /// ### Receives an Ethernet frame, `None` if nothing arrives within `timeout`
+    ///
+    fn receive(&mut self, timeout: Duration) -> io::Result<Option<Vec<u8>>>;
+}
+
+/// ### PROFINET device answering to DCP
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DcpDevice {
+    pub mac: [u8; 6],
+    /// Name of station (empty if not assigned)
+    pub station_name: String,
+    /// Type of station (e.g. "S7-1500")
+    pub device_type: String,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Device role bits (0x01 IO-Device, 0x02 IO-Controller, 0x04 IO-Multidevice, 0x08 PN-Supervisor)
+    pub role: u8,
+    /// 0.0.0.0 if not assigned
+    pub ip: Ipv4Addr,
+    pub subnet_mask: Ipv4Addr,
+    pub gateway: Ipv4Addr,
+}
+
+impl DcpDevice {
+    /// ### Returns true if the device is made by Siemens (vendor ID 0x002A)
+    ///
+    pub fn is_siemens(&self) -> bool {
+        self.vendor_id == VENDOR_ID_SIEMENS
+    }
+
+    /// ### Returns the MAC address as "AA:BB:CC:DD:EE:FF"
+    ///
+    pub fn mac_string(&self) -> String {
+        self.mac.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(":")
+    }
+}
+
+/// ### DCP requester
+///
+pub struct Dcp {
+    link: Box<dyn DcpLink>,
+    xid: u32,
+}
+
+/// Builds a DCP request frame
+fn dcp_frame(destination: [u8; 6], source: [u8; 6], frame_id: u16, service_id: u8, xid: u32, response_delay: u16, blocks: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(ETH_MIN_FRAME_LEN.max(ETH_HEADER_LEN + DCP_HEADER_LEN + blocks.len()));
+    frame.extend_from_slice(&destination);
+    frame.extend_from_slice(&source);
+    frame.extend_from_slice(&ETHERTYPE_PROFINET.to_be_bytes());
+    frame.extend_from_slice(&frame_id.to_be_bytes());
+    frame.push(service_id);
+    frame.push(SERVICE_TYPE_REQUEST);
+    frame.extend_from_slice(&xid.to_be_bytes());
+    frame.extend_from_slice(&response_delay.to_be_bytes());
+    frame.extend_from_slice(&(blocks.len() as u16).to_be_bytes());
+    frame.extend_from_slice(blocks);
+    frame.resize(frame.len().max(ETH_MIN_FRAME_LEN), 0x00);
+    frame
+}
+
+/// DCP response: source MAC, frame ID, service ID, service type, Xid and blocks
+struct DcpResponse<'a> {
+    source: [u8; 6],
+    frame_id: u16,
+    service_id: u8,
+    service_type: u8,
+    xid: u32,
+    blocks: &'a [u8],
+}
+
+/// Decodes a DCP frame, `None` if it isn't one
+fn parse_frame(frame: &[u8]) -> Option<DcpResponse<'_>> {
+    let source: [u8; 6] = frame.get(6..12)?.try_into().ok()?;
+    let mut offset = 12;
+    let mut ethertype = u16::from_be_bytes(frame.get(offset..offset + 2)?.try_into().ok()?);
+    if ethertype == ETHERTYPE_VLAN {
+        offset += 4;
+        ethertype = u16::from_be_bytes(frame.get(offset..offset + 2)?.try_into().ok()?);
+    }
+    if ethertype != ETHERTYPE_PROFINET {
+        return None;
+    }
+    let dcp = frame.get(offset + 2..)?;
+    if dcp.len() < DCP_HEADER_LEN {
+        return None;
+    }
+    let data_len = u16::from_be_bytes([dcp[10], dcp[11]]) as usize;
+    Some(DcpResponse {
+        source,
+        frame_id: u16::from_be_bytes([dcp[0], dcp[1]]),
+        service_id: dcp[2],
+        service_type: dcp[3],
+        xid: u32::from_be_bytes([dcp[4], dcp[5], dcp[6], dcp[7]]),
+        blocks: dcp.get(DCP_HEADER_LEN..DCP_HEADER_LEN + data_len)?,
+    })
+}
+
+/// Iterates over the blocks: (option, suboption, value)
+fn blocks(mut data: &[u8]) -> impl Iterator<Item = (u8, u8, &[u8])> {
+    std::iter::from_fn(move || {
+        if data.len() < BLOCK_HEADER_LEN {
+            return None;
+        }
+        let length = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let value = data.get(BLOCK_HEADER_LEN..BLOCK_HEADER_LEN + length)?;
+        let block = (data[0], data[1], value);
+        // Blocks are padded to an even length
+        data = data.get(BLOCK_HEADER_LEN + length + length % 2..).unwrap_or(&[]);
+        Some(block)
+    })
+}
+
+fn ipv4(bytes: &[u8]) -> Ipv4Addr {
+    Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])
+}
+
+/// Encodes a Set request block: option, suboption, length, qualifier and value, padded
+fn set_block(option: u8, suboption: u8, qualifier: u16, value: &[u8]) -> Vec<u8> {
+    let mut block = vec![option, suboption];
+    block.extend_from_slice(&((BLOCK_INFO_LEN + value.len()) as u16).to_be_bytes());
+    block.extend_from_slice(&qualifier.to_be_bytes());
+    block.extend_from_slice(value);
+    if value.len() % 2 == 1 {
+        block.push(0x00);
+    }
+    block
+}
+
+/// Text of the BlockError of a Set response
+fn block_error_text(error: u8) -> &'static str {
+    match error {
+        0x01 => "option not supported",
+        0x02 => "suboption not supported",
+        0x03 => "suboption not set",
+        0x04 => "resource error",
+        0x05 => "set not possible by local reasons",
+        0x06 => "in operation, set not possible",
+        _ => "unknown error",
+    }
+}
+
+/// Checks a name of station (lowercase letters, digits, '-' and '.', up to 240 characters)
+fn check_station_name(name: &str) -> Result<(), S7Error> {
+    let valid = name.len() <= MAX_STATION_NAME_LEN
+        && name.bytes().all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-' || byte == b'.')
+        && name.split('.').all(|label| !label.is_empty() && label.len() <= 63 && !label.starts_with('-') && !label.ends_with('-'));
+    if valid { Ok(()) } else { Err(S7Error::InvalidFunParameter) }
+}
+
+/// Decodes the blocks of an Identify response
+fn parse_device(source: [u8; 6], data: &[u8]) -> DcpDevice {
+    let mut device = DcpDevice {
+        mac: source,
+        station_name: String::new(),
+        device_type: String::new(),
+        vendor_id: 0,
+        device_id: 0,
+        role: 0,
+        ip: Ipv4Addr::UNSPECIFIED,
+        subnet_mask: Ipv4Addr::UNSPECIFIED,
+        gateway: Ipv4Addr::UNSPECIFIED,
+    };
+    for (option, suboption, value) in blocks(data) {
+        // Response blocks start with the BlockInfo
+        let Some(value) = value.get(BLOCK_INFO_LEN..) else {
+            continue;
+        };
+        let text = || String::from_utf8_lossy(value).trim_end_matches('\0').to_string();
+        match (option, suboption) {
+            (OPT_IP, SUB_IP_MAC) if value.len() >= 6 => device.mac.copy_from_slice(&value[..6]),
+            (OPT_IP, SUB_IP_PARAMETER) if value.len() >= 12 => {
+                device.ip = ipv4(&value[0..4]);
+                device.subnet_mask = ipv4(&value[4..8]);
+                device.gateway = ipv4(&value[8..12]);
+            }
+            (OPT_DEVICE, SUB_DEVICE_TYPE) => device.device_type = text(),
+            (OPT_DEVICE, SUB_DEVICE_NAME) => device.station_name = text(),
+            (OPT_DEVICE, SUB_DEVICE_ID) if value.len() >= 4 => {
+                device.vendor_id = u16::from_be_bytes([value[0], value[1]]);
+                device.device_id = u16::from_be_bytes([value[2], value[3]]);
+            }
+            (OPT_DEVICE, SUB_DEVICE_ROLE) if !value.is_empty() => device.role = value[0],
+            _ => {}
+        }
+    }
+    device
+}
+
+impl Dcp {
+    /// ### Creates a requester over a link
+    ///
+    pub fn new(link: Box<dyn DcpLink>) -> Self {
+        // The transaction IDs start from a time based value, as the other tools on the segment do
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.subsec_nanos()).unwrap_or(0);
+        Dcp { link, xid: seed }
+    }
+
+    /// ### Opens a requester on a network interface
+    ///
+    /// ### Parameters
+    /// - `interface`: Interface name (e.g. "eth0", see `EthernetLink::interfaces()`).
+    ///
+    /// ### Errors
+    /// - Reported by `EthernetLink::open()`
+    ///
+    pub fn open(interface: &str) -> Result<Self, S7Error> {
+        Ok(Dcp::new(Box::new(EthernetLink::open(interface)?)))
+    }
+
+    fn next_xid(&mut self) -> u32 {
+        self.xid = self.xid.wrapping_add(1);
+        self.xid
+    }
+
+    /// ### Finds the PROFINET devices of the segment
+    ///
+    /// Sends an Identify All request to the DCP multicast address and collects the responses.
+    ///
+    /// ### Parameters
+    /// - `timeout`: Time waited for the responses.
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<DcpDevice>>)` the devices answering, in order of response, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: I/O error of the link.
+    ///
+    /// ### Notes
+    /// All the vendors answer, use `DcpDevice::is_siemens()` to filter the Siemens devices.
+    ///
+    pub fn identify_all(&mut self, timeout: Duration) -> Result<Vec<DcpDevice>, S7Error> {
+        let xid = self.next_xid();
+        let request = dcp_frame(DCP_MULTICAST_MAC, self.link.mac_address(), FRAME_ID_IDENTIFY_REQ, SERVICE_IDENTIFY,
+            xid, RESPONSE_DELAY_FACTOR, &[OPT_ALL, SUB_ALL, 0x00, 0x00]);
+        self.link.send(&request)?;
+
+        let mut devices: Vec<DcpDevice> = Vec::new();
+        let deadline = Instant::now() + timeout;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()).filter(|remaining| !remaining.is_zero()) {
+            let Some(frame) = self.link.receive(remaining)? else {
+                break;
+            };
+            let Some(response) = parse_frame(&frame) else {
+                continue;
+            };
+            if response.frame_id != FRAME_ID_IDENTIFY_RES || response.service_id != SERVICE_IDENTIFY
+                || response.service_type != SERVICE_TYPE_SUCCESS || response.xid != xid {
+                continue;
+            }
+            let device = parse_device(response.source, response.blocks);
+            // A device may answer on several ports
+            if !devices.iter().any(|known| known.mac == device.mac) {
+                devices.push(device);
+            }
+        }
+        Ok(devices)
+    }
+
+    /// ### Assigns the IP suite to a device
+    ///
+    /// ### Parameters
+    /// - `mac`: MAC address of the device (see `identify_all()`).
+    /// - `ip`, `subnet_mask`, `gateway`: The IP suite, `gateway` 0.0.0.0 (`Ipv4Addr::UNSPECIFIED`) if none.
+    ///   An IP 0.0.0.0 removes the address.
+    /// - `permanent`: true to keep the address after a power cycle, false until the next one.
+    /// - `timeout`: Time waited for the response.
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: No response within the timeout (`TimedOut`), or I/O error of the link.
+    /// - `S7Error::Other`: The device refused the setting (e.g. "set not possible by local reasons"
+    ///   for a CPU whose address is fixed by its hardware configuration).
+    ///
+    pub fn set_ip(&mut self, mac: [u8; 6], ip: Ipv4Addr, subnet_mask: Ipv4Addr, gateway: Ipv4Addr, permanent: bool, timeout: Duration) -> Result<(), S7Error> {
+        let value = [ip.octets(), subnet_mask.octets(), gateway.octets()].concat();
+        let qualifier = if permanent { QUALIFIER_PERMANENT } else { QUALIFIER_TEMPORARY };
+        self.set(mac, &set_block(OPT_IP, SUB_IP_PARAMETER, qualifier, &value), timeout)
+    }
+
+    /// ### Assigns the name of station to a device
+    ///
+    /// ### Parameters
+    /// - `mac`: MAC address of the device.
+    /// - `name`: PROFINET name: lowercase letters, digits, '-' and '.', labels of max 63 characters
+    ///   (e.g. "plc-line1"), empty to remove the name.
+    /// - `permanent`: true to keep the name after a power cycle, false until the next one.
+    /// - `timeout`: Time waited for the response.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Invalid name.
+    /// - Other as `set_ip()`
+    ///
+    pub fn set_station_name(&mut self, mac: [u8; 6], name: &str, permanent: bool, timeout: Duration) -> Result<(), S7Error> {
+        if !name.is_empty() {
+            check_station_name(name)?;
+        }
+        let qualifier = if permanent { QUALIFIER_PERMANENT } else { QUALIFIER_TEMPORARY };
+        self.set(mac, &set_block(OPT_DEVICE, SUB_DEVICE_NAME, qualifier, name.as_bytes()), timeout)
+    }
+
+    /// ### Flashes the LEDs of a device, to find it in the cabinet
+    ///
+    /// The device flashes for about 3 seconds; repeat the call to keep it flashing.
+    ///
+    /// ### Errors
+    /// - As `set_ip()`
+    ///
+    pub fn signal(&mut self, mac: [u8; 6], timeout: Duration) -> Result<(), S7Error> {
+        self.set(mac, &set_block(OPT_CONTROL, SUB_CONTROL_SIGNAL, 0x0000, &SIGNAL_FLASH_ONCE.to_be_bytes()), timeout)
+    }
+
+    /// Sends a Set request and checks the response of the device
+    fn set(&mut self, mac: [u8; 6], blocks_data: &[u8], timeout: Duration) -> Result<(), S7Error> {
+        let xid = self.next_xid();
+        let request = dcp_frame(mac, self.link.mac_address(), FRAME_ID_GET_SET, SERVICE_SET, xid, 0x0000, blocks_data);
+        self.link.send(&request)?;
+
+        let deadline = Instant::now() + timeout;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()).filter(|remaining| !remaining.is_zero()) {
+            let Some(frame) = self.link.receive(remaining)? else {
+                break;
+            };
+            let Some(response) = parse_frame(&frame) else {
+                continue;
+            };
+            if response.source != mac || response.frame_id != FRAME_ID_GET_SET || response.service_id != SERVICE_SET || response.xid != xid {
+                continue;
+            }
+            if response.service_type != SERVICE_TYPE_SUCCESS {
+                return Err(S7Error::Other("dcp: set not supported by the device".to_string()));
+            }
+            // A Control/Response block per block set: the option, the suboption and the error
+            for (option, suboption, value) in blocks(response.blocks) {
+                if (option, suboption) == (OPT_CONTROL, SUB_CONTROL_RESPONSE) && value.len() >= 3 && value[2] != 0 {
+                    return Err(S7Error::Other(format!("dcp: set refused by the device ({})", block_error_text(value[2]))));
+                }
+            }
+            return Ok(());
+        }
+        Err(S7Error::Io(io::Error::new(io::ErrorKind::TimedOut, "dcp: no response to the set request")))
+    }
+}
+
+/// ### Raw socket of a network interface
+///
+pub struct EthernetLink {
+    mac: [u8; 6],
+    sender: Box<dyn DataLinkSender>,
+    receiver: Box<dyn DataLinkReceiver>,
+}
+
+impl EthernetLink {
+    /// ### Opens a raw socket on a network interface
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: Unknown interface or interface without a MAC address.
+    /// - `S7Error::Io`: The socket can't be opened (e.g. missing administrator rights).
+    ///
+    pub fn open(interface: &str) -> Result<Self, S7Error> {
+        let network_interface = pnet_datalink::interfaces().into_iter()
+            .find(|candidate| candidate.name == interface)
+            .ok_or_else(|| S7Error::Other(format!("dcp: unknown interface \"{}\"", interface)))?;
+        let mac = network_interface.mac
+            .ok_or_else(|| S7Error::Other(format!("dcp: interface \"{}\" has no MAC address", interface)))?;
+        let config = Config { read_timeout: Some(POLL_TIMEOUT), ..Default::default() };
+        match pnet_datalink::channel(&network_interface, config)? {
+            Channel::Ethernet(sender, receiver) => Ok(EthernetLink {
+                mac: [mac.0, mac.1, mac.2, mac.3, mac.4, mac.5],
+                sender,
+                receiver,
+            }),
+            _ => Err(S7Error::Other(format!("dcp: interface \"{}\" is not an Ethernet interface", interface))),
+        }
+    }
+
+    /// ### Returns the names of the network interfaces having a MAC address
+    ///
+    pub fn interfaces() -> Vec<String> {
+        pnet_datalink::interfaces().into_iter()
+            .filter(|interface| interface.mac.is_some_and(|mac| mac.0 | mac.1 | mac.2 | mac.3 | mac.4 | mac.5 != 0))
+            .map(|interface| interface.name)
+            .collect()
+    }
+}
+
+impl DcpLink for EthernetLink {
+    fn mac_address(&self) -> [u8; 6] {
+        self.mac
+    }
+
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.sender.send_to(frame, None).unwrap_or_else(|| Err(io::Error::other("send buffer full")))
+    }
+
+    fn receive(&mut self, timeout: Duration) -> io::Result<Option<Vec<u8>>> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            match self.receiver.next() {
+                Ok(frame) if is_profinet(frame) => return Ok(Some(frame.to_vec())),
+                Ok(_) => {} // Other traffic of the interface
+                Err(error) if matches!(error.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// EtherType PROFINET, VLAN tagged or not
+fn is_profinet(frame: &[u8]) -> bool {
+    let ethertype = |offset: usize| frame.get(offset..offset + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+    ethertype(12) == Some(ETHERTYPE_PROFINET) || (ethertype(12) == Some(ETHERTYPE_VLAN) && ethertype(16) == Some(ETHERTYPE_PROFINET))
+}
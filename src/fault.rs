@@ -0,0 +1,335 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Fault injection
+//!
+//! `FaultTransport` decorates a transport and disturbs the telegrams coming from the PLC according
+//! to a `FaultScenario`: latency, short reads, dropped bytes, fragmented frames, lost frames and
+//! mid-transfer disconnects. It allows proving the reconnect/retry logic of an application against
+//! a simulator (or a real PLC) before deploying it to a plant.
+//!
+//! The faults are bound to the received frames (TPKT telegrams), counted from 1 at each connection:
+//! frame 1 is the COTP Connection Confirm, frame 2 the Setup Communication response, and the
+//! following ones are the responses to the requests of the application.
+//!
+//! A scenario can be written as a script, one step per line, `#` starts a comment:
+//!
+//! ```text
+//! # frame  fault        arguments
+//! 3        latency      500     # delivers frame 3 after 500 ms
+//! 4        short-read   3       # reads of frame 4 return at most 3 bytes
+//! 5        drop         2 7     # discards 2 bytes of frame 5, from offset 7 (default 0)
+//! 6        fragment     4 20    # delivers frame 6 in pieces of 4 bytes, 20 ms apart (default 0)
+//! 7        timeout              # frame 7 is lost, the read times out
+//! 8        disconnect   10      # the connection drops after 10 bytes of frame 8 (default 0)
+//! ```
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::fault::{fault_wrapper, FaultScenario};
+//!
+//! let scenario = FaultScenario::parse("5 fragment 1 5\n8 disconnect 12").unwrap();
+//! let mut client = S7Client::new();
+//! // The first connection is disturbed, the following ones (after the reconnection) are clean
+//! client.set_transport_wrapper(Some(fault_wrapper(vec![scenario])));
+//! client.connect_s71200_1500("127.0.0.1").unwrap();
+//! ```
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use crate::frame::TPKT_LEN;
+use crate::transport::{Transport, TransportWrapper};
+
+/// ### Fault applied to a received frame
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// The frame is delivered after the delay
+    Latency(Duration),
+    /// Each read of the frame returns at most the given number of bytes
+    ShortReads(usize),
+    /// `count` bytes of the frame are discarded, starting at `offset`
+    DropBytes { offset: usize, count: usize },
+    /// The frame is delivered in pieces of `size` bytes, `delay` apart
+    Fragment { size: usize, delay: Duration },
+    /// The frame is lost: the read fails with `io::ErrorKind::TimedOut` after the read timeout
+    Timeout,
+    /// The connection drops after `after` bytes of the frame: the following reads and writes fail
+    Disconnect { after: usize },
+}
+
+/// ### Step of a scenario
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultStep {
+    /// Received frame, from 1
+    pub frame: u64,
+    pub fault: Fault,
+}
+
+/// ### List of faults to inject into a connection
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultScenario {
+    pub steps: Vec<FaultStep>,
+}
+
+impl FaultScenario {
+    /// ### Creates an empty scenario (no faults)
+    ///
+    pub fn new() -> Self {
+        FaultScenario::default()
+    }
+
+    /// ### Adds a step to the scenario
+    ///
+    /// ### Parameters
+    /// - `frame`: Received frame, from 1. More faults can be bound to the same frame.
+    /// - `fault`: The fault to inject.
+    ///
+    pub fn step(mut self, frame: u64, fault: Fault) -> Self {
+        self.steps.push(FaultStep { frame, fault });
+        self
+    }
+
+    /// ### Parses a scenario script
+    ///
+    /// ### Errors
+    /// - `io::ErrorKind::InvalidData`: Malformed line (the message contains the line number).
+    ///
+    pub fn parse(script: &str) -> io::Result<Self> {
+        let mut scenario = FaultScenario::new();
+        for (index, line) in script.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let step = parse_step(line).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("scenario line {}: malformed step", index + 1))
+            })?;
+            scenario.steps.push(step);
+        }
+        Ok(scenario)
+    }
+
+    /// ### Loads a scenario script from a file
+    ///
+    /// ### Errors
+    /// - Reported by `parse()`.
+    /// - Other I/O errors opening or reading the file.
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+fn parse_step(line: &str) -> Option<FaultStep> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let frame: u64 = fields.first()?.parse().ok().filter(|frame| *frame > 0)?;
+    let arg = |index: usize| -> Option<u64> { fields.get(index + 2)?.parse().ok() };
+    let optional = |index: usize| -> Option<u64> {
+        match fields.get(index + 2) {
+            Some(field) => field.parse().ok(),
+            None => Some(0),
+        }
+    };
+    let (fault, arg_count) = match *fields.get(1)? {
+        "latency" => (Fault::Latency(Duration::from_millis(arg(0)?)), 1),
+        "short-read" => (Fault::ShortReads(arg(0)?.max(1) as usize), 1),
+        "drop" => (Fault::DropBytes { count: arg(0)? as usize, offset: optional(1)? as usize }, 2),
+        "fragment" => (
+            Fault::Fragment {
+                size: arg(0)?.max(1) as usize,
+                delay: Duration::from_millis(optional(1)?),
+            },
+            2,
+        ),
+        "timeout" => (Fault::Timeout, 0),
+        "disconnect" => (Fault::Disconnect { after: optional(0)? as usize }, 1),
+        _ => return None,
+    };
+    if fields.len() > arg_count + 2 {
+        return None;
+    }
+    Some(FaultStep { frame, fault })
+}
+
+/// ### Transport decorator injecting the faults of a scenario
+///
+pub struct FaultTransport<T: Transport> {
+    inner: T,
+    steps: Vec<FaultStep>,
+    /// Received frames so far
+    frame: u64,
+    /// Bytes of the current frame not yet delivered
+    pending: VecDeque<u8>,
+    /// Largest read of the current frame (short reads and fragments)
+    chunk_size: Option<usize>,
+    fragment_delay: Duration,
+    /// The connection drops when the current frame is delivered
+    disconnect_pending: bool,
+    disconnected: bool,
+    read_timeout: Option<Duration>,
+}
+
+impl<T: Transport> FaultTransport<T> {
+    /// ### Creates the transport
+    ///
+    /// ### Parameters
+    /// - `inner`: The transport to disturb.
+    /// - `scenario`: The faults to inject.
+    ///
+    pub fn new(inner: T, scenario: FaultScenario) -> Self {
+        FaultTransport {
+            inner,
+            steps: scenario.steps,
+            frame: 0,
+            pending: VecDeque::new(),
+            chunk_size: None,
+            fragment_delay: Duration::ZERO,
+            disconnect_pending: false,
+            disconnected: false,
+            read_timeout: None,
+        }
+    }
+
+    /// ### Returns the number of frames received so far
+    ///
+    pub fn frames(&self) -> u64 {
+        self.frame
+    }
+
+    /// ### Returns the disturbed transport
+    ///
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn connection_lost() -> io::Error {
+        io::Error::new(io::ErrorKind::ConnectionReset, "connection dropped by fault injection")
+    }
+
+    /// Reads the next whole frame from the inner transport and applies its faults
+    fn next_frame(&mut self) -> io::Result<()> {
+        let mut frame = vec![0u8; TPKT_LEN];
+        self.inner.read_exact(&mut frame)?;
+        let length = u16::from_be_bytes([frame[2], frame[3]]) as usize;
+        if length > TPKT_LEN {
+            frame.resize(length, 0);
+            self.inner.read_exact(&mut frame[TPKT_LEN..])?;
+        }
+        self.frame += 1;
+        self.chunk_size = None;
+        self.fragment_delay = Duration::ZERO;
+
+        let faults: Vec<Fault> = self.steps.iter().filter(|step| step.frame == self.frame).map(|step| step.fault).collect();
+        for fault in faults {
+            match fault {
+                Fault::Latency(delay) => thread::sleep(delay),
+                Fault::ShortReads(size) => self.chunk_size = Some(size),
+                Fault::DropBytes { offset, count } => {
+                    let start = offset.min(frame.len());
+                    let end = offset.saturating_add(count).min(frame.len());
+                    frame.drain(start..end);
+                }
+                Fault::Fragment { size, delay } => {
+                    self.chunk_size = Some(size);
+                    self.fragment_delay = delay;
+                }
+                Fault::Timeout => {
+                    if let Some(timeout) = self.read_timeout {
+                        thread::sleep(timeout);
+                    }
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "frame lost by fault injection"));
+                }
+                Fault::Disconnect { after } => {
+                    frame.truncate(after);
+                    self.disconnect_pending = true;
+                }
+            }
+        }
+        self.pending.extend(frame);
+        Ok(())
+    }
+}
+
+impl<T: Transport> Read for FaultTransport<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if !self.pending.is_empty() && !self.fragment_delay.is_zero() {
+            thread::sleep(self.fragment_delay);
+        }
+        // Loops while the frames are dropped or truncated to nothing
+        while self.pending.is_empty() {
+            if self.disconnect_pending {
+                self.disconnect_pending = false;
+                self.disconnected = true;
+                let _ = self.inner.shutdown(Shutdown::Both);
+            }
+            if self.disconnected {
+                return Err(Self::connection_lost());
+            }
+            self.next_frame()?;
+        }
+        let size = buf.len().min(self.pending.len()).min(self.chunk_size.unwrap_or(usize::MAX));
+        for (dst, src) in buf.iter_mut().zip(self.pending.drain(..size)) {
+            *dst = src;
+        }
+        Ok(size)
+    }
+}
+
+impl<T: Transport> Write for FaultTransport<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.disconnected {
+            return Err(Self::connection_lost());
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.disconnected {
+            return Err(Self::connection_lost());
+        }
+        self.inner.flush()
+    }
+}
+
+impl<T: Transport> Transport for FaultTransport<T> {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.read_timeout = timeout;
+        self.inner.set_read_timeout(timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_write_timeout(timeout)
+    }
+
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+}
+
+/// ### Returns a transport wrapper injecting the faults, for `S7Client::set_transport_wrapper()`
+///
+/// ### Parameters
+/// - `scenarios`: The n-th scenario is applied to the n-th connection of the client; the
+///   connections after the last scenario are not disturbed.
+///
+pub fn fault_wrapper(scenarios: Vec<FaultScenario>) -> TransportWrapper {
+    let mut scenarios = VecDeque::from(scenarios);
+    Box::new(move |transport| {
+        Ok(match scenarios.pop_front() {
+            Some(scenario) => Box::new(FaultTransport::new(transport, scenario)),
+            None => transport,
+        })
+    })
+}
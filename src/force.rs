@@ -0,0 +1,376 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Force / unforce (PG function)
+//!
+//! Forcing gives inputs, outputs and Merkers a fixed value that the CPU keeps regardless of the
+//! process and of the user program, until the forces are removed. It is the Userdata job of the
+//! "Programmer commands" group (0x1, subfunction 0x10) sent by the STEP7 force tables.
+//!
+//! A force acts on live process I/O, so it is a two-step operation: `prepare_force()` validates the
+//! job and returns a `ForceConfirmation` with a one-time token and a summary to show to the
+//! operator; `execute_force()` sends the job only if it receives the same token, on the same
+//! connection (a reconnection, even to the same PLC, invalidates it), within
+//! `FORCE_CONFIRMATION_TIMEOUT`. The confirmation is consumed by the call.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::{S7Client, S7_AREA_PA, S7_AREA_MK};
+//! use rust7::force::{ForceItem, ForceJob};
+//!
+//! let mut client = S7Client::new();
+//! client.connect_rack_slot("192.168.0.100", 0, 2).unwrap();
+//!
+//! let job = ForceJob::Force(vec![
+//!     ForceItem::new(S7_AREA_PA, 0, &[0x01]),       // QB0 = 16#01
+//!     ForceItem::new(S7_AREA_MK, 10, &[0x12, 0x34]), // MW10 = 16#1234
+//! ]);
+//! let confirmation = client.prepare_force(job).unwrap();
+//! println!("{}\nType {} to confirm", confirmation.summary(), confirmation.token());
+//! let token = confirmation.token(); // The code typed by the operator
+//! client.execute_force(confirmation, token).unwrap();
+//!
+//! // End of the test
+//! let confirmation = client.prepare_force(ForceJob::UnforceAll).unwrap();
+//! let token = confirmation.token();
+//! client.execute_force(confirmation, token).unwrap();
+//! ```
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::client::{S7Client, S7Error, S7ErrorCodes, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE, S7_WL_BYTE};
+use crate::audit::PendingAudit;
+use crate::frame::{S7UserDataParams, UD_GROUP_PROG, UD_SUBFN_FORCE};
+use crate::multivar::S7DataItem;
+use crate::observer::WriteRequest;
+
+const RES_SUCCESS: u8 = 0xFF;
+const TS_OCTET_STRING: u8 = 0x09;
+const DATA_HEADER_LEN: usize = 4; // Return code + Transport size + Length
+/// Job header: Reserved, Job, Item count
+const JOB_HEADER_LEN: usize = 4;
+/// Item specification: Area code, Repetition factor, DB number, Start byte
+const FORCE_ITEM_LEN: usize = 6;
+
+// Jobs
+const JOB_FORCE: u8 = 0x01;
+const JOB_UNFORCE_ALL: u8 = 0x02;
+
+// Area codes of the items (VarTab notation): area in the high nibble, width in the low one
+const AREA_CODE_MK: u8 = 0x00;
+const AREA_CODE_PE: u8 = 0x10;
+const AREA_CODE_PA: u8 = 0x20;
+
+/// Max number of items of a force job
+pub const MAX_FORCE_ITEMS: usize = 16;
+/// Time allowed between `prepare_force()` and `execute_force()`
+pub const FORCE_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// ### Value forced on a byte, word or double word
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForceItem {
+    /// `S7_AREA_PE`, `S7_AREA_PA` or `S7_AREA_MK`
+    pub area: u8,
+    /// Byte index
+    pub start: u16,
+    /// Value forced: 1 (byte), 2 (word) or 4 (double word) byte, big-endian as in the CPU
+    pub value: Vec<u8>,
+}
+
+impl ForceItem {
+    pub fn new(area: u8, start: u16, value: &[u8]) -> Self {
+        ForceItem { area, start, value: value.to_vec() }
+    }
+
+    /// ### Returns the address in the STEP 7 notation (e.g. `QB0`, `MW10`, `ID4`)
+    ///
+    pub fn address(&self) -> String {
+        let area = match self.area {
+            S7_AREA_PE => "I",
+            S7_AREA_PA => "Q",
+            S7_AREA_MK => "M",
+            _ => "?",
+        };
+        let width = match self.value.len() {
+            1 => "B",
+            2 => "W",
+            4 => "D",
+            _ => "?",
+        };
+        format!("{}{}{}", area, width, self.start)
+    }
+
+    fn check(&self) -> Result<(), S7Error> {
+        if !matches!(self.area, S7_AREA_PE | S7_AREA_PA | S7_AREA_MK) || !matches!(self.value.len(), 1 | 2 | 4) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        Ok(())
+    }
+
+    /// Area code: 1 = byte, 2 = word, 3 = double word
+    fn area_code(&self) -> u8 {
+        let area = match self.area {
+            S7_AREA_PE => AREA_CODE_PE,
+            S7_AREA_PA => AREA_CODE_PA,
+            _ => AREA_CODE_MK,
+        };
+        area | match self.value.len() { 1 => 0x01, 2 => 0x02, _ => 0x03 }
+    }
+}
+
+/// ### Force job
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForceJob {
+    /// Forces the values (added to the forces already active)
+    Force(Vec<ForceItem>),
+    /// Removes all the forces of the CPU
+    UnforceAll,
+}
+
+/// ### Force job waiting for the confirmation of the operator
+///
+/// Returned by `prepare_force()` and consumed by `execute_force()`.
+///
+#[derive(Debug)]
+pub struct ForceConfirmation {
+    job: ForceJob,
+    token: u32,
+    plc: String,
+    connection_id: u64,
+    expires: Instant,
+}
+
+impl ForceConfirmation {
+    /// ### Returns the one-time token to confirm the job (6 digits)
+    ///
+    pub fn token(&self) -> u32 {
+        self.token
+    }
+
+    /// ### Returns the job to confirm
+    ///
+    pub fn job(&self) -> &ForceJob {
+        &self.job
+    }
+
+    /// ### Returns the description of the job, one line per value, to show to the operator
+    ///
+    pub fn summary(&self) -> String {
+        match &self.job {
+            ForceJob::Force(items) => items.iter()
+                .map(|item| format!("Force {} = 16#{} on {}", item.address(), hex(&item.value), self.plc))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ForceJob::UnforceAll => format!("Remove all the forces on {}", self.plc),
+        }
+    }
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+/// ### Encodes the data part of a force job
+///
+/// ```text
+/// [0]    Reserved           0x00
+/// [1]    Job                0x01 Force, 0x02 Unforce all
+/// [2..4] Item count
+/// Items (6 byte each):
+///        Area code          0x01/02/03 MB/MW/MD, 0x11.. IB.., 0x21.. QB..
+///        Repetition factor  0x01
+///        DB number          0x0000
+///        Start byte
+/// Values (one per item):
+///        Return code 0x00, Transport size 0x09, Length (2), Value, padding to even length
+/// ```
+pub(crate) fn encode_force_job(job: &ForceJob) -> Vec<u8> {
+    let (code, items): (u8, &[ForceItem]) = match job {
+        ForceJob::Force(items) => (JOB_FORCE, items),
+        ForceJob::UnforceAll => (JOB_UNFORCE_ALL, &[]),
+    };
+    let mut payload = vec![0x00, code];
+    payload.extend_from_slice(&(items.len() as u16).to_be_bytes());
+    for item in items {
+        payload.extend_from_slice(&[item.area_code(), 0x01, 0x00, 0x00]);
+        payload.extend_from_slice(&item.start.to_be_bytes());
+    }
+    for item in items {
+        payload.extend_from_slice(&[0x00, TS_OCTET_STRING]);
+        payload.extend_from_slice(&(item.value.len() as u16).to_be_bytes());
+        payload.extend_from_slice(&item.value);
+        if item.value.len() % 2 != 0 {
+            payload.push(0x00);
+        }
+    }
+    let mut data = vec![RES_SUCCESS, TS_OCTET_STRING];
+    data.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    data.extend_from_slice(&payload);
+    data
+}
+
+/// ### Decodes the data part of a force job (simulator side)
+///
+pub(crate) fn decode_force_job(data: &[u8]) -> Option<ForceJob> {
+    let payload = data.get(DATA_HEADER_LEN..)?;
+    let header = payload.get(..JOB_HEADER_LEN)?;
+    let count = u16::from_be_bytes([header[2], header[3]]) as usize;
+    match header[1] {
+        JOB_UNFORCE_ALL => return Some(ForceJob::UnforceAll),
+        JOB_FORCE if (1..=MAX_FORCE_ITEMS).contains(&count) => {}
+        _ => return None,
+    }
+    let mut values_offset = JOB_HEADER_LEN + count * FORCE_ITEM_LEN;
+    let mut items = Vec::with_capacity(count);
+    for spec in payload.get(JOB_HEADER_LEN..values_offset)?.chunks_exact(FORCE_ITEM_LEN) {
+        let area = match spec[0] & 0xF0 {
+            AREA_CODE_PE => S7_AREA_PE,
+            AREA_CODE_PA => S7_AREA_PA,
+            AREA_CODE_MK => S7_AREA_MK,
+            _ => return None,
+        };
+        let length = u16::from_be_bytes(payload.get(values_offset + 2..values_offset + 4)?.try_into().ok()?) as usize;
+        let value = payload.get(values_offset + 4..values_offset + 4 + length)?;
+        values_offset += 4 + length + length % 2;
+        items.push(ForceItem::new(area, u16::from_be_bytes([spec[4], spec[5]]), value));
+    }
+    Some(ForceJob::Force(items))
+}
+
+/// Writes of a force job, as checked by the write policy and audited (one with no data for `UnforceAll`)
+fn force_writes(job: &ForceJob) -> Vec<WriteRequest<'_>> {
+    match job {
+        ForceJob::Force(items) => items.iter()
+            .map(|item| WriteRequest { operation: "force", area: item.area, db_number: 0, start: item.start.into(), wordlen: S7_WL_BYTE, data: &item.value, nck: None })
+            .collect(),
+        ForceJob::UnforceAll => vec![WriteRequest { operation: "force", area: 0, db_number: 0, start: 0, wordlen: S7_WL_BYTE, data: &[], nck: None }],
+    }
+}
+
+/// Pseudo-random 6 digits token
+fn new_token() -> u32 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos()).unwrap_or(0);
+    let mut seed = nanos as u64 ^ (std::process::id() as u64) << 32;
+    seed ^= seed << 13;
+    seed ^= seed >> 7;
+    seed ^= seed << 17;
+    100_000 + (seed % 900_000) as u32
+}
+
+impl S7Client {
+    /// ### Prepares a force job, to be confirmed
+    ///
+    /// ### Parameters
+    /// - `job`: Values to force, or `ForceJob::UnforceAll`.
+    ///
+    /// ### Returns
+    /// `Ok(<ForceConfirmation>)` to pass to `execute_force()` with its token, or `Err(<S7Error>)`.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: No items or more than `MAX_FORCE_ITEMS`, an area other
+    ///   than `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_MK`, or a value not 1, 2 or 4 byte long.
+    /// - `S7Error::NotConnected`: The client is not connected.
+    ///
+    pub fn prepare_force(&mut self, job: ForceJob) -> Result<ForceConfirmation, S7Error> {
+        if let ForceJob::Force(items) = &job {
+            if items.is_empty() || items.len() > MAX_FORCE_ITEMS {
+                return Err(S7Error::InvalidFunParameter);
+            }
+            for item in items {
+                item.check()?;
+            }
+        }
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+        Ok(ForceConfirmation {
+            job,
+            token: new_token(),
+            plc: self.plc_address(),
+            connection_id: self.connection_id,
+            expires: Instant::now() + FORCE_CONFIRMATION_TIMEOUT,
+        })
+    }
+
+    /// ### Sends a confirmed force job
+    ///
+    /// ### Parameters
+    /// - `confirmation`: The job returned by `prepare_force()`.
+    /// - `token`: The token confirmed by the operator.
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: Wrong token, confirmation expired or prepared on another connection
+    ///   (the client reconnected since `prepare_force()`); nothing is sent.
+    /// - `S7Error::WriteDenied`: The write policy refused a value (see `set_write_policy()`).
+    /// - `S7Error::AccessDenied`: The CPU doesn't allow writes (see `set_check_write_protection()`).
+    /// - `S7Error::S7Unspecified`: The CPU refused the job (e.g. protection level, forcing not
+    ///   supported by the CPU).
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    /// ### Notes
+    /// - The values are checked by the write policy as `write_area()` byte writes (operation
+    ///   `"force"`), `ForceJob::UnforceAll` as a `"force"` with no data; in dry-run mode (see
+    ///   `set_dry_run()`) the values are notified but not sent.
+    /// - Each value is reported to the audit hook (see `set_audit_hook()`), `ForceJob::UnforceAll`
+    ///   as a single record with no data.
+    /// - The forces stay active after the disconnection: remove them with `ForceJob::UnforceAll`.
+    ///
+    pub fn execute_force(&mut self, confirmation: ForceConfirmation, token: u32) -> Result<(), S7Error> {
+        let writes = force_writes(&confirmation.job);
+        let mut pending = self.audit_begin(writes.len());
+        let result = self.execute_force_job(&confirmation, &writes, token, &mut pending);
+        if let Some(pending) = pending {
+            for (index, write) in writes.into_iter().enumerate() {
+                self.audit(&pending, index, write, result.as_ref().copied());
+            }
+        }
+        result
+    }
+
+    /// Checks and sends a force job, reading the old values to audit
+    fn execute_force_job(&mut self, confirmation: &ForceConfirmation, writes: &[WriteRequest], token: u32, pending: &mut Option<PendingAudit>) -> Result<(), S7Error> {
+        if token != confirmation.token {
+            return Err(S7Error::Other("force not confirmed: wrong token".to_string()));
+        }
+        if Instant::now() > confirmation.expires {
+            return Err(S7Error::Other("force not confirmed: confirmation expired".to_string()));
+        }
+        if !self.connected {
+            return Err(S7Error::NotConnected);
+        }
+        if self.connection_id != confirmation.connection_id {
+            return Err(S7Error::Other("force not confirmed: prepared on another connection".to_string()));
+        }
+        for write in writes {
+            self.authorize_write(write)?;
+        }
+        self.check_write_protection(&[])?;
+        if let ForceJob::Force(items) = &confirmation.job {
+            let reads: Vec<S7DataItem> = items.iter()
+                .map(|item| S7DataItem::new(item.area, S7_WL_BYTE, 0, item.start.into(), item.value.len() as u16))
+                .collect();
+            self.audit_read_old_values(pending, &reads);
+        }
+        if self.dry_run() {
+            for write in writes.iter().filter(|write| !write.data.is_empty()) {
+                self.dry_run_write(write)?;
+            }
+            return Ok(());
+        }
+
+        let params = S7UserDataParams::request(UD_GROUP_PROG, UD_SUBFN_FORCE);
+        let (_, data) = self.userdata(&params, &encode_force_job(&confirmation.job))?;
+        match data.first() {
+            Some(&RES_SUCCESS) => Ok(()),
+            Some(&code) => Err(S7Error::S7Unspecified(S7ErrorCodes::item(code))),
+            None => Err(S7Error::IsoInvalidTelegram),
+        }
+    }
+
+    /// Address of the connected PLC, shown in the summary of the confirmations
+    fn plc_address(&self) -> String {
+        self.endpoint.as_ref().map(|(address, _, _)| address.clone()).unwrap_or_default()
+    }
+}
@@ -0,0 +1,1169 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Protocol frames
+//!
+//! Typed representation of the headers that make up an S7 telegram:
+//!
+//! ```text
+//! +------+------+-----------+------------+------+
+//! | TPKT | COTP | S7 Header | Parameters | Data |
+//! +------+------+-----------+------------+------+
+//!   4      3       10/12
+//! ```
+//!
+//! Every struct has an `encode()` method, which appends the wire representation to a buffer,
+//! and a `decode()` method, which parses (and checks) it from the beginning of a slice.
+
+use crate::client::S7Error;
+
+/// RFC 1006 ID
+pub const ISO_ID: u8 = 0x03;
+/// S7 Protocol ID
+pub const S7_ID: u8 = 0x32;
+/// TPKT header size
+pub const TPKT_LEN: usize = 4;
+/// COTP Data header size
+pub const COTP_DT_LEN: usize = 3;
+/// ISO Header length (TPKT + COTP Data)
+pub const TPKT_ISO_LEN: usize = TPKT_LEN + COTP_DT_LEN;
+/// COTP Connection request PDU type
+pub const COTP_CR: u8 = 0xE0;
+/// COTP Connection confirm PDU type
+pub const COTP_CC: u8 = 0xD0;
+/// COTP Disconnect request PDU type
+pub const COTP_DR: u8 = 0x80;
+/// COTP Data PDU type
+pub const COTP_DT: u8 = 0xF0;
+/// ISO End of Trasmission
+pub const EOT: u8 = 0x80;
+
+/// COTP parameter: TPDU size
+pub const COTP_PAR_TPDU_SIZE: u8 = 0xC0;
+/// COTP parameter: Calling (source) TSAP
+pub const COTP_PAR_SRC_TSAP: u8 = 0xC1;
+/// COTP parameter: Called (destination) TSAP
+pub const COTP_PAR_DST_TSAP: u8 = 0xC2;
+
+/// S7 ROSCTR: Job request
+pub const ROSCTR_JOB: u8 = 0x01;
+/// S7 ROSCTR: Acknowledge without data
+pub const ROSCTR_ACK: u8 = 0x02;
+/// S7 ROSCTR: Acknowledge with data
+pub const ROSCTR_ACK_DATA: u8 = 0x03;
+/// S7 ROSCTR: Userdata
+pub const ROSCTR_USERDATA: u8 = 0x07;
+
+/// S7 Job header size
+pub const S7_JOB_HEADER_LEN: usize = 10;
+/// S7 Ack-Data header size (Job header + error class/code)
+pub const S7_ACK_HEADER_LEN: usize = 12;
+
+/// S7 Function: Setup communication (PDU negotiation)
+pub const FN_SETUP_COMM: u8 = 0xF0;
+/// S7 Function: Read Var
+pub const FN_READ_VAR: u8 = 0x04;
+/// S7 Function: Write Var
+pub const FN_WRITE_VAR: u8 = 0x05;
+
+/// Userdata method: request
+pub const UD_METHOD_REQUEST: u8 = 0x11;
+/// Userdata method: response
+pub const UD_METHOD_RESPONSE: u8 = 0x12;
+/// Userdata type: request (high nibble)
+pub const UD_TYPE_REQUEST: u8 = 0x40;
+/// Userdata type: response (high nibble)
+pub const UD_TYPE_RESPONSE: u8 = 0x80;
+/// Userdata type: push (high nibble), telegram sent spontaneously by the CPU
+pub const UD_TYPE_PUSH: u8 = 0x00;
+/// Userdata function group: Programmer commands (PG functions)
+pub const UD_GROUP_PROG: u8 = 0x01;
+/// Userdata subfunction (Programmer commands): Force job
+pub const UD_SUBFN_FORCE: u8 = 0x10;
+/// Userdata function group: Block functions
+pub const UD_GROUP_BLOCK: u8 = 0x03;
+/// Userdata subfunction: List blocks of type
+pub const UD_SUBFN_LIST_BLOCKS_OF_TYPE: u8 = 0x02;
+/// Userdata subfunction: Get block info
+pub const UD_SUBFN_BLOCK_INFO: u8 = 0x03;
+/// Userdata function group: CPU functions (SZL)
+pub const UD_GROUP_SZL: u8 = 0x04;
+/// Userdata subfunction: Read SZL
+pub const UD_SUBFN_READ_SZL: u8 = 0x01;
+/// Userdata subfunction (CPU functions): Message service (alarm subscription)
+pub const UD_SUBFN_MESSAGE_SERVICE: u8 = 0x02;
+/// Userdata subfunction (CPU functions): ALARM_8 indication
+pub const UD_SUBFN_ALARM8_IND: u8 = 0x05;
+/// Userdata subfunction (CPU functions): NOTIFY indication
+pub const UD_SUBFN_NOTIFY_IND: u8 = 0x06;
+/// Userdata subfunction (CPU functions): ALARM acknowledgement indication
+pub const UD_SUBFN_ALARM_ACK_IND: u8 = 0x0C;
+/// Userdata subfunction (CPU functions): ALARM_SQ indication
+pub const UD_SUBFN_ALARMSQ_IND: u8 = 0x11;
+/// Userdata subfunction (CPU functions): ALARM_S indication
+pub const UD_SUBFN_ALARMS_IND: u8 = 0x12;
+/// Userdata subfunction (CPU functions): ALARM query (pending alarms)
+pub const UD_SUBFN_ALARM_QUERY: u8 = 0x13;
+/// Userdata subfunction (CPU functions): NOTIFY_8 indication
+pub const UD_SUBFN_NOTIFY8_IND: u8 = 0x16;
+/// Userdata function group: Programmed block communication (BSEND/BRCV)
+pub const UD_GROUP_BSEND: u8 = 0x06;
+/// Userdata subfunction: BSEND/BRCV data
+pub const UD_SUBFN_BSEND: u8 = 0x01;
+/// Userdata function group: Time functions
+pub const UD_GROUP_TIME: u8 = 0x07;
+/// Userdata subfunction: Read the CPU clock
+pub const UD_SUBFN_READ_CLOCK: u8 = 0x01;
+/// Userdata subfunction: Set the CPU clock
+pub const UD_SUBFN_SET_CLOCK: u8 = 0x02;
+
+/// S7 Item specification size
+pub const ITEM_SPEC_LEN: usize = 12;
+/// S7 Data item header size
+pub const DATA_ITEM_HEADER_LEN: usize = 4;
+
+/// ### TPKT (RFC 1006) header
+///
+/// ```text
+/// [0] RFC 1006 ID     0x03
+/// [1] Reserved        0x00
+/// [2] HI Length       Whole telegram length (TPKT included)
+/// [3] LO Length
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TpktHeader {
+    /// Whole telegram length, TPKT header included
+    pub length: u16,
+}
+
+impl TpktHeader {
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.push(ISO_ID);
+        buffer.push(0x00);
+        buffer.extend_from_slice(&self.length.to_be_bytes());
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidHeader`: Short buffer or wrong RFC 1006 ID.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < TPKT_LEN || buffer[0] != ISO_ID {
+            return Err(S7Error::IsoInvalidHeader);
+        }
+        Ok(TpktHeader { length: u16::from_be_bytes([buffer[2], buffer[3]]) })
+    }
+}
+
+/// ### COTP Data (DT) header
+///
+/// ```text
+/// [0] Length          0x02
+/// [1] PDU Type        0xF0
+/// [2] EOT + TPDU Nr   0x80 (last fragment)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CotpData {
+    /// Last data unit of the telegram (EOT)
+    pub eot: bool,
+}
+
+impl CotpData {
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.push(0x02);
+        buffer.push(COTP_DT);
+        buffer.push(if self.eot { EOT } else { 0x00 });
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidHeader`: Short buffer, wrong length or not a DT PDU.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < COTP_DT_LEN || buffer[0] != 0x02 || buffer[1] != COTP_DT {
+            return Err(S7Error::IsoInvalidHeader);
+        }
+        Ok(CotpData { eot: buffer[2] & EOT != 0 })
+    }
+}
+
+/// ### COTP Connection Request (CR) / Connection Confirm (CC)
+///
+/// ```text
+/// [0] Length indicator (header length - 1)
+/// [1] PDU Type         0xE0 (CR) / 0xD0 (CC)
+/// [2] Dst Reference
+/// [4] Src Reference
+/// [6] Class + Options
+/// [7] Parameters       TPDU size (0xC0), Src TSAP (0xC1), Dst TSAP (0xC2)...
+/// ```
+///
+/// A TPDU size of 0 and empty TSAPs are not encoded (and are returned when the parameter is missing).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CotpConnection {
+    /// `COTP_CR` or `COTP_CC`
+    pub pdu_type: u8,
+    pub dst_ref: u16,
+    pub src_ref: u16,
+    pub class: u8,
+    /// TPDU size code: size = 2^code (e.g. 0x0A = 1024 byte)
+    pub tpdu_size: u8,
+    pub src_tsap: Vec<u8>,
+    pub dst_tsap: Vec<u8>,
+    /// Other parameters `(code, value)`, encoded after the standard ones.
+    /// A code of the standard ones (0xC0, 0xC1, 0xC2) replaces it.
+    pub extra_params: Vec<(u8, Vec<u8>)>,
+}
+
+impl CotpConnection {
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        let start = buffer.len();
+        buffer.push(0x00); // Length indicator (see below)
+        buffer.push(self.pdu_type);
+        buffer.extend_from_slice(&self.dst_ref.to_be_bytes());
+        buffer.extend_from_slice(&self.src_ref.to_be_bytes());
+        buffer.push(self.class);
+
+        let overridden = |code: u8| self.extra_params.iter().any(|(extra, _)| *extra == code);
+        let mut push_param = |code: u8, value: &[u8]| {
+            buffer.push(code);
+            buffer.push(value.len() as u8);
+            buffer.extend_from_slice(value);
+        };
+        if self.tpdu_size != 0 && !overridden(COTP_PAR_TPDU_SIZE) {
+            push_param(COTP_PAR_TPDU_SIZE, &[self.tpdu_size]);
+        }
+        if !self.src_tsap.is_empty() && !overridden(COTP_PAR_SRC_TSAP) {
+            push_param(COTP_PAR_SRC_TSAP, &self.src_tsap);
+        }
+        if !self.dst_tsap.is_empty() && !overridden(COTP_PAR_DST_TSAP) {
+            push_param(COTP_PAR_DST_TSAP, &self.dst_tsap);
+        }
+        for (code, value) in &self.extra_params {
+            push_param(*code, value);
+        }
+        buffer[start] = (buffer.len() - start - 1) as u8;
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Short buffer or malformed parameters.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < 7 || buffer.len() < buffer[0] as usize + 1 {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        let end = buffer[0] as usize + 1;
+        let mut conn = CotpConnection {
+            pdu_type: buffer[1],
+            dst_ref: u16::from_be_bytes([buffer[2], buffer[3]]),
+            src_ref: u16::from_be_bytes([buffer[4], buffer[5]]),
+            class: buffer[6],
+            tpdu_size: 0,
+            src_tsap: Vec::new(),
+            dst_tsap: Vec::new(),
+            extra_params: Vec::new(),
+        };
+
+        // Parameters are TLV encoded
+        let mut pos = 7;
+        while pos + 2 <= end {
+            let code = buffer[pos];
+            let len = buffer[pos + 1] as usize;
+            if pos + 2 + len > end {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+            let value = &buffer[pos + 2..pos + 2 + len];
+            match code {
+                COTP_PAR_TPDU_SIZE if len == 1 => conn.tpdu_size = value[0],
+                COTP_PAR_SRC_TSAP => conn.src_tsap = value.to_vec(),
+                COTP_PAR_DST_TSAP => conn.dst_tsap = value.to_vec(),
+                _ => conn.extra_params.push((code, value.to_vec())),
+            }
+            pos += 2 + len;
+        }
+
+        Ok(conn)
+    }
+}
+
+/// ### COTP Disconnect Request (DR)
+///
+/// ```text
+/// [0] Length indicator 0x06
+/// [1] PDU Type         0x80
+/// [2] Dst Reference
+/// [4] Src Reference
+/// [6] Reason           0x80 = Normal disconnect
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CotpDisconnect {
+    pub dst_ref: u16,
+    pub src_ref: u16,
+    pub reason: u8,
+}
+
+impl CotpDisconnect {
+    /// Encoded size
+    pub const LEN: usize = 7;
+
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.push((Self::LEN - 1) as u8);
+        buffer.push(COTP_DR);
+        buffer.extend_from_slice(&self.dst_ref.to_be_bytes());
+        buffer.extend_from_slice(&self.src_ref.to_be_bytes());
+        buffer.push(self.reason);
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Short buffer or not a DR PDU.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < Self::LEN || buffer[1] != COTP_DR {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        Ok(CotpDisconnect {
+            dst_ref: u16::from_be_bytes([buffer[2], buffer[3]]),
+            src_ref: u16::from_be_bytes([buffer[4], buffer[5]]),
+            reason: buffer[6],
+        })
+    }
+}
+
+/// ### S7 Header
+///
+/// ```text
+/// [0] Protocol ID       0x32
+/// [1] ROSCTR            Job, Ack, Ack-Data, Userdata
+/// [2] Redundancy ID     0x0000
+/// [4] PDU Reference
+/// [6] Parameters length
+/// [8] Data length
+/// [10] Error class      (Ack and Ack-Data only)
+/// [11] Error code       (Ack and Ack-Data only)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7Header {
+    pub rosctr: u8,
+    pub pdu_ref: u16,
+    pub param_len: u16,
+    pub data_len: u16,
+    pub error_class: u8,
+    pub error_code: u8,
+}
+
+impl S7Header {
+    /// ### Creates a Job header
+    ///
+    pub fn job(pdu_ref: u16, param_len: u16, data_len: u16) -> Self {
+        S7Header {
+            rosctr: ROSCTR_JOB,
+            pdu_ref,
+            param_len,
+            data_len,
+            error_class: 0,
+            error_code: 0,
+        }
+    }
+
+    /// ### Creates a Userdata header
+    ///
+    pub fn userdata(pdu_ref: u16, param_len: u16, data_len: u16) -> Self {
+        S7Header {
+            rosctr: ROSCTR_USERDATA,
+            ..S7Header::job(pdu_ref, param_len, data_len)
+        }
+    }
+
+    /// ### Returns the encoded size of the header (depends on ROSCTR)
+    ///
+    pub fn encoded_len(&self) -> usize {
+        if self.has_error_fields() { S7_ACK_HEADER_LEN } else { S7_JOB_HEADER_LEN }
+    }
+
+    fn has_error_fields(&self) -> bool {
+        self.rosctr == ROSCTR_ACK || self.rosctr == ROSCTR_ACK_DATA
+    }
+
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.push(S7_ID);
+        buffer.push(self.rosctr);
+        buffer.extend_from_slice(&[0x00, 0x00]);
+        buffer.extend_from_slice(&self.pdu_ref.to_be_bytes());
+        buffer.extend_from_slice(&self.param_len.to_be_bytes());
+        buffer.extend_from_slice(&self.data_len.to_be_bytes());
+        if self.has_error_fields() {
+            buffer.push(self.error_class);
+            buffer.push(self.error_code);
+        }
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Short buffer or wrong S7 Protocol ID.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < S7_JOB_HEADER_LEN || buffer[0] != S7_ID {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        let mut header = S7Header {
+            rosctr: buffer[1],
+            pdu_ref: u16::from_be_bytes([buffer[4], buffer[5]]),
+            param_len: u16::from_be_bytes([buffer[6], buffer[7]]),
+            data_len: u16::from_be_bytes([buffer[8], buffer[9]]),
+            error_class: 0,
+            error_code: 0,
+        };
+        if header.has_error_fields() {
+            if buffer.len() < S7_ACK_HEADER_LEN {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+            header.error_class = buffer[10];
+            header.error_code = buffer[11];
+        }
+        Ok(header)
+    }
+}
+
+/// ### S7 Setup Communication parameters (PDU negotiation)
+///
+/// ```text
+/// [0] Function          0xF0
+/// [1] Reserved          0x00
+/// [2] Max AmQ calling   Max parallel jobs (client side)
+/// [4] Max AmQ called    Max parallel jobs (CPU side)
+/// [6] PDU length
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7SetupComm {
+    pub max_amq_calling: u16,
+    pub max_amq_called: u16,
+    pub pdu_length: u16,
+}
+
+impl S7SetupComm {
+    /// Encoded size
+    pub const LEN: usize = 8;
+
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.push(FN_SETUP_COMM);
+        buffer.push(0x00);
+        buffer.extend_from_slice(&self.max_amq_calling.to_be_bytes());
+        buffer.extend_from_slice(&self.max_amq_called.to_be_bytes());
+        buffer.extend_from_slice(&self.pdu_length.to_be_bytes());
+    }
+
+    /// ### Errors
+    /// - `S7Error::PduNegotiationFailed`: Short buffer or wrong function.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < Self::LEN || buffer[0] != FN_SETUP_COMM {
+            return Err(S7Error::PduNegotiationFailed);
+        }
+        Ok(S7SetupComm {
+            max_amq_calling: u16::from_be_bytes([buffer[2], buffer[3]]),
+            max_amq_called: u16::from_be_bytes([buffer[4], buffer[5]]),
+            pdu_length: u16::from_be_bytes([buffer[6], buffer[7]]),
+        })
+    }
+}
+
+/// ### S7 Userdata parameters
+///
+/// ```text
+/// [0] Param head         0x00 0x01 0x12
+/// [3] Param length       4 (first request) or 8 (responses and follow-up requests)
+/// [4] Method             0x11 request, 0x12 response
+/// [5] Type + Group       High nibble: 4 request, 8 response, Low nibble: function group
+/// [6] Subfunction
+/// [7] Sequence number
+/// --- Only if param length = 8
+/// [8] Data unit reference
+/// [9] Last data unit     0x00 = last, 0x01 = more data follows
+/// [10] Error code
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7UserDataParams {
+    pub method: u8,
+    pub type_group: u8,
+    pub subfunction: u8,
+    pub sequence: u8,
+    /// Present when `extended` is true
+    pub data_unit_ref: u8,
+    /// Present when `extended` is true
+    pub last_data_unit: u8,
+    /// Present when `extended` is true
+    pub error_code: u16,
+    /// 8 byte parameters (responses and follow-up requests)
+    pub extended: bool,
+}
+
+impl S7UserDataParams {
+    /// ### Creates the parameters of a userdata request
+    ///
+    /// ### Parameters
+    /// - `group`: Function group (e.g. `UD_GROUP_SZL`).
+    /// - `subfunction`: Subfunction inside the group.
+    ///
+    pub fn request(group: u8, subfunction: u8) -> Self {
+        S7UserDataParams {
+            method: UD_METHOD_REQUEST,
+            type_group: UD_TYPE_REQUEST | (group & 0x0F),
+            subfunction,
+            sequence: 0,
+            data_unit_ref: 0,
+            last_data_unit: 0,
+            error_code: 0,
+            extended: false,
+        }
+    }
+
+    /// ### Encoded size
+    ///
+    pub fn encoded_len(&self) -> usize {
+        if self.extended { 12 } else { 8 }
+    }
+
+    /// ### Returns the function group
+    ///
+    pub fn group(&self) -> u8 {
+        self.type_group & 0x0F
+    }
+
+    /// ### Returns true if other data units follow (multi-part responses)
+    ///
+    pub fn more_data(&self) -> bool {
+        self.extended && self.last_data_unit != 0x00
+    }
+
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&[0x00, 0x01, 0x12]);
+        buffer.push(if self.extended { 8 } else { 4 });
+        buffer.push(self.method);
+        buffer.push(self.type_group);
+        buffer.push(self.subfunction);
+        buffer.push(self.sequence);
+        if self.extended {
+            buffer.push(self.data_unit_ref);
+            buffer.push(self.last_data_unit);
+            buffer.extend_from_slice(&self.error_code.to_be_bytes());
+        }
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Short buffer or wrong param head.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < 8 || buffer[0..3] != [0x00, 0x01, 0x12] {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        let extended = buffer[3] >= 8;
+        if extended && buffer.len() < 12 {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        Ok(S7UserDataParams {
+            method: buffer[4],
+            type_group: buffer[5],
+            subfunction: buffer[6],
+            sequence: buffer[7],
+            data_unit_ref: if extended { buffer[8] } else { 0 },
+            last_data_unit: if extended { buffer[9] } else { 0 },
+            error_code: if extended { u16::from_be_bytes([buffer[10], buffer[11]]) } else { 0 },
+            extended,
+        })
+    }
+}
+
+/// ### Builds a complete Userdata telegram (TPKT + COTP + S7 Header + Parameters + Data)
+///
+pub fn userdata_telegram(pdu_ref: u16, params: &S7UserDataParams, data: &[u8]) -> Vec<u8> {
+    let param_len = params.encoded_len();
+    let length = TPKT_ISO_LEN + S7_JOB_HEADER_LEN + param_len + data.len();
+
+    let mut telegram = Vec::with_capacity(length);
+    TpktHeader { length: length as u16 }.encode(&mut telegram);
+    CotpData { eot: true }.encode(&mut telegram);
+    S7Header::userdata(pdu_ref, param_len as u16, data.len() as u16).encode(&mut telegram);
+    params.encode(&mut telegram);
+    telegram.extend_from_slice(data);
+    telegram
+}
+
+/// ### Splits a Userdata PDU (TPKT and COTP stripped) into header, parameters and data
+///
+/// ### Errors
+/// - `S7Error::IsoInvalidTelegram`: Not a Userdata PDU or inconsistent lengths.
+///
+pub fn split_userdata(pdu: &[u8]) -> Result<(S7Header, S7UserDataParams, &[u8]), S7Error> {
+    let header = S7Header::decode(pdu)?;
+    if header.rosctr != ROSCTR_USERDATA {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    let params_offset = header.encoded_len();
+    let data_offset = params_offset + header.param_len as usize;
+    if pdu.len() < data_offset + header.data_len as usize {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    let params = S7UserDataParams::decode(&pdu[params_offset..data_offset])?;
+    Ok((header, params, &pdu[data_offset..data_offset + header.data_len as usize]))
+}
+
+/// ### S7 Item specification (S7ANY addressing)
+///
+/// ```text
+/// [0] Var spec.          0x12
+/// [1] Remaining length   0x0A
+/// [2] Syntax ID          0x10 (S7ANY)
+/// [3] WordLen
+/// [4] Amount (elements)
+/// [6] DB Number
+/// [8] Area
+/// [9] 24 bit Address (bits)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7ItemSpec {
+    pub wordlen: u8,
+    pub amount: u16,
+    pub db_number: u16,
+    pub area: u8,
+    /// Bit address inside the area (byte index * 8 + bit)
+    pub address: u32,
+}
+
+impl S7ItemSpec {
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&[0x12, 0x0A, 0x10, self.wordlen]);
+        buffer.extend_from_slice(&self.amount.to_be_bytes());
+        buffer.extend_from_slice(&self.db_number.to_be_bytes());
+        buffer.push(self.area);
+        buffer.extend_from_slice(&self.address.to_be_bytes()[1..]);
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Short buffer or not an S7ANY item.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < ITEM_SPEC_LEN || buffer[0] != 0x12 || buffer[2] != 0x10 {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        Ok(S7ItemSpec {
+            wordlen: buffer[3],
+            amount: u16::from_be_bytes([buffer[4], buffer[5]]),
+            db_number: u16::from_be_bytes([buffer[6], buffer[7]]),
+            area: buffer[8],
+            address: u32::from_be_bytes([0, buffer[9], buffer[10], buffer[11]]),
+        })
+    }
+}
+
+/// ### S7 Data item header
+///
+/// ```text
+/// [0] Return code        0xFF = Success (always 0x00 in requests)
+/// [1] Transport size     TS_RES_BIT, TS_RES_BYTE...
+/// [2] Length             In bits for TS_RES_BYTE, in bytes otherwise
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataItemHeader {
+    pub return_code: u8,
+    pub transport_size: u8,
+    pub length: u16,
+}
+
+impl DataItemHeader {
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.push(self.return_code);
+        buffer.push(self.transport_size);
+        buffer.extend_from_slice(&self.length.to_be_bytes());
+    }
+
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Short buffer.
+    ///
+    pub fn decode(buffer: &[u8]) -> Result<Self, S7Error> {
+        if buffer.len() < DATA_ITEM_HEADER_LEN {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        Ok(DataItemHeader {
+            return_code: buffer[0],
+            transport_size: buffer[1],
+            length: u16::from_be_bytes([buffer[2], buffer[3]]),
+        })
+    }
+}
+
+/// ### Detailed description of a malformed telegram (strict-parse mode)
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    /// Offset of the offending field inside the telegram (TPKT included)
+    pub offset: usize,
+    /// Field name
+    pub field: &'static str,
+    /// Expected value
+    pub expected: String,
+    /// Received value
+    pub got: String,
+    /// Hex dump of the whole telegram
+    pub dump: String,
+}
+
+impl std::fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "offset {} ({}): expected {}, got {}\n{}", self.offset, self.field, self.expected, self.got, self.dump)
+    }
+}
+
+/// ### Field-by-field checker of a received telegram
+///
+/// Each check returns `S7Error::MalformedTelegram` with a `ParseDiagnostic` on failure.
+///
+pub struct StrictParser<'a> {
+    telegram: &'a [u8],
+}
+
+impl<'a> StrictParser<'a> {
+    pub fn new(telegram: &'a [u8]) -> Self {
+        StrictParser { telegram }
+    }
+
+    /// ### Builds the error for a field
+    ///
+    pub fn fail(&self, offset: usize, field: &'static str, expected: impl std::fmt::Display, got: impl std::fmt::Display) -> S7Error {
+        S7Error::MalformedTelegram(Box::new(ParseDiagnostic {
+            offset,
+            field,
+            expected: expected.to_string(),
+            got: got.to_string(),
+            dump: hex_dump(self.telegram),
+        }))
+    }
+
+    /// ### Checks that the telegram is at least `len` bytes long
+    ///
+    pub fn min_len(&self, len: usize, field: &'static str) -> Result<(), S7Error> {
+        if self.telegram.len() < len {
+            return Err(self.fail(self.telegram.len(), field, format!("{} byte telegram", len), format!("{} byte", self.telegram.len())));
+        }
+        Ok(())
+    }
+
+    /// ### Checks a single byte
+    ///
+    pub fn byte(&self, offset: usize, field: &'static str, expected: u8) -> Result<u8, S7Error> {
+        self.min_len(offset + 1, field)?;
+        let got = self.telegram[offset];
+        if got != expected {
+            return Err(self.fail(offset, field, format!("0x{:02X}", expected), format!("0x{:02X}", got)));
+        }
+        Ok(got)
+    }
+
+    /// ### Checks a big-endian word
+    ///
+    pub fn word(&self, offset: usize, field: &'static str, expected: u16) -> Result<u16, S7Error> {
+        let got = self.read_word(offset, field)?;
+        if got != expected {
+            return Err(self.fail(offset, field, format!("0x{:04X}", expected), format!("0x{:04X}", got)));
+        }
+        Ok(got)
+    }
+
+    /// ### Reads a big-endian word without checking its value
+    ///
+    pub fn read_word(&self, offset: usize, field: &'static str) -> Result<u16, S7Error> {
+        self.min_len(offset + 2, field)?;
+        Ok(u16::from_be_bytes([self.telegram[offset], self.telegram[offset + 1]]))
+    }
+
+    /// ### Checks the TPKT and COTP Data headers
+    ///
+    pub fn iso_header(&self) -> Result<(), S7Error> {
+        self.byte(0, "TPKT version", ISO_ID)?;
+        self.byte(1, "TPKT reserved", 0x00)?;
+        self.word(2, "TPKT length", self.telegram.len() as u16)?;
+        self.byte(4, "COTP length", 0x02)?;
+        self.byte(5, "COTP PDU type", COTP_DT)?;
+        self.byte(6, "COTP EOT", EOT)?;
+        Ok(())
+    }
+
+    /// ### Checks the headers of an Ack-Data telegram
+    ///
+    /// TPKT, COTP, S7 header (ROSCTR, PDU reference, lengths) and the echoed function.
+    ///
+    /// ### Returns
+    /// The offset of the data part.
+    ///
+    pub fn ack_data(&self, pdu_ref: u16, function: u8) -> Result<usize, S7Error> {
+        self.iso_header()?;
+        let s7 = TPKT_ISO_LEN;
+        self.byte(s7, "S7 protocol ID", S7_ID)?;
+        self.byte(s7 + 1, "S7 ROSCTR", ROSCTR_ACK_DATA)?;
+        self.word(s7 + 2, "S7 redundancy ID", 0x0000)?;
+        self.word(s7 + 4, "S7 PDU reference", pdu_ref)?;
+        let param_len = self.read_word(s7 + 6, "S7 parameters length")? as usize;
+        let data_len = self.read_word(s7 + 8, "S7 data length")? as usize;
+        let expected_len = s7 + S7_ACK_HEADER_LEN + param_len + data_len;
+        if expected_len != self.telegram.len() {
+            return Err(self.fail(s7 + 6, "S7 parameters + data length", self.telegram.len() - s7 - S7_ACK_HEADER_LEN, param_len + data_len));
+        }
+        if param_len == 0 {
+            return Err(self.fail(s7 + 6, "S7 parameters length", "> 0", 0));
+        }
+        self.byte(s7 + S7_ACK_HEADER_LEN, "S7 function", function)?;
+        Ok(s7 + S7_ACK_HEADER_LEN + param_len)
+    }
+}
+
+/// Hex dump used in diagnostics
+fn hex_dump(data: &[u8]) -> String {
+    format_hex_with(data, 0, HexFormat { width: 16, ascii: false })
+}
+
+/// ### Layout of `format_hex_with()`
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexFormat {
+    /// Bytes per row (Default = 16)
+    pub width: usize,
+    /// Appends the printable ASCII characters of each row, '.' for the others (Default = true)
+    pub ascii: bool,
+}
+
+impl Default for HexFormat {
+    fn default() -> Self {
+        HexFormat { width: 16, ascii: true }
+    }
+}
+
+/// ### Formats a buffer as hex dump, 16 bytes per row with the ASCII column
+///
+/// ### Parameters
+/// - `data`: Bytes to format.
+/// - `base_offset`: Offset printed for the first byte (e.g. the start of the DB area read).
+///
+/// ### Example
+/// ```rust
+/// use rust7::format_hex;
+///
+/// assert_eq!(format_hex(b"Rust7\x00\x01", 0x10), "0010: 52 75 73 74 37 00 01                             Rust7..\n");
+/// ```
+///
+pub fn format_hex(data: &[u8], base_offset: usize) -> String {
+    format_hex_with(data, base_offset, HexFormat::default())
+}
+
+/// ### Formats a buffer as hex dump with a custom layout
+///
+/// Each row contains the offset (4 hex digits, 8 if the offsets exceed 0xFFFF), the bytes and
+/// optionally the ASCII column; each row ends with a newline. A width of 0 is taken as 16.
+///
+pub fn format_hex_with(data: &[u8], base_offset: usize, format: HexFormat) -> String {
+    let width = if format.width == 0 { 16 } else { format.width };
+    let digits = if base_offset + data.len() > 0x10000 { 8 } else { 4 };
+    let mut dump = String::new();
+    for (i, row) in data.chunks(width).enumerate() {
+        dump.push_str(&format!("{:0digits$X}:", base_offset + i * width, digits = digits));
+        for byte in row {
+            dump.push_str(&format!(" {:02X}", byte));
+        }
+        if format.ascii {
+            dump.push_str(&" ".repeat((width - row.len()) * 3 + 2));
+            dump.extend(row.iter().map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }));
+        }
+        dump.push('\n');
+    }
+    dump
+}
+
+/// ### Renders a telegram field by field (like the Wireshark s7comm dissector)
+///
+/// `frame` can be a whole telegram (starting with TPKT) or a bare S7 PDU (starting with 0x32).
+/// Malformed or truncated telegrams are decoded as far as possible, the rest is dumped in hex.
+///
+/// ### Example
+/// ```text
+/// TPKT: version 3, length 31
+/// COTP: DT (0xF0), EOT yes
+/// S7: ROSCTR Job (0x01), PDU ref 0x0500, param length 14, data length 0
+///   Function: Read Var (0x04), items 1
+///   Item [1]: BYTE x 4, DB 5, DBB 3.0 (area 0x84)
+/// ```
+///
+pub fn decode_frame(frame: &[u8]) -> String {
+    let mut out = String::new();
+    if let Err(offset) = decode_telegram(frame, &mut out) {
+        out.push_str(&format!("!! truncated or malformed at offset {}\n", offset));
+        if offset < frame.len() {
+            out.push_str(&hex_dump(&frame[offset..]));
+        }
+    }
+    out
+}
+
+/// Err(offset) = decoding stopped at offset
+fn decode_telegram(frame: &[u8], out: &mut String) -> Result<(), usize> {
+    if frame.first() == Some(&S7_ID) {
+        return decode_s7(frame, 0, out);
+    }
+
+    let tpkt = TpktHeader::decode(frame).map_err(|_| 0usize)?;
+    out.push_str(&format!("TPKT: version {}, length {}\n", frame[0], tpkt.length));
+    if frame.len() < TPKT_LEN + 2 {
+        return Err(TPKT_LEN);
+    }
+
+    let cotp = &frame[TPKT_LEN..];
+    match cotp[1] {
+        COTP_CR | COTP_CC => {
+            let conn = CotpConnection::decode(cotp).map_err(|_| TPKT_LEN)?;
+            out.push_str(&format!(
+                "COTP: {} (0x{:02X}), dst ref 0x{:04X}, src ref 0x{:04X}, class {}\n",
+                if conn.pdu_type == COTP_CR { "Connection Request" } else { "Connection Confirm" },
+                conn.pdu_type, conn.dst_ref, conn.src_ref, conn.class >> 4
+            ));
+            if conn.tpdu_size != 0 {
+                let size = 1usize.checked_shl(conn.tpdu_size as u32).unwrap_or(0);
+                out.push_str(&format!("  TPDU size: 0x{:02X} ({} byte)\n", conn.tpdu_size, size));
+            }
+            out.push_str(&format!("  Src TSAP: {}\n", hex_bytes(&conn.src_tsap)));
+            out.push_str(&format!("  Dst TSAP: {}\n", hex_bytes(&conn.dst_tsap)));
+            for (code, value) in &conn.extra_params {
+                out.push_str(&format!("  Parameter 0x{:02X}: {}\n", code, hex_bytes(value)));
+            }
+            Ok(())
+        }
+        COTP_DR => {
+            let dr = CotpDisconnect::decode(cotp).map_err(|_| TPKT_LEN)?;
+            out.push_str(&format!(
+                "COTP: Disconnect Request (0x80), dst ref 0x{:04X}, src ref 0x{:04X}, reason 0x{:02X}\n",
+                dr.dst_ref, dr.src_ref, dr.reason
+            ));
+            Ok(())
+        }
+        COTP_DT => {
+            let dt = CotpData::decode(cotp).map_err(|_| TPKT_LEN)?;
+            out.push_str(&format!("COTP: DT (0xF0), EOT {}\n", if dt.eot { "yes" } else { "no" }));
+            if frame.len() == TPKT_ISO_LEN {
+                return Ok(());
+            }
+            decode_s7(frame, TPKT_ISO_LEN, out)
+        }
+        other => {
+            out.push_str(&format!("COTP: unknown PDU type 0x{:02X}\n", other));
+            Err(TPKT_LEN)
+        }
+    }
+}
+
+fn decode_s7(frame: &[u8], start: usize, out: &mut String) -> Result<(), usize> {
+    let pdu = &frame[start..];
+    let header = S7Header::decode(pdu).map_err(|_| start)?;
+    out.push_str(&format!(
+        "S7: ROSCTR {} (0x{:02X}), PDU ref 0x{:04X}, param length {}, data length {}\n",
+        rosctr_name(header.rosctr), header.rosctr, header.pdu_ref, header.param_len, header.data_len
+    ));
+    if header.rosctr == ROSCTR_ACK || header.rosctr == ROSCTR_ACK_DATA {
+        out.push_str(&format!("  Error: class 0x{:02X}, code 0x{:02X}\n", header.error_class, header.error_code));
+    }
+
+    let params_offset = start + header.encoded_len();
+    let data_offset = params_offset + header.param_len as usize;
+    let data_end = data_offset + header.data_len as usize;
+    if frame.len() < data_end {
+        return Err(frame.len().min(params_offset));
+    }
+    let params = &frame[params_offset..data_offset];
+    let data = &frame[data_offset..data_end];
+
+    if header.rosctr == ROSCTR_USERDATA {
+        return decode_userdata(params, data, params_offset, out);
+    }
+    let Some(&function) = params.first() else {
+        return Ok(());
+    };
+
+    match function {
+        FN_SETUP_COMM => {
+            let setup = S7SetupComm::decode(params).map_err(|_| params_offset)?;
+            out.push_str(&format!(
+                "  Function: Setup communication (0xF0), max AmQ calling {}, max AmQ called {}, PDU length {}\n",
+                setup.max_amq_calling, setup.max_amq_called, setup.pdu_length
+            ));
+        }
+        FN_READ_VAR | FN_WRITE_VAR => {
+            let name = if function == FN_READ_VAR { "Read Var" } else { "Write Var" };
+            let count = *params.get(1).ok_or(params_offset + 1)? as usize;
+            out.push_str(&format!("  Function: {} (0x{:02X}), items {}\n", name, function, count));
+
+            if header.rosctr == ROSCTR_JOB {
+                for i in 0..count {
+                    let offset = 2 + i * ITEM_SPEC_LEN;
+                    let item = S7ItemSpec::decode(params.get(offset..).unwrap_or(&[]))
+                        .map_err(|_| params_offset + offset)?;
+                    out.push_str(&format!("  Item [{}]: {}\n", i + 1, describe_item(&item)));
+                }
+                if function == FN_WRITE_VAR {
+                    decode_data_items(data, count, data_offset, true, out)?;
+                }
+            } else if function == FN_READ_VAR {
+                decode_data_items(data, count, data_offset, false, out)?;
+            } else {
+                for (i, code) in data.iter().enumerate() {
+                    out.push_str(&format!("  Item [{}]: return code {} (0x{:02X})\n", i + 1, return_code_name(*code), code));
+                }
+            }
+        }
+        other => {
+            out.push_str(&format!("  Function: 0x{:02X}\n", other));
+            out.push_str(&format!("  Parameters: {}\n", hex_bytes(params)));
+            if !data.is_empty() {
+                out.push_str(&format!("  Data: {}\n", hex_bytes(data)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn decode_data_items(data: &[u8], count: usize, start: usize, request: bool, out: &mut String) -> Result<(), usize> {
+    let mut offset = 0;
+    for i in 0..count {
+        let item = DataItemHeader::decode(data.get(offset..).unwrap_or(&[])).map_err(|_| start + offset)?;
+        let size = match item.transport_size {
+            0x04 | 0x05 => item.length as usize / 8, // Length in bits
+            _ => item.length as usize,
+        };
+        let payload_offset = offset + DATA_ITEM_HEADER_LEN;
+        if request {
+            out.push_str(&format!("  Data [{}]: transport size 0x{:02X}, length {}\n", i + 1, item.transport_size, item.length));
+        } else {
+            out.push_str(&format!(
+                "  Data [{}]: return code {} (0x{:02X}), transport size 0x{:02X}, length {}\n",
+                i + 1, return_code_name(item.return_code), item.return_code, item.transport_size, item.length
+            ));
+        }
+        if item.return_code == RES_SUCCESS_CODE || request {
+            let payload = data.get(payload_offset..payload_offset + size).ok_or(start + payload_offset)?;
+            out.push_str(&format!("    Payload: {}\n", hex_bytes(payload)));
+            offset = payload_offset + size;
+            // Items are word aligned, except the last one
+            if size % 2 == 1 && i < count - 1 {
+                offset += 1;
+            }
+        } else {
+            offset = payload_offset;
+        }
+    }
+    Ok(())
+}
+
+fn decode_userdata(params: &[u8], data: &[u8], start: usize, out: &mut String) -> Result<(), usize> {
+    let ud = S7UserDataParams::decode(params).map_err(|_| start)?;
+    let kind = match ud.type_group >> 4 {
+        0x4 => "Request",
+        0x8 => "Response",
+        0x0 => "Push",
+        _ => "Unknown",
+    };
+    out.push_str(&format!(
+        "  Userdata: {} (0x{:02X}), group {} (0x{:X}), subfunction 0x{:02X}, sequence {}\n",
+        kind, ud.type_group, group_name(ud.group()), ud.group(), ud.subfunction, ud.sequence
+    ));
+    if ud.extended {
+        out.push_str(&format!(
+            "  Data unit ref {}, last data unit {}, error 0x{:04X}\n",
+            ud.data_unit_ref, if ud.last_data_unit == 0 { "yes" } else { "no" }, ud.error_code
+        ));
+    }
+    if data.len() >= DATA_ITEM_HEADER_LEN {
+        out.push_str(&format!(
+            "  Data: return code {} (0x{:02X}), transport size 0x{:02X}, length {}\n",
+            return_code_name(data[0]), data[0], data[1], u16::from_be_bytes([data[2], data[3]])
+        ));
+        let payload = &data[DATA_ITEM_HEADER_LEN..];
+        if ud.group() == UD_GROUP_SZL && ud.subfunction == UD_SUBFN_READ_SZL && payload.len() >= 4 {
+            out.push_str(&format!(
+                "    SZL ID 0x{:04X}, index 0x{:04X}\n",
+                u16::from_be_bytes([payload[0], payload[1]]), u16::from_be_bytes([payload[2], payload[3]])
+            ));
+        }
+        if !payload.is_empty() {
+            out.push_str(&format!("    Payload: {}\n", hex_bytes(payload)));
+        }
+    }
+    Ok(())
+}
+
+const RES_SUCCESS_CODE: u8 = 0xFF;
+
+fn describe_item(item: &S7ItemSpec) -> String {
+    let byte = item.address >> 3;
+    let bit = item.address & 0x07;
+    let (area, prefix) = match item.area {
+        0x81 => ("I".to_string(), "I"),
+        0x82 => ("Q".to_string(), "Q"),
+        0x83 => ("M".to_string(), "M"),
+        0x84 => (format!("DB {}", item.db_number), "DBB"),
+        other => (format!("area 0x{:02X}", other), "Byte"),
+    };
+    format!(
+        "{} x {}, {}, {} {}.{} (area 0x{:02X})",
+        wordlen_name(item.wordlen), item.amount, area, prefix, byte, bit, item.area
+    )
+}
+
+fn rosctr_name(rosctr: u8) -> &'static str {
+    match rosctr {
+        ROSCTR_JOB => "Job",
+        ROSCTR_ACK => "Ack",
+        ROSCTR_ACK_DATA => "Ack-Data",
+        ROSCTR_USERDATA => "Userdata",
+        _ => "Unknown",
+    }
+}
+
+fn wordlen_name(wordlen: u8) -> &'static str {
+    match wordlen {
+        0x01 => "BIT",
+        0x02 => "BYTE",
+        0x03 => "CHAR",
+        0x04 => "WORD",
+        0x05 => "INT",
+        0x06 => "DWORD",
+        0x07 => "DINT",
+        0x08 => "REAL",
+        0x1C => "COUNTER",
+        0x1D => "TIMER",
+        _ => "UNKNOWN",
+    }
+}
+
+fn return_code_name(code: u8) -> &'static str {
+    match code {
+        0x00 => "Reserved",
+        0x01 => "Hardware fault",
+        0x03 => "Access denied",
+        0x05 => "Invalid address",
+        0x06 => "Data type not supported",
+        0x07 => "Data type inconsistent",
+        0x0A => "Object does not exist",
+        0xFF => "Success",
+        _ => "Unknown",
+    }
+}
+
+fn group_name(group: u8) -> &'static str {
+    match group {
+        0x0 => "Mode transition",
+        0x1 => "Programmer commands",
+        0x2 => "Cyclic data",
+        0x3 => "Block functions",
+        0x4 => "CPU functions",
+        0x5 => "Security",
+        0x6 => "BSEND/BRCV",
+        0x7 => "Time functions",
+        0xF => "NC programming",
+        _ => "Unknown",
+    }
+}
+
+fn hex_bytes(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ")
+}
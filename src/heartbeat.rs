@@ -0,0 +1,192 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Heartbeat writer
+//!
+//! PLC programs usually supervise the PC connection by watching a bit that must toggle, or a
+//! counter that must change, within a time limit. `Heartbeat` writes that signal periodically
+//! through an `S7Worker`, as long as the application declares itself healthy: when it doesn't
+//! (`set_healthy(false)`) or stops the heartbeat, the PLC watchdog trips.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::{S7Client, S7DataType, S7_AREA_DB};
+//! use rust7::heartbeat::Heartbeat;
+//! use rust7::tag::Tag;
+//! use rust7::worker::S7Worker;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! let worker = S7Worker::spawn(client);
+//!
+//! // DB10.DBX0.0 toggled every second
+//! let heartbeat = Heartbeat::start(
+//!     worker.handle(),
+//!     Tag::new("PC.Alive", S7_AREA_DB, 10, 0, S7DataType::Bool),
+//!     Duration::from_secs(1),
+//! ).unwrap();
+//!
+//! // ... the application loop reports its health
+//! heartbeat.set_healthy(false);
+//! heartbeat.stop();
+//! ```
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use crate::client::S7Error;
+use crate::tag::Tag;
+use crate::value::{S7DataType, S7Value};
+use crate::worker::{is_worker_stopped, S7WorkerHandle};
+
+/// State shared with the writer thread
+#[derive(Default)]
+struct Shared {
+    healthy: AtomicBool,
+    beats: AtomicU64,
+    last_error: Mutex<Option<S7Error>>,
+}
+
+/// ### Periodic writer of a watchdog signal
+///
+/// Stops when `stop()` is called or the heartbeat is dropped, or when the worker stops.
+///
+pub struct Heartbeat {
+    shared: Arc<Shared>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Returns the value written at the `beat`-th beat (from 1)
+fn beat_value(data_type: S7DataType, beat: u64) -> S7Value {
+    if data_type == S7DataType::Bool {
+        return S7Value::Bool(beat % 2 == 1);
+    }
+    // Wraps to 0 past the maximum of the type
+    let period: u64 = match data_type {
+        S7DataType::SInt => 1 << 7,
+        S7DataType::Byte | S7DataType::USInt => 1 << 8,
+        S7DataType::Int => 1 << 15,
+        S7DataType::Word | S7DataType::UInt => 1 << 16,
+        S7DataType::DInt => 1 << 31,
+        S7DataType::DWord | S7DataType::UDInt => 1 << 32,
+        _ => 1 << 53, // Exact range of f64
+    };
+    S7Value::from_f64((beat % period) as f64, data_type).unwrap_or(S7Value::Bool(false))
+}
+
+impl Heartbeat {
+    /// ### Starts the heartbeat
+    ///
+    /// The first beat is written immediately, then one every `interval`: a `BOOL` tag is
+    /// toggled (true, false, true...), an integer tag is incremented (1, 2, 3...) and wraps to 0
+    /// past the maximum of its type.
+    ///
+    /// ### Parameters
+    /// - `worker`: Handle of the worker owning the connection. A high priority handle
+    ///   (`with_priority(JobPriority::High)`) keeps the beats regular while bulk jobs are queued.
+    /// - `tag`: Address of the signal, `BOOL` or integer type (`BYTE`, `INT`, `DINT`, `WORD`...).
+    /// - `interval`: Period of the beats, well below the watchdog time of the PLC.
+    ///
+    /// ### Returns
+    /// `Ok(<Heartbeat>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Tag of another type, scaled tag or interval of 0.
+    ///
+    /// ### Notes
+    /// A failed write doesn't stop the heartbeat (see `take_error()`): the next beat is tried at
+    /// the next interval, e.g. after the application reconnected the client.
+    ///
+    pub fn start(worker: S7WorkerHandle, tag: Tag, interval: Duration) -> Result<Heartbeat, S7Error> {
+        let valid_type = matches!(tag.data_type,
+            S7DataType::Bool | S7DataType::Byte | S7DataType::Word | S7DataType::DWord | S7DataType::LWord |
+            S7DataType::SInt | S7DataType::USInt | S7DataType::Int | S7DataType::UInt |
+            S7DataType::DInt | S7DataType::UDInt | S7DataType::LInt | S7DataType::ULInt);
+        if !valid_type || tag.scaling.is_some() || interval.is_zero() {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        let shared = Arc::new(Shared { healthy: AtomicBool::new(true), ..Default::default() });
+        let (stop, stop_receiver) = mpsc::channel::<()>();
+        let state = shared.clone();
+        let thread = std::thread::spawn(move || {
+            let mut beat = 0u64;
+            loop {
+                if state.healthy.load(Ordering::Relaxed) {
+                    beat += 1;
+                    let value = beat_value(tag.data_type, beat);
+                    let tag = tag.clone();
+                    match worker.execute(move |client| tag.write(client, &value)).wait() {
+                        Ok(()) => {
+                            state.beats.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(error) if is_worker_stopped(&error) => break,
+                        Err(error) => *state.last_error.lock().unwrap() = Some(error),
+                    }
+                }
+                match stop_receiver.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    _ => break, // Stopped or dropped
+                }
+            }
+        });
+
+        Ok(Heartbeat {
+            shared,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+
+    /// ### Declares the application healthy (beats written) or not (beats suspended)
+    ///
+    /// While not healthy the signal is left unchanged, so the PLC watchdog trips.
+    ///
+    pub fn set_healthy(&self, healthy: bool) {
+        self.shared.healthy.store(healthy, Ordering::Relaxed);
+    }
+
+    /// ### Returns true if the beats are written
+    ///
+    pub fn is_healthy(&self) -> bool {
+        self.shared.healthy.load(Ordering::Relaxed)
+    }
+
+    /// ### Returns the number of beats written successfully
+    ///
+    pub fn beats(&self) -> u64 {
+        self.shared.beats.load(Ordering::Relaxed)
+    }
+
+    /// ### Returns (and clears) the last write error
+    ///
+    pub fn take_error(&self) -> Option<S7Error> {
+        self.shared.last_error.lock().unwrap().take()
+    }
+
+    /// ### Stops the heartbeat
+    ///
+    /// Waits for the beat in progress, if any. The signal keeps its last value.
+    ///
+    pub fn stop(mut self) {
+        self.join();
+    }
+
+    fn join(&mut self) {
+        // Dropping the sender wakes the thread
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
@@ -0,0 +1,477 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Tag historian (feature `historian`)
+//!
+//! `Historian` stores the values of the subscribed tags into files, without a database: CSV
+//! files, readable by any spreadsheet, or a compact binary format read back by `read_log()`.
+//! The values are buffered and written in batches (see `with_flush()`), the files are rotated
+//! by size and/or age and the oldest ones are deleted (see `with_rotation()`).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::{S7Client, S7DataType, S7_AREA_DB};
+//! use rust7::historian::{Historian, LogFormat};
+//! use rust7::tag::{Subscription, Tag};
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! let mut subscription = Subscription::new(Duration::from_secs(1));
+//! subscription.add_tag(Tag::new("Line1.Speed", S7_AREA_DB, 100, 0, S7DataType::Real));
+//! subscription.add_tag(Tag::new("Line1.Count", S7_AREA_DB, 100, 4, S7DataType::DInt));
+//!
+//! // New file every 10 MB, the last 20 files kept
+//! let mut historian = Historian::new("/var/log/line1", LogFormat::Csv).unwrap()
+//!     .with_rotation(Some(10_000_000), None, Some(20));
+//! loop {
+//!     let updates = subscription.poll(&mut client).unwrap();
+//!     historian.log(&updates).unwrap();
+//!     subscription.wait();
+//! }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::client::S7Error;
+use crate::tag::TagUpdate;
+use crate::value::{S7DataType, S7Value};
+
+/// Signature of the binary files
+const BINARY_MAGIC: &[u8; 6] = b"S7HIST";
+const BINARY_VERSION: u8 = 1;
+/// Binary record: tag definition
+const RECORD_TAG: u8 = 0x01;
+/// Binary record: value
+const RECORD_VALUE: u8 = 0x02;
+const FLAG_STALE: u8 = 0x01;
+const CSV_HEADER: &str = "time,time_ms,tag,value,quality\n";
+
+// Default flush policy
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+const FLUSH_RECORDS: usize = 1000;
+
+/// ### Format of the files
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One line per value: `time,time_ms,tag,value,quality` (UTC time, ms since 1970-01-01, GOOD or STALE)
+    Csv,
+    /// Compact binary records, read back by `read_log()`
+    Binary,
+}
+
+/// ### Value stored by the historian
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    /// Tag name
+    pub name: String,
+    pub data_type: S7DataType,
+    pub value: S7Value,
+    pub timestamp: SystemTime,
+    /// The value was no longer fresh (see `Subscription::check_stale()`)
+    pub stale: bool,
+}
+
+impl From<&TagUpdate> for LogRecord {
+    fn from(update: &TagUpdate) -> Self {
+        LogRecord {
+            name: update.name.clone(),
+            data_type: update.data_type,
+            value: update.value.clone(),
+            timestamp: update.timestamp,
+            stale: update.stale,
+        }
+    }
+}
+
+/// File being written
+struct LogFile {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    size: u64,
+    opened: Instant,
+    /// Binary format: ID of the tags defined in the file
+    tag_ids: HashMap<String, u16>,
+}
+
+/// ### Writer of the tag values into rotated files
+///
+/// The pending values are written when the historian is dropped.
+///
+pub struct Historian {
+    directory: PathBuf,
+    prefix: String,
+    format: LogFormat,
+    max_file_size: Option<u64>,
+    max_file_age: Option<Duration>,
+    max_files: Option<usize>,
+    flush_interval: Duration,
+    flush_records: usize,
+    pending: Vec<LogRecord>,
+    last_flush: Instant,
+    file: Option<LogFile>,
+    /// Files written, oldest first
+    files: VecDeque<PathBuf>,
+    sequence: u32,
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn unix_ms(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_millis() as i64,
+        Err(before) => -(before.duration().as_millis() as i64),
+    }
+}
+
+/// Formats a time as `YYYY-MM-DDThh:mm:ss.mmmZ` (UTC)
+fn utc_string(time: SystemTime) -> String {
+    let ms = unix_ms(time).max(0) as u64;
+    let (days, secs) = (ms / 86_400_000, ms / 1000 % 86_400);
+    // Civil date from the days since 1970-01-01
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, secs / 3600, secs / 60 % 60, secs % 60, ms % 1000)
+}
+
+impl Historian {
+    /// ### Creates a historian writing into a directory
+    ///
+    /// The defaults are: files named `history_<unix time>_<sequence>`, no rotation, values written every
+    /// 5 s or every 1000 values.
+    ///
+    /// ### Parameters
+    /// - `directory`: Directory of the files, created if missing.
+    /// - `format`: `LogFormat::Csv` or `LogFormat::Binary`.
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The directory cannot be created.
+    ///
+    pub fn new<P: AsRef<Path>>(directory: P, format: LogFormat) -> Result<Self, S7Error> {
+        fs::create_dir_all(directory.as_ref())?;
+        Ok(Historian {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: "history".to_string(),
+            format,
+            max_file_size: None,
+            max_file_age: None,
+            max_files: None,
+            flush_interval: FLUSH_INTERVAL,
+            flush_records: FLUSH_RECORDS,
+            pending: Vec::new(),
+            last_flush: Instant::now(),
+            file: None,
+            files: VecDeque::new(),
+            sequence: 0,
+        })
+    }
+
+    /// ### Sets the prefix of the file names (Default = "history")
+    ///
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// ### Sets the rotation of the files
+    ///
+    /// ### Parameters
+    /// - `max_size`: A new file is started when the current one reaches this size (byte).
+    /// - `max_age`: A new file is started when the current one is older (e.g. one per hour).
+    /// - `max_files`: Number of files kept, the oldest ones are deleted (`None` = all kept).
+    ///
+    /// ### Notes
+    /// Only the files written by this historian are deleted, not the ones of previous runs.
+    ///
+    pub fn with_rotation(mut self, max_size: Option<u64>, max_age: Option<Duration>, max_files: Option<usize>) -> Self {
+        self.max_file_size = max_size;
+        self.max_file_age = max_age;
+        self.max_files = max_files.map(|files| files.max(1));
+        self
+    }
+
+    /// ### Sets when the buffered values are written
+    ///
+    /// ### Parameters
+    /// - `interval`: Max time a value waits in memory (a crash loses at most this interval).
+    /// - `max_records`: Values buffered before writing them anyway.
+    ///
+    pub fn with_flush(mut self, interval: Duration, max_records: usize) -> Self {
+        self.flush_interval = interval;
+        self.flush_records = max_records.max(1);
+        self
+    }
+
+    /// ### Stores the updates returned by `Subscription::poll()` (or `check_stale()`)
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The values cannot be written (they stay buffered, see `flush()`).
+    ///
+    pub fn log(&mut self, updates: &[TagUpdate]) -> Result<(), S7Error> {
+        self.pending.extend(updates.iter().map(LogRecord::from));
+        self.flush_if_due()
+    }
+
+    /// ### Stores a value
+    ///
+    /// ### Errors
+    /// - Reported by `log()`
+    ///
+    pub fn log_value(&mut self, name: &str, data_type: S7DataType, value: &S7Value, timestamp: SystemTime) -> Result<(), S7Error> {
+        self.pending.push(LogRecord {
+            name: name.to_string(),
+            data_type,
+            value: value.clone(),
+            timestamp,
+            stale: false,
+        });
+        self.flush_if_due()
+    }
+
+    /// ### Writes the buffered values if the flush interval or the batch size is reached
+    ///
+    /// ### Errors
+    /// - Reported by `flush()`
+    ///
+    pub fn flush_if_due(&mut self) -> Result<(), S7Error> {
+        if self.pending.len() >= self.flush_records || self.last_flush.elapsed() >= self.flush_interval {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// ### Writes the buffered values to the file
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The file cannot be created or written. The values not written stay
+    ///   buffered and are written by the next flush.
+    ///
+    pub fn flush(&mut self) -> Result<(), S7Error> {
+        self.last_flush = Instant::now();
+        let pending = std::mem::take(&mut self.pending);
+        for (index, record) in pending.iter().enumerate() {
+            if let Err(error) = self.write_record(record) {
+                self.pending = pending[index..].to_vec();
+                return Err(error);
+            }
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// ### Returns the number of values not yet written
+    ///
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// ### Returns the path of the file being written, if any
+    ///
+    pub fn current_file(&self) -> Option<&Path> {
+        self.file.as_ref().map(|file| file.path.as_path())
+    }
+
+    /// Writes a record, rotating the file if needed
+    fn write_record(&mut self, record: &LogRecord) -> Result<(), S7Error> {
+        let rotate = self.file.as_ref().is_some_and(|file| {
+            self.max_file_size.is_some_and(|max| file.size >= max)
+                || self.max_file_age.is_some_and(|max| file.opened.elapsed() >= max)
+        });
+        if rotate {
+            self.close_file()?;
+        }
+        if self.file.is_none() {
+            self.open_file()?;
+        }
+        let format = self.format;
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+
+        let mut buffer = Vec::new();
+        match format {
+            LogFormat::Csv => {
+                let line = format!("{},{},{},{},{}\n",
+                    utc_string(record.timestamp),
+                    unix_ms(record.timestamp),
+                    csv_field(&record.name),
+                    csv_field(&record.value.to_string()),
+                    if record.stale { "STALE" } else { "GOOD" });
+                buffer.extend_from_slice(line.as_bytes());
+            }
+            LogFormat::Binary => {
+                let next_id = file.tag_ids.len() as u16;
+                let id = match file.tag_ids.get(&record.name) {
+                    Some(id) => *id,
+                    None => {
+                        // Tag definition: ID, name, type
+                        let name = record.name.as_bytes();
+                        let data_type = record.data_type.to_string();
+                        buffer.push(RECORD_TAG);
+                        buffer.extend_from_slice(&next_id.to_le_bytes());
+                        buffer.extend_from_slice(&(name.len() as u16).to_le_bytes());
+                        buffer.extend_from_slice(name);
+                        buffer.push(data_type.len() as u8);
+                        buffer.extend_from_slice(data_type.as_bytes());
+                        file.tag_ids.insert(record.name.clone(), next_id);
+                        next_id
+                    }
+                };
+                // Value: ID, time (ms), flags, value in the PLC layout
+                let value = record.value.encode(record.data_type)?;
+                buffer.push(RECORD_VALUE);
+                buffer.extend_from_slice(&id.to_le_bytes());
+                buffer.extend_from_slice(&unix_ms(record.timestamp).to_le_bytes());
+                buffer.push(if record.stale { FLAG_STALE } else { 0 });
+                buffer.extend_from_slice(&(value.len() as u16).to_le_bytes());
+                buffer.extend_from_slice(&value);
+            }
+        }
+        file.writer.write_all(&buffer)?;
+        file.size += buffer.len() as u64;
+        Ok(())
+    }
+
+    fn open_file(&mut self) -> Result<(), S7Error> {
+        let extension = match self.format {
+            LogFormat::Csv => "csv",
+            LogFormat::Binary => "s7h",
+        };
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+        // The sequence keeps the names in order when several files are created in the same second
+        let mut path;
+        loop {
+            self.sequence += 1;
+            path = self.directory.join(format!("{}_{}_{:04}.{}", self.prefix, secs, self.sequence, extension));
+            if !path.exists() {
+                break;
+            }
+        }
+
+        let mut writer = BufWriter::new(File::create(&path)?);
+        let header: Vec<u8> = match self.format {
+            LogFormat::Csv => CSV_HEADER.as_bytes().to_vec(),
+            LogFormat::Binary => [&BINARY_MAGIC[..], &[BINARY_VERSION]].concat(),
+        };
+        writer.write_all(&header)?;
+        s7_debug!("historian: new file {}", path.display());
+
+        self.files.push_back(path.clone());
+        if let Some(max_files) = self.max_files {
+            while self.files.len() > max_files {
+                if let Some(oldest) = self.files.pop_front() {
+                    let _ = fs::remove_file(oldest);
+                }
+            }
+        }
+        self.file = Some(LogFile {
+            writer,
+            path,
+            size: header.len() as u64,
+            opened: Instant::now(),
+            tag_ids: HashMap::new(),
+        });
+        Ok(())
+    }
+
+    fn close_file(&mut self) -> Result<(), S7Error> {
+        if let Some(mut file) = self.file.take() {
+            file.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Historian {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// ### Reads a binary file written by the historian
+///
+/// ### Returns
+/// `Ok(<Vec<LogRecord>>)` the values in the order written, or `Err(<S7Error>)`
+///
+/// ### Errors
+/// - `S7Error::Io`: The file cannot be read.
+/// - `S7Error::Other`: Not a historian file, or corrupted (a truncated last record, e.g. after a
+///   power loss, is ignored).
+///
+pub fn read_log<P: AsRef<Path>>(path: P) -> Result<Vec<LogRecord>, S7Error> {
+    let mut data = Vec::new();
+    BufReader::new(File::open(path)?).read_to_end(&mut data)?;
+    let corrupted = || S7Error::Other("historian: corrupted file".to_string());
+    if data.len() < BINARY_MAGIC.len() + 1 || &data[..BINARY_MAGIC.len()] != BINARY_MAGIC {
+        return Err(S7Error::Other("historian: not a historian file".to_string()));
+    }
+    if data[BINARY_MAGIC.len()] != BINARY_VERSION {
+        return Err(S7Error::Other(format!("historian: unsupported version {}", data[BINARY_MAGIC.len()])));
+    }
+
+    let mut tags: HashMap<u16, (String, S7DataType)> = HashMap::new();
+    let mut records = Vec::new();
+    let mut offset = BINARY_MAGIC.len() + 1;
+    let field = |offset: usize, len: usize| data.get(offset..offset + len);
+    while offset < data.len() {
+        match data[offset] {
+            RECORD_TAG => {
+                let Some(header) = field(offset + 1, 4) else { break };
+                let id = u16::from_le_bytes([header[0], header[1]]);
+                let name_len = u16::from_le_bytes([header[2], header[3]]) as usize;
+                let Some(name) = field(offset + 5, name_len) else { break };
+                let Some(&type_len) = data.get(offset + 5 + name_len) else { break };
+                let Some(type_name) = field(offset + 6 + name_len, type_len as usize) else { break };
+                let data_type = String::from_utf8_lossy(type_name).parse::<S7DataType>().map_err(|_| corrupted())?;
+                tags.insert(id, (String::from_utf8_lossy(name).into_owned(), data_type));
+                offset += 6 + name_len + type_len as usize;
+            }
+            RECORD_VALUE => {
+                let Some(header) = field(offset + 1, 13) else { break };
+                let id = u16::from_le_bytes([header[0], header[1]]);
+                let ms = i64::from_le_bytes(header[2..10].try_into().map_err(|_| corrupted())?);
+                let flags = header[10];
+                let value_len = u16::from_le_bytes([header[11], header[12]]) as usize;
+                let Some(raw) = field(offset + 14, value_len) else { break };
+                let (name, data_type) = tags.get(&id).ok_or_else(corrupted)?;
+                let timestamp = if ms >= 0 {
+                    UNIX_EPOCH + Duration::from_millis(ms as u64)
+                } else {
+                    UNIX_EPOCH - Duration::from_millis(ms.unsigned_abs())
+                };
+                records.push(LogRecord {
+                    name: name.clone(),
+                    data_type: *data_type,
+                    value: S7Value::decode(*data_type, raw).map_err(|_| corrupted())?,
+                    timestamp,
+                    stale: flags & FLAG_STALE != 0,
+                });
+                offset += 14 + value_len;
+            }
+            _ => return Err(corrupted()),
+        }
+    }
+    Ok(records)
+}
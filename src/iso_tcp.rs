@@ -0,0 +1,344 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### ISO-on-TCP (RFC 1006) transport
+//!
+//! TPKT framing and COTP class 0 (connection, data, disconnection), independent of the S7 layer.
+//! The S7 client and partner are built on it, other RFC 1006 protocols (e.g. Sinumerik, S5
+//! gateways) can reuse it as well.
+//!
+//! The functions work on any byte stream (`TcpStream`, `Transport`...), `IsoTcpConnection` owns
+//! the stream of a connected client.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::iso_tcp::{IsoConnectParams, IsoTcpConnection};
+//!
+//! let params = IsoConnectParams::new(vec![0x01, 0x00], vec![0x03, 0x01]);
+//! let mut connection = IsoTcpConnection::connect("192.168.0.100:102", &params, Duration::from_secs(3)).unwrap();
+//! connection.send(&[0x32, 0x01, 0x00, 0x00]).unwrap(); // Whatever the upper protocol is
+//! let reply = connection.recv().unwrap();
+//! connection.disconnect();
+//! ```
+
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use crate::client::S7Error;
+use crate::frame::{
+    CotpConnection, CotpData, CotpDisconnect, TpktHeader,
+    COTP_CC, COTP_CR, COTP_DR, COTP_DT, COTP_DT_LEN, TPKT_LEN,
+};
+use crate::transport::Transport;
+
+/// ISO-on-TCP well-known port
+pub const ISO_TCP_PORT: u16 = 102;
+/// Minimum COTP CR/CC length (header without parameters)
+const COTP_CONN_MIN_LEN: usize = 7;
+/// Max COTP header length (the length indicator is a byte, 0xFF is reserved)
+const COTP_MAX_HEADER_LEN: usize = 255;
+/// Max length of the data reassembled by `IsoTcpConnection::recv()`
+const DEFAULT_MAX_DATA_LEN: usize = 65536;
+
+/// ### Function customizing the COTP Connection Request sent by `S7Client`
+///
+pub type CotpRequestHook = Box<dyn FnMut(&mut CotpConnection) + Send>;
+
+/// ### Parameters of the COTP Connection Request
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsoConnectParams {
+    /// Local (calling) TSAP
+    pub local_tsap: Vec<u8>,
+    /// Remote (called) TSAP
+    pub remote_tsap: Vec<u8>,
+    /// COTP reference of the local side
+    pub local_ref: u16,
+    /// TPDU size requested: 2^code byte (e.g. 0x0A = 1024 byte), 0 = parameter omitted
+    pub tpdu_size_code: u8,
+    /// Class and options (Default 0x00: class 0, no options)
+    pub class: u8,
+    /// Additional parameters `(code, value)`, see `CotpConnection::extra_params`
+    pub extra_params: Vec<(u8, Vec<u8>)>,
+}
+
+impl IsoConnectParams {
+    /// ### Creates the parameters (local reference 0x0001, TPDU size 1024 byte)
+    ///
+    pub fn new(local_tsap: Vec<u8>, remote_tsap: Vec<u8>) -> Self {
+        IsoConnectParams {
+            local_tsap,
+            remote_tsap,
+            local_ref: 0x0001,
+            tpdu_size_code: 0x0A,
+            class: 0x00,
+            extra_params: Vec::new(),
+        }
+    }
+
+    /// ### Builds the COTP Connection Request
+    ///
+    pub fn connection_request(&self) -> CotpConnection {
+        CotpConnection {
+            pdu_type: COTP_CR,
+            dst_ref: 0x0000,
+            src_ref: self.local_ref,
+            class: self.class,
+            tpdu_size: self.tpdu_size_code,
+            src_tsap: self.local_tsap.clone(),
+            dst_tsap: self.remote_tsap.clone(),
+            extra_params: self.extra_params.clone(),
+        }
+    }
+}
+
+/// ### Writes a TPKT packet carrying the COTP TPDU `tpdu`
+///
+/// ### Errors
+/// - `S7Error::InvalidFunParameter`: The packet would exceed 65535 byte.
+/// - `S7Error::Io`: Socket error.
+///
+pub fn write_packet<S: Write + ?Sized>(stream: &mut S, tpdu: &[u8]) -> Result<(), S7Error> {
+    let length = TPKT_LEN + tpdu.len();
+    if length > u16::MAX as usize {
+        return Err(S7Error::InvalidFunParameter);
+    }
+    let mut packet = Vec::with_capacity(length);
+    TpktHeader { length: length as u16 }.encode(&mut packet);
+    packet.extend_from_slice(tpdu);
+    stream.write_all(&packet)?;
+    Ok(())
+}
+
+/// ### Reads a whole TPKT packet
+///
+/// ### Returns
+/// `Ok(<packet>)` TPKT header included, or `Err(<S7Error>)`
+///
+/// ### Errors
+/// - `S7Error::IsoInvalidHeader`: Wrong RFC 1006 ID or packet shorter than a COTP header.
+/// - `S7Error::Io`: Socket error, timeout or connection closed (`UnexpectedEof`).
+///
+pub fn read_packet<S: Read + ?Sized>(stream: &mut S) -> Result<Vec<u8>, S7Error> {
+    let mut header = [0u8; TPKT_LEN];
+    stream.read_exact(&mut header)?;
+    let length = TpktHeader::decode(&header)?.length as usize;
+    if length < TPKT_LEN + 2 {
+        return Err(S7Error::IsoInvalidHeader);
+    }
+    let mut packet = vec![0u8; length];
+    packet[..TPKT_LEN].copy_from_slice(&header);
+    stream.read_exact(&mut packet[TPKT_LEN..])?;
+    Ok(packet)
+}
+
+/// ### Sends a COTP Connection Request
+///
+/// ### Parameters
+/// - `request`: The CR, usually built by `IsoConnectParams::connection_request()`.
+///
+/// ### Errors
+/// - `S7Error::InvalidFunParameter`: The parameters don't fit into the COTP header (max 255 byte,
+///   e.g. the TSAPs can't exceed 241 byte together) or a parameter value exceeds 255 byte.
+/// - `S7Error::Io`: Socket error.
+///
+pub fn send_connect_request<S: Write + ?Sized>(stream: &mut S, request: &CotpConnection) -> Result<(), S7Error> {
+    let values = [&request.src_tsap, &request.dst_tsap].into_iter().chain(request.extra_params.iter().map(|(_, value)| value));
+    if values.into_iter().any(|value| value.len() > u8::MAX as usize) {
+        return Err(S7Error::InvalidFunParameter);
+    }
+    let mut tpdu = Vec::new();
+    request.encode(&mut tpdu);
+    if tpdu.len() > COTP_MAX_HEADER_LEN {
+        return Err(S7Error::InvalidFunParameter);
+    }
+    write_packet(stream, &tpdu)
+}
+
+/// ### Decodes the COTP Connection Confirm
+///
+/// ### Parameters
+/// - `packet`: Whole packet, as returned by `read_packet()`.
+///
+/// ### Errors
+/// - `S7Error::IsoConnectionFailed`: Not a CC (e.g. a DR, the peer refused the connection) or malformed.
+///
+pub fn parse_connect_confirm(packet: &[u8]) -> Result<CotpConnection, S7Error> {
+    if packet.len() < TPKT_LEN + COTP_CONN_MIN_LEN {
+        return Err(S7Error::IsoConnectionFailed);
+    }
+    let confirm = CotpConnection::decode(&packet[TPKT_LEN..]).map_err(|_| S7Error::IsoConnectionFailed)?;
+    if confirm.pdu_type != COTP_CC {
+        return Err(S7Error::IsoConnectionFailed);
+    }
+    Ok(confirm)
+}
+
+/// ### COTP connection: sends the CR and waits for the CC
+///
+/// ### Returns
+/// `Ok(<CotpConnection>)` the Connection Confirm, or `Err(<S7Error>)`
+///
+/// ### Errors
+/// - `S7Error::IsoConnectionFailed`: The peer refused the connection (wrong TSAP...).
+/// - Other reported by `read_packet()`
+///
+pub fn cotp_connect<S: Read + Write + ?Sized>(stream: &mut S, params: &IsoConnectParams) -> Result<CotpConnection, S7Error> {
+    send_connect_request(stream, &params.connection_request())?;
+    let packet = read_packet(stream)?;
+    parse_connect_confirm(&packet)
+}
+
+/// ### Sends `data` in a single COTP DT TPDU (EOT)
+///
+pub fn send_data<S: Write + ?Sized>(stream: &mut S, data: &[u8]) -> Result<(), S7Error> {
+    let mut tpdu = Vec::with_capacity(COTP_DT_LEN + data.len());
+    CotpData { eot: true }.encode(&mut tpdu);
+    tpdu.extend_from_slice(data);
+    write_packet(stream, &tpdu)
+}
+
+/// ### Receives the data of a COTP DT sequence
+///
+/// DT fragments are reassembled up to the one marked EOT.
+///
+/// ### Parameters
+/// - `max_len`: Max length of the reassembled data.
+///
+/// ### Errors
+/// - `S7Error::ConnectionClosed`: The peer sent a Disconnect Request.
+/// - `S7Error::IsoFragmentedPacket`: The data exceeds `max_len`.
+/// - `S7Error::IsoInvalidTelegram`: Unexpected TPDU.
+/// - Other reported by `read_packet()`
+///
+pub fn recv_data<S: Read + ?Sized>(stream: &mut S, max_len: usize) -> Result<Vec<u8>, S7Error> {
+    let mut data = Vec::new();
+    loop {
+        let packet = read_packet(stream)?;
+        let tpdu = &packet[TPKT_LEN..];
+        match tpdu[1] {
+            COTP_DT => {
+                let dt = CotpData::decode(tpdu)?;
+                let payload = &tpdu[COTP_DT_LEN..];
+                if data.len() + payload.len() > max_len {
+                    return Err(S7Error::IsoFragmentedPacket);
+                }
+                data.extend_from_slice(payload);
+                if dt.eot {
+                    return Ok(data);
+                }
+            }
+            COTP_DR => return Err(S7Error::ConnectionClosed),
+            _ => return Err(S7Error::IsoInvalidTelegram),
+        }
+    }
+}
+
+/// ### Sends a COTP Disconnect Request (reason: normal disconnect)
+///
+pub fn send_disconnect<S: Write + ?Sized>(stream: &mut S, dst_ref: u16, src_ref: u16) -> Result<(), S7Error> {
+    let mut tpdu = Vec::with_capacity(CotpDisconnect::LEN);
+    CotpDisconnect {
+        dst_ref,
+        src_ref,
+        reason: 0x80,
+    }.encode(&mut tpdu);
+    write_packet(stream, &tpdu)
+}
+
+/// ### Connected ISO-on-TCP client
+///
+pub struct IsoTcpConnection {
+    stream: Box<dyn Transport>,
+    confirm: CotpConnection,
+    local_ref: u16,
+    tpdu_size_code: u8,
+    max_data_len: usize,
+}
+
+impl IsoTcpConnection {
+    /// ### Opens the TCP connection and performs the COTP connection
+    ///
+    /// ### Parameters
+    /// - `address`: Peer address, e.g. `"192.168.0.100:102"`.
+    /// - `params`: COTP connection parameters.
+    /// - `timeout`: TCP connection timeout, also used as read/write timeout.
+    ///
+    /// ### Errors
+    /// - `S7Error::TcpConnectionFailed`: Unreachable peer.
+    /// - Other reported by `cotp_connect()`
+    ///
+    pub fn connect<A: ToSocketAddrs>(address: A, params: &IsoConnectParams, timeout: Duration) -> Result<Self, S7Error> {
+        let address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or(S7Error::TcpConnectionFailed)?;
+        let stream = TcpStream::connect_timeout(&address, timeout).map_err(|_| S7Error::TcpConnectionFailed)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Self::connect_transport(Box::new(stream), params)
+    }
+
+    /// ### Performs the COTP connection over an open transport
+    ///
+    pub fn connect_transport(mut stream: Box<dyn Transport>, params: &IsoConnectParams) -> Result<Self, S7Error> {
+        let confirm = cotp_connect(stream.as_mut(), params)?;
+        Ok(IsoTcpConnection {
+            stream,
+            confirm,
+            local_ref: params.local_ref,
+            tpdu_size_code: params.tpdu_size_code,
+            max_data_len: DEFAULT_MAX_DATA_LEN,
+        })
+    }
+
+    /// ### Returns the Connection Confirm received from the peer
+    ///
+    pub fn confirm(&self) -> &CotpConnection {
+        &self.confirm
+    }
+
+    /// ### Returns the TPDU size negotiated (byte)
+    ///
+    /// The TPDU size parameter is optional into the CC, if missing the requested one applies.
+    ///
+    pub fn tpdu_size(&self) -> usize {
+        let code = if self.confirm.tpdu_size != 0 { self.confirm.tpdu_size } else { self.tpdu_size_code };
+        1usize.checked_shl(code as u32).unwrap_or(0)
+    }
+
+    /// ### Sets the max length of the data reassembled by `recv()` (Default 64 KB)
+    ///
+    pub fn set_max_data_len(&mut self, max_data_len: usize) {
+        self.max_data_len = max_data_len;
+    }
+
+    /// ### Sends a data unit (see `send_data()`)
+    ///
+    pub fn send(&mut self, data: &[u8]) -> Result<(), S7Error> {
+        send_data(self.stream.as_mut(), data)
+    }
+
+    /// ### Receives a data unit (see `recv_data()`)
+    ///
+    pub fn recv(&mut self) -> Result<Vec<u8>, S7Error> {
+        recv_data(self.stream.as_mut(), self.max_data_len)
+    }
+
+    /// ### Returns the underlying transport (e.g. to change the timeouts)
+    ///
+    pub fn transport_mut(&mut self) -> &mut dyn Transport {
+        self.stream.as_mut()
+    }
+
+    /// ### Sends the Disconnect Request and closes the stream
+    ///
+    /// Errors are ignored: we are closing anyway.
+    ///
+    pub fn disconnect(mut self) {
+        let _ = send_disconnect(self.stream.as_mut(), self.confirm.src_ref, self.local_ref);
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
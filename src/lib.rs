@@ -1,11 +1,81 @@
 #![forbid(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
+#[macro_use]
+mod logging;
+
 pub mod client;
+pub mod frame;
+pub mod iso_tcp;
+pub mod value;
+pub mod tag;
+pub mod retry;
+pub mod szl;
+pub mod partner;
+pub mod bsend;
+pub mod sim;
+pub mod transport;
+pub mod replay;
+pub mod pcap;
+pub mod stats;
+pub mod worker;
+pub mod multivar;
+pub mod compat;
+pub mod socks;
+pub mod fault;
+pub mod bench;
+pub mod blocks;
+pub mod analog;
+pub mod heartbeat;
+pub mod write_queue;
+pub mod cache;
+pub mod observer;
+pub mod audit;
+pub mod authorize;
+pub mod force;
+pub mod redundant;
+pub mod alarms;
+pub mod nck;
+pub mod dbtools;
+pub mod address;
+pub mod clock;
+
+#[cfg(feature = "serde")]
+pub mod tag_config;
+
+#[cfg(feature = "config")]
+pub mod config;
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+#[cfg(feature = "async")]
+pub mod stream;
 
+#[cfg(feature = "codec")]
+pub mod codec;
+
+#[cfg(feature = "ssh")]
+pub mod ssh;
+
+#[cfg(feature = "dcp")]
+pub mod dcp;
+
+#[cfg(feature = "historian")]
+pub mod historian;
+
+pub use frame::{decode_frame, format_hex, format_hex_with, HexFormat};
 pub use client::{
-    S7Client, S7Error,
+    S7Client, S7Error, S7ErrorClass, S7ErrorCodes, ErrorContext, NegotiationInfo, HealthReport, S7Routing, S7ClientConfig,
+    DropBehavior,
     CT_PG, CT_OP, CT_S7,
     S7_AREA_PE, S7_AREA_PA, S7_AREA_MK, S7_AREA_DB,
     S7_WL_BIT, S7_WL_BYTE,
 };
+
+pub use value::{S7Value, S7DataType};
+pub use szl::{PlcStatus, LedState, LedStatus, MemoryArea, MemoryInfo, CommResources, ModuleIdent, Protection};
+pub use stats::{TrafficStats, TransferReport, ChunkTiming};
+pub use multivar::S7DataItem;
+pub use address::{AreaAddress, BitAddress, DbAddress};
+pub use clock::{ClockDrift, ClockSync};
@@ -0,0 +1,30 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! Records emitted through the `log` facade (feature `log`)
+//!
+//! Without the feature the arguments are only type-checked, never evaluated.
+
+/// Debug record: connection events, negotiation outcome, errors, retries
+macro_rules! s7_debug {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)+);
+        #[cfg(not(feature = "log"))]
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    };
+}
+
+/// Trace record: chunking decisions
+macro_rules! s7_trace {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)+);
+        #[cfg(not(feature = "log"))]
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    };
+}
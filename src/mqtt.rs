@@ -0,0 +1,197 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### PLC to MQTT bridge (feature `mqtt`)
+//!
+//! Publishes the changed values of a tag subscription to an MQTT broker.
+//!
+//! Each tag is published on its own topic, built from a template where `{tag}` is
+//! replaced with the tag name (a single topic level: no `/`, `+` or `#`), with a JSON payload:
+//!
+//! ```text
+//! {"value": 21.5, "type": "REAL", "timestamp": 1723712345123}
+//! ```
+//!
+//! The updates of the tags with edge detection also carry `"edge": "rising"` or `"edge": "falling"`,
+//! the stale values (see `Subscription::check_stale()`) `"stale": true`.
+//!
+//! The publisher also maintains a status topic: `online` is published (retained) on creation,
+//! and the broker publishes `offline` (Last Will) if the bridge disappears without saying goodbye.
+
+use std::thread::JoinHandle;
+use std::time::{Duration, UNIX_EPOCH};
+use rumqttc::{Client, LastWill, MqttOptions, QoS};
+use crate::client::{S7Client, S7Error};
+use crate::tag::{Edge, Subscription, TagUpdate};
+
+const STATUS_ONLINE: &str = "online";
+const STATUS_OFFLINE: &str = "offline";
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Returns true if the text can't be used inside a topic to publish to (wildcards, null char)
+fn invalid_topic_text(text: &str) -> bool {
+    text.contains(['+', '#', '\0'])
+}
+
+/// ### MQTT publisher of tag updates
+///
+pub struct MqttPublisher {
+    client: Client,
+    topic_template: String,
+    status_topic: String,
+    qos: QoS,
+    retain: bool,
+    event_loop: Option<JoinHandle<()>>,
+}
+
+impl MqttPublisher {
+    /// ### Creates the publisher and connects to the broker
+    ///
+    /// The MQTT event loop runs on a background thread, the connection is (re)established
+    /// automatically by rumqttc.
+    ///
+    /// ### Parameters
+    /// - `options`: Broker options (host, port, client id, credentials...). The Last Will is overwritten.
+    /// - `topic_template`: Topic of each tag, `{tag}` is replaced with the tag name (e.g. "plant/line1/{tag}").
+    /// - `status_topic`: Topic for the bridge status (`online` / `offline`).
+    ///
+    /// ### Returns
+    /// `Ok(<MqttPublisher>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The template doesn't contain `{tag}`, or a topic contains
+    ///   the wildcards `+` or `#`.
+    /// - `S7Error::Other`: The MQTT request queue rejected the status message.
+    ///
+    pub fn new(mut options: MqttOptions, topic_template: &str, status_topic: &str) -> Result<Self, S7Error> {
+        if !topic_template.contains("{tag}") || invalid_topic_text(topic_template) || invalid_topic_text(status_topic) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        options.set_last_will(LastWill::new(status_topic, STATUS_OFFLINE, QoS::AtLeastOnce, true));
+        let (client, mut connection) = Client::new(options, 64);
+
+        let event_loop = std::thread::spawn(move || {
+            // Connection errors are retried by rumqttc on the next iteration,
+            // the loop ends when the client is dropped.
+            for notification in connection.iter() {
+                match notification {
+                    Err(rumqttc::ConnectionError::RequestsDone) => break,
+                    Err(_) => std::thread::sleep(RECONNECT_DELAY),
+                    Ok(_) => {}
+                }
+            }
+        });
+
+        let publisher = MqttPublisher {
+            client,
+            topic_template: topic_template.to_string(),
+            status_topic: status_topic.to_string(),
+            qos: QoS::AtLeastOnce,
+            retain: false,
+            event_loop: Some(event_loop),
+        };
+
+        publisher.client
+            .try_publish(&publisher.status_topic, QoS::AtLeastOnce, true, STATUS_ONLINE)
+            .map_err(|e| S7Error::Other(e.to_string()))?;
+
+        Ok(publisher)
+    }
+
+    /// ### Sets QoS and retain flag of the tag messages
+    ///
+    /// Defaults are `QoS::AtLeastOnce` and no retain.
+    ///
+    pub fn set_qos(&mut self, qos: QoS, retain: bool) {
+        self.qos = qos;
+        self.retain = retain;
+    }
+
+    /// ### Publishes a list of tag updates
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: A tag name is empty or contains `/`, `+` or `#` (it would
+    ///   change the topic levels or be a wildcard); nothing is published.
+    /// - `S7Error::Other`: The MQTT request queue is full (64 messages, e.g. while the broker is
+    ///   unreachable): the message and the rest of `updates` are dropped instead of waiting.
+    ///
+    pub fn publish(&self, updates: &[TagUpdate]) -> Result<(), S7Error> {
+        if updates.iter().any(|update| update.name.is_empty() || update.name.contains('/') || invalid_topic_text(&update.name)) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        for update in updates {
+            let topic = self.topic_template.replace("{tag}", &update.name);
+            let timestamp = update.timestamp
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+
+            let mut payload = serde_json::json!({
+                "value": update.value.to_json(),
+                "type": update.data_type.to_string(),
+                "timestamp": timestamp,
+            });
+            match update.edge {
+                Some(Edge::Rising) => payload["edge"] = "rising".into(),
+                Some(Edge::Falling) => payload["edge"] = "falling".into(),
+                None => {}
+            }
+            if update.stale {
+                payload["stale"] = true.into();
+            }
+            if let Some(trend) = update.trend {
+                payload["trend"] = serde_json::json!({
+                    "rate": trend.rate,
+                    "min": trend.min,
+                    "max": trend.max,
+                    "avg": trend.avg,
+                });
+            }
+
+            self.client
+                .try_publish(topic, self.qos, self.retain, payload.to_string())
+                .map_err(|e| S7Error::Other(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// ### Polls the subscription once and publishes the changed values
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)` number of published values, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `Subscription::poll()` or `publish()`
+    ///
+    /// ### Notes
+    /// The tags that became stale (`Subscription::check_stale()`) are published also if the poll failed.
+    ///
+    pub fn poll_and_publish(&self, subscription: &mut Subscription, client: &mut S7Client) -> Result<usize, S7Error> {
+        let polled = subscription.poll(client);
+        let stale = subscription.check_stale();
+        self.publish(&stale)?;
+        let updates = polled?;
+        self.publish(&updates)?;
+        Ok(updates.len() + stale.len())
+    }
+
+    /// ### Publishes `offline` on the status topic and disconnects from the broker
+    ///
+    /// Called automatically on drop, it never blocks: if the request queue is full, `offline` is
+    /// left to the Last Will. The event loop thread is not joined (it could be waiting for an
+    /// unreachable broker), it ends by itself once the requests are flushed.
+    ///
+    pub fn close(&mut self) {
+        if self.event_loop.take().is_some() {
+            let _ = self.client.try_publish(&self.status_topic, QoS::AtLeastOnce, true, STATUS_OFFLINE);
+            let _ = self.client.try_disconnect();
+        }
+    }
+}
+
+impl Drop for MqttPublisher {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
@@ -0,0 +1,378 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Multi-variable Read/Write
+//!
+//! `read_multi_vars()` and `write_multi_vars()` transfer several variables, of different areas and
+//! DBs, in a single telegram. The variables are described by `S7DataItem`, which has the shape of
+//! the Snap7 `TS7DataItem`, so the code using `Cli_ReadMultiVars` / `Cli_WriteMultiVars` can be
+//! ported almost line by line.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::{S7Client, S7DataItem, S7_AREA_DB, S7_AREA_MK, S7_WL_BIT, S7_WL_BYTE};
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! let mut items = [
+//!     S7DataItem::new(S7_AREA_DB, S7_WL_BYTE, 100, 0, 16), // DB100.DBB0..15
+//!     S7DataItem::new(S7_AREA_MK, S7_WL_BYTE, 0, 10, 2),   // MB10..11
+//!     S7DataItem::new(S7_AREA_DB, S7_WL_BIT, 5, 8 * 4 + 2, 1), // DB5.DBX4.2
+//! ];
+//! client.read_multi_vars(&mut items).unwrap();
+//! for item in &items {
+//!     match &item.result {
+//!         Ok(()) => println!("{:?}", item.data),
+//!         Err(e) => println!("{}", e),
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+use std::time::Instant;
+use crate::client::{check_ack_error, item_result, S7Client, S7Error, S7_AREA_DB, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE, S7_WL_BIT, S7_WL_BYTE};
+use crate::audit::PendingAudit;
+use crate::observer::WriteRequest;
+use crate::frame::{
+    CotpData, DataItemHeader, S7Header, S7ItemSpec, TpktHeader,
+    DATA_ITEM_HEADER_LEN, FN_READ_VAR, FN_WRITE_VAR, ITEM_SPEC_LEN, S7_ACK_HEADER_LEN, S7_JOB_HEADER_LEN, TPKT_ISO_LEN,
+};
+
+/// Max number of variables in a telegram (as Snap7)
+pub const MAX_VARS: usize = 20;
+
+const PDU_REF: u16 = 0x0500;
+/// Size of the addressable area (the start of an item spec is a 24-bit bit address)
+const MAX_AREA_BYTES: usize = 1 << 21;
+
+// Transport sizes (data items)
+const TS_RES_BIT: u8 = 0x03;
+const TS_RES_BYTE: u8 = 0x04;
+const TS_RES_INT: u8 = 0x05;
+const TS_RES_REAL: u8 = 0x07;
+const TS_RES_OCTET: u8 = 0x09;
+
+/// ### Variable of a multi-variable Read/Write
+///
+/// Same fields as the Snap7 `TS7DataItem`: `data` is the buffer (`pdata`), `result` the outcome of
+/// the variable (`Result`).
+///
+#[derive(Debug)]
+pub struct S7DataItem {
+    /// S7 memory area (`S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_MK`, `S7_AREA_DB`)
+    pub area: u8,
+    /// Word length (`S7_WL_BIT`, `S7_WL_BYTE`)
+    pub wordlen: u8,
+    /// DB number (ignored for non-DB areas)
+    pub db: u16,
+    /// Starting element index (byte index for bytes, bit index for bits)
+    pub start: u32,
+    /// Number of elements
+    pub amount: u16,
+    /// Data read, or to write (a byte per element)
+    pub data: Vec<u8>,
+    /// Outcome of the variable, set by `read_multi_vars()` / `write_multi_vars()`
+    pub result: Result<(), S7Error>,
+}
+
+impl S7DataItem {
+    /// ### Creates an item with a zeroed buffer of `amount` byte
+    ///
+    pub fn new(area: u8, wordlen: u8, db: u16, start: u32, amount: u16) -> Self {
+        S7DataItem {
+            area,
+            wordlen,
+            db,
+            start,
+            amount,
+            data: vec![0u8; amount as usize],
+            result: Ok(()),
+        }
+    }
+
+    /// ### Creates an item to write `data` (`amount` = data length)
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: `data` is longer than 65535 byte (the `amount` limit).
+    ///
+    pub fn with_data(area: u8, wordlen: u8, db: u16, start: u32, data: Vec<u8>) -> Result<Self, S7Error> {
+        let amount = u16::try_from(data.len()).map_err(|_| S7Error::InvalidFunParameter)?;
+        Ok(S7DataItem {
+            area,
+            wordlen,
+            db,
+            start,
+            amount,
+            data,
+            result: Ok(()),
+        })
+    }
+
+    fn check(&self) -> Result<(), S7Error> {
+        const AREAS: [u8; 4] = [S7_AREA_PE, S7_AREA_PA, S7_AREA_MK, S7_AREA_DB];
+        if !AREAS.contains(&self.area) || (self.wordlen != S7_WL_BIT && self.wordlen != S7_WL_BYTE) || self.amount == 0 {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let first_byte = if self.wordlen == S7_WL_BIT { self.start / 8 } else { self.start };
+        if first_byte as usize >= MAX_AREA_BYTES {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        Ok(())
+    }
+
+    fn spec(&self) -> S7ItemSpec {
+        S7ItemSpec {
+            wordlen: self.wordlen,
+            amount: self.amount,
+            db_number: self.db,
+            area: self.area,
+            address: if self.wordlen == S7_WL_BIT { self.start } else { self.start << 3 },
+        }
+    }
+}
+
+/// Size of the data item, including the fill byte that word-aligns all but the last one
+fn aligned(size: usize, last: bool) -> usize {
+    size + if last { 0 } else { size % 2 }
+}
+
+/// Returns true if a Read Var of items of the given sizes fits into the PDU (request and response)
+pub(crate) fn read_fits(sizes: &[usize], pdu_length: usize) -> bool {
+    let request_len = S7_JOB_HEADER_LEN + 2 + sizes.len() * ITEM_SPEC_LEN;
+    let response_len = S7_ACK_HEADER_LEN + 2 + sizes.iter().enumerate()
+        .map(|(i, size)| DATA_ITEM_HEADER_LEN + aligned(*size, i == sizes.len() - 1))
+        .sum::<usize>();
+    request_len <= pdu_length && response_len <= pdu_length
+}
+
+/// Builds the Read/Write Var job telegram
+fn job_telegram(function: u8, items: &[S7DataItem], data: &[u8]) -> Vec<u8> {
+    let param_len = 2 + items.len() * ITEM_SPEC_LEN;
+    let total_len = TPKT_ISO_LEN + S7_JOB_HEADER_LEN + param_len + data.len();
+    let mut request = Vec::with_capacity(total_len);
+    TpktHeader { length: total_len as u16 }.encode(&mut request);
+    CotpData { eot: true }.encode(&mut request);
+    S7Header::job(PDU_REF, param_len as u16, data.len() as u16).encode(&mut request);
+    request.push(function);
+    request.push(items.len() as u8);
+    for item in items {
+        item.spec().encode(&mut request);
+    }
+    request.extend_from_slice(data);
+    request
+}
+
+/// Checks the response header and returns the offset of the data part
+fn check_response(pdu: &[u8], function: u8, count: usize) -> Result<usize, S7Error> {
+    let header = S7Header::decode(pdu)?;
+    check_ack_error(&header)?;
+    let offset = header.encoded_len();
+    if pdu.get(offset..offset + 2) != Some(&[function, count as u8][..]) {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    Ok(offset + 2)
+}
+
+impl S7Client {
+    /// ### Reads several variables in a single telegram
+    ///
+    /// ### Parameters
+    /// - `items`: The variables to read, up to `MAX_VARS`. The `data` of each item is replaced
+    ///   with the bytes read, its `result` with the outcome of the variable.
+    ///
+    /// ### Returns
+    /// `Ok(())` if the telegram was exchanged (check the `result` of each item), or `Err(<S7Error>)`.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: No items, more than `MAX_VARS`, an invalid item, or the
+    ///   request/response doesn't fit into the negotiated PDU.
+    /// - `S7Error::S7Unspecified`: The CPU rejected the whole request.
+    /// - `S7Error::CpuStopped`: The CPU rejected the whole request because it's in STOP.
+    /// - Other reported by `read_area()`
+    ///
+    /// ### Notes
+    /// - Unlike `read_area()` the variables are not split into chunks: the response, that is 4
+    ///   byte of header plus the data of each item, must fit into the PDU.
+    /// - A variable answered with a length other than its `amount` gets `S7Error::WrongDataSize`
+    ///   in its `result` (`data` holds the bytes received).
+    ///
+    pub fn read_multi_vars(&mut self, items: &mut [S7DataItem]) -> Result<(), S7Error> {
+        self.last_time = 0.0;
+        self.chunks = 0;
+
+        if items.is_empty() || items.len() > MAX_VARS {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        items.iter().try_for_each(S7DataItem::check)?;
+
+        let sizes: Vec<usize> = items.iter().map(|item| item.amount as usize).collect();
+        if self.connected && !read_fits(&sizes, self.pdu_length as usize) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        let start_time = Instant::now();
+        self.chunks = 1;
+        for area in items.iter().map(|item| item.area).collect::<BTreeSet<u8>>() {
+            self.count_area_request(area);
+        }
+        let (pdu, mut offset) = self.observe("read_multi_vars", items.len(), |client| {
+            let pdu = client.exchange(&job_telegram(FN_READ_VAR, items, &[]))?;
+            let offset = check_response(&pdu, FN_READ_VAR, items.len())?;
+            Ok((pdu, offset))
+        })?;
+
+        let count = items.len();
+        for (i, item) in items.iter_mut().enumerate() {
+            let header = DataItemHeader::decode(pdu.get(offset..).ok_or(S7Error::IsoInvalidTelegram)?)?;
+            offset += DATA_ITEM_HEADER_LEN;
+            item.result = item_result(header.return_code);
+            if item.result.is_err() {
+                continue;
+            }
+            let size = match header.transport_size {
+                TS_RES_BYTE | TS_RES_INT => header.length as usize >> 3,
+                TS_RES_BIT | TS_RES_REAL | TS_RES_OCTET => header.length as usize,
+                _ => header.length as usize,
+            };
+            let payload = pdu.get(offset..offset + size).ok_or(S7Error::IsoInvalidTelegram)?;
+            item.data = payload.to_vec();
+            if size != item.amount as usize {
+                item.result = Err(S7Error::WrongDataSize);
+            }
+            offset += aligned(size, i == count - 1);
+        }
+
+        self.last_time = start_time.elapsed().as_secs_f64() * 1000.0;
+        Ok(())
+    }
+
+    /// ### Writes several variables in a single telegram
+    ///
+    /// ### Parameters
+    /// - `items`: The variables to write, up to `MAX_VARS`. The first `amount` byte of `data` are
+    ///   written, the `result` of each item is replaced with the outcome of the variable.
+    ///
+    /// ### Returns
+    /// `Ok(())` if the telegram was exchanged (check the `result` of each item), or `Err(<S7Error>)`.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: No items, more than `MAX_VARS`, an invalid item, or the
+    ///   request doesn't fit into the negotiated PDU.
+    /// - `S7Error::SizeMismatch`: An item with `data` shorter than `amount` (or longer than 65535
+    ///   byte), or a bit item with `amount` > 1.
+    /// - `S7Error::S7Unspecified`: The CPU rejected the whole request.
+    /// - `S7Error::CpuStopped`: The CPU rejected the whole request because it's in STOP.
+    /// - `S7Error::AccessDenied`: The CPU or a DB doesn't allow writes (see `set_check_write_protection()`).
+    /// - `S7Error::WriteDenied`: The write policy refused a variable (see `set_write_policy()`).
+    /// - Other reported by `write_area()`
+    ///
+    pub fn write_multi_vars(&mut self, items: &mut [S7DataItem]) -> Result<(), S7Error> {
+        let mut pending = self.audit_begin(items.len());
+        let result = self.write_multi_vars_job(items, &mut pending);
+        if let Some(pending) = pending {
+            for (index, item) in items.iter().enumerate() {
+                let data = item.data.get(..item.amount as usize).unwrap_or(&item.data);
+                let write = WriteRequest { operation: "write_multi_vars", area: item.area, db_number: item.db, start: item.start, wordlen: item.wordlen, data, nck: None };
+                let item_result = match &result {
+                    Ok(()) => item.result.as_ref().map(|_| ()),
+                    Err(error) => Err(error),
+                };
+                self.audit(&pending, index, write, item_result);
+            }
+        }
+        result
+    }
+
+    fn write_multi_vars_job(&mut self, items: &mut [S7DataItem], pending: &mut Option<PendingAudit>) -> Result<(), S7Error> {
+        self.last_time = 0.0;
+        self.chunks = 0;
+
+        if items.is_empty() || items.len() > MAX_VARS {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        for item in items.iter() {
+            item.check()?;
+            if item.data.len() < item.amount as usize || item.data.len() > u16::MAX as usize {
+                return Err(S7Error::SizeMismatch { size: item.data.len(), expected: item.amount as usize });
+            }
+            if item.wordlen == S7_WL_BIT && item.amount > 1 {
+                return Err(S7Error::SizeMismatch { size: item.amount as usize, expected: 1 });
+            }
+        }
+
+        let mut data = Vec::new();
+        for (i, item) in items.iter().enumerate() {
+            let size = item.amount as usize;
+            let (transport_size, length) = if item.wordlen == S7_WL_BIT {
+                (TS_RES_BIT, size as u16)
+            } else {
+                (TS_RES_BYTE, (size << 3) as u16)
+            };
+            DataItemHeader { return_code: 0x00, transport_size, length }.encode(&mut data);
+            data.extend_from_slice(&item.data[..size]);
+            data.resize(data.len() + aligned(size, i == items.len() - 1) - size, 0x00);
+        }
+
+        let request_len = S7_JOB_HEADER_LEN + 2 + items.len() * ITEM_SPEC_LEN + data.len();
+        if self.connected && request_len > self.pdu_length as usize {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        for item in items.iter() {
+            let size = item.amount as usize;
+            self.authorize_write(&WriteRequest {
+                operation: "write_multi_vars",
+                area: item.area,
+                db_number: item.db,
+                start: item.start,
+                wordlen: item.wordlen,
+                data: &item.data[..size],
+                nck: None,
+            })?;
+        }
+        let dbs: Vec<u16> = items.iter().filter(|item| item.area == S7_AREA_DB).map(|item| item.db).collect();
+        self.check_write_protection(&dbs)?;
+        self.audit_read_old_values(pending, items);
+        if self.dry_run() {
+            for item in items.iter_mut() {
+                let size = item.amount as usize;
+                self.dry_run_write(&WriteRequest {
+                    operation: "write_multi_vars",
+                    area: item.area,
+                    db_number: item.db,
+                    start: item.start,
+                    wordlen: item.wordlen,
+                    data: &item.data[..size],
+                    nck: None,
+                })?;
+                item.result = Ok(());
+            }
+            return Ok(());
+        }
+
+        let start_time = Instant::now();
+        self.chunks = 1;
+        for area in items.iter().map(|item| item.area).collect::<BTreeSet<u8>>() {
+            self.count_area_request(area);
+        }
+        if let Some(cache) = self.read_cache.as_mut() {
+            for item in items.iter() {
+                cache.invalidate_write(item.area, item.db, item.start, item.wordlen, item.amount as usize);
+            }
+        }
+        let (pdu, offset) = self.observe("write_multi_vars", items.len(), |client| {
+            let pdu = client.exchange(&job_telegram(FN_WRITE_VAR, items, &data))?;
+            let offset = check_response(&pdu, FN_WRITE_VAR, items.len())?;
+            Ok((pdu, offset))
+        })?;
+
+        let return_codes = pdu.get(offset..offset + items.len()).ok_or(S7Error::IsoInvalidTelegram)?;
+        for (item, return_code) in items.iter_mut().zip(return_codes) {
+            item.result = item_result(*return_code);
+        }
+
+        self.last_time = start_time.elapsed().as_secs_f64() * 1000.0;
+        Ok(())
+    }
+}
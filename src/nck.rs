@@ -0,0 +1,327 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Sinumerik NCK variables
+//!
+//! The NCK of a Sinumerik 840D answers the same Read/Write Var jobs of a CPU, over the same ISO
+//! connection; only the addressing of the items differs: instead of area/DB/offset an NCK
+//! variable is addressed by area, unit, module, column and line (syntax ID 0x82), as listed in
+//! the "NC variables" documentation (or shown by the NC-Var selector).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::nck::{NckAddress, NCK_AREA_C, NCK_MODULE_SMA};
+//!
+//! let mut client = S7Client::new();
+//! client.connect_rack_slot("192.168.214.1", 0, 3).unwrap();
+//!
+//! // Column 2 of the machine axes state data of channel 1, axes 1..3
+//! let positions = NckAddress::new(NCK_AREA_C, 1, NCK_MODULE_SMA, 2, 1).with_lines(3);
+//! let data = client.read_nck(&positions).unwrap();
+//! for (axis, value) in data.chunks_exact(8).enumerate() {
+//!     println!("Axis {} = {}", axis + 1, f64::from_le_bytes(value.try_into().unwrap()));
+//! }
+//! ```
+
+use std::fmt;
+use std::time::Instant;
+use crate::client::{check_ack_error, item_result, S7Client, S7Error, S7_WL_BYTE};
+use crate::frame::{CotpData, DataItemHeader, S7Header, TpktHeader, DATA_ITEM_HEADER_LEN, FN_READ_VAR, FN_WRITE_VAR,
+    S7_JOB_HEADER_LEN, TPKT_ISO_LEN};
+use crate::audit::PendingAudit;
+use crate::multivar::MAX_VARS;
+use crate::observer::WriteRequest;
+
+const PDU_REF: u16 = 0x0700;
+/// Item specification: Var spec + Length + Syntax ID + 7 byte of address
+const NCK_ITEM_LEN: usize = 10;
+const SYNTAX_ID_NCK: u8 = 0x82;
+
+// Transport sizes (data items)
+const TS_RES_BYTE: u8 = 0x04;
+const TS_RES_INT: u8 = 0x05;
+const TS_RES_OCTET: u8 = 0x09;
+
+/// NCK area: NCK
+pub const NCK_AREA_N: u8 = 0;
+/// NCK area: Mode group
+pub const NCK_AREA_B: u8 = 1;
+/// NCK area: Channel
+pub const NCK_AREA_C: u8 = 2;
+/// NCK area: Axis
+pub const NCK_AREA_A: u8 = 3;
+/// NCK area: Tool
+pub const NCK_AREA_T: u8 = 4;
+/// NCK area: Feed drive
+pub const NCK_AREA_V: u8 = 5;
+/// NCK area: Main drive
+pub const NCK_AREA_H: u8 = 6;
+/// NCK area: MMC
+pub const NCK_AREA_M: u8 = 7;
+
+/// NCK module: Global system data (Y)
+pub const NCK_MODULE_Y: u8 = 0x10;
+/// NCK module: Tool data (TO)
+pub const NCK_MODULE_TO: u8 = 0x14;
+/// NCK module: Arithmetic (R) parameters (RP)
+pub const NCK_MODULE_RP: u8 = 0x15;
+/// NCK module: Setting data (SE)
+pub const NCK_MODULE_SE: u8 = 0x16;
+/// NCK module: Machine data (M)
+pub const NCK_MODULE_M: u8 = 0x1A;
+/// NCK module: State data, spindle (SSP)
+pub const NCK_MODULE_SSP: u8 = 0x72;
+/// NCK module: State data, geometry axes (SGA)
+pub const NCK_MODULE_SGA: u8 = 0x73;
+/// NCK module: State data, machine axes (SMA)
+pub const NCK_MODULE_SMA: u8 = 0x74;
+/// NCK module: State data (S)
+pub const NCK_MODULE_S: u8 = 0x7F;
+
+/// ### Address of an NCK variable
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NckAddress {
+    /// `NCK_AREA_N`, `NCK_AREA_B`, `NCK_AREA_C`...
+    pub area: u8,
+    /// Unit of the area (e.g. channel number), 0..=31
+    pub unit: u8,
+    /// Module (block), e.g. `NCK_MODULE_SMA`
+    pub module: u8,
+    /// Column (variable number in the module)
+    pub column: u16,
+    /// First line (e.g. axis number), from 1
+    pub line: u16,
+    /// Number of consecutive lines read
+    pub line_count: u8,
+}
+
+impl NckAddress {
+    /// ### Address of one line of a variable
+    ///
+    pub fn new(area: u8, unit: u8, module: u8, column: u16, line: u16) -> Self {
+        NckAddress { area, unit, module, column, line, line_count: 1 }
+    }
+
+    /// ### Reads `line_count` consecutive lines, from `line`
+    ///
+    pub fn with_lines(mut self, line_count: u8) -> Self {
+        self.line_count = line_count;
+        self
+    }
+
+    fn check(&self) -> Result<(), S7Error> {
+        if self.area > NCK_AREA_M || self.unit > 0x1F || self.line_count == 0 {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        Ok(())
+    }
+
+    /// ```text
+    /// [0] Var spec 0x12   [1] Length 8   [2] Syntax ID 0x82   [3] Area (3 bit) + Unit (5 bit)
+    /// [4] Column (2)   [6] Line (2)   [8] Module   [9] Line count
+    /// ```
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&[0x12, 0x08, SYNTAX_ID_NCK, (self.area << 5) | (self.unit & 0x1F)]);
+        buffer.extend_from_slice(&self.column.to_be_bytes());
+        buffer.extend_from_slice(&self.line.to_be_bytes());
+        buffer.extend_from_slice(&[self.module, self.line_count]);
+    }
+}
+
+impl fmt::Display for NckAddress {
+    /// NCK, area letter and unit, module, column and lines (e.g. `NCK C1/0x74/2[1..3]`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let area = ["N", "B", "C", "A", "T", "V", "H", "M"].get(self.area as usize).copied().unwrap_or("?");
+        write!(f, "NCK {}{}/0x{:02X}/{}[{}", area, self.unit, self.module, self.column, self.line)?;
+        if self.line_count > 1 {
+            write!(f, "..{}", self.line as u32 + self.line_count as u32 - 1)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Builds the Read/Write Var job telegram of NCK items
+fn nck_telegram(function: u8, addresses: &[NckAddress], data: &[u8]) -> Vec<u8> {
+    let param_len = 2 + addresses.len() * NCK_ITEM_LEN;
+    let total_len = TPKT_ISO_LEN + S7_JOB_HEADER_LEN + param_len + data.len();
+    let mut request = Vec::with_capacity(total_len);
+    TpktHeader { length: total_len as u16 }.encode(&mut request);
+    CotpData { eot: true }.encode(&mut request);
+    S7Header::job(PDU_REF, param_len as u16, data.len() as u16).encode(&mut request);
+    request.push(function);
+    request.push(addresses.len() as u8);
+    for address in addresses {
+        address.encode(&mut request);
+    }
+    request.extend_from_slice(data);
+    request
+}
+
+/// Checks the response header and returns the offset of the data part
+fn check_response(pdu: &[u8], function: u8, count: usize) -> Result<usize, S7Error> {
+    let header = S7Header::decode(pdu)?;
+    check_ack_error(&header)?;
+    let offset = header.encoded_len();
+    if pdu.get(offset..offset + 2) != Some(&[function, count as u8][..]) {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    Ok(offset + 2)
+}
+
+impl S7Client {
+    /// ### Reads an NCK variable
+    ///
+    /// ### Parameters
+    /// - `address`: Address of the variable (all its lines are returned one after the other).
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<u8>>)` raw value, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Area or unit out of range, or line count of 0.
+    /// - `S7Error::S7InvalidAddress`, `S7Error::S7NotFound`: The NCK refused the address.
+    /// - Other reported by `read_area()`
+    ///
+    /// ### Notes
+    /// The NCK sends the numeric values in little-endian order (e.g. `f64::from_le_bytes()` for
+    /// a `double` variable), unlike the S7 CPUs.
+    ///
+    pub fn read_nck(&mut self, address: &NckAddress) -> Result<Vec<u8>, S7Error> {
+        self.read_nck_vars(std::slice::from_ref(address))?.remove(0)
+    }
+
+    /// ### Reads an NCK variable of type `double`
+    ///
+    /// ### Errors
+    /// - `S7Error::WrongDataSize`: The variable is not 8 byte long.
+    /// - Reported by `read_nck()`
+    ///
+    pub fn read_nck_f64(&mut self, address: &NckAddress) -> Result<f64, S7Error> {
+        let data = self.read_nck(&address.with_lines(1))?;
+        let bytes: [u8; 8] = data.as_slice().try_into().map_err(|_| S7Error::WrongDataSize)?;
+        Ok(f64::from_le_bytes(bytes))
+    }
+
+    /// ### Reads several NCK variables in a single telegram
+    ///
+    /// ### Parameters
+    /// - `addresses`: The variables, up to `MAX_VARS`.
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<Result<Vec<u8>, S7Error>>>)` value or error of each variable if the telegram was
+    /// exchanged, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: No variables, more than `MAX_VARS` or an invalid address.
+    /// - `S7Error::S7Unspecified`: The NCK rejected the whole request (e.g. the response doesn't
+    ///   fit into the PDU: read fewer variables or lines).
+    /// - Other reported by `read_area()`
+    ///
+    pub fn read_nck_vars(&mut self, addresses: &[NckAddress]) -> Result<Vec<Result<Vec<u8>, S7Error>>, S7Error> {
+        self.last_time = 0.0;
+        if addresses.is_empty() || addresses.len() > MAX_VARS {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        addresses.iter().try_for_each(NckAddress::check)?;
+
+        let start_time = Instant::now();
+        let (pdu, mut offset) = self.observe("read_nck", addresses.len(), |client| {
+            let pdu = client.exchange(&nck_telegram(FN_READ_VAR, addresses, &[]))?;
+            let offset = check_response(&pdu, FN_READ_VAR, addresses.len())?;
+            Ok((pdu, offset))
+        })?;
+
+        let mut values = Vec::with_capacity(addresses.len());
+        for i in 0..addresses.len() {
+            let header = DataItemHeader::decode(pdu.get(offset..).ok_or(S7Error::IsoInvalidTelegram)?)?;
+            offset += DATA_ITEM_HEADER_LEN;
+            if let Err(error) = item_result(header.return_code) {
+                values.push(Err(error));
+                continue;
+            }
+            let size = match header.transport_size {
+                TS_RES_BYTE | TS_RES_INT => header.length as usize >> 3,
+                _ => header.length as usize,
+            };
+            let payload = pdu.get(offset..offset + size).ok_or(S7Error::IsoInvalidTelegram)?;
+            values.push(Ok(payload.to_vec()));
+            offset += size + if i == addresses.len() - 1 { 0 } else { size % 2 };
+        }
+
+        self.last_time = start_time.elapsed().as_secs_f64() * 1000.0;
+        Ok(values)
+    }
+
+    /// ### Writes an NCK variable
+    ///
+    /// ### Parameters
+    /// - `address`: Address of the variable.
+    /// - `data`: Raw value of all the lines (little-endian numbers, see `read_nck()`).
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Invalid address, empty data or request larger than the PDU.
+    /// - `S7Error::WriteDenied`: The write policy refused the write (see `set_write_policy()`).
+    /// - `S7Error::AccessDenied`: The variable is read-only or protected.
+    /// - Other reported by `write_area()`
+    ///
+    /// ### Notes
+    /// The write policy receives a `WriteRequest` with the operation `write_nck` and the address
+    /// in `nck` (see `WriteAllowList::with_nck_area()`); the same request is reported to the audit
+    /// hook (see `set_audit_hook()`) and, in dry-run mode, to the observer (see `set_dry_run()`).
+    ///
+    /// ### Example
+    /// ```rust
+    /// use rust7::{S7Client, S7Error};
+    /// use rust7::authorize::WriteAllowList;
+    /// use rust7::nck::{NckAddress, NCK_AREA_C, NCK_MODULE_RP};
+    ///
+    /// let mut client = S7Client::new();
+    /// client.set_write_policy(Some(WriteAllowList::new().with_db(10).into_policy()));
+    /// // R parameter 1 of channel 1: the NCK is not in the allow-list, nothing is sent
+    /// let error = client.write_nck(&NckAddress::new(NCK_AREA_C, 1, NCK_MODULE_RP, 1, 2), &1.5f64.to_le_bytes()).unwrap_err();
+    /// assert!(matches!(error, S7Error::WriteDenied { .. }));
+    /// ```
+    ///
+    pub fn write_nck(&mut self, address: &NckAddress, data: &[u8]) -> Result<(), S7Error> {
+        let write = WriteRequest { operation: "write_nck", area: 0, db_number: 0, start: 0, wordlen: S7_WL_BYTE, data, nck: Some(*address) };
+        let mut pending = self.audit_begin(1);
+        let result = self.write_nck_job(address, &write, &mut pending);
+        if let Some(pending) = pending {
+            self.audit(&pending, 0, write, result.as_ref().copied());
+        }
+        result
+    }
+
+    /// Checks and sends a `write_nck()`, reading the old value to audit
+    fn write_nck_job(&mut self, address: &NckAddress, write: &WriteRequest, pending: &mut Option<PendingAudit>) -> Result<(), S7Error> {
+        self.last_time = 0.0;
+        let data = write.data;
+        address.check()?;
+        let request_len = S7_JOB_HEADER_LEN + 2 + NCK_ITEM_LEN + DATA_ITEM_HEADER_LEN + data.len();
+        if data.is_empty() || (self.connected && request_len > self.pdu_length as usize) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        self.authorize_write(write)?;
+        self.audit_read_old_nck(pending, address);
+        if self.dry_run() {
+            return self.dry_run_write(write);
+        }
+
+        let mut item = Vec::with_capacity(DATA_ITEM_HEADER_LEN + data.len());
+        DataItemHeader { return_code: 0x00, transport_size: TS_RES_OCTET, length: data.len() as u16 }.encode(&mut item);
+        item.extend_from_slice(data);
+
+        let start_time = Instant::now();
+        let pdu = self.observe("write_nck", data.len(), |client| {
+            let pdu = client.exchange(&nck_telegram(FN_WRITE_VAR, std::slice::from_ref(address), &item))?;
+            let offset = check_response(&pdu, FN_WRITE_VAR, 1)?;
+            Ok(pdu[offset..].to_vec())
+        })?;
+        item_result(*pdu.first().ok_or(S7Error::IsoInvalidTelegram)?)?;
+
+        self.last_time = start_time.elapsed().as_secs_f64() * 1000.0;
+        Ok(())
+    }
+}
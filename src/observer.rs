@@ -0,0 +1,160 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Telemetry hooks
+//!
+//! An `S7Observer` is notified of the requests of a client, of their outcome and of the
+//! reconnections, and of the poll cycles of the subscriptions. It's implemented once (counters,
+//! histograms, traces...) and attached to any number of clients and subscriptions, without
+//! depending on a specific logging or metrics ecosystem.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//! use std::time::Duration;
+//! use rust7::{S7Client, S7Error};
+//! use rust7::observer::{S7Observer, S7Request};
+//! use rust7::tag::Subscription;
+//!
+//! #[derive(Default)]
+//! struct Metrics {
+//!     requests: AtomicU64,
+//!     errors: AtomicU64,
+//! }
+//!
+//! impl S7Observer for Metrics {
+//!     fn on_request(&self, _request: &S7Request) {
+//!         self.requests.fetch_add(1, Ordering::Relaxed);
+//!     }
+//!     fn on_error(&self, request: &S7Request, error: &S7Error) {
+//!         self.errors.fetch_add(1, Ordering::Relaxed);
+//!         eprintln!("{} failed: {}", request.operation, error);
+//!     }
+//! }
+//!
+//! let metrics = Arc::new(Metrics::default());
+//! let mut client = S7Client::new();
+//! client.set_observer(Some(metrics.clone()));
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! let mut subscription = Subscription::new(Duration::from_millis(500));
+//! subscription.set_observer(Some(metrics.clone()));
+//! ```
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::client::{step7_address, S7Client, S7Error};
+use crate::nck::NckAddress;
+
+/// ### Request notified to an observer
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7Request {
+    /// `read_area`, `write_area`, `read_bits`, `read_multi_vars`, `write_multi_vars`, `read_nck`,
+    /// `write_nck`, `userdata` (SZL, clock, blocks...) or `poll` (a subscription cycle)
+    pub operation: &'static str,
+    /// Bytes read or written, bits for `read_bits`, variables for the multi-variable operations
+    /// and `read_nck`, bytes of the request data for `userdata`, tags for `poll`
+    pub size: usize,
+}
+
+/// ### Write of a variable, or of a block of data
+///
+/// Notified to `S7Observer::on_dry_run()` when the client is in dry-run mode (see `S7Client::set_dry_run()`).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteRequest<'a> {
+    /// `write_area` (also for the methods based on it), `write_multi_vars`, `force` or `write_nck`
+    pub operation: &'static str,
+    pub area: u8,
+    /// DB number (ignored for non-DB areas)
+    pub db_number: u16,
+    /// First element (byte index, bit index for bit access)
+    pub start: u32,
+    pub wordlen: u8,
+    /// Data to write (a byte per element, empty for the `force` of `ForceJob::UnforceAll`)
+    pub data: &'a [u8],
+    /// Address of the NCK variable for `write_nck` (`area`, `db_number` and `start` are 0)
+    pub nck: Option<NckAddress>,
+}
+
+impl WriteRequest<'_> {
+    /// ### Returns the address in the STEP 7 notation (e.g. `DB10.DBB4`, `M2.3`), or of the NCK variable
+    ///
+    /// `ForceJob::UnforceAll` is reported as `all the forces`.
+    ///
+    pub fn address(&self) -> String {
+        match &self.nck {
+            Some(nck) => nck.to_string(),
+            None if self.operation == "force" && self.data.is_empty() => "all the forces".to_string(),
+            None => step7_address(self.area, self.db_number, self.start, self.wordlen),
+        }
+    }
+}
+
+/// ### Receiver of the telemetry events
+///
+/// All the methods have an empty default implementation: implement only the needed ones.
+/// They are called on the thread running the operation, synchronously: keep them short.
+///
+pub trait S7Observer: Send + Sync {
+    /// A request is going to be sent
+    fn on_request(&self, _request: &S7Request) {}
+
+    /// The request succeeded in `time`
+    fn on_response(&self, _request: &S7Request, _time: Duration) {}
+
+    /// The request failed
+    fn on_error(&self, _request: &S7Request, _error: &S7Error) {}
+
+    /// A write was validated but not sent, the client is in dry-run mode (see `S7Client::set_dry_run()`)
+    fn on_dry_run(&self, _write: &WriteRequest) {}
+
+    /// `reconnect()` (also called by the automatic retries) reconnected, or failed to reconnect,
+    /// to the PLC at `address`
+    fn on_reconnect(&self, _address: &str, _result: Result<(), &S7Error>) {}
+}
+
+/// Runs `job` notifying the request and its outcome to `observer`
+pub(crate) fn observe<T, C, F>(observer: Option<Arc<dyn S7Observer>>, request: S7Request, context: &mut C, job: F) -> Result<T, S7Error>
+where
+    F: FnOnce(&mut C) -> Result<T, S7Error>,
+{
+    let Some(observer) = observer else {
+        return job(context);
+    };
+    observer.on_request(&request);
+    let start = Instant::now();
+    let result = job(context);
+    match &result {
+        Ok(_) => observer.on_response(&request, start.elapsed()),
+        Err(error) => observer.on_error(&request, error),
+    }
+    result
+}
+
+impl S7Client {
+    /// ### Attaches a telemetry observer
+    ///
+    /// ### Parameters
+    /// - `observer`: The observer, `None` removes it (Default). The same observer can be attached
+    ///   to several clients and subscriptions.
+    ///
+    /// ### Notes
+    /// - Each request sent is notified, so a retried operation (see `set_retry_policy()`) is
+    ///   notified at each attempt; the reads served by the read cache are not notified.
+    /// - An `S7Worker` notifies the observer of the client it owns.
+    ///
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn S7Observer>>) {
+        self.observer = observer;
+    }
+
+    /// Runs `job` notifying the request to the observer, if any
+    pub(crate) fn observe<T, F>(&mut self, operation: &'static str, size: usize, job: F) -> Result<T, S7Error>
+    where
+        F: FnOnce(&mut S7Client) -> Result<T, S7Error>,
+    {
+        observe(self.observer.clone(), S7Request { operation, size }, self, job)
+    }
+}
@@ -0,0 +1,926 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Passive partner (incoming S7 connections)
+//!
+//! `S7Partner` listens for ISO-on-TCP connections opened by a CPU and serves its requests.
+//! This is how PLC-initiated communication works: the CPU is configured with a connection to our
+//! station and uses PUT/GET to write/read the areas that we register here.
+//!
+//! ```text
+//!   CPU (active)  --- PUT (Write Var) --->  S7Partner  (registered areas)
+//!                 <--- GET (Read Var) ---
+//! ```
+//!
+//! The partner runs in background threads: one for the listener and one for each connection.
+//! The application exchanges data through `read_area()` / `write_area()` and, optionally, is
+//! notified of every access through an event channel.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7_AREA_DB;
+//! use rust7::partner::{S7Partner, PartnerEvent};
+//!
+//! let mut partner = S7Partner::new();
+//! partner.register_area(S7_AREA_DB, 10, vec![0u8; 256]);
+//! let events = partner.subscribe_events();
+//! partner.start("0.0.0.0:102").unwrap();
+//!
+//! for event in events {
+//!     if let PartnerEvent::Write { db_number, start, size, .. } = event {
+//!         let mut data = vec![0u8; size];
+//!         partner.read_area(S7_AREA_DB, db_number, start, &mut data).unwrap();
+//!         println!("DB{} written: {:?}", db_number, data);
+//!     }
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use crate::client::{S7Error, S7_AREA_DB, S7_WL_BIT};
+use crate::frame::{
+    CotpData, CotpConnection, CotpDisconnect, S7Header, S7SetupComm, S7ItemSpec, DataItemHeader,
+    S7UserDataParams, split_userdata, TPKT_LEN, TPKT_ISO_LEN, COTP_CR, COTP_CC, COTP_DR, COTP_DT, ROSCTR_JOB, ROSCTR_ACK,
+    ROSCTR_ACK_DATA, ROSCTR_USERDATA, FN_SETUP_COMM, FN_READ_VAR, FN_WRITE_VAR, ITEM_SPEC_LEN,
+    DATA_ITEM_HEADER_LEN, S7_ACK_HEADER_LEN, UD_METHOD_RESPONSE, UD_TYPE_RESPONSE, UD_GROUP_BSEND, UD_SUBFN_BSEND,
+    UD_GROUP_SZL, UD_SUBFN_READ_SZL, UD_GROUP_BLOCK, UD_SUBFN_LIST_BLOCKS_OF_TYPE, UD_SUBFN_BLOCK_INFO,
+    UD_GROUP_PROG, UD_SUBFN_FORCE,
+};
+use crate::iso_tcp;
+use crate::szl::{PlcStatus, SZL_ID_CPU_STATUS};
+use crate::bsend::{BlockFragment, BlockReceiver, fragment_ack, FRAGMENT_ACK_DATA};
+use crate::blocks::{BLOCK_DB, BLOCK_FLAG_WRITE_PROTECTED};
+use crate::force::{decode_force_job, ForceJob};
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const PDU_LEN_MAX: u16 = 960;
+
+// Return codes
+const RES_SUCCESS: u8 = 0xFF;
+const RES_ACCESS_DENIED: u8 = 0x03;
+const RES_INVALID_ADDRESS: u8 = 0x05;
+const RES_DATA_TYPE_NOT_SUPPORTED: u8 = 0x06;
+const RES_NOT_FOUND: u8 = 0x0A;
+
+// Transport sizes (data items)
+const TS_RES_BIT: u8 = 0x03;
+const TS_RES_BYTE: u8 = 0x04;
+const TS_RES_INT: u8 = 0x05;
+const TS_RES_REAL: u8 = 0x07;
+const TS_RES_OCTET: u8 = 0x09;
+
+// Error class/code of the Ack sent for unsupported functions
+const ERR_FUNCTION_NOT_SUPPORTED: (u8, u8) = (0x81, 0x04);
+// Error class/code of the Ack sent for a Read Var whose response exceeds the PDU
+const ERR_PDU_SIZE: (u8, u8) = (0x85, 0x00);
+// SZL 0x0424 record
+const SZL_CPU_STATUS_RECORD_LEN: usize = 20;
+const SZL_EVENT_MODE_TRANSITION: u16 = 0x4302;
+// Userdata error: function not available
+const UD_ERR_NOT_AVAILABLE: u16 = 0xD401;
+// Userdata error: block not found
+const UD_ERR_BLOCK_NOT_FOUND: u16 = 0xD209;
+// Block info record (DBs of the simulator)
+const BLOCK_INFO_LEN: usize = 70;
+const BLOCK_LANG_DB: u8 = 0x05;
+// Sequence number of the multi-part block lists
+const BLOCK_LIST_SEQUENCE: u8 = 0x01;
+
+/// Registered areas, keyed by (area, DB number). The DB number is 0 for non-DB areas.
+type AreaMap = Arc<Mutex<HashMap<(u8, u16), Vec<u8>>>>;
+
+/// ### Activity notification of the partner
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartnerEvent {
+    /// A CPU connected (after the COTP handshake)
+    Connected(SocketAddr),
+    /// The connection was closed
+    Disconnected(SocketAddr),
+    /// The CPU read (GET) an area
+    Read {
+        peer: SocketAddr,
+        area: u8,
+        db_number: u16,
+        /// Starting byte
+        start: usize,
+        /// Size in bytes
+        size: usize,
+        /// S7 return code sent back (0xFF = success)
+        return_code: u8,
+    },
+    /// A block sent by the BSEND of the CPU was received
+    BlockReceived {
+        peer: SocketAddr,
+        /// R_ID of the BSEND instance
+        r_id: u32,
+        data: Vec<u8>,
+    },
+    /// The CPU wrote (PUT) an area
+    Write {
+        peer: SocketAddr,
+        area: u8,
+        db_number: u16,
+        /// Starting byte
+        start: usize,
+        /// Size in bytes
+        size: usize,
+        /// S7 return code sent back (0xFF = success)
+        return_code: u8,
+    },
+}
+
+/// ### Areas served by the partner
+///
+/// Cheap to clone: all the clones share the same memory.
+///
+#[derive(Clone, Default)]
+pub struct PartnerAreas(AreaMap);
+
+impl PartnerAreas {
+    /// ### Registers (or replaces) an area
+    ///
+    /// ### Parameters
+    /// - `area`: S7 memory area constant (e.g., `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_DB`, `S7_AREA_MK`).
+    /// - `db_number`: DB number (ignored for non-DB areas).
+    /// - `data`: Initial content, its length is the size of the area.
+    ///
+    /// ### Notes
+    /// Areas can be registered also while the partner is running.
+    ///
+    pub fn register_area(&self, area: u8, db_number: u16, data: Vec<u8>) {
+        self.0.lock().unwrap().insert(area_key(area, db_number), data);
+    }
+
+    /// ### Removes an area
+    ///
+    pub fn unregister_area(&self, area: u8, db_number: u16) {
+        self.0.lock().unwrap().remove(&area_key(area, db_number));
+    }
+
+    /// ### Reads from a registered area
+    ///
+    /// ### Errors
+    /// - `S7Error::S7NotFound`: The area is not registered.
+    /// - `S7Error::S7InvalidAddress`: The range exceeds the area size.
+    ///
+    pub fn read_area(&self, area: u8, db_number: u16, start: usize, buffer: &mut [u8]) -> Result<(), S7Error> {
+        let areas = self.0.lock().unwrap();
+        let data = areas.get(&area_key(area, db_number)).ok_or(S7Error::S7NotFound)?;
+        let source = data.get(start..start + buffer.len()).ok_or(S7Error::S7InvalidAddress)?;
+        buffer.copy_from_slice(source);
+        Ok(())
+    }
+
+    /// ### Writes into a registered area
+    ///
+    /// ### Errors
+    /// - `S7Error::S7NotFound`: The area is not registered.
+    /// - `S7Error::S7InvalidAddress`: The range exceeds the area size.
+    ///
+    pub fn write_area(&self, area: u8, db_number: u16, start: usize, buffer: &[u8]) -> Result<(), S7Error> {
+        let mut areas = self.0.lock().unwrap();
+        let data = areas.get_mut(&area_key(area, db_number)).ok_or(S7Error::S7NotFound)?;
+        let target = data.get_mut(start..start + buffer.len()).ok_or(S7Error::S7InvalidAddress)?;
+        target.copy_from_slice(buffer);
+        Ok(())
+    }
+}
+
+/// ### Passive S7 partner
+///
+pub struct S7Partner {
+    areas: PartnerAreas,
+    cpu_status: Arc<Mutex<Option<PlcStatus>>>,
+    write_protected: Arc<Mutex<HashSet<u16>>>,
+    pdu_length: u16,
+    events: Option<Sender<PartnerEvent>>,
+    stop: Arc<AtomicBool>,
+    /// Streams of the open sessions, by session id, to close them on stop
+    connections: Arc<Mutex<HashMap<u64, TcpStream>>>,
+    listener: Option<JoinHandle<()>>,
+    local_address: Option<SocketAddr>,
+}
+
+impl S7Partner {
+    /// ### Creates a new partner without areas
+    ///
+    pub fn new() -> Self {
+        S7Partner {
+            areas: PartnerAreas::default(),
+            cpu_status: Arc::new(Mutex::new(None)),
+            write_protected: Arc::new(Mutex::new(HashSet::new())),
+            pdu_length: 480,
+            events: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            connections: Arc::new(Mutex::new(HashMap::new())),
+            listener: None,
+            local_address: None,
+        }
+    }
+
+    /// ### Registers (or replaces) an area
+    ///
+    /// For further info, please refer to `PartnerAreas::register_area()`
+    ///
+    pub fn register_area(&self, area: u8, db_number: u16, data: Vec<u8>) {
+        self.areas.register_area(area, db_number, data);
+    }
+
+    /// ### Removes an area
+    ///
+    pub fn unregister_area(&self, area: u8, db_number: u16) {
+        self.areas.unregister_area(area, db_number);
+    }
+
+    /// ### Reads from a registered area
+    ///
+    /// For further info, please refer to `PartnerAreas::read_area()`
+    ///
+    pub fn read_area(&self, area: u8, db_number: u16, start: usize, buffer: &mut [u8]) -> Result<(), S7Error> {
+        self.areas.read_area(area, db_number, start, buffer)
+    }
+
+    /// ### Writes into a registered area
+    ///
+    /// For further info, please refer to `PartnerAreas::write_area()`
+    ///
+    pub fn write_area(&self, area: u8, db_number: u16, start: usize, buffer: &[u8]) -> Result<(), S7Error> {
+        self.areas.write_area(area, db_number, start, buffer)
+    }
+
+    /// ### Returns a handle to the registered areas
+    ///
+    /// The handle can be moved into other threads to exchange data while the partner is running.
+    ///
+    pub fn areas(&self) -> PartnerAreas {
+        self.areas.clone()
+    }
+
+    /// ### Emulates the CPU status query
+    ///
+    /// When set, the SZL 0x0424 request (`S7Client::get_plc_status()`) is answered with the given status,
+    /// otherwise it is refused as any other Userdata function. The block functions (list, block
+    /// info) and the force jobs are answered only when it's set, as by an emulated CPU.
+    ///
+    pub fn set_cpu_status(&self, status: Option<PlcStatus>) {
+        *self.cpu_status.lock().unwrap() = status;
+    }
+
+    /// ### Write-protects a registered DB
+    ///
+    /// The writes to a protected DB are refused (access denied) and its block info carries the
+    /// flag `BLOCK_FLAG_WRITE_PROTECTED`.
+    ///
+    /// ### Notes
+    /// The block info is answered only once the CPU status is set (see `set_cpu_status()`): without
+    /// it, the protection check of the client (`S7Client::set_check_write_protection()`) can't
+    /// see the flag, and only the write itself is refused. `S7Simulator` sets the status (RUN).
+    ///
+    pub fn set_db_write_protected(&self, db_number: u16, protected: bool) {
+        let mut write_protected = self.write_protected.lock().unwrap();
+        if protected {
+            write_protected.insert(db_number);
+        } else {
+            write_protected.remove(&db_number);
+        }
+    }
+
+    /// ### Sets the max PDU length accepted in the negotiation
+    ///
+    /// ### Parameters
+    /// - `pdu_length`: 240..=960 (Default = 480)
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Value out of range or partner running.
+    ///
+    pub fn set_pdu_length(&mut self, pdu_length: u16) -> Result<(), S7Error> {
+        if self.listener.is_some() || !(240..=PDU_LEN_MAX).contains(&pdu_length) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        self.pdu_length = pdu_length;
+        Ok(())
+    }
+
+    /// ### Returns a channel receiving the partner events
+    ///
+    /// Must be called before `start()`. Calling it again replaces the previous channel.
+    ///
+    pub fn subscribe_events(&mut self) -> Receiver<PartnerEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.events = Some(sender);
+        receiver
+    }
+
+    /// ### Starts listening
+    ///
+    /// ### Parameters
+    /// - `address`: Local address (e.g. "0.0.0.0:102", port 0 to let the OS choose).
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Already started.
+    /// - `S7Error::Io`: The address can't be bound.
+    ///
+    pub fn start(&mut self, address: &str) -> Result<(), S7Error> {
+        if self.listener.is_some() {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        self.local_address = Some(listener.local_addr()?);
+        self.stop.store(false, Ordering::SeqCst);
+
+        let session = Session {
+            areas: self.areas.clone(),
+            cpu_status: self.cpu_status.clone(),
+            write_protected: self.write_protected.clone(),
+            pdu_length: self.pdu_length,
+            events: self.events.clone(),
+        };
+        let stop = self.stop.clone();
+        let connections = self.connections.clone();
+
+        self.listener = Some(std::thread::spawn(move || {
+            let mut next_id = 0u64;
+            while !stop.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, peer)) => {
+                        if stream.set_nonblocking(false).is_err() {
+                            continue;
+                        }
+                        let id = next_id;
+                        next_id += 1;
+                        if let Ok(clone) = stream.try_clone() {
+                            connections.lock().unwrap().insert(id, clone);
+                        }
+                        let session = session.clone();
+                        let connections = connections.clone();
+                        std::thread::spawn(move || {
+                            session.serve(stream, peer);
+                            connections.lock().unwrap().remove(&id);
+                        });
+                    }
+                    // WouldBlock (no pending connections) or transient accept errors
+                    Err(_) => std::thread::sleep(ACCEPT_POLL),
+                }
+            }
+        }));
+        Ok(())
+    }
+
+    /// ### Returns the local address once started
+    ///
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.local_address
+    }
+
+    /// ### Stops listening and closes all the connections
+    ///
+    /// Called automatically on drop.
+    ///
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+        for (_, stream) in self.connections.lock().unwrap().drain() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        self.local_address = None;
+    }
+}
+
+impl Default for S7Partner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for S7Partner {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn area_key(area: u8, db_number: u16) -> (u8, u16) {
+    if area == S7_AREA_DB { (area, db_number) } else { (area, 0) }
+}
+
+/// Size in bytes of one element of the S7ANY word length
+fn element_size(wordlen: u8) -> Option<usize> {
+    match wordlen {
+        0x02 | 0x03 => Some(1),        // Byte, Char
+        0x04 | 0x05 => Some(2),        // Word, Int
+        0x06..=0x08 => Some(4),        // DWord, DInt, Real
+        _ => None,
+    }
+}
+
+/// State of a single connection
+#[derive(Default)]
+struct Connection {
+    pdu_length: u16,
+    blocks: BlockReceiver,
+    /// Entries of the block list not yet sent (multi-part responses)
+    block_list: Vec<u8>,
+}
+
+/// State shared by the connection threads
+#[derive(Clone)]
+struct Session {
+    areas: PartnerAreas,
+    cpu_status: Arc<Mutex<Option<PlcStatus>>>,
+    write_protected: Arc<Mutex<HashSet<u16>>>,
+    pdu_length: u16,
+    events: Option<Sender<PartnerEvent>>,
+}
+
+impl Session {
+    fn notify(&self, event: PartnerEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
+    }
+
+    fn serve(self, mut stream: TcpStream, peer: SocketAddr) {
+        // On protocol errors the connection is dropped, as a CPU would do
+        let _ = self.handle(&mut stream, peer);
+        let _ = stream.shutdown(Shutdown::Both);
+        self.notify(PartnerEvent::Disconnected(peer));
+    }
+
+    fn handle(&self, stream: &mut TcpStream, peer: SocketAddr) -> Result<(), S7Error> {
+        let mut connection = Connection {
+            pdu_length: self.pdu_length,
+            ..Connection::default()
+        };
+
+        loop {
+            let packet = match iso_tcp::read_packet(stream) {
+                Ok(packet) => packet,
+                Err(S7Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            let body = &packet[TPKT_LEN..];
+
+            match body[1] {
+                COTP_CR => {
+                    let request = CotpConnection::decode(body)?;
+                    let confirm = CotpConnection {
+                        pdu_type: COTP_CC,
+                        dst_ref: request.src_ref,
+                        src_ref: 0x0001,
+                        class: request.class,
+                        tpdu_size: if request.tpdu_size == 0 { 0x0A } else { request.tpdu_size.min(0x0A) },
+                        src_tsap: request.src_tsap,
+                        dst_tsap: request.dst_tsap,
+                        extra_params: Vec::new(),
+                    };
+                    let mut cotp = Vec::new();
+                    confirm.encode(&mut cotp);
+                    iso_tcp::write_packet(stream, &cotp)?;
+                    self.notify(PartnerEvent::Connected(peer));
+                }
+                COTP_DR => {
+                    let _ = CotpDisconnect::decode(body)?;
+                    return Ok(());
+                }
+                COTP_DT => {
+                    let cotp = CotpData::decode(body)?;
+                    if !cotp.eot {
+                        return Err(S7Error::IsoFragmentedPacket);
+                    }
+                    if let Some(response) = self.handle_pdu(&body[TPKT_ISO_LEN - TPKT_LEN..], &mut connection, peer)? {
+                        iso_tcp::send_data(stream, &response)?;
+                    }
+                }
+                _ => return Err(S7Error::IsoInvalidTelegram),
+            }
+        }
+    }
+
+    /// Returns the S7 response PDU (None = no response)
+    fn handle_pdu(&self, pdu: &[u8], connection: &mut Connection, peer: SocketAddr) -> Result<Option<Vec<u8>>, S7Error> {
+        let header = S7Header::decode(pdu)?;
+        let params_offset = header.encoded_len();
+        let data_offset = params_offset + header.param_len as usize;
+        if pdu.len() < data_offset + header.data_len as usize || header.param_len == 0 {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+        let params = &pdu[params_offset..data_offset];
+        let data = &pdu[data_offset..data_offset + header.data_len as usize];
+
+        match header.rosctr {
+            ROSCTR_JOB => match params[0] {
+                FN_SETUP_COMM => {
+                    let request = S7SetupComm::decode(params)?;
+                    connection.pdu_length = request.pdu_length.clamp(240, self.pdu_length);
+                    let mut response = Vec::new();
+                    ack_data_header(header.pdu_ref, S7SetupComm::LEN, 0).encode(&mut response);
+                    S7SetupComm {
+                        max_amq_calling: 1,
+                        max_amq_called: 1,
+                        pdu_length: connection.pdu_length,
+                    }.encode(&mut response);
+                    Ok(Some(response))
+                }
+                FN_READ_VAR => Ok(Some(self.read_var(header.pdu_ref, params, connection.pdu_length, peer)?)),
+                FN_WRITE_VAR => Ok(Some(self.write_var(header.pdu_ref, params, data, peer)?)),
+                _ => Ok(Some(error_ack(header.pdu_ref, ERR_FUNCTION_NOT_SUPPORTED))),
+            },
+            ROSCTR_USERDATA => {
+                let (_, request, data) = split_userdata(pdu)?;
+                if request.group() == UD_GROUP_BSEND && request.subfunction == UD_SUBFN_BSEND {
+                    return self.block_fragment(header.pdu_ref, &request, data, connection, peer).map(Some);
+                }
+                if let Some(response) = self.cpu_status_query(header.pdu_ref, &request, data) {
+                    return Ok(Some(response));
+                }
+                if let Some(response) = self.block_function(header.pdu_ref, &request, data, connection) {
+                    return Ok(Some(response));
+                }
+                if let Some(response) = self.force_job(header.pdu_ref, &request, data) {
+                    return Ok(Some(response));
+                }
+
+                let params = S7UserDataParams {
+                    method: UD_METHOD_RESPONSE,
+                    type_group: UD_TYPE_RESPONSE | request.group(),
+                    subfunction: request.subfunction,
+                    sequence: request.sequence,
+                    data_unit_ref: 0,
+                    last_data_unit: 0,
+                    error_code: UD_ERR_NOT_AVAILABLE,
+                    extended: true,
+                };
+                Ok(Some(userdata_response(header.pdu_ref, &params, &[RES_NOT_FOUND, 0x00, 0x00, 0x00])))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Answers the SZL 0x0424 request if the CPU status is emulated
+    fn cpu_status_query(&self, pdu_ref: u16, request: &S7UserDataParams, data: &[u8]) -> Option<Vec<u8>> {
+        let status = (*self.cpu_status.lock().unwrap())?;
+        if request.group() != UD_GROUP_SZL || request.subfunction != UD_SUBFN_READ_SZL || data.len() < 8 {
+            return None;
+        }
+        let id = u16::from_be_bytes([data[4], data[5]]);
+        if id != SZL_ID_CPU_STATUS {
+            return None;
+        }
+
+        // Record: Event ID, Reserved, Mode transition ID, then unused info (20 byte)
+        let mut record = vec![0u8; SZL_CPU_STATUS_RECORD_LEN];
+        record[0..2].copy_from_slice(&SZL_EVENT_MODE_TRANSITION.to_be_bytes());
+        record[2] = 0xFF;
+        record[3] = status.mode();
+
+        let length = 8 + record.len();
+        let mut szl = vec![RES_SUCCESS, TS_RES_OCTET];
+        szl.extend_from_slice(&(length as u16).to_be_bytes());
+        szl.extend_from_slice(&data[4..8]); // ID + Index
+        szl.extend_from_slice(&(record.len() as u16).to_be_bytes());
+        szl.extend_from_slice(&1u16.to_be_bytes());
+        szl.extend_from_slice(&record);
+
+        let params = S7UserDataParams {
+            method: UD_METHOD_RESPONSE,
+            type_group: UD_TYPE_RESPONSE | UD_GROUP_SZL,
+            subfunction: UD_SUBFN_READ_SZL,
+            sequence: request.sequence,
+            data_unit_ref: 0,
+            last_data_unit: 0,
+            error_code: 0,
+            extended: true,
+        };
+        Some(userdata_response(pdu_ref, &params, &szl))
+    }
+
+    /// Answers the block list and block info requests of the registered DBs, if the CPU is emulated
+    fn block_function(&self, pdu_ref: u16, request: &S7UserDataParams, data: &[u8], connection: &mut Connection) -> Option<Vec<u8>> {
+        self.cpu_status.lock().unwrap().as_ref()?;
+        if request.group() != UD_GROUP_BLOCK {
+            return None;
+        }
+        let mut params = S7UserDataParams {
+            method: UD_METHOD_RESPONSE,
+            type_group: UD_TYPE_RESPONSE | UD_GROUP_BLOCK,
+            subfunction: request.subfunction,
+            sequence: request.sequence,
+            data_unit_ref: 0,
+            last_data_unit: 0,
+            error_code: 0,
+            extended: true,
+        };
+        let entries = match request.subfunction {
+            UD_SUBFN_LIST_BLOCKS_OF_TYPE => {
+                if !request.extended {
+                    // First request: [Return code, TS, Length(2), 0x30, Block type]
+                    let mut numbers: Vec<u16> = match data.get(5) {
+                        Some(&BLOCK_DB) => self.areas.0.lock().unwrap().keys()
+                            .filter(|(area, _)| *area == S7_AREA_DB)
+                            .map(|(_, number)| *number)
+                            .collect(),
+                        _ => Vec::new(),
+                    };
+                    numbers.sort_unstable();
+                    connection.block_list = numbers.iter()
+                        .flat_map(|number| { let n = number.to_be_bytes(); [n[0], n[1], 0x22, BLOCK_LANG_DB] })
+                        .collect();
+                }
+                if connection.block_list.is_empty() && !request.extended {
+                    params.error_code = UD_ERR_BLOCK_NOT_FOUND;
+                    return Some(userdata_response(pdu_ref, &params, &[RES_NOT_FOUND, 0x00, 0x00, 0x00]));
+                }
+                // Userdata header + extended parameters + data header
+                let capacity = (connection.pdu_length as usize).saturating_sub(10 + 12 + 4) / 4 * 4;
+                let part = connection.block_list.len().min(capacity);
+                let entries: Vec<u8> = connection.block_list.drain(..part).collect();
+                if !connection.block_list.is_empty() {
+                    params.sequence = BLOCK_LIST_SEQUENCE;
+                    params.last_data_unit = 0x01;
+                }
+                entries
+            }
+            UD_SUBFN_BLOCK_INFO => {
+                // [Return code, TS, Length(2), 0x30, Block type, Number (5 ASCII digits), 'A']
+                let number = data.get(6..11)
+                    .and_then(|digits| std::str::from_utf8(digits).ok())
+                    .and_then(|digits| digits.parse::<u16>().ok());
+                let size = match (data.get(5), number) {
+                    (Some(&BLOCK_DB), Some(number)) => self.areas.0.lock().unwrap().get(&(S7_AREA_DB, number)).map(|db| db.len()),
+                    _ => None,
+                };
+                let (Some(number), Some(size)) = (number, size) else {
+                    params.error_code = UD_ERR_BLOCK_NOT_FOUND;
+                    return Some(userdata_response(pdu_ref, &params, &[RES_NOT_FOUND, 0x00, 0x00, 0x00]));
+                };
+                let mut info = vec![0u8; BLOCK_INFO_LEN];
+                info[0] = 0x01;
+                info[1] = b'0';
+                if self.write_protected.lock().unwrap().contains(&number) {
+                    info[9] = BLOCK_FLAG_WRITE_PROTECTED;
+                }
+                info[10] = BLOCK_LANG_DB;
+                info[11] = BLOCK_DB;
+                info[12..14].copy_from_slice(&number.to_be_bytes());
+                let mc7_size = size.min(u16::MAX as usize) as u16;
+                // Load memory: data + header and interface (approximation)
+                info[14..18].copy_from_slice(&(mc7_size as u32 + 92).to_be_bytes());
+                info[40..42].copy_from_slice(&mc7_size.to_be_bytes());
+                info[42..50].copy_from_slice(b"RUST7\0\0\0");
+                info[50..58].copy_from_slice(b"SIM\0\0\0\0\0");
+                info[66] = 0x01;
+                info
+            }
+            _ => return None,
+        };
+        let mut response = vec![RES_SUCCESS, TS_RES_OCTET];
+        response.extend_from_slice(&(entries.len() as u16).to_be_bytes());
+        response.extend_from_slice(&entries);
+        Some(userdata_response(pdu_ref, &params, &response))
+    }
+
+    /// Writes the forced values into the registered areas, if the CPU is emulated
+    ///
+    /// The values are written once, they are not kept against the following writes.
+    ///
+    fn force_job(&self, pdu_ref: u16, request: &S7UserDataParams, data: &[u8]) -> Option<Vec<u8>> {
+        self.cpu_status.lock().unwrap().as_ref()?;
+        if request.group() != UD_GROUP_PROG || request.subfunction != UD_SUBFN_FORCE {
+            return None;
+        }
+        let return_code = match decode_force_job(data) {
+            Some(ForceJob::Force(items)) => {
+                let mut areas = self.areas.0.lock().unwrap();
+                let fits = items.iter().all(|item| areas.get(&area_key(item.area, 0))
+                    .is_some_and(|area| item.start as usize + item.value.len() <= area.len()));
+                if fits {
+                    for item in items {
+                        if let Some(area) = areas.get_mut(&area_key(item.area, 0)) {
+                            let start = item.start as usize;
+                            area[start..start + item.value.len()].copy_from_slice(&item.value);
+                        }
+                    }
+                    RES_SUCCESS
+                } else {
+                    RES_INVALID_ADDRESS
+                }
+            }
+            Some(ForceJob::UnforceAll) => RES_SUCCESS,
+            None => RES_DATA_TYPE_NOT_SUPPORTED,
+        };
+        let params = S7UserDataParams {
+            method: UD_METHOD_RESPONSE,
+            type_group: UD_TYPE_RESPONSE | UD_GROUP_PROG,
+            subfunction: UD_SUBFN_FORCE,
+            sequence: request.sequence,
+            data_unit_ref: 0,
+            last_data_unit: 0,
+            error_code: 0,
+            extended: true,
+        };
+        Some(userdata_response(pdu_ref, &params, &[return_code, 0x00, 0x00, 0x00]))
+    }
+
+    /// Handles a BSEND fragment and returns its acknowledge
+    fn block_fragment(&self, pdu_ref: u16, request: &S7UserDataParams, data: &[u8], connection: &mut Connection, peer: SocketAddr) -> Result<Vec<u8>, S7Error> {
+        let fragment = BlockFragment::decode(data)?;
+        if let Some((r_id, data)) = connection.blocks.push(fragment, !request.more_data())? {
+            self.notify(PartnerEvent::BlockReceived { peer, r_id, data });
+        }
+        Ok(userdata_response(pdu_ref, &fragment_ack(request, 0x01), &FRAGMENT_ACK_DATA))
+    }
+
+    /// The request is refused as a whole, as a CPU does, if the response would exceed the PDU
+    fn read_var(&self, pdu_ref: u16, params: &[u8], pdu_length: u16, peer: SocketAddr) -> Result<Vec<u8>, S7Error> {
+        let count = *params.get(1).ok_or(S7Error::IsoInvalidTelegram)? as usize;
+        let mut data = Vec::new();
+        let mut events = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let offset = 2 + i * ITEM_SPEC_LEN;
+            let item = S7ItemSpec::decode(params.get(offset..).ok_or(S7Error::IsoInvalidTelegram)?)?;
+            let (return_code, payload, start) = self.read_item(&item);
+
+            if return_code == RES_SUCCESS {
+                let (transport_size, length) = if item.wordlen == S7_WL_BIT {
+                    (TS_RES_BIT, payload.len() as u16)
+                } else {
+                    (TS_RES_BYTE, (payload.len() << 3) as u16)
+                };
+                DataItemHeader { return_code, transport_size, length }.encode(&mut data);
+                data.extend_from_slice(&payload);
+                // Items are word aligned, except the last one
+                if payload.len() % 2 == 1 && i < count - 1 {
+                    data.push(0x00);
+                }
+            } else {
+                DataItemHeader { return_code, transport_size: 0x00, length: 0 }.encode(&mut data);
+            }
+
+            events.push(PartnerEvent::Read {
+                peer,
+                area: item.area,
+                db_number: item.db_number,
+                start,
+                size: payload.len(),
+                return_code,
+            });
+        }
+
+        if S7_ACK_HEADER_LEN + 2 + data.len() > pdu_length as usize {
+            return Ok(error_ack(pdu_ref, ERR_PDU_SIZE));
+        }
+        events.into_iter().for_each(|event| self.notify(event));
+
+        let mut response = Vec::new();
+        ack_data_header(pdu_ref, 2, data.len()).encode(&mut response);
+        response.extend_from_slice(&[FN_READ_VAR, count as u8]);
+        response.extend_from_slice(&data);
+        Ok(response)
+    }
+
+    /// Returns (return code, payload, start byte)
+    fn read_item(&self, item: &S7ItemSpec) -> (u8, Vec<u8>, usize) {
+        let areas = self.areas.0.lock().unwrap();
+        let start = (item.address >> 3) as usize;
+        let Some(area) = areas.get(&area_key(item.area, item.db_number)) else {
+            return (RES_NOT_FOUND, Vec::new(), start);
+        };
+
+        if item.wordlen == S7_WL_BIT {
+            // A byte per bit, the item can straddle byte boundaries
+            let first = item.address as usize;
+            let bits = (first..first + item.amount as usize)
+                .map(|bit| area.get(bit >> 3).map(|byte| (byte >> (bit & 0x07)) & 0x01))
+                .collect::<Option<Vec<u8>>>();
+            return match bits {
+                Some(bits) if !bits.is_empty() => (RES_SUCCESS, bits, start),
+                _ => (RES_INVALID_ADDRESS, Vec::new(), start),
+            };
+        }
+
+        let Some(size) = element_size(item.wordlen) else {
+            return (RES_DATA_TYPE_NOT_SUPPORTED, Vec::new(), start);
+        };
+        match area.get(start..start + size * item.amount as usize) {
+            Some(payload) => (RES_SUCCESS, payload.to_vec(), start),
+            None => (RES_INVALID_ADDRESS, Vec::new(), start),
+        }
+    }
+
+    fn write_var(&self, pdu_ref: u16, params: &[u8], data: &[u8], peer: SocketAddr) -> Result<Vec<u8>, S7Error> {
+        let count = *params.get(1).ok_or(S7Error::IsoInvalidTelegram)? as usize;
+        let mut return_codes = Vec::with_capacity(count);
+        let mut data_offset = 0;
+
+        for i in 0..count {
+            let offset = 2 + i * ITEM_SPEC_LEN;
+            let item = S7ItemSpec::decode(params.get(offset..).ok_or(S7Error::IsoInvalidTelegram)?)?;
+            let header = DataItemHeader::decode(data.get(data_offset..).ok_or(S7Error::IsoInvalidTelegram)?)?;
+            let size = match header.transport_size {
+                TS_RES_BIT | TS_RES_REAL | TS_RES_OCTET => header.length as usize,
+                TS_RES_BYTE | TS_RES_INT => header.length as usize >> 3,
+                _ => header.length as usize,
+            };
+            let payload_offset = data_offset + DATA_ITEM_HEADER_LEN;
+            let payload = data.get(payload_offset..payload_offset + size).ok_or(S7Error::IsoInvalidTelegram)?;
+            data_offset = payload_offset + size + (size % 2);
+
+            let (return_code, start) = self.write_item(&item, payload);
+            return_codes.push(return_code);
+
+            self.notify(PartnerEvent::Write {
+                peer,
+                area: item.area,
+                db_number: item.db_number,
+                start,
+                size,
+                return_code,
+            });
+        }
+
+        let mut response = Vec::new();
+        ack_data_header(pdu_ref, 2, return_codes.len()).encode(&mut response);
+        response.extend_from_slice(&[FN_WRITE_VAR, count as u8]);
+        response.extend_from_slice(&return_codes);
+        Ok(response)
+    }
+
+    /// Returns (return code, start byte)
+    fn write_item(&self, item: &S7ItemSpec, payload: &[u8]) -> (u8, usize) {
+        let start = (item.address >> 3) as usize;
+        if item.area == S7_AREA_DB && self.write_protected.lock().unwrap().contains(&item.db_number) {
+            return (RES_ACCESS_DENIED, start);
+        }
+        let mut areas = self.areas.0.lock().unwrap();
+        let Some(area) = areas.get_mut(&area_key(item.area, item.db_number)) else {
+            return (RES_NOT_FOUND, start);
+        };
+
+        if item.wordlen == S7_WL_BIT {
+            let bit = (item.address & 0x07) as u8;
+            return match (area.get_mut(start), payload.first()) {
+                (Some(byte), Some(value)) => {
+                    if *value != 0 {
+                        *byte |= 1 << bit;
+                    } else {
+                        *byte &= !(1 << bit);
+                    }
+                    (RES_SUCCESS, start)
+                }
+                _ => (RES_INVALID_ADDRESS, start),
+            };
+        }
+
+        match area.get_mut(start..start + payload.len()) {
+            Some(target) => {
+                target.copy_from_slice(payload);
+                (RES_SUCCESS, start)
+            }
+            None => (RES_INVALID_ADDRESS, start),
+        }
+    }
+}
+
+fn userdata_response(pdu_ref: u16, params: &S7UserDataParams, data: &[u8]) -> Vec<u8> {
+    let mut response = Vec::new();
+    S7Header::userdata(pdu_ref, params.encoded_len() as u16, data.len() as u16).encode(&mut response);
+    params.encode(&mut response);
+    response.extend_from_slice(data);
+    response
+}
+
+/// Ack without parameters and data, refusing the job with the given error class and code
+fn error_ack(pdu_ref: u16, (error_class, error_code): (u8, u8)) -> Vec<u8> {
+    let mut response = Vec::new();
+    S7Header {
+        rosctr: ROSCTR_ACK,
+        pdu_ref,
+        param_len: 0,
+        data_len: 0,
+        error_class,
+        error_code,
+    }.encode(&mut response);
+    response
+}
+
+fn ack_data_header(pdu_ref: u16, param_len: usize, data_len: usize) -> S7Header {
+    S7Header {
+        rosctr: ROSCTR_ACK_DATA,
+        pdu_ref,
+        param_len: param_len as u16,
+        data_len: data_len as u16,
+        error_class: 0,
+        error_code: 0,
+    }
+}
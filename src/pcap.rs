@@ -0,0 +1,272 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### PCAP export of the session traffic
+//!
+//! Writes the telegrams exchanged with the PLC into a pcapng file that Wireshark dissects as
+//! TCP → TPKT → COTP → S7comm, without capturing on the machine itself (often not permitted on
+//! production gateways).
+//!
+//! The IPv4 and TCP headers are synthesized (addresses, ports, sequence numbers, checksums),
+//! the payload is the real byte stream.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::pcap::PcapTransport;
+//!
+//! let mut client = S7Client::new();
+//! client.set_transport_wrapper(Some(Box::new(|transport| {
+//!     Ok(Box::new(PcapTransport::create(transport, "session.pcapng", None)?))
+//! })));
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddrV4};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::replay::{CaptureRecord, Direction};
+use crate::transport::Transport;
+
+const BLOCK_SHB: u32 = 0x0A0D_0D0A;
+const BLOCK_IDB: u32 = 0x0000_0001;
+const BLOCK_EPB: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const LINKTYPE_RAW: u16 = 101; // Raw IPv4/IPv6
+const IP_HEADER_LEN: usize = 20;
+const TCP_HEADER_LEN: usize = 20;
+const MAX_SEGMENT: usize = 65535 - IP_HEADER_LEN - TCP_HEADER_LEN;
+const TCP_PSH_ACK: u8 = 0x18;
+
+/// Default endpoints when the real ones are unknown
+const DEFAULT_CLIENT: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 49152);
+const DEFAULT_PLC: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 102);
+
+/// ### pcapng writer of a single TCP session
+///
+pub struct PcapWriter {
+    file: BufWriter<File>,
+    client: SocketAddrV4,
+    plc: SocketAddrV4,
+    client_seq: u32,
+    plc_seq: u32,
+    ip_id: u16,
+}
+
+impl PcapWriter {
+    /// ### Creates (or truncates) the pcapng file
+    ///
+    /// ### Parameters
+    /// - `path`: pcapng file.
+    /// - `endpoints`: `Some((client, plc))` addresses shown in the capture, `None` = 10.0.0.1:49152 → 10.0.0.2:102.
+    ///
+    pub fn create<P: AsRef<Path>>(path: P, endpoints: Option<(SocketAddrV4, SocketAddrV4)>) -> io::Result<Self> {
+        let (client, plc) = endpoints.unwrap_or((DEFAULT_CLIENT, DEFAULT_PLC));
+        let mut writer = PcapWriter {
+            file: BufWriter::new(File::create(path)?),
+            client,
+            plc,
+            client_seq: 1,
+            plc_seq: 1,
+            ip_id: 1,
+        };
+
+        // Section Header Block
+        let mut shb = Vec::new();
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes()); // Major version
+        shb.extend_from_slice(&0u16.to_le_bytes()); // Minor version
+        shb.extend_from_slice(&(-1i64).to_le_bytes()); // Section length: unknown
+        writer.write_block(BLOCK_SHB, &shb)?;
+
+        // Interface Description Block (timestamps in µs, the default resolution)
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes()); // Reserved
+        idb.extend_from_slice(&0u32.to_le_bytes()); // Snap length: unlimited
+        writer.write_block(BLOCK_IDB, &idb)?;
+
+        writer.file.flush()?;
+        Ok(writer)
+    }
+
+    /// ### Writes a chunk of the byte stream
+    ///
+    /// ### Parameters
+    /// - `direction`: `Direction::Sent` (client → PLC) or `Direction::Received`.
+    /// - `timestamp`: Time of the chunk.
+    /// - `data`: Bytes of the stream (any boundary, TPKT reassembly is done by Wireshark).
+    ///
+    pub fn write_chunk(&mut self, direction: Direction, timestamp: SystemTime, data: &[u8]) -> io::Result<()> {
+        let micros = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+
+        for segment in data.chunks(MAX_SEGMENT) {
+            let packet = self.tcp_packet(direction, segment);
+
+            let mut epb = Vec::with_capacity(20 + packet.len() + 3);
+            epb.extend_from_slice(&0u32.to_le_bytes()); // Interface ID
+            epb.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+            epb.extend_from_slice(&(micros as u32).to_le_bytes());
+            epb.extend_from_slice(&(packet.len() as u32).to_le_bytes()); // Captured length
+            epb.extend_from_slice(&(packet.len() as u32).to_le_bytes()); // Original length
+            epb.extend_from_slice(&packet);
+            self.write_block(BLOCK_EPB, &epb)?;
+        }
+        self.file.flush()
+    }
+
+    fn write_block(&mut self, block_type: u32, body: &[u8]) -> io::Result<()> {
+        let padding = (4 - body.len() % 4) % 4;
+        let total_len = (12 + body.len() + padding) as u32;
+        self.file.write_all(&block_type.to_le_bytes())?;
+        self.file.write_all(&total_len.to_le_bytes())?;
+        self.file.write_all(body)?;
+        self.file.write_all(&[0u8; 3][..padding])?;
+        self.file.write_all(&total_len.to_le_bytes())
+    }
+
+    /// Builds IPv4 + TCP headers around the payload and advances the sequence numbers
+    fn tcp_packet(&mut self, direction: Direction, payload: &[u8]) -> Vec<u8> {
+        let (source, destination, seq, ack) = match direction {
+            Direction::Sent => (self.client, self.plc, self.client_seq, self.plc_seq),
+            Direction::Received => (self.plc, self.client, self.plc_seq, self.client_seq),
+        };
+        match direction {
+            Direction::Sent => self.client_seq = self.client_seq.wrapping_add(payload.len() as u32),
+            Direction::Received => self.plc_seq = self.plc_seq.wrapping_add(payload.len() as u32),
+        }
+        let total_len = IP_HEADER_LEN + TCP_HEADER_LEN + payload.len();
+
+        let mut packet = Vec::with_capacity(total_len);
+        // IPv4
+        packet.extend_from_slice(&[0x45, 0x00]);
+        packet.extend_from_slice(&(total_len as u16).to_be_bytes());
+        packet.extend_from_slice(&self.ip_id.to_be_bytes());
+        packet.extend_from_slice(&[0x40, 0x00, 64, 6]); // Don't fragment, TTL, TCP
+        packet.extend_from_slice(&[0x00, 0x00]); // Checksum (below)
+        packet.extend_from_slice(&source.ip().octets());
+        packet.extend_from_slice(&destination.ip().octets());
+        let checksum = internet_checksum(&packet[..IP_HEADER_LEN], 0);
+        packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+        self.ip_id = self.ip_id.wrapping_add(1);
+
+        // TCP
+        let tcp_start = packet.len();
+        packet.extend_from_slice(&source.port().to_be_bytes());
+        packet.extend_from_slice(&destination.port().to_be_bytes());
+        packet.extend_from_slice(&seq.to_be_bytes());
+        packet.extend_from_slice(&ack.to_be_bytes());
+        packet.extend_from_slice(&[(TCP_HEADER_LEN as u8 / 4) << 4, TCP_PSH_ACK]);
+        packet.extend_from_slice(&0xFFFFu16.to_be_bytes()); // Window
+        packet.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // Checksum (below), Urgent pointer
+        packet.extend_from_slice(payload);
+
+        // Pseudo header: source, destination, protocol, TCP length
+        let mut pseudo = 0u32;
+        for pair in source.ip().octets().chunks(2).chain(destination.ip().octets().chunks(2)) {
+            pseudo += u16::from_be_bytes([pair[0], pair[1]]) as u32;
+        }
+        pseudo += 6 + (TCP_HEADER_LEN + payload.len()) as u32;
+        let checksum = internet_checksum(&packet[tcp_start..], pseudo);
+        packet[tcp_start + 16..tcp_start + 18].copy_from_slice(&checksum.to_be_bytes());
+
+        packet
+    }
+}
+
+/// One's complement sum of 16 bit words (RFC 1071)
+fn internet_checksum(data: &[u8], initial: u32) -> u16 {
+    let mut sum = initial;
+    for pair in data.chunks(2) {
+        let word = if pair.len() == 2 { u16::from_be_bytes([pair[0], pair[1]]) } else { (pair[0] as u16) << 8 };
+        sum += word as u32;
+    }
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// ### Converts a record-and-replay capture into a pcapng file
+///
+/// ### Parameters
+/// - `records`: Capture (see `replay::load_capture()`).
+/// - `path`: pcapng file.
+/// - `start`: Time of the beginning of the capture (the records only have the elapsed time).
+///
+pub fn export_capture<P: AsRef<Path>>(records: &[CaptureRecord], path: P, start: SystemTime) -> io::Result<()> {
+    let mut writer = PcapWriter::create(path, None)?;
+    for record in records {
+        writer.write_chunk(record.direction, start + record.elapsed, &record.data)?;
+    }
+    Ok(())
+}
+
+/// ### Transport decorator writing the traffic to a pcapng file
+///
+pub struct PcapTransport<T: Transport> {
+    inner: T,
+    writer: PcapWriter,
+}
+
+impl<T: Transport> PcapTransport<T> {
+    /// ### Creates (or truncates) the pcapng file
+    ///
+    /// ### Parameters
+    /// - `inner`: The transport to capture.
+    /// - `path`: pcapng file.
+    /// - `endpoints`: Addresses shown in the capture (see `PcapWriter::create()`).
+    ///
+    pub fn create<P: AsRef<Path>>(inner: T, path: P, endpoints: Option<(SocketAddrV4, SocketAddrV4)>) -> io::Result<Self> {
+        Ok(PcapTransport {
+            inner,
+            writer: PcapWriter::create(path, endpoints)?,
+        })
+    }
+
+    /// ### Returns the captured transport
+    ///
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Transport> Read for PcapTransport<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.inner.read(buf)?;
+        if size > 0 {
+            self.writer.write_chunk(Direction::Received, SystemTime::now(), &buf[..size])?;
+        }
+        Ok(size)
+    }
+}
+
+impl<T: Transport> Write for PcapTransport<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let size = self.inner.write(buf)?;
+        if size > 0 {
+            self.writer.write_chunk(Direction::Sent, SystemTime::now(), &buf[..size])?;
+        }
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Transport> Transport for PcapTransport<T> {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_read_timeout(timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_write_timeout(timeout)
+    }
+
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+}
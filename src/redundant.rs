@@ -0,0 +1,351 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Redundant connection with automatic failover
+//!
+//! `RedundantS7Client` holds two endpoints of the same process data (the two CPs of an S7-400H or
+//! S7-1500R/H, or two network paths to the same CPU), connects to the preferred one and, when the
+//! connection is lost, switches to the other one and repeats the operation. The application is
+//! notified of each change of state, e.g. to show which CPU it's acquiring from.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7DataType;
+//! use rust7::S7_AREA_DB;
+//! use rust7::redundant::{RedundancyEvent, RedundantS7Client, S7Endpoint};
+//!
+//! let mut client = RedundantS7Client::new(
+//!     S7Endpoint::rack_slot("192.168.0.100", 0, 3), // CPU 0
+//!     S7Endpoint::rack_slot("192.168.0.101", 1, 3), // CPU 1
+//! );
+//! client.set_listener(Some(Box::new(|event: &RedundancyEvent| println!("{:?}", event))));
+//! client.connect().unwrap();
+//!
+//! loop {
+//!     // A CPU switched off during the read is transparent: the read is repeated on the other one
+//!     let speed = client.read_value(S7_AREA_DB, 100, 0, S7DataType::Real).unwrap();
+//!     println!("Speed = {} (endpoint {:?})", speed, client.active_endpoint());
+//!     std::thread::sleep(std::time::Duration::from_millis(500));
+//! }
+//! ```
+
+use std::io;
+use crate::client::{S7Client, S7Error};
+use crate::value::{S7DataType, S7Value};
+
+/// Index of the primary endpoint
+pub const PRIMARY: usize = 0;
+/// Index of the secondary endpoint
+pub const SECONDARY: usize = 1;
+
+/// ### Address of one of the redundant partners
+///
+/// The CPU is addressed by `tsaps` if set, otherwise by `rack` and `slot`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S7Endpoint {
+    /// PLC (or CP) address
+    pub address: String,
+    pub rack: u16,
+    pub slot: u16,
+    /// (local, remote) TSAPs
+    pub tsaps: Option<(u16, u16)>,
+}
+
+impl S7Endpoint {
+    /// ### Endpoint addressed by rack and slot
+    ///
+    pub fn rack_slot(address: &str, rack: u16, slot: u16) -> Self {
+        S7Endpoint { address: address.to_string(), rack, slot, tsaps: None }
+    }
+
+    /// ### Endpoint addressed by TSAPs
+    ///
+    pub fn tsap(address: &str, local_tsap: u16, remote_tsap: u16) -> Self {
+        S7Endpoint { address: address.to_string(), rack: 0, slot: 0, tsaps: Some((local_tsap, remote_tsap)) }
+    }
+
+    fn connect(&self, client: &mut S7Client) -> Result<(), S7Error> {
+        match self.tsaps {
+            Some((local_tsap, remote_tsap)) => client.connect_tsap(&self.address, local_tsap, remote_tsap),
+            None => client.connect_rack_slot(&self.address, self.rack, self.slot),
+        }
+    }
+}
+
+/// ### Change of state of a redundant client
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedundancyEvent {
+    /// Connected to the endpoint (`PRIMARY` or `SECONDARY`), by `connect()`, `switch_to()` or
+    /// after the connection to the same endpoint was restored
+    Connected(usize),
+    /// The connection to `from` was lost, the client switched to `to`
+    FailedOver { from: usize, to: usize },
+    /// None of the endpoints is reachable (notified once, until an endpoint is connected again)
+    Lost,
+}
+
+/// Callback notified of the changes of state
+pub type RedundancyListener = Box<dyn FnMut(&RedundancyEvent) + Send>;
+
+/// Returns true if the error means that the current endpoint is gone
+fn is_connection_lost(error: &S7Error) -> bool {
+    match error.root() {
+        S7Error::NotConnected => true,
+        S7Error::Io(e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => true,
+        error => error.is_transport(),
+    }
+}
+
+/// ### Client connected to one of two redundant endpoints
+///
+pub struct RedundantS7Client {
+    client: S7Client,
+    endpoints: [S7Endpoint; 2],
+    preferred: usize,
+    active: Option<usize>,
+    failovers: u64,
+    /// `Lost` already notified
+    lost: bool,
+    listener: Option<RedundancyListener>,
+}
+
+impl RedundantS7Client {
+    /// ### Creates a redundant client (not connected)
+    ///
+    /// ### Parameters
+    /// - `primary`: Preferred endpoint.
+    /// - `secondary`: Endpoint used when the primary is not reachable.
+    ///
+    pub fn new(primary: S7Endpoint, secondary: S7Endpoint) -> Self {
+        Self::with_client(S7Client::new(), primary, secondary)
+    }
+
+    /// ### Creates a redundant client using a configured client (timeouts, PDU size, observer...)
+    ///
+    /// ### Notes
+    /// The client must not have a retry policy (see `S7Client::set_retry_policy()`): it would
+    /// reconnect to the same endpoint instead of failing over.
+    ///
+    pub fn with_client(mut client: S7Client, primary: S7Endpoint, secondary: S7Endpoint) -> Self {
+        client.disconnect();
+        RedundantS7Client {
+            client,
+            endpoints: [primary, secondary],
+            preferred: PRIMARY,
+            active: None,
+            failovers: 0,
+            lost: false,
+            listener: None,
+        }
+    }
+
+    /// ### Sets the callback notified of the changes of state
+    ///
+    /// ### Parameters
+    /// - `listener`: The callback, `None` removes it (Default). It's called on the thread running
+    ///   the operation: keep it short.
+    ///
+    pub fn set_listener(&mut self, listener: Option<RedundancyListener>) {
+        self.listener = listener;
+    }
+
+    /// ### Sets the endpoint tried first by `connect()`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Index other than `PRIMARY` or `SECONDARY`.
+    ///
+    pub fn set_preferred(&mut self, index: usize) -> Result<(), S7Error> {
+        if index > SECONDARY {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        self.preferred = index;
+        Ok(())
+    }
+
+    /// ### Connects to the preferred endpoint or, if not reachable, to the other one
+    ///
+    /// ### Errors
+    /// - The error of the last endpoint tried (reported by `S7Client::connect_tsap()`) if none is
+    ///   reachable; `RedundancyEvent::Lost` is notified.
+    ///
+    pub fn connect(&mut self) -> Result<(), S7Error> {
+        self.client.disconnect();
+        self.active = None;
+        let preferred = self.preferred;
+        self.recover(&[preferred, 1 - preferred], None).map(|_| ())
+    }
+
+    /// ### Switches the connection to an endpoint, e.g. back to the primary after a failover
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Index other than `PRIMARY` or `SECONDARY`.
+    /// - Reported by `S7Client::connect_tsap()`. The client stays disconnected: the next operation
+    ///   connects to an endpoint reachable.
+    ///
+    pub fn switch_to(&mut self, index: usize) -> Result<(), S7Error> {
+        if index > SECONDARY {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        if self.active == Some(index) && self.client.connected {
+            return Ok(());
+        }
+        self.client.disconnect();
+        self.active = None;
+        self.endpoints[index].connect(&mut self.client)?;
+        self.active = Some(index);
+        self.lost = false;
+        self.notify(RedundancyEvent::Connected(index));
+        Ok(())
+    }
+
+    /// ### Closes the connection
+    ///
+    pub fn disconnect(&mut self) {
+        self.client.disconnect();
+        self.active = None;
+    }
+
+    /// ### Returns the endpoint in use (`PRIMARY` or `SECONDARY`), `None` if not connected
+    ///
+    pub fn active_endpoint(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// ### Returns the endpoint `index` (`PRIMARY` or `SECONDARY`)
+    ///
+    pub fn endpoint(&self, index: usize) -> Option<&S7Endpoint> {
+        self.endpoints.get(index)
+    }
+
+    /// ### Returns the number of failovers since the creation
+    ///
+    pub fn failovers(&self) -> u64 {
+        self.failovers
+    }
+
+    /// ### Returns the client connected to the active endpoint
+    ///
+    pub fn client(&self) -> &S7Client {
+        &self.client
+    }
+
+    /// ### Returns the client connected to the active endpoint, e.g. to change its settings
+    ///
+    /// ### Notes
+    /// Operations made directly on the client are not failed over: use `execute()`.
+    ///
+    pub fn client_mut(&mut self) -> &mut S7Client {
+        &mut self.client
+    }
+
+    /// ### Runs a job on the active endpoint, failing over if the connection is lost
+    ///
+    /// If not connected, the client connects first (see `connect()`). If the job fails with a
+    /// transport error, a timeout or `S7Error::NotConnected`, the client connects to the other
+    /// endpoint (or, if not reachable, again to the same one) and runs the job once more.
+    ///
+    /// ### Parameters
+    /// - `job`: The operation, e.g. `|client| subscription.poll(client)`.
+    ///
+    /// ### Returns
+    /// `Ok(<T>)` result of the job, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by the job. The errors reported by the PLC (address, access...) are returned
+    ///   without failing over.
+    /// - The error of the job if none of the endpoints is reachable; `RedundancyEvent::Lost` is
+    ///   notified and the next call tries to connect again.
+    ///
+    /// ### Notes
+    /// A write interrupted by the connection loss may have reached the PLC before being
+    /// repeated: the job should be idempotent (as the Write Var of area data).
+    ///
+    pub fn execute<T, F>(&mut self, mut job: F) -> Result<T, S7Error>
+    where
+        F: FnMut(&mut S7Client) -> Result<T, S7Error>,
+    {
+        if self.active.is_none() || !self.client.connected {
+            let preferred = self.active.unwrap_or(self.preferred);
+            self.client.disconnect();
+            self.active = None;
+            self.recover(&[preferred, 1 - preferred], None)?;
+        }
+        match job(&mut self.client) {
+            Err(error) if is_connection_lost(&error) => {
+                // Always Some here: set by the connection above
+                let from = self.active.unwrap_or(self.preferred);
+                s7_debug!("redundancy: endpoint {} lost: {}", from, error);
+                self.client.disconnect();
+                self.active = None;
+                if self.recover(&[1 - from, from], Some(from)).is_err() {
+                    return Err(error);
+                }
+                job(&mut self.client)
+            }
+            result => result,
+        }
+    }
+
+    /// ### Reads a block of data (see `S7Client::read_area()`), failing over if needed
+    ///
+    pub fn read_area(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &mut [u8]) -> Result<(), S7Error> {
+        self.execute(|client| client.read_area(area, db_number, start, wordlen, buffer))
+    }
+
+    /// ### Writes a block of data (see `S7Client::write_area()`), failing over if needed
+    ///
+    pub fn write_area(&mut self, area: u8, db_number: u16, start: u32, wordlen: u8, buffer: &[u8]) -> Result<(), S7Error> {
+        self.execute(|client| client.write_area(area, db_number, start, wordlen, buffer))
+    }
+
+    /// ### Reads a typed value (see `S7Client::read_value()`), failing over if needed
+    ///
+    pub fn read_value(&mut self, area: u8, db_number: u16, start: u32, data_type: S7DataType) -> Result<S7Value, S7Error> {
+        self.execute(|client| client.read_value(area, db_number, start, data_type))
+    }
+
+    /// ### Writes a typed value (see `S7Client::write_value()`), failing over if needed
+    ///
+    pub fn write_value(&mut self, area: u8, db_number: u16, start: u32, data_type: S7DataType, value: &S7Value) -> Result<(), S7Error> {
+        self.execute(|client| client.write_value(area, db_number, start, data_type, value))
+    }
+
+    /// Connects to the first endpoint reachable of `order`, notifying the new state
+    fn recover(&mut self, order: &[usize], lost: Option<usize>) -> Result<usize, S7Error> {
+        let mut last_error = S7Error::NotConnected;
+        for &index in order {
+            match self.endpoints[index].connect(&mut self.client) {
+                Ok(()) => {
+                    self.active = Some(index);
+                    self.lost = false;
+                    match lost {
+                        Some(from) if from != index => {
+                            self.failovers += 1;
+                            s7_debug!("redundancy: failed over from endpoint {} to {}", from, index);
+                            self.notify(RedundancyEvent::FailedOver { from, to: index });
+                        }
+                        _ => self.notify(RedundancyEvent::Connected(index)),
+                    }
+                    return Ok(index);
+                }
+                Err(error) => {
+                    s7_debug!("redundancy: endpoint {} ({}) not reachable: {}", index, self.endpoints[index].address, error);
+                    last_error = error;
+                }
+            }
+        }
+        if !self.lost {
+            self.lost = true;
+            self.notify(RedundancyEvent::Lost);
+        }
+        Err(last_error)
+    }
+
+    fn notify(&mut self, event: RedundancyEvent) {
+        if let Some(listener) = self.listener.as_mut() {
+            listener(&event);
+        }
+    }
+}
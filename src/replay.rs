@@ -0,0 +1,329 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Record and replay of PLC exchanges
+//!
+//! `RecordingTransport` captures the traffic of a real connection into a text file, `ReplayTransport`
+//! plays it back to the client later, without the PLC. If the client sends something different from
+//! the capture, the replay fails with an `io::ErrorKind::InvalidData` error describing the divergence.
+//! This allows building regression tests from field captures of misbehaving devices.
+//!
+//! The capture file has one record per line, `#` starts a comment:
+//!
+//! ```text
+//! # rust7 capture
+//! <elapsed µs> > <hex bytes>     Client -> PLC
+//! <elapsed µs> < <hex bytes>     PLC -> Client
+//! ```
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::replay::{RecordingTransport, ReplayTransport};
+//!
+//! // Record
+//! let mut client = S7Client::new();
+//! client.set_transport_wrapper(Some(Box::new(|transport| {
+//!     Ok(Box::new(RecordingTransport::create(transport, "capture.txt")?))
+//! })));
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! // ... reads and writes ...
+//! client.disconnect();
+//!
+//! // Replay (same TSAPs of the recorded connection)
+//! let mut client = S7Client::new();
+//! let replay = ReplayTransport::open("capture.txt").unwrap();
+//! client.connect_transport(Box::new(replay), 0x0100, 0x0100).unwrap();
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::net::Shutdown;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use crate::transport::Transport;
+
+/// ### Direction of a captured chunk
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Client -> PLC
+    Sent,
+    /// PLC -> Client
+    Received,
+}
+
+/// ### Captured chunk of traffic
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    /// Time since the beginning of the capture
+    pub elapsed: Duration,
+    pub direction: Direction,
+    pub data: Vec<u8>,
+}
+
+impl CaptureRecord {
+    fn to_line(&self) -> String {
+        let mut line = format!(
+            "{} {} ",
+            self.elapsed.as_micros(),
+            if self.direction == Direction::Sent { '>' } else { '<' }
+        );
+        for byte in &self.data {
+            let _ = write!(line, "{:02X}", byte);
+        }
+        line
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let elapsed = Duration::from_micros(fields.next()?.parse().ok()?);
+        let direction = match fields.next()? {
+            ">" => Direction::Sent,
+            "<" => Direction::Received,
+            _ => return None,
+        };
+        let hex = fields.next().unwrap_or("");
+        if !hex.len().is_multiple_of(2) {
+            return None;
+        }
+        let data = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        Some(CaptureRecord { elapsed, direction, data })
+    }
+}
+
+/// ### Loads a capture file
+///
+/// ### Errors
+/// - `io::ErrorKind::InvalidData`: Malformed line (the message contains the line number).
+/// - Other I/O errors opening or reading the file.
+///
+pub fn load_capture<P: AsRef<Path>>(path: P) -> io::Result<Vec<CaptureRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let record = CaptureRecord::from_line(line).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("capture line {}: malformed record", index + 1))
+        })?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// ### Transport decorator writing the traffic to a capture file
+///
+pub struct RecordingTransport<T: Transport> {
+    inner: T,
+    file: BufWriter<File>,
+    start: Instant,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    /// ### Creates (or truncates) the capture file
+    ///
+    /// ### Parameters
+    /// - `inner`: The transport to record.
+    /// - `path`: Capture file.
+    ///
+    pub fn create<P: AsRef<Path>>(inner: T, path: P) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "# rust7 capture")?;
+        Ok(RecordingTransport {
+            inner,
+            file,
+            start: Instant::now(),
+        })
+    }
+
+    fn record(&mut self, direction: Direction, data: &[u8]) -> io::Result<()> {
+        let record = CaptureRecord {
+            elapsed: self.start.elapsed(),
+            direction,
+            data: data.to_vec(),
+        };
+        writeln!(self.file, "{}", record.to_line())?;
+        // Flushed at each record, so the capture survives a crash of the application
+        self.file.flush()
+    }
+
+    /// ### Returns the recorded transport
+    ///
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Transport> Read for RecordingTransport<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.inner.read(buf)?;
+        if size > 0 {
+            self.record(Direction::Received, &buf[..size])?;
+        }
+        Ok(size)
+    }
+}
+
+impl<T: Transport> Write for RecordingTransport<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let size = self.inner.write(buf)?;
+        if size > 0 {
+            self.record(Direction::Sent, &buf[..size])?;
+        }
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_read_timeout(timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_write_timeout(timeout)
+    }
+
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+}
+
+/// ### Transport replaying a capture
+///
+/// Reads return the recorded PLC data; writes are compared byte by byte with the recorded client data.
+/// Chunk boundaries don't need to match, only the byte sequence of each direction and their order.
+///
+pub struct ReplayTransport {
+    records: VecDeque<CaptureRecord>,
+    /// Index of the current record (for the divergence messages)
+    index: usize,
+    /// Bytes of the current record not yet consumed
+    pending: VecDeque<u8>,
+    pending_direction: Option<Direction>,
+}
+
+impl ReplayTransport {
+    /// ### Loads a capture file
+    ///
+    /// ### Errors
+    /// - Reported by `load_capture()`
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(load_capture(path)?))
+    }
+
+    /// ### Creates the transport from a list of records
+    ///
+    pub fn new(records: Vec<CaptureRecord>) -> Self {
+        ReplayTransport {
+            records: records.into(),
+            index: 0,
+            pending: VecDeque::new(),
+            pending_direction: None,
+        }
+    }
+
+    /// ### Returns true if the whole capture was replayed
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty() && self.records.is_empty()
+    }
+
+    /// Makes the next record current, if the current one is consumed
+    fn advance(&mut self) {
+        while self.pending.is_empty() {
+            let Some(record) = self.records.pop_front() else {
+                self.pending_direction = None;
+                return;
+            };
+            self.index += 1;
+            self.pending_direction = Some(record.direction);
+            self.pending.extend(record.data);
+        }
+    }
+
+    fn divergence(&self, message: String) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, format!("replay diverged at record {}: {}", self.index, message))
+    }
+}
+
+impl Read for ReplayTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.advance();
+        match self.pending_direction {
+            None => Ok(0), // End of capture: the PLC closed the connection
+            Some(Direction::Sent) => Err(self.divergence(format!(
+                "the client waits for data, the capture expects it to send {} more byte",
+                self.pending.len()
+            ))),
+            Some(Direction::Received) => {
+                let size = buf.len().min(self.pending.len());
+                for (target, source) in buf.iter_mut().zip(self.pending.drain(..size)) {
+                    *target = source;
+                }
+                Ok(size)
+            }
+        }
+    }
+}
+
+impl Write for ReplayTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.advance();
+        match self.pending_direction {
+            None => Err(self.divergence("the client sends data after the end of the capture".to_string())),
+            Some(Direction::Received) => Err(self.divergence(format!(
+                "the client sends data, the capture expects it to receive {} more byte",
+                self.pending.len()
+            ))),
+            Some(Direction::Sent) => {
+                let size = buf.len().min(self.pending.len());
+                for (offset, (&got, &expected)) in buf[..size].iter().zip(self.pending.iter()).enumerate() {
+                    if got != expected {
+                        return Err(self.divergence(format!(
+                            "byte {} of the sent data: expected 0x{:02X}, got 0x{:02X}",
+                            offset, expected, got
+                        )));
+                    }
+                }
+                self.pending.drain(..size);
+                Ok(size)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn set_read_timeout(&mut self, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn set_write_timeout(&mut self, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self, _how: Shutdown) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Retry policies
+//!
+//! A `RetryPolicy` decides if, and after how long, a failed operation (or connection) is attempted again.
+//!
+//! Two policies are provided:
+//! - `FixedDelay`: same delay between attempts, good for local networks.
+//! - `ExponentialBackoff`: growing delay with random jitter, good for cellular/VPN links and to avoid
+//!   that many gateways reconnect to the same CPU all at the same moment.
+//!
+//! You can implement the trait yourself for any other strategy.
+//!
+//! With `S7Client::set_retry_policy()` the client retries by itself the Read/Write operations
+//! failed with a transport or protocol error, reconnecting if needed.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::S7Client;
+//! use rust7::retry::ExponentialBackoff;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! client.set_retry_policy(Some(Box::new(ExponentialBackoff::new(
+//!     Duration::from_millis(200),
+//!     Duration::from_secs(5),
+//!     Some(5),
+//! ))));
+//!
+//! let mut buffer = [0u8; 64];
+//! client.read_db(100, 0, &mut buffer).unwrap(); // Survives a cable unplugged for a few seconds
+//! ```
+
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::client::{S7Client, S7Error};
+
+/// ### Retry strategy
+///
+pub trait RetryPolicy: Send {
+    /// ### Returns the delay before the next attempt
+    ///
+    /// ### Parameters
+    /// - `attempt`: Number of the retry about to be made (1 = first retry after the initial failure).
+    ///
+    /// ### Returns
+    /// `Some(<delay>)` to retry after the delay, `None` to give up.
+    ///
+    fn next_delay(&mut self, attempt: u32) -> Option<Duration>;
+}
+
+impl<P: RetryPolicy + ?Sized> RetryPolicy for Box<P> {
+    fn next_delay(&mut self, attempt: u32) -> Option<Duration> {
+        (**self).next_delay(attempt)
+    }
+}
+
+/// ### Constant delay between attempts
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedDelay {
+    /// Delay between attempts
+    pub delay: Duration,
+    /// Max number of retries (None = retry forever)
+    pub max_attempts: Option<u32>,
+}
+
+impl FixedDelay {
+    pub fn new(delay: Duration, max_attempts: Option<u32>) -> Self {
+        FixedDelay { delay, max_attempts }
+    }
+}
+
+impl RetryPolicy for FixedDelay {
+    fn next_delay(&mut self, attempt: u32) -> Option<Duration> {
+        match self.max_attempts {
+            Some(max) if attempt > max => None,
+            _ => Some(self.delay),
+        }
+    }
+}
+
+/// ### Exponential backoff with jitter
+///
+/// The delay of the n-th retry is `initial * multiplier^(n-1)`, capped to `max_delay`, then
+/// randomized by ±`jitter` (e.g. 0.2 = ±20%).
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExponentialBackoff {
+    /// Delay before the first retry
+    pub initial: Duration,
+    /// Upper bound of the delay (before jitter)
+    pub max_delay: Duration,
+    /// Growth factor between two retries
+    pub multiplier: f64,
+    /// Random variation (0.0..=1.0) applied to each delay
+    pub jitter: f64,
+    /// Max number of retries (None = retry forever)
+    pub max_attempts: Option<u32>,
+    seed: u64,
+}
+
+impl ExponentialBackoff {
+    /// ### Creates a policy doubling the delay at each retry, with ±20% jitter
+    ///
+    /// ### Parameters
+    /// - `initial`: Delay before the first retry.
+    /// - `max_delay`: Upper bound of the delay.
+    /// - `max_attempts`: Max number of retries (None = retry forever).
+    ///
+    pub fn new(initial: Duration, max_delay: Duration, max_attempts: Option<u32>) -> Self {
+        // No need of a crypto-grade generator, the clock is a good enough seed
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x2545_F491_4F6C_DD1D);
+
+        ExponentialBackoff {
+            initial,
+            max_delay,
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts,
+            seed: seed | 1,
+        }
+    }
+
+    /// ### Sets the growth factor (min 1.0)
+    ///
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// ### Sets the random variation (0.0 = deterministic delays)
+    ///
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// xorshift64: returns a value in 0.0..1.0
+    fn random(&mut self) -> f64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn next_delay(&mut self, attempt: u32) -> Option<Duration> {
+        if let Some(max) = self.max_attempts {
+            if attempt > max {
+                return None;
+            }
+        }
+
+        let exponent = attempt.saturating_sub(1).min(64) as i32;
+        let delay = (self.initial.as_secs_f64() * self.multiplier.powi(exponent))
+            .min(self.max_delay.as_secs_f64());
+        let variation = 1.0 + self.jitter * (2.0 * self.random() - 1.0);
+
+        Some(Duration::from_secs_f64((delay * variation).max(0.0)))
+    }
+}
+
+impl S7Client {
+    /// ### Sets the policy of the automatic retries
+    ///
+    /// `read_area()`, `write_area()`, `read_bits()` and the methods based on them are retried
+    /// when they fail with a recoverable error (see `S7Error::is_recoverable()`), as long as the
+    /// policy allows: after a transport error the client is reconnected (`reconnect()`) before the
+    /// retry, after a protocol error (e.g. a timeout) the operation is retried on the same connection.
+    ///
+    /// ### Parameters
+    /// - `policy`: The retry policy, `None` disables the retries (Default).
+    ///
+    /// ### Notes
+    /// - The errors reported by the PLC (e.g. `S7Error::S7InvalidAddress`) are never retried.
+    /// - A write whose response was lost is sent again: the PLC writes the same data twice.
+    /// - Use `with_retry()` to apply the policy to any other operation.
+    ///
+    pub fn set_retry_policy(&mut self, policy: Option<Box<dyn RetryPolicy>>) {
+        self.retry_policy = policy;
+    }
+
+    /// ### Runs an operation, retrying it according to the retry policy
+    ///
+    /// ### Parameters
+    /// - `operation`: The operation to run, e.g. `|client| client.get_plc_status()`.
+    ///
+    /// ### Returns
+    /// The result of the last attempt. Without a policy (see `set_retry_policy()`) the operation
+    /// runs once.
+    ///
+    /// ### Notes
+    /// The operations run by `operation` are not retried on their own: the policy applies only once.
+    ///
+    pub fn with_retry<T, F>(&mut self, mut operation: F) -> Result<T, S7Error>
+    where
+        F: FnMut(&mut S7Client) -> Result<T, S7Error>,
+    {
+        let Some(mut policy) = self.retry_policy.take() else {
+            return operation(self);
+        };
+
+        let mut attempt = 0;
+        let mut reconnect = false;
+        let result = loop {
+            let result = if reconnect {
+                self.reconnect().and_then(|_| operation(self))
+            } else {
+                operation(self)
+            };
+            match &result {
+                Err(error) if error.is_recoverable() => reconnect = error.is_transport(),
+                _ => break result,
+            }
+            attempt += 1;
+            match policy.next_delay(attempt) {
+                Some(delay) => {
+                    s7_debug!("retry {} in {:?}{}", attempt, delay, if reconnect { " after a reconnection" } else { "" });
+                    thread::sleep(delay);
+                }
+                None => break result,
+            }
+        };
+
+        self.retry_policy = Some(policy);
+        result
+    }
+}
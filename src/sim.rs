@@ -0,0 +1,286 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### In-memory PLC simulator
+//!
+//! `S7Simulator` is an `S7Partner` that behaves like a CPU in RUN: it serves the registered
+//! DB/M/I/Q areas, answers the CPU status query and can change its values over time through a
+//! script. It lets examples, tests and demos run on any machine, without a PLC.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::{S7Client, S7DataType, S7Value, S7_AREA_DB};
+//! use rust7::sim::S7Simulator;
+//!
+//! let mut sim = S7Simulator::new();
+//! sim.register_db(100, vec![0u8; 512]);
+//! // Speed ramps up by 1.5 every 100 ms
+//! sim.every(Duration::from_millis(100), |areas| {
+//!     let mut speed = [0u8; 4];
+//!     areas.read_area(S7_AREA_DB, 100, 0, &mut speed).unwrap();
+//!     let speed = f32::from_be_bytes(speed) + 1.5;
+//!     areas.write_area(S7_AREA_DB, 100, 0, &speed.to_be_bytes()).unwrap();
+//! });
+//! sim.start("127.0.0.1:0").unwrap();
+//!
+//! let mut client = S7Client::new();
+//! client.set_connection_port(sim.local_address().unwrap().port()).unwrap();
+//! client.connect_s71200_1500("127.0.0.1").unwrap();
+//! let speed = client.read_value(S7_AREA_DB, 100, 0, S7DataType::Real).unwrap();
+//! ```
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crate::client::{S7Error, S7_AREA_DB};
+use crate::partner::{PartnerAreas, PartnerEvent, S7Partner};
+use crate::szl::PlcStatus;
+use crate::value::{S7DataType, S7Value};
+
+const SCRIPT_POLL: Duration = Duration::from_millis(20);
+const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+type Action = Box<dyn FnMut(&PartnerAreas) + Send>;
+
+/// Scripted action
+struct Task {
+    /// Time of the next execution (since start)
+    at: Duration,
+    /// None = runs once
+    period: Option<Duration>,
+    action: Action,
+}
+
+/// ### In-memory PLC simulator
+///
+pub struct S7Simulator {
+    partner: S7Partner,
+    tasks: Arc<Mutex<Vec<Task>>>,
+    stop: Arc<AtomicBool>,
+    script: Option<JoinHandle<()>>,
+}
+
+impl S7Simulator {
+    /// ### Creates a simulator in RUN, without areas
+    ///
+    pub fn new() -> Self {
+        let partner = S7Partner::new();
+        partner.set_cpu_status(Some(PlcStatus::Run));
+        S7Simulator {
+            partner,
+            tasks: Arc::new(Mutex::new(Vec::new())),
+            stop: Arc::new(AtomicBool::new(false)),
+            script: None,
+        }
+    }
+
+    /// ### Registers (or replaces) a Data Block
+    ///
+    /// ### Parameters
+    /// - `db_number`: DB number.
+    /// - `data`: Initial content, its length is the size of the DB.
+    ///
+    pub fn register_db(&self, db_number: u16, data: Vec<u8>) {
+        self.partner.register_area(S7_AREA_DB, db_number, data);
+    }
+
+    /// ### Write-protects a registered DB (the writes are refused, see `S7Partner::set_db_write_protected()`)
+    ///
+    pub fn set_db_write_protected(&self, db_number: u16, protected: bool) {
+        self.partner.set_db_write_protected(db_number, protected);
+    }
+
+    /// ### Registers (or replaces) a non-DB area
+    ///
+    /// ### Parameters
+    /// - `area`: `S7_AREA_PE`, `S7_AREA_PA` or `S7_AREA_MK`.
+    /// - `data`: Initial content, its length is the size of the area.
+    ///
+    pub fn register_area(&self, area: u8, data: Vec<u8>) {
+        self.partner.register_area(area, 0, data);
+    }
+
+    /// ### Sets the CPU status returned to `S7Client::get_plc_status()` (Default = RUN)
+    ///
+    pub fn set_cpu_status(&self, status: PlcStatus) {
+        self.partner.set_cpu_status(Some(status));
+    }
+
+    /// ### Returns a handle to the simulated memory
+    ///
+    pub fn areas(&self) -> PartnerAreas {
+        self.partner.areas()
+    }
+
+    /// ### Writes a typed value into the simulated memory
+    ///
+    /// ### Parameters
+    /// - `area`: S7 memory area constant.
+    /// - `db_number`: DB number (ignored for non-DB areas).
+    /// - `start`: Starting byte index (bit index, i.e. `byte_num * 8 + bit_idx`, for `S7DataType::Bool`).
+    /// - `data_type`: Type of the variable.
+    /// - `value`: Value to write, it must match `data_type`.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The value doesn't match the data type.
+    /// - `S7Error::S7NotFound`: The area is not registered.
+    /// - `S7Error::S7InvalidAddress`: The range exceeds the area size.
+    ///
+    pub fn set_value(&self, area: u8, db_number: u16, start: usize, data_type: S7DataType, value: &S7Value) -> Result<(), S7Error> {
+        let data = value.encode(data_type)?;
+        let areas = self.partner.areas();
+
+        if data_type == S7DataType::Bool {
+            let mut byte = [0u8; 1];
+            areas.read_area(area, db_number, start >> 3, &mut byte)?;
+            let mask = 1u8 << (start & 0x07);
+            byte[0] = if data[0] != 0 { byte[0] | mask } else { byte[0] & !mask };
+            return areas.write_area(area, db_number, start >> 3, &byte);
+        }
+        areas.write_area(area, db_number, start, &data)
+    }
+
+    /// ### Reads a typed value from the simulated memory
+    ///
+    /// For the parameters look at `set_value()`
+    ///
+    /// ### Errors
+    /// - `S7Error::S7NotFound`: The area is not registered.
+    /// - `S7Error::S7InvalidAddress`: The range exceeds the area size.
+    ///
+    pub fn value(&self, area: u8, db_number: u16, start: usize, data_type: S7DataType) -> Result<S7Value, S7Error> {
+        let areas = self.partner.areas();
+
+        if data_type == S7DataType::Bool {
+            let mut byte = [0u8; 1];
+            areas.read_area(area, db_number, start >> 3, &mut byte)?;
+            return Ok(S7Value::Bool(byte[0] & (1 << (start & 0x07)) != 0));
+        }
+        let mut data = vec![0u8; data_type.size()];
+        areas.read_area(area, db_number, start, &mut data)?;
+        S7Value::decode(data_type, &data)
+    }
+
+    /// ### Runs an action once, `at` after `start()`
+    ///
+    /// Actions scheduled while the simulator is running are relative to the same start time,
+    /// if `at` is already elapsed they run immediately.
+    ///
+    pub fn schedule<F>(&self, at: Duration, action: F)
+    where
+        F: FnOnce(&PartnerAreas) + Send + 'static,
+    {
+        let mut action = Some(action);
+        self.add_task(at, None, Box::new(move |areas| {
+            if let Some(action) = action.take() {
+                action(areas);
+            }
+        }));
+    }
+
+    /// ### Writes raw bytes, `at` after `start()`
+    ///
+    /// Writes outside the registered areas are ignored.
+    ///
+    pub fn schedule_write(&self, at: Duration, area: u8, db_number: u16, start: usize, data: Vec<u8>) {
+        self.schedule(at, move |areas| {
+            let _ = areas.write_area(area, db_number, start, &data);
+        });
+    }
+
+    /// ### Runs an action periodically, starting one period after `start()`
+    ///
+    pub fn every<F>(&self, period: Duration, action: F)
+    where
+        F: FnMut(&PartnerAreas) + Send + 'static,
+    {
+        let period = period.max(MIN_PERIOD);
+        self.add_task(period, Some(period), Box::new(action));
+    }
+
+    fn add_task(&self, at: Duration, period: Option<Duration>, action: Action) {
+        self.tasks.lock().unwrap().push(Task { at, period, action });
+    }
+
+    /// ### Returns a channel receiving the connections and the accesses of the clients
+    ///
+    /// Must be called before `start()`.
+    ///
+    pub fn subscribe_events(&mut self) -> Receiver<PartnerEvent> {
+        self.partner.subscribe_events()
+    }
+
+    /// ### Starts the simulator
+    ///
+    /// ### Parameters
+    /// - `address`: Local address (e.g. "127.0.0.1:102", port 0 to let the OS choose).
+    ///
+    /// ### Errors
+    /// - Reported by `S7Partner::start()`
+    ///
+    pub fn start(&mut self, address: &str) -> Result<(), S7Error> {
+        self.partner.start(address)?;
+        self.stop.store(false, Ordering::SeqCst);
+
+        let tasks = self.tasks.clone();
+        let stop = self.stop.clone();
+        let areas = self.partner.areas();
+        let start_time = Instant::now();
+
+        self.script = Some(std::thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                let now = start_time.elapsed();
+                let mut next = now + SCRIPT_POLL;
+
+                tasks.lock().unwrap().retain_mut(|task| {
+                    if task.at <= now {
+                        (task.action)(&areas);
+                        match task.period {
+                            Some(period) => task.at += period,
+                            None => return false,
+                        }
+                    }
+                    next = next.min(task.at);
+                    true
+                });
+
+                std::thread::sleep(next.saturating_sub(start_time.elapsed()));
+            }
+        }));
+        Ok(())
+    }
+
+    /// ### Returns the local address once started
+    ///
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.partner.local_address()
+    }
+
+    /// ### Stops the script and the partner
+    ///
+    /// Called automatically on drop.
+    ///
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(script) = self.script.take() {
+            let _ = script.join();
+        }
+        self.partner.stop();
+    }
+}
+
+impl Default for S7Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for S7Simulator {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,221 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### SOCKS5 proxy (RFC 1928)
+//!
+//! Plant networks are often reachable only through a jump host exposing a SOCKS5 proxy (e.g.
+//! `ssh -D`). With `S7Client::set_proxy()` the client dials the proxy and asks it to open the TCP
+//! connection to the PLC; the ISO-on-TCP and S7 layers are unaffected.
+//!
+//! Only the CONNECT command is used. The authentication methods are "no authentication" and
+//! username/password (RFC 1929).
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::socks::ProxyAuth;
+//!
+//! let mut client = S7Client::new();
+//! client.set_proxy("jumphost.plant.local:1080", Some(ProxyAuth::new("operator", "secret"))).unwrap();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! ```
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::IpAddr;
+use crate::client::S7Error;
+
+/// Default SOCKS port, used if the proxy address has none
+pub const SOCKS_PORT: u16 = 1080;
+
+const SOCKS_VERSION: u8 = 0x05;
+const AUTH_VERSION: u8 = 0x01;
+const METHOD_NONE: u8 = 0x00;
+const METHOD_USER_PASSWORD: u8 = 0x02;
+const METHOD_NOT_ACCEPTABLE: u8 = 0xFF;
+const CMD_CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+/// ### Username/password credentials of the proxy (RFC 1929)
+///
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyAuth {
+    pub username: String,
+    pub password: String,
+}
+
+impl fmt::Debug for ProxyAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Keeps the password out of the logs
+        f.debug_struct("ProxyAuth")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+impl ProxyAuth {
+    /// ### Creates the credentials
+    ///
+    pub fn new(username: &str, password: &str) -> Self {
+        ProxyAuth {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+}
+
+/// SOCKS5 proxy settings of the client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Socks5Proxy {
+    /// Proxy address, "host:port" or "[IPv6]:port" (the port defaults to 1080)
+    pub address: String,
+    pub auth: Option<ProxyAuth>,
+}
+
+impl Socks5Proxy {
+    /// Returns the proxy address with the port
+    pub fn address_with_port(&self) -> String {
+        match split_host_port(&self.address, SOCKS_PORT) {
+            (host, port) if host.contains(':') => format!("[{}]:{}", host, port),
+            (host, port) => format!("{}:{}", host, port),
+        }
+    }
+}
+
+/// Splits "host:port", "[IPv6]:port", "[IPv6]", a bare IPv6 address or a host without port into
+/// host (without the brackets) and port
+pub(crate) fn split_host_port(address: &str, default_port: u16) -> (&str, u16) {
+    if let Some((host, rest)) = address.strip_prefix('[').and_then(|address| address.split_once(']')) {
+        let port = rest.strip_prefix(':').and_then(|port| port.parse().ok()).unwrap_or(default_port);
+        return (host, port);
+    }
+    match address.rsplit_once(':') {
+        // A host with more than one ':' is a bare IPv6 address, without port
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (host, port),
+            Err(_) => (address, default_port),
+        },
+        _ => (address, default_port),
+    }
+}
+
+fn proxy_error(message: &str) -> S7Error {
+    S7Error::Other(format!("SOCKS5 proxy: {}", message))
+}
+
+fn reply_text(reply: u8) -> &'static str {
+    match reply {
+        0x01 => "general failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+/// ### Asks the proxy to connect to `host:port`
+///
+/// ### Parameters
+/// - `stream`: The connection to the proxy, just opened.
+/// - `host`: Destination address. An IP address is sent as it is, a host name is resolved by the proxy.
+/// - `port`: Destination port.
+/// - `auth`: Credentials, or `None` if the proxy doesn't require authentication.
+///
+/// ### Returns
+/// `Ok(())` when the proxy connected to the destination: from now on `stream` carries the
+/// traffic to the destination.
+///
+/// ### Errors
+/// - `S7Error::InvalidFunParameter`: Host name, username or password longer than 255 byte.
+/// - `S7Error::Other`: The proxy refused the authentication or the connection (the message contains the reason).
+/// - `S7Error::Io`: I/O error.
+///
+pub fn socks5_connect<S: Read + Write + ?Sized>(stream: &mut S, host: &str, port: u16, auth: Option<&ProxyAuth>) -> Result<(), S7Error> {
+    // Method negotiation
+    let method = if auth.is_some() { METHOD_USER_PASSWORD } else { METHOD_NONE };
+    stream.write_all(&[SOCKS_VERSION, 0x01, method])?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(proxy_error("invalid reply, not a SOCKS5 proxy"));
+    }
+    match (reply[1], auth) {
+        (METHOD_NONE, _) => {}
+        (METHOD_USER_PASSWORD, Some(auth)) => authenticate(stream, auth)?,
+        (METHOD_NOT_ACCEPTABLE, _) => return Err(proxy_error("no acceptable authentication method")),
+        _ => return Err(proxy_error("unexpected authentication method")),
+    }
+
+    // Connect request
+    let mut request = vec![SOCKS_VERSION, CMD_CONNECT, 0x00];
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            if host.is_empty() || host.len() > u8::MAX as usize {
+                return Err(S7Error::InvalidFunParameter);
+            }
+            request.push(ATYP_DOMAIN);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    // Reply: VER REP RSV ATYP BND.ADDR BND.PORT
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header)?;
+    if header[0] != SOCKS_VERSION {
+        return Err(proxy_error("invalid reply"));
+    }
+    if header[1] != 0x00 {
+        return Err(proxy_error(reply_text(header[1])));
+    }
+    let address_len = match header[3] {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => return Err(proxy_error("invalid bound address type")),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound)?;
+    Ok(())
+}
+
+/// Username/password sub-negotiation (RFC 1929)
+fn authenticate<S: Read + Write + ?Sized>(stream: &mut S, auth: &ProxyAuth) -> Result<(), S7Error> {
+    let (username, password) = (auth.username.as_bytes(), auth.password.as_bytes());
+    if username.len() > u8::MAX as usize || password.len() > u8::MAX as usize {
+        return Err(S7Error::InvalidFunParameter);
+    }
+    let mut request = vec![AUTH_VERSION, username.len() as u8];
+    request.extend_from_slice(username);
+    request.push(password.len() as u8);
+    request.extend_from_slice(password);
+    stream.write_all(&request)?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[1] != 0x00 {
+        return Err(proxy_error("authentication failed"));
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### SSH tunnel (feature `ssh`)
+//!
+//! `SshTunnel` opens an SSH session to a gateway of the remote station and a direct-tcpip
+//! channel (the same as `ssh -L`) from the gateway to the PLC. The channel is a `Transport`, so
+//! the client runs over it with `connect_transport()`, without manual port forwards.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::ssh::{HostKeyCheck, SshAuth, SshConfig};
+//!
+//! let config = SshConfig {
+//!     address: "station12.example.com:22".to_string(),
+//!     username: "scada".to_string(),
+//!     auth: SshAuth::KeyFile { private_key: "/home/scada/.ssh/id_ed25519".into(), passphrase: None },
+//!     host_key: HostKeyCheck::KnownHosts("/home/scada/.ssh/known_hosts".into()),
+//! };
+//!
+//! let mut client = S7Client::new();
+//! client.connect_ssh(&config, "192.168.0.100", 0, 2).unwrap();
+//! ```
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::Duration;
+use ssh2::{Channel, CheckResult, HashType, KnownHostFileKind, Session};
+use crate::client::{S7Client, S7Error};
+use crate::socks::split_host_port;
+use crate::transport::Transport;
+
+/// Default SSH port, used if the gateway address has none
+pub const SSH_PORT: u16 = 22;
+
+/// ### Authentication method
+///
+#[derive(Clone, PartialEq, Eq)]
+pub enum SshAuth {
+    Password(String),
+    /// Private key file (OpenSSH or PEM format)
+    KeyFile { private_key: PathBuf, passphrase: Option<String> },
+    /// Keys of the running ssh-agent
+    Agent,
+}
+
+impl fmt::Debug for SshAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Keeps the secrets out of the logs
+        match self {
+            SshAuth::Password(_) => write!(f, "Password(***)"),
+            SshAuth::KeyFile { private_key, .. } => write!(f, "KeyFile({:?})", private_key),
+            SshAuth::Agent => write!(f, "Agent"),
+        }
+    }
+}
+
+/// ### Verification of the gateway host key
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostKeyCheck {
+    /// The key must be listed in an OpenSSH known_hosts file
+    KnownHosts(PathBuf),
+    /// SHA-256 digest of the key (the raw bytes of the "SHA256:..." fingerprint)
+    Fingerprint([u8; 32]),
+    /// Any key is accepted: the connection is exposed to man-in-the-middle attacks, test use only
+    AcceptAny,
+}
+
+/// ### SSH gateway settings
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshConfig {
+    /// Gateway address, "host:port" or "[IPv6]:port" (the port defaults to 22)
+    pub address: String,
+    pub username: String,
+    pub auth: SshAuth,
+    pub host_key: HostKeyCheck,
+}
+
+fn ssh_error(message: &str, error: ssh2::Error) -> S7Error {
+    S7Error::Other(format!("SSH: {}: {}", message, error))
+}
+
+/// ### Direct-tcpip channel to the PLC through an SSH gateway
+///
+pub struct SshTunnel {
+    session: Session,
+    channel: Channel,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+}
+
+impl SshTunnel {
+    /// ### Opens the SSH session and the channel to `host:port`
+    ///
+    /// ### Parameters
+    /// - `config`: SSH gateway settings.
+    /// - `host`: PLC address, as seen from the gateway.
+    /// - `port`: PLC port (102).
+    /// - `timeout`: Bounds the TCP connection to the gateway and each step of the SSH handshake.
+    ///
+    /// ### Errors
+    /// - `S7Error::TcpConnectionFailed`: Unresolvable gateway address.
+    /// - `S7Error::Other`: SSH handshake, host key verification, authentication or channel
+    ///   opening failed (the message contains the reason).
+    /// - `S7Error::Io`: TCP connection to the gateway failed.
+    ///
+    pub fn open(config: &SshConfig, host: &str, port: u16, timeout: Duration) -> Result<SshTunnel, S7Error> {
+        let (gateway_host, gateway_port) = split_host_port(&config.address, SSH_PORT);
+        let addr = (gateway_host, gateway_port).to_socket_addrs()?.next().ok_or(S7Error::TcpConnectionFailed)?;
+        let tcp = TcpStream::connect_timeout(&addr, timeout)?;
+        tcp.set_nodelay(true)?;
+
+        let mut session = Session::new().map_err(|e| ssh_error("session", e))?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(timeout.as_millis().min(u32::MAX as u128) as u32);
+        session.handshake().map_err(|e| ssh_error("handshake", e))?;
+
+        verify_host_key(&session, &config.host_key, gateway_host, gateway_port)?;
+
+        let result = match &config.auth {
+            SshAuth::Password(password) => session.userauth_password(&config.username, password),
+            SshAuth::KeyFile { private_key, passphrase } => {
+                session.userauth_pubkey_file(&config.username, None, private_key, passphrase.as_deref())
+            }
+            SshAuth::Agent => session.userauth_agent(&config.username),
+        };
+        result.map_err(|e| ssh_error("authentication failed", e))?;
+        if !session.authenticated() {
+            return Err(S7Error::Other("SSH: authentication failed".to_string()));
+        }
+
+        let channel = session.channel_direct_tcpip(host, port, None).map_err(|e| ssh_error("channel to the PLC", e))?;
+        Ok(SshTunnel {
+            session,
+            channel,
+            read_timeout: Some(timeout),
+            write_timeout: Some(timeout),
+        })
+    }
+
+    /// The session has a single timeout: the longest one is applied to reads and writes
+    fn apply_timeouts(&self) {
+        let timeout_ms = match (self.read_timeout, self.write_timeout) {
+            (Some(read), Some(write)) => read.max(write).as_millis().clamp(1, u32::MAX as u128) as u32,
+            _ => 0, // No timeout
+        };
+        self.session.set_timeout(timeout_ms);
+    }
+}
+
+fn verify_host_key(session: &Session, check: &HostKeyCheck, host: &str, port: u16) -> Result<(), S7Error> {
+    let mismatch = || S7Error::Other("SSH: host key verification failed".to_string());
+    match check {
+        HostKeyCheck::AcceptAny => Ok(()),
+        HostKeyCheck::Fingerprint(expected) => match session.host_key_hash(HashType::Sha256) {
+            Some(hash) if hash == expected => Ok(()),
+            _ => Err(mismatch()),
+        },
+        HostKeyCheck::KnownHosts(path) => {
+            let (key, _) = session.host_key().ok_or_else(mismatch)?;
+            let mut known_hosts = session.known_hosts().map_err(|e| ssh_error("known hosts", e))?;
+            known_hosts.read_file(path, KnownHostFileKind::OpenSSH).map_err(|e| ssh_error("known hosts file", e))?;
+            let result = if port == SSH_PORT {
+                known_hosts.check(host, key)
+            } else {
+                known_hosts.check_port(host, port, key)
+            };
+            match result {
+                CheckResult::Match => Ok(()),
+                _ => Err(mismatch()),
+            }
+        }
+    }
+}
+
+impl Read for SshTunnel {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.channel.read(buf)
+    }
+}
+
+impl Write for SshTunnel {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.channel.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.channel.flush()
+    }
+}
+
+impl Transport for SshTunnel {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.read_timeout = timeout;
+        self.apply_timeouts();
+        Ok(())
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.write_timeout = timeout;
+        self.apply_timeouts();
+        Ok(())
+    }
+
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()> {
+        match how {
+            Shutdown::Write => self.channel.send_eof()?,
+            Shutdown::Read | Shutdown::Both => {
+                self.channel.close()?;
+                let _ = self.session.disconnect(None, "closed", None);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl S7Client {
+    /// ### Connects to a PLC through an SSH gateway, using Rack and Slot
+    ///
+    /// Opens an `SshTunnel` to `ip` (the connection port of the client) and connects over it
+    /// as `connect_rack_slot()` does.
+    ///
+    /// ### Parameters
+    /// - `config`: SSH gateway settings.
+    /// - `ip`: PLC address, as seen from the gateway.
+    /// - `rack`, `slot`: CPU/CU Rack and Slot.
+    ///
+    /// ### Errors
+    /// - Reported by `SshTunnel::open()` (bounded by the connection timeout) and `connect_transport()`.
+    ///
+    pub fn connect_ssh(&mut self, config: &SshConfig, ip: &str, rack: u16, slot: u16) -> Result<(), S7Error> {
+        let (local_tsap, remote_tsap) = self.rack_slot_tsaps(rack, slot);
+        let tunnel = SshTunnel::open(config, ip, self.connection_port(), self.connection_timeout())?;
+        self.connect_transport(Box::new(tunnel), local_tsap, remote_tsap)
+    }
+}
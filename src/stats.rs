@@ -0,0 +1,226 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Traffic counters
+//!
+//! The client counts the telegrams and the bytes exchanged with the PLC, the failed operations and
+//! the requests sent to each memory area. The counters are cumulative across reconnections, see
+//! `S7Client::traffic_stats()` and `S7Client::reset_traffic_stats()`.
+//!
+//! With `S7Client::set_transfer_report()` the client also records the size and the duration of
+//! each chunk of the last `read_area()`/`write_area()`, to find which part of a large transfer is
+//! slow on a flaky link (see `S7Client::last_transfer_report()`).
+
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::frame::TPKT_LEN;
+use crate::transport::Transport;
+
+/// ### Snapshot of the traffic counters
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrafficStats {
+    /// Telegrams (TPKT frames) sent
+    pub frames_sent: u64,
+    /// Telegrams (TPKT frames) received
+    pub frames_received: u64,
+    /// Bytes sent (TPKT headers included)
+    pub bytes_sent: u64,
+    /// Bytes received (TPKT headers included)
+    pub bytes_received: u64,
+    /// Operations failed with a transport or protocol error, i.e. those requiring a retry or a reconnection
+    pub errors: u64,
+    /// Read/Write requests (PDUs) sent to each area
+    pub area_requests: BTreeMap<u8, u64>,
+}
+
+/// ### Timing of a chunk of a Read/Write transfer
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChunkTiming {
+    /// First element of the chunk, relative to the start of the transfer
+    pub offset: usize,
+    /// Elements transferred
+    pub size: usize,
+    /// Time from the request to the response, or to the error (ms)
+    pub time: f64,
+    /// Times the chunk was sent again after a failure
+    pub retries: u32,
+}
+
+/// ### Detailed report of the last `read_area()`/`write_area()`
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransferReport {
+    /// Chunks in transfer order; if the transfer failed, the last one is the failed chunk
+    pub chunks: Vec<ChunkTiming>,
+    /// True if the whole transfer succeeded
+    pub completed: bool,
+    /// Start of the chunk in progress
+    started: Option<Instant>,
+}
+
+impl TransferReport {
+    /// ### Returns the sum of the chunk times (ms)
+    ///
+    pub fn total_time(&self) -> f64 {
+        self.chunks.iter().map(|chunk| chunk.time).sum()
+    }
+
+    /// ### Returns the slowest chunk
+    ///
+    pub fn slowest(&self) -> Option<&ChunkTiming> {
+        self.chunks.iter().max_by(|a, b| a.time.total_cmp(&b.time))
+    }
+
+    /// Opens the timing of a chunk
+    pub(crate) fn begin_chunk(&mut self, offset: usize, size: usize) {
+        self.chunks.push(ChunkTiming { offset, size, ..Default::default() });
+        self.started = Some(Instant::now());
+    }
+
+    /// Counts a retry of the chunk in progress
+    pub(crate) fn retry(&mut self) {
+        if let Some(chunk) = self.chunks.last_mut() {
+            chunk.retries += 1;
+        }
+    }
+
+    /// Closes the timing of the chunk in progress
+    pub(crate) fn end_chunk(&mut self) {
+        if let (Some(started), Some(chunk)) = (self.started.take(), self.chunks.last_mut()) {
+            chunk.time = started.elapsed().as_secs_f64() * 1000.0;
+        }
+    }
+
+    /// Closes the transfer, timing the chunk interrupted by an error
+    pub(crate) fn finish(&mut self, completed: bool) {
+        self.end_chunk();
+        self.completed = completed;
+    }
+}
+
+/// Counters updated by `CountingTransport`
+#[derive(Debug, Default)]
+pub(crate) struct FrameCounters {
+    pub frames_sent: AtomicU64,
+    pub frames_received: AtomicU64,
+    pub bytes_sent: AtomicU64,
+    pub bytes_received: AtomicU64,
+}
+
+impl FrameCounters {
+    pub fn snapshot(&self) -> TrafficStats {
+        TrafficStats {
+            frames_sent: self.frames_sent.load(Ordering::Relaxed),
+            frames_received: self.frames_received.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+
+    pub fn reset(&self) {
+        self.frames_sent.store(0, Ordering::Relaxed);
+        self.frames_received.store(0, Ordering::Relaxed);
+        self.bytes_sent.store(0, Ordering::Relaxed);
+        self.bytes_received.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Follows the TPKT boundaries of a byte stream
+#[derive(Debug, Default)]
+struct FrameTracker {
+    header: [u8; TPKT_LEN],
+    header_len: usize,
+    remaining: usize,
+}
+
+impl FrameTracker {
+    /// Returns the number of frames completed by `data`
+    fn feed(&mut self, mut data: &[u8]) -> u64 {
+        let mut frames = 0;
+        while !data.is_empty() {
+            if self.header_len < TPKT_LEN {
+                let size = (TPKT_LEN - self.header_len).min(data.len());
+                self.header[self.header_len..self.header_len + size].copy_from_slice(&data[..size]);
+                self.header_len += size;
+                data = &data[size..];
+                if self.header_len == TPKT_LEN {
+                    let length = u16::from_be_bytes([self.header[2], self.header[3]]) as usize;
+                    self.remaining = length.saturating_sub(TPKT_LEN);
+                }
+            } else {
+                let size = self.remaining.min(data.len());
+                self.remaining -= size;
+                data = &data[size..];
+            }
+            if self.header_len == TPKT_LEN && self.remaining == 0 {
+                frames += 1;
+                self.header_len = 0;
+            }
+        }
+        frames
+    }
+}
+
+/// Transport decorator updating the frame counters
+pub(crate) struct CountingTransport {
+    inner: Box<dyn Transport>,
+    counters: Arc<FrameCounters>,
+    sent: FrameTracker,
+    received: FrameTracker,
+}
+
+impl CountingTransport {
+    pub fn new(inner: Box<dyn Transport>, counters: Arc<FrameCounters>) -> Self {
+        CountingTransport {
+            inner,
+            counters,
+            sent: FrameTracker::default(),
+            received: FrameTracker::default(),
+        }
+    }
+}
+
+impl Read for CountingTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.inner.read(buf)?;
+        self.counters.bytes_received.fetch_add(size as u64, Ordering::Relaxed);
+        let frames = self.received.feed(&buf[..size]);
+        self.counters.frames_received.fetch_add(frames, Ordering::Relaxed);
+        Ok(size)
+    }
+}
+
+impl Write for CountingTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let size = self.inner.write(buf)?;
+        self.counters.bytes_sent.fetch_add(size as u64, Ordering::Relaxed);
+        let frames = self.sent.feed(&buf[..size]);
+        self.counters.frames_sent.fetch_add(frames, Ordering::Relaxed);
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Transport for CountingTransport {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_read_timeout(timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_write_timeout(timeout)
+    }
+
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+}
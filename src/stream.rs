@@ -0,0 +1,116 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Subscriptions as async streams (feature `async`)
+//!
+//! `Subscription::into_stream()` polls a subscription through an `S7Worker` and delivers the
+//! changed values as a `futures::Stream<Item = TagUpdate>`. The stream is runtime-agnostic
+//! (tokio, async-std, smol, `futures::executor`...): the polling runs on a background thread and
+//! the updates are handed over through a `futures` channel.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use futures::StreamExt;
+//! use rust7::{S7Client, S7DataType, S7_AREA_DB};
+//! use rust7::tag::{Subscription, Tag};
+//! use rust7::worker::S7Worker;
+//!
+//! # async fn run() {
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! let worker = S7Worker::spawn(client);
+//!
+//! let mut subscription = Subscription::new(Duration::from_millis(500));
+//! subscription.add_tag(Tag::new("Speed", S7_AREA_DB, 100, 0, S7DataType::Real));
+//!
+//! let mut stream = subscription.into_stream(worker.handle());
+//! while let Some(update) = stream.next().await {
+//!     println!("{} = {}", update.name, update.value);
+//! }
+//! # }
+//! ```
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use futures_channel::mpsc::{self, UnboundedReceiver};
+use futures_core::Stream;
+use crate::client::S7Error;
+use crate::tag::{Subscription, TagUpdate};
+use crate::worker::S7WorkerHandle;
+
+/// ### Stream of the value changes of a subscription
+///
+/// Ends when the worker stops. Dropping the stream stops the polling.
+///
+pub struct SubscriptionStream {
+    receiver: UnboundedReceiver<TagUpdate>,
+    last_error: Arc<Mutex<Option<S7Error>>>,
+}
+
+impl SubscriptionStream {
+    /// ### Returns (and clears) the last poll error
+    ///
+    /// A failed poll doesn't end the stream: the subscription is polled again at the next
+    /// interval (e.g. after the application reconnected the client).
+    ///
+    pub fn take_error(&self) -> Option<S7Error> {
+        self.last_error.lock().unwrap().take()
+    }
+}
+
+impl Stream for SubscriptionStream {
+    type Item = TagUpdate;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<TagUpdate>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl Subscription {
+    /// ### Polls the subscription in background and returns the stream of the changed values
+    ///
+    /// ### Parameters
+    /// - `worker`: Handle of the worker owning the connection. Each poll is a job, so the same
+    ///   connection can serve other jobs and subscriptions in the meantime.
+    ///
+    /// ### Notes
+    /// The first poll reports all the tags, as `poll()` does. With a stale factor the stream also
+    /// delivers the updates of `check_stale()`, after each poll.
+    ///
+    pub fn into_stream(mut self, worker: S7WorkerHandle) -> SubscriptionStream {
+        let (sender, receiver) = mpsc::unbounded();
+        let last_error = Arc::new(Mutex::new(None));
+        let error = last_error.clone();
+
+        std::thread::spawn(move || {
+            while !sender.is_closed() {
+                self.wait();
+                let Ok((subscription, result)) = worker.execute(move |client| {
+                    let result = self.poll(client);
+                    Ok((self, result))
+                }).wait() else {
+                    break; // Worker stopped
+                };
+                self = subscription;
+
+                let mut updates = match result {
+                    Ok(updates) => updates,
+                    Err(e) => {
+                        *error.lock().unwrap() = Some(e);
+                        Vec::new()
+                    }
+                };
+                updates.extend(self.check_stale());
+                for update in updates {
+                    if sender.unbounded_send(update).is_err() {
+                        return; // Stream dropped
+                    }
+                }
+            }
+        });
+
+        SubscriptionStream { receiver, last_error }
+    }
+}
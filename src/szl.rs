@@ -0,0 +1,977 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### System Status Lists (SZL)
+//!
+//! The SZL are read-only lists, kept by the CPU, describing its state, its modules and its resources.
+//! Each list is identified by an ID and an index (see the Siemens "System Software for S7-300/400 -
+//! System and Standard Functions" manual for their layout).
+
+use std::fmt;
+use crate::client::{S7Client, S7Error, S7ErrorClass, S7ErrorCodes};
+use crate::frame::{S7UserDataParams, UD_GROUP_SZL, UD_METHOD_RESPONSE, UD_SUBFN_READ_SZL, UD_TYPE_REQUEST};
+
+const SZL_HEADER_LEN: usize = 8; // ID + Index + LENTHDR + N_DR
+const TS_OCTET_STRING: u8 = 0x09;
+const RES_SUCCESS: u8 = 0xFF;
+const RES_NOT_FOUND: u8 = 0x0A;
+/// Parts of a multi-part list, guards against a CPU that never ends it
+const MAX_SZL_PARTS: usize = 1024;
+
+/// SZL ID of the CPU operating mode (status of the module LEDs / mode transitions)
+pub const SZL_ID_CPU_STATUS: u16 = 0x0424;
+/// SZL ID of the status of all the CPU LEDs
+pub const SZL_ID_LED_ALL: u16 = 0x0074;
+/// SZL ID of the status of one CPU LED (Index = LED ID)
+pub const SZL_ID_LED: u16 = 0x0174;
+
+// LED IDs (low byte of the `cpu_led_id` field of the SZL 0x0074 records)
+pub const LED_SF: u8 = 0x01;     // Group error
+pub const LED_INTF: u8 = 0x02;   // Internal error
+pub const LED_EXTF: u8 = 0x03;   // External error
+pub const LED_RUN: u8 = 0x04;
+pub const LED_STOP: u8 = 0x05;
+pub const LED_FRCE: u8 = 0x06;   // Force job active
+pub const LED_CRST: u8 = 0x07;   // Restart
+pub const LED_BAF: u8 = 0x08;    // Battery fault / overload
+pub const LED_USR: u8 = 0x09;
+pub const LED_USR1: u8 = 0x0A;
+pub const LED_BUS1F: u8 = 0x0B;  // Bus error interface 1
+pub const LED_BUS2F: u8 = 0x0C;  // Bus error interface 2
+pub const LED_REDF: u8 = 0x0D;   // Redundancy error
+pub const LED_MSTR: u8 = 0x0E;   // Master (H CPUs)
+pub const LED_RACK0: u8 = 0x0F;
+pub const LED_RACK1: u8 = 0x10;
+pub const LED_RACK2: u8 = 0x11;
+pub const LED_IFM1F: u8 = 0x12;  // Interface error module 1
+pub const LED_IFM2F: u8 = 0x13;  // Interface error module 2
+pub const LED_BUS3F: u8 = 0x14;  // Bus error interface 3
+pub const LED_MAINT: u8 = 0x15;  // Maintenance request
+pub const LED_DC24V: u8 = 0x16;
+
+// LEDs queried one by one when the CPU doesn't have the SZL 0x0074
+const MAIN_LEDS: [u8; 4] = [LED_RUN, LED_STOP, LED_SF, LED_FRCE];
+
+/// SZL ID of the memory areas (work, load, backup memory)
+pub const SZL_ID_MEMORY_AREAS: u16 = 0x0013;
+
+// Memory area IDs (`index` field of the SZL 0x0013 records)
+pub const MEM_WORK: u16 = 0x0001;
+pub const MEM_LOAD_INTEGRATED: u16 = 0x0002;
+pub const MEM_LOAD_PLUGGED: u16 = 0x0003;
+pub const MEM_LOAD_MAX_PLUGGED: u16 = 0x0004; // Max size of the plug-in load memory
+pub const MEM_BACKUP: u16 = 0x0005;
+
+const MEMORY_RECORD_LEN: usize = 36;
+
+/// SZL ID of the communication capability parameters (Index 0x0001: general communication data)
+pub const SZL_ID_COMM_PARAMS: u16 = 0x0131;
+/// SZL ID of the communication status data (Index 0x0001: connection resources)
+pub const SZL_ID_COMM_STATUS: u16 = 0x0132;
+
+const COMM_GENERAL_INDEX: u16 = 0x0001;
+const COMM_RECORD_MIN_LEN: usize = 20;
+
+/// SZL ID of the module identification (order number, hardware and firmware version)
+pub const SZL_ID_MODULE_IDENT: u16 = 0x0011;
+/// SZL ID of the component identification (names, serial number)
+pub const SZL_ID_COMPONENT_IDENT: u16 = 0x001C;
+
+// Records of the SZL 0x0011 (28 byte: index, MLFB[20], BGTyp, Ausbg, Ausbe)
+const IDENT_MODULE: u16 = 0x0001;
+const IDENT_HARDWARE: u16 = 0x0006;
+const IDENT_FIRMWARE: u16 = 0x0007;
+const IDENT_RECORD_LEN: usize = 28;
+
+// Records of the SZL 0x001C (34 byte: index + 32 byte text)
+const COMPONENT_MODULE_NAME: u16 = 0x0002;
+const COMPONENT_PLANT_ID: u16 = 0x0003;
+const COMPONENT_SERIAL_NUMBER: u16 = 0x0005;
+const COMPONENT_MODULE_TYPE: u16 = 0x0007;
+const COMPONENT_RECORD_MIN_LEN: usize = 26;
+
+/// SZL ID of the communication status data (Index 0x0004: protection level and mode selector)
+pub const SZL_ID_PROTECTION: u16 = 0x0232;
+const PROTECTION_INDEX: u16 = 0x0004;
+const PROTECTION_RECORD_MIN_LEN: usize = 12;
+
+// Protection levels (`switch_level`, `param_level`, `level`)
+pub const PROTECTION_NONE: u16 = 1;
+pub const PROTECTION_WRITE: u16 = 2;
+pub const PROTECTION_READ_WRITE: u16 = 3;
+
+// Positions of the mode selector (`mode_selector`, 0 = unknown)
+pub const SELECTOR_RUN: u16 = 1;
+pub const SELECTOR_RUN_P: u16 = 2;
+pub const SELECTOR_STOP: u16 = 3;
+pub const SELECTOR_MRES: u16 = 4;
+
+/// ### SZL list read from the CPU
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SzlList {
+    /// SZL ID
+    pub id: u16,
+    /// SZL Index
+    pub index: u16,
+    /// Length of each record (LENTHDR)
+    pub record_len: u16,
+    /// Number of records (N_DR)
+    pub record_count: u16,
+    /// Records, one after the other
+    pub data: Vec<u8>,
+}
+
+impl SzlList {
+    /// ### Returns an iterator over the records of the list
+    ///
+    pub fn records(&self) -> impl Iterator<Item = &[u8]> {
+        self.data.chunks_exact(self.record_len.max(1) as usize)
+    }
+}
+
+/// ### CPU operating mode
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlcStatus {
+    Unknown,
+    Stop,
+    Startup,
+    Run,
+}
+
+impl PlcStatus {
+    /// ### Maps the operating mode code of the SZL 0x0424 record
+    ///
+    pub fn from_mode(mode: u8) -> Self {
+        match mode & 0x0F {
+            0x01..=0x04 => PlcStatus::Stop,
+            0x05..=0x07 => PlcStatus::Startup,
+            0x08 => PlcStatus::Run,
+            _ => PlcStatus::Unknown,
+        }
+    }
+
+    /// ### Returns the operating mode code (as in the SZL 0x0424 record)
+    ///
+    pub fn mode(&self) -> u8 {
+        match self {
+            PlcStatus::Unknown => 0x00,
+            PlcStatus::Stop => 0x04,
+            PlcStatus::Startup => 0x06,
+            PlcStatus::Run => 0x08,
+        }
+    }
+}
+
+impl fmt::Display for PlcStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlcStatus::Unknown => write!(f, "UNKNOWN"),
+            PlcStatus::Stop => write!(f, "STOP"),
+            PlcStatus::Startup => write!(f, "STARTUP"),
+            PlcStatus::Run => write!(f, "RUN"),
+        }
+    }
+}
+
+/// ### State of a CPU LED
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedState {
+    Off,
+    On,
+    /// Flashing at 2 Hz
+    Blinking,
+    /// Flashing at 0.5 Hz
+    BlinkingSlow,
+}
+
+impl LedState {
+    /// ### Maps the `led_on` and `led_blink` bytes of the SZL 0x0074 record
+    ///
+    pub fn from_szl(led_on: u8, led_blink: u8) -> Self {
+        match led_blink {
+            0x01 => LedState::Blinking,
+            0x02 => LedState::BlinkingSlow,
+            _ if led_on != 0 => LedState::On,
+            _ => LedState::Off,
+        }
+    }
+
+    /// ### Returns true if the LED is lit or flashing
+    ///
+    pub fn is_lit(&self) -> bool {
+        *self != LedState::Off
+    }
+}
+
+impl fmt::Display for LedState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedState::Off => write!(f, "OFF"),
+            LedState::On => write!(f, "ON"),
+            LedState::Blinking => write!(f, "BLINKING"),
+            LedState::BlinkingSlow => write!(f, "BLINKING SLOW"),
+        }
+    }
+}
+
+/// ### A CPU LED
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuLed {
+    /// `cpu_led_id`: rack number (bits 8..10, H CPUs) and LED ID (low byte, `LED_XXX`)
+    pub id: u16,
+    pub state: LedState,
+}
+
+impl CpuLed {
+    /// ### LED ID (`LED_XXX`)
+    ///
+    pub fn led(&self) -> u8 {
+        (self.id & 0x00FF) as u8
+    }
+
+    /// ### Rack of the CPU (H systems, 0 otherwise)
+    ///
+    pub fn rack(&self) -> u8 {
+        ((self.id >> 8) & 0x07) as u8
+    }
+
+    /// ### Label printed on the CPU front panel (empty for unknown IDs)
+    ///
+    pub fn name(&self) -> &'static str {
+        match self.led() {
+            LED_SF => "SF",
+            LED_INTF => "INTF",
+            LED_EXTF => "EXTF",
+            LED_RUN => "RUN",
+            LED_STOP => "STOP",
+            LED_FRCE => "FRCE",
+            LED_CRST => "CRST",
+            LED_BAF => "BAF",
+            LED_USR => "USR",
+            LED_USR1 => "USR1",
+            LED_BUS1F => "BUS1F",
+            LED_BUS2F => "BUS2F",
+            LED_REDF => "REDF",
+            LED_MSTR => "MSTR",
+            LED_RACK0 => "RACK0",
+            LED_RACK1 => "RACK1",
+            LED_RACK2 => "RACK2",
+            LED_IFM1F => "IFM1F",
+            LED_IFM2F => "IFM2F",
+            LED_BUS3F => "BUS3F",
+            LED_MAINT => "MAINT",
+            LED_DC24V => "DC24V",
+            _ => "",
+        }
+    }
+}
+
+/// ### Status of the CPU front panel LEDs
+///
+/// The LEDs present depend on the CPU: `run()`, `stop()`, `error()` and `force()` return `None`
+/// when the CPU doesn't have (or doesn't report) the LED.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LedStatus {
+    /// LEDs in the order reported by the CPU
+    pub leds: Vec<CpuLed>,
+}
+
+impl LedStatus {
+    /// ### Decodes an SZL 0x0074 or 0x0174 list
+    ///
+    /// Record: [0] `cpu_led_id` (2), [2] `led_on`, [3] `led_blink`
+    ///
+    pub fn from_szl(szl: &SzlList) -> Self {
+        let leds = szl.records()
+            .filter(|record| record.len() >= 4)
+            .map(|record| CpuLed {
+                id: u16::from_be_bytes([record[0], record[1]]),
+                state: LedState::from_szl(record[2], record[3]),
+            })
+            .collect();
+        LedStatus { leds }
+    }
+
+    /// ### State of a LED (first rack reporting it)
+    ///
+    /// ### Parameters
+    /// - `led`: LED ID (`LED_XXX`).
+    ///
+    pub fn get(&self, led: u8) -> Option<LedState> {
+        self.leds.iter().find(|l| l.led() == led).map(|l| l.state)
+    }
+
+    /// ### RUN LED
+    ///
+    pub fn run(&self) -> Option<LedState> {
+        self.get(LED_RUN)
+    }
+
+    /// ### STOP LED
+    ///
+    pub fn stop(&self) -> Option<LedState> {
+        self.get(LED_STOP)
+    }
+
+    /// ### Error LED
+    ///
+    /// SF (group error) or, on the CPUs without it, the first lit among INTF and EXTF.
+    ///
+    pub fn error(&self) -> Option<LedState> {
+        if let Some(state) = self.get(LED_SF) {
+            return Some(state);
+        }
+        let faults: Vec<LedState> = [LED_INTF, LED_EXTF].iter().filter_map(|&led| self.get(led)).collect();
+        faults.iter().find(|state| state.is_lit()).or(faults.first()).copied()
+    }
+
+    /// ### FRCE LED (force job active)
+    ///
+    pub fn force(&self) -> Option<LedState> {
+        self.get(LED_FRCE)
+    }
+}
+
+impl fmt::Display for LedStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, led) in self.leds.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match led.name() {
+                "" => write!(f, "LED 0x{:02X}", led.led())?,
+                name => write!(f, "{}", name)?,
+            }
+            write!(f, ": {}", led.state)?;
+        }
+        Ok(())
+    }
+}
+
+/// ### A memory area of the CPU (SZL 0x0013 record)
+///
+/// The area is split in a volatile and a retentive part, each with its size, the bytes in use
+/// and the largest free block.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryArea {
+    /// Area ID (`MEM_WORK`, `MEM_LOAD_INTEGRATED`...)
+    pub index: u16,
+    /// Memory type: 1 = volatile (RAM), 2 = non-volatile (FEPROM), 3 = mixed
+    pub code: u16,
+    /// Total size (byte)
+    pub size: u32,
+    /// Logical mode of the area (bit 0 volatile, bit 1 retentive, bit 2 mixed, bit 3 code, bit 4 data)
+    pub mode: u16,
+    pub volatile_size: u32,
+    pub volatile_used: u32,
+    pub volatile_largest_free: u32,
+    pub retentive_size: u32,
+    pub retentive_used: u32,
+    pub retentive_largest_free: u32,
+}
+
+impl MemoryArea {
+    /// ### Decodes a 36 byte record
+    ///
+    /// ```text
+    /// [0] index (2)  [2] code (2)  [4] size (4)  [8] mode (2)  [10] granu (2)
+    /// [12] ber1 (4)  [16] belegt1 (4)  [20] block1 (4)
+    /// [24] ber2 (4)  [28] belegt2 (4)  [32] block2 (4)
+    /// ```
+    pub fn from_record(record: &[u8]) -> Option<Self> {
+        if record.len() < MEMORY_RECORD_LEN {
+            return None;
+        }
+        let word = |i: usize| u16::from_be_bytes([record[i], record[i + 1]]);
+        let dword = |i: usize| u32::from_be_bytes([record[i], record[i + 1], record[i + 2], record[i + 3]]);
+        Some(MemoryArea {
+            index: word(0),
+            code: word(2),
+            size: dword(4),
+            mode: word(8),
+            volatile_size: dword(12),
+            volatile_used: dword(16),
+            volatile_largest_free: dword(20),
+            retentive_size: dword(24),
+            retentive_used: dword(28),
+            retentive_largest_free: dword(32),
+        })
+    }
+
+    /// ### Bytes in use (volatile + retentive)
+    ///
+    pub fn used(&self) -> u64 {
+        self.volatile_used as u64 + self.retentive_used as u64
+    }
+
+    /// ### Usable bytes (volatile + retentive, or the total size if the parts are not reported)
+    ///
+    pub fn capacity(&self) -> u64 {
+        match self.volatile_size as u64 + self.retentive_size as u64 {
+            0 => self.size as u64,
+            parts => parts,
+        }
+    }
+
+    /// ### Free bytes
+    ///
+    pub fn free(&self) -> u64 {
+        self.capacity().saturating_sub(self.used())
+    }
+
+    /// ### Percentage in use (0.0 for an empty or unreported area)
+    ///
+    pub fn usage_percent(&self) -> f64 {
+        match self.capacity() {
+            0 => 0.0,
+            capacity => self.used() as f64 * 100.0 / capacity as f64,
+        }
+    }
+}
+
+/// ### Memory areas of the CPU
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryInfo {
+    /// Areas in the order reported by the CPU
+    pub areas: Vec<MemoryArea>,
+}
+
+impl MemoryInfo {
+    /// ### Decodes an SZL 0x0013 list
+    ///
+    pub fn from_szl(szl: &SzlList) -> Self {
+        MemoryInfo { areas: szl.records().filter_map(MemoryArea::from_record).collect() }
+    }
+
+    /// ### Returns an area by ID (`MEM_XXX`)
+    ///
+    pub fn area(&self, index: u16) -> Option<&MemoryArea> {
+        self.areas.iter().find(|area| area.index == index)
+    }
+
+    /// ### Work memory
+    ///
+    pub fn work_memory(&self) -> Option<&MemoryArea> {
+        self.area(MEM_WORK)
+    }
+
+    /// ### Load memory: the plugged memory card if present (not empty), the integrated one otherwise
+    ///
+    pub fn load_memory(&self) -> Option<&MemoryArea> {
+        self.area(MEM_LOAD_PLUGGED)
+            .filter(|area| area.capacity() > 0)
+            .or_else(|| self.area(MEM_LOAD_INTEGRATED))
+    }
+}
+
+/// ### Connection resources of the CPU
+///
+/// Limits from the SZL 0x0131 (Index 1) and usage from the SZL 0x0132 (Index 1). A connection is
+/// refused when `free_connections` is 0: the PG/OP reserved connections can be taken only by
+/// the corresponding connection type.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommResources {
+    /// Max PDU size (byte)
+    pub max_pdu: u16,
+    /// Max number of connections
+    pub max_connections: u16,
+    /// MPI transmission rate (bit/s)
+    pub mpi_baudrate: u32,
+    /// Communication bus transmission rate (bit/s)
+    pub kbus_baudrate: u32,
+    /// Connections reserved for PG
+    pub reserved_pg: u16,
+    /// Connections reserved for OP/OS
+    pub reserved_op: u16,
+    /// PG connections in use
+    pub used_pg: u16,
+    /// OP/OS connections in use
+    pub used_op: u16,
+    /// Configured connections
+    pub configured: u16,
+    /// Configured connections established
+    pub configured_established: u16,
+    /// Free connections
+    pub free_connections: u16,
+    /// Free connections in use
+    pub free_used: u16,
+    /// Max communication load of the CPU (%)
+    pub max_comm_load: u16,
+}
+
+impl CommResources {
+    /// ### Decodes the records of the SZL 0x0131 and 0x0132 (Index 1)
+    ///
+    /// ```text
+    /// 0x0131: [0] index  [2] pdu  [4] anz  [6] mpi_bps (4)  [10] kbus_bps (4)
+    /// 0x0132: [0] index  [2] res_pg  [4] res_os  [6] u_pg  [8] u_os  [10] proj
+    ///         [12] auf  [14] free  [16] used  [18] last
+    /// ```
+    pub fn from_records(params: &[u8], status: &[u8]) -> Option<Self> {
+        if params.len() < 14 || status.len() < COMM_RECORD_MIN_LEN {
+            return None;
+        }
+        let word = |r: &[u8], i: usize| u16::from_be_bytes([r[i], r[i + 1]]);
+        let dword = |r: &[u8], i: usize| u32::from_be_bytes([r[i], r[i + 1], r[i + 2], r[i + 3]]);
+        Some(CommResources {
+            max_pdu: word(params, 2),
+            max_connections: word(params, 4),
+            mpi_baudrate: dword(params, 6),
+            kbus_baudrate: dword(params, 10),
+            reserved_pg: word(status, 2),
+            reserved_op: word(status, 4),
+            used_pg: word(status, 6),
+            used_op: word(status, 8),
+            configured: word(status, 10),
+            configured_established: word(status, 12),
+            free_connections: word(status, 14),
+            free_used: word(status, 16),
+            max_comm_load: word(status, 18),
+        })
+    }
+
+    /// ### Connections in use (PG + OP + configured + free)
+    ///
+    pub fn used_connections(&self) -> u16 {
+        self.used_pg
+            .saturating_add(self.used_op)
+            .saturating_add(self.configured_established)
+            .saturating_add(self.free_used)
+    }
+
+    /// ### Free connections still available to a new client
+    ///
+    pub fn available(&self) -> u16 {
+        self.free_connections.saturating_sub(self.free_used)
+    }
+}
+
+/// ### Protection of the CPU
+///
+/// Record of the SZL 0x0232 (Index 4). The valid protection level (`level`) combines the one set
+/// by the mode selector (key switch, e.g. RUN = write protection) and the one assigned in the
+/// hardware configuration; a level reached by a password (legitimation) is not reflected.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Protection {
+    /// Protection level set by the mode selector (`PROTECTION_XXX`)
+    pub switch_level: u16,
+    /// Protection level assigned in the hardware configuration (0 = not assigned)
+    pub param_level: u16,
+    /// Valid protection level of the CPU
+    pub level: u16,
+    /// Position of the mode selector (`SELECTOR_XXX`, 0 = unknown)
+    pub mode_selector: u16,
+    /// Position of the startup switch (1 = CRST, 2 = WRST, 0 = unknown)
+    pub startup_switch: u16,
+}
+
+impl Protection {
+    /// ### Decodes a record of the SZL 0x0232 (Index 4)
+    ///
+    /// ```text
+    /// [0] index  [2] sch_schal  [4] sch_par  [6] sch_rel  [8] bart_sch  [10] anl_sch
+    /// ```
+    pub fn from_record(record: &[u8]) -> Option<Self> {
+        if record.len() < PROTECTION_RECORD_MIN_LEN {
+            return None;
+        }
+        let word = |i: usize| u16::from_be_bytes([record[i], record[i + 1]]);
+        Some(Protection {
+            switch_level: word(2),
+            param_level: word(4),
+            level: word(6),
+            mode_selector: word(8),
+            startup_switch: word(10),
+        })
+    }
+
+    /// ### True if the writes are refused (protection level 2 or 3)
+    ///
+    pub fn write_protected(&self) -> bool {
+        self.level >= PROTECTION_WRITE
+    }
+
+    /// ### True if the reads are refused as well (protection level 3)
+    ///
+    pub fn read_protected(&self) -> bool {
+        self.level >= PROTECTION_READ_WRITE
+    }
+
+    /// ### Position of the mode selector, e.g. "RUN-P"
+    ///
+    pub fn mode_selector_name(&self) -> &'static str {
+        match self.mode_selector {
+            SELECTOR_RUN => "RUN",
+            SELECTOR_RUN_P => "RUN-P",
+            SELECTOR_STOP => "STOP",
+            SELECTOR_MRES => "MRES",
+            _ => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Protection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.level {
+            PROTECTION_NONE => "no protection",
+            PROTECTION_WRITE => "write protection",
+            PROTECTION_READ_WRITE => "read/write protection",
+            _ => "unknown",
+        };
+        write!(f, "protection level {} ({}), mode selector {}", self.level, level, self.mode_selector_name())
+    }
+}
+
+/// ### Identification of the CPU
+///
+/// The fields the CPU doesn't report are `None`.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleIdent {
+    /// Order number (MLFB), e.g. "6ES7 315-2EH14-0AB0"
+    pub order_number: String,
+    /// Hardware product version
+    pub hardware_version: Option<u16>,
+    /// Firmware version, e.g. "V3.2.6"
+    pub firmware_version: Option<String>,
+    /// Serial number of the module
+    pub serial_number: Option<String>,
+    /// Module type name, e.g. "CPU 315-2 PN/DP"
+    pub module_type: Option<String>,
+    /// Module name set in the hardware configuration
+    pub module_name: Option<String>,
+    /// Plant designation (module tag)
+    pub plant_id: Option<String>,
+}
+
+/// Text field of an SZL record, without the padding spaces and NULs
+fn szl_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_matches(|c: char| c == ' ' || c == '\0').to_string()
+}
+
+impl ModuleIdent {
+    /// ### Decodes the SZL 0x0011 and, if available, 0x001C lists
+    ///
+    /// Returns `None` if the SZL 0x0011 has no module record.
+    ///
+    pub fn from_szl(ident: &SzlList, component: Option<&SzlList>) -> Option<Self> {
+        let record = |index: u16| ident.records()
+            .find(|record| record.len() >= IDENT_RECORD_LEN && u16::from_be_bytes([record[0], record[1]]) == index);
+        let module = record(IDENT_MODULE)?;
+
+        // Ausbe = hardware version; for the firmware: Ausbg = 'V' + major, Ausbe = minor + patch
+        let hardware_version = record(IDENT_HARDWARE).or(Some(module))
+            .map(|record| u16::from_be_bytes([record[26], record[27]]));
+        let firmware_version = record(IDENT_FIRMWARE)
+            .filter(|record| record[24] == b'V')
+            .map(|record| format!("V{}.{}.{}", record[25], record[26], record[27]));
+
+        let text = |index: u16| component?.records()
+            .find(|record| record.len() >= COMPONENT_RECORD_MIN_LEN && u16::from_be_bytes([record[0], record[1]]) == index)
+            .map(|record| szl_text(&record[2..]))
+            .filter(|text| !text.is_empty());
+
+        Some(ModuleIdent {
+            order_number: szl_text(&module[2..22]),
+            hardware_version,
+            firmware_version,
+            serial_number: text(COMPONENT_SERIAL_NUMBER),
+            module_type: text(COMPONENT_MODULE_TYPE),
+            module_name: text(COMPONENT_MODULE_NAME),
+            plant_id: text(COMPONENT_PLANT_ID),
+        })
+    }
+}
+
+/// Checks the return code of an SZL response and returns its data (Length bytes after the header)
+fn szl_payload(data: &[u8]) -> Result<&[u8], S7Error> {
+    // Return code + Transport size + Length
+    if data.len() < 4 {
+        return Err(S7Error::IsoInvalidTelegram);
+    }
+    match data[0] {
+        RES_SUCCESS => {}
+        RES_NOT_FOUND => return Err(S7Error::S7NotFound),
+        code => return Err(S7Error::S7Unspecified(S7ErrorCodes::item(code))),
+    }
+    let length = (u16::from_be_bytes([data[2], data[3]]) as usize).min(data.len() - 4);
+    Ok(&data[4..4 + length])
+}
+
+impl S7Client {
+    /// ### Reads a System Status List
+    ///
+    /// ### Parameters
+    /// - `id`: SZL ID (e.g. `SZL_ID_CPU_STATUS`).
+    /// - `index`: SZL Index.
+    ///
+    /// ### Returns
+    /// `Ok(<SzlList>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`: The client is not connected.
+    /// - `S7Error::S7NotFound`: The CPU doesn't have the requested list.
+    /// - `S7Error::IsoInvalidTelegram`: Inconsistent response.
+    /// - `S7Error::S7Unspecified`: The CPU refused the request.
+    /// - `S7Error::Io`: network I/O error.
+    ///
+    /// ### Notes
+    /// Lists longer than a PDU are sent in parts: the following parts are requested until the CPU
+    /// marks the last one, and `record_count` is set to the records of the whole list.
+    ///
+    pub fn read_szl(&mut self, id: u16, index: u16) -> Result<SzlList, S7Error> {
+        let params = S7UserDataParams::request(UD_GROUP_SZL, UD_SUBFN_READ_SZL);
+        let mut request = vec![RES_SUCCESS, TS_OCTET_STRING, 0x00, 0x04];
+        request.extend_from_slice(&id.to_be_bytes());
+        request.extend_from_slice(&index.to_be_bytes());
+
+        let (mut res_params, data) = self.userdata(&params, &request)?;
+        let payload = szl_payload(&data)?;
+        if payload.len() < SZL_HEADER_LEN {
+            return Err(S7Error::IsoInvalidTelegram);
+        }
+
+        let mut szl = SzlList {
+            id: u16::from_be_bytes([payload[0], payload[1]]),
+            index: u16::from_be_bytes([payload[2], payload[3]]),
+            record_len: u16::from_be_bytes([payload[4], payload[5]]),
+            record_count: u16::from_be_bytes([payload[6], payload[7]]),
+            data: payload[SZL_HEADER_LEN..].to_vec(),
+        };
+
+        // Long lists (e.g. the diagnostic buffer) span more PDUs: the following parts contain only
+        // records, requested until the CPU marks the last one
+        let mut parts = 1;
+        while res_params.more_data() {
+            if parts == MAX_SZL_PARTS {
+                return Err(S7Error::IsoInvalidTelegram);
+            }
+            let follow_up = S7UserDataParams {
+                method: UD_METHOD_RESPONSE,
+                type_group: UD_TYPE_REQUEST | UD_GROUP_SZL,
+                subfunction: UD_SUBFN_READ_SZL,
+                sequence: res_params.sequence,
+                data_unit_ref: 0,
+                last_data_unit: 0,
+                error_code: 0,
+                extended: true,
+            };
+            let (params, data) = self.userdata(&follow_up, &[RES_NOT_FOUND, 0x00, 0x00, 0x00])?;
+            szl.data.extend_from_slice(szl_payload(&data)?);
+            res_params = params;
+            parts += 1;
+        }
+        if parts > 1 && szl.record_len > 0 {
+            // N_DR of the first part counts only its own records
+            szl.record_count = (szl.data.len() / szl.record_len as usize).min(u16::MAX as usize) as u16;
+        }
+        Ok(szl)
+    }
+
+    /// ### Returns the operating mode of the CPU
+    ///
+    /// ### Returns
+    /// `Ok(<PlcStatus>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `read_szl()`
+    ///
+    pub fn get_plc_status(&mut self) -> Result<PlcStatus, S7Error> {
+        let szl = self.read_szl(SZL_ID_CPU_STATUS, 0x0000)?;
+
+        // Record: [0] Event ID (2), [2] Reserved, [3] Mode transition ID (low nibble = current mode)
+        let status = match szl.records().next() {
+            Some(record) if record.len() >= 4 => PlcStatus::from_mode(record[3]),
+            _ => PlcStatus::Unknown,
+        };
+        Ok(status)
+    }
+
+    /// ### Returns the status of the CPU front panel LEDs
+    ///
+    /// Reads the SZL 0x0074 (all LEDs); if the CPU doesn't have it, RUN, STOP, SF and FRCE are
+    /// read one by one with the SZL 0x0174, skipping the ones the CPU doesn't have.
+    ///
+    /// ### Returns
+    /// `Ok(<LedStatus>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::S7NotFound`: The CPU has neither list.
+    /// - Reported by `read_szl()`
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::S7Client;
+    ///
+    /// let mut client = S7Client::new();
+    /// client.connect_rack_slot("192.168.0.100", 0, 2).unwrap();
+    /// let leds = client.get_led_status().unwrap();
+    /// if leds.error().is_some_and(|state| state.is_lit()) {
+    ///     println!("CPU fault: {}", leds);
+    /// }
+    /// ```
+    ///
+    pub fn get_led_status(&mut self) -> Result<LedStatus, S7Error> {
+        match self.read_szl(SZL_ID_LED_ALL, 0x0000) {
+            Ok(szl) => return Ok(LedStatus::from_szl(&szl)),
+            Err(S7Error::S7NotFound) => {}
+            Err(e) => return Err(e),
+        }
+        let mut status = LedStatus::default();
+        for led in MAIN_LEDS {
+            match self.read_szl(SZL_ID_LED, led as u16) {
+                Ok(szl) => status.leds.extend(LedStatus::from_szl(&szl).leds),
+                Err(S7Error::S7NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if status.leds.is_empty() {
+            return Err(S7Error::S7NotFound);
+        }
+        Ok(status)
+    }
+
+    /// ### Returns the state of a single CPU LED (SZL 0x0174)
+    ///
+    /// ### Parameters
+    /// - `led`: LED ID (`LED_XXX`).
+    ///
+    /// ### Returns
+    /// `Ok(<LedState>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::S7NotFound`: The CPU doesn't have the LED.
+    /// - Reported by `read_szl()`
+    ///
+    pub fn get_led(&mut self, led: u8) -> Result<LedState, S7Error> {
+        let szl = self.read_szl(SZL_ID_LED, led as u16)?;
+        LedStatus::from_szl(&szl).get(led).ok_or(S7Error::S7NotFound)
+    }
+
+    /// ### Returns the size and the usage of the CPU memory areas (SZL 0x0013)
+    ///
+    /// Checking the free work and load memory before a download avoids failing halfway.
+    ///
+    /// ### Returns
+    /// `Ok(<MemoryInfo>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `read_szl()`
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::S7Client;
+    ///
+    /// let mut client = S7Client::new();
+    /// client.connect_rack_slot("192.168.0.100", 0, 2).unwrap();
+    /// let memory = client.get_memory_info().unwrap();
+    /// if let Some(work) = memory.work_memory() {
+    ///     println!("Work memory: {} of {} byte used ({:.1}%)", work.used(), work.capacity(), work.usage_percent());
+    /// }
+    /// ```
+    ///
+    pub fn get_memory_info(&mut self) -> Result<MemoryInfo, S7Error> {
+        let szl = self.read_szl(SZL_ID_MEMORY_AREAS, 0x0000)?;
+        Ok(MemoryInfo::from_szl(&szl))
+    }
+
+    /// ### Returns the connection resources of the CPU (SZL 0x0131/0x0132, Index 1)
+    ///
+    /// Tells why a connection is refused: all the free connections taken, or none left for
+    /// the connection type requested.
+    ///
+    /// ### Returns
+    /// `Ok(<CommResources>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Records too short.
+    /// - Reported by `read_szl()`
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::S7Client;
+    ///
+    /// let mut client = S7Client::new();
+    /// client.connect_rack_slot("192.168.0.100", 0, 2).unwrap();
+    /// let res = client.get_comm_resources().unwrap();
+    /// println!("{} of {} connections in use, {} available", res.used_connections(), res.max_connections, res.available());
+    /// ```
+    ///
+    pub fn get_comm_resources(&mut self) -> Result<CommResources, S7Error> {
+        let params = self.read_szl(SZL_ID_COMM_PARAMS, COMM_GENERAL_INDEX)?;
+        let status = self.read_szl(SZL_ID_COMM_STATUS, COMM_GENERAL_INDEX)?;
+        let resources = match (params.records().next(), status.records().next()) {
+            (Some(params), Some(status)) => CommResources::from_records(params, status),
+            _ => None,
+        };
+        resources.ok_or(S7Error::IsoInvalidTelegram)
+    }
+
+    /// ### Returns the protection level of the CPU (SZL 0x0232, Index 4)
+    ///
+    /// ### Returns
+    /// `Ok(<Protection>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: Record too short.
+    /// - Reported by `read_szl()`
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::S7Client;
+    ///
+    /// let mut client = S7Client::new();
+    /// client.connect_rack_slot("192.168.0.100", 0, 2).unwrap();
+    /// let protection = client.get_protection().unwrap();
+    /// if protection.write_protected() {
+    ///     println!("Writes refused: {}", protection);
+    /// }
+    /// ```
+    ///
+    pub fn get_protection(&mut self) -> Result<Protection, S7Error> {
+        let szl = self.read_szl(SZL_ID_PROTECTION, PROTECTION_INDEX)?;
+        let protection = szl.records().next().and_then(Protection::from_record);
+        protection.ok_or(S7Error::IsoInvalidTelegram)
+    }
+
+    /// ### Returns the identification of the CPU (SZL 0x0011 and 0x001C)
+    ///
+    /// Order number, hardware and firmware version come from the SZL 0x0011; serial number and
+    /// names from the SZL 0x001C, left `None` if the CPU doesn't have the list.
+    ///
+    /// ### Returns
+    /// `Ok(<ModuleIdent>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::IsoInvalidTelegram`: The SZL 0x0011 has no module record.
+    /// - Reported by `read_szl()`
+    ///
+    /// ### Example
+    /// ```rust,no_run
+    /// use rust7::S7Client;
+    ///
+    /// let mut client = S7Client::new();
+    /// client.connect_rack_slot("192.168.0.100", 0, 2).unwrap();
+    /// let ident = client.get_module_ident().unwrap();
+    /// println!("{} {:?} S/N {:?}", ident.order_number, ident.firmware_version, ident.serial_number);
+    /// ```
+    ///
+    pub fn get_module_ident(&mut self) -> Result<ModuleIdent, S7Error> {
+        let ident = self.read_szl(SZL_ID_MODULE_IDENT, 0x0000)?;
+        let component = match self.read_szl(SZL_ID_COMPONENT_IDENT, 0x0000) {
+            Ok(component) => Some(component),
+            Err(e) if e.class() == S7ErrorClass::Plc => None,
+            Err(e) => return Err(e),
+        };
+        ModuleIdent::from_szl(&ident, component.as_ref()).ok_or(S7Error::IsoInvalidTelegram)
+    }
+}
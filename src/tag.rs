@@ -0,0 +1,923 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use crate::address::{AreaAddress, BitAddress};
+use crate::client::{S7Client, S7Error, S7ErrorClass};
+use crate::clock::ClockDrift;
+use crate::observer::{observe, S7Observer, S7Request};
+use crate::value::{S7Value, S7DataType};
+
+/// ### Linear scaling of a tag (raw range -> engineering range)
+///
+/// `engineering = eng_low + (raw - raw_low) * (eng_high - eng_low) / (raw_high - raw_low) + offset`
+///
+/// With `clamp` the engineering value (before the offset) is limited to the engineering range, and
+/// so the raw value written is limited to the raw range.
+///
+/// ### Example
+/// ```rust
+/// use rust7::tag::Scaling;
+///
+/// // 0..27648 -> 0..100 °C, sensor calibrated 0.5 °C low
+/// let scaling = Scaling::new(0.0, 27648.0, 0.0, 100.0).with_offset(0.5).with_clamp(true);
+/// assert_eq!(scaling.to_engineering(13824.0), 50.5);
+/// assert_eq!(scaling.to_raw(50.5), 13824.0);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scaling {
+    pub raw_low: f64,
+    pub raw_high: f64,
+    pub eng_low: f64,
+    pub eng_high: f64,
+    /// Added to the engineering value (Default = 0)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub offset: f64,
+    /// Limits the values to the ranges (Default = false)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub clamp: bool,
+}
+
+impl Scaling {
+    /// ### Creates a scaling without offset and clamping
+    ///
+    /// ### Parameters
+    /// - `raw_low`, `raw_high`: Raw range (PLC values).
+    /// - `eng_low`, `eng_high`: Engineering range. It can be inverted (`eng_low > eng_high`).
+    ///
+    pub fn new(raw_low: f64, raw_high: f64, eng_low: f64, eng_high: f64) -> Self {
+        Scaling { raw_low, raw_high, eng_low, eng_high, offset: 0.0, clamp: false }
+    }
+
+    /// ### Sets the offset added to the engineering value
+    ///
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// ### Enables or disables the clamping to the ranges
+    ///
+    pub fn with_clamp(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// ### Returns true if both ranges are finite and not empty
+    ///
+    pub fn is_valid(&self) -> bool {
+        [self.raw_low, self.raw_high, self.eng_low, self.eng_high, self.offset].iter().all(|v| v.is_finite())
+            && self.raw_low != self.raw_high
+            && self.eng_low != self.eng_high
+    }
+
+    fn clamp_eng(&self, value: f64) -> f64 {
+        if self.clamp {
+            value.clamp(self.eng_low.min(self.eng_high), self.eng_low.max(self.eng_high))
+        } else {
+            value
+        }
+    }
+
+    /// ### Converts a raw value into engineering units
+    ///
+    pub fn to_engineering(&self, raw: f64) -> f64 {
+        let eng = self.eng_low + (raw - self.raw_low) * (self.eng_high - self.eng_low) / (self.raw_high - self.raw_low);
+        self.clamp_eng(eng) + self.offset
+    }
+
+    /// ### Converts an engineering value into the raw value
+    ///
+    pub fn to_raw(&self, engineering: f64) -> f64 {
+        let eng = self.clamp_eng(engineering - self.offset);
+        self.raw_low + (eng - self.eng_low) * (self.raw_high - self.raw_low) / (self.eng_high - self.eng_low)
+    }
+}
+
+/// ### Deadband of a floating-point tag
+///
+/// A new value is reported by a `Subscription` only if it differs from the last reported one by
+/// more than the deadband.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Deadband {
+    /// Absolute change, in the units of the tag (engineering units if scaled)
+    Absolute(f64),
+    /// Percentage of the engineering span of the scaling, or of the last reported value if the
+    /// tag is not scaled
+    Percent(f64),
+}
+
+impl Deadband {
+    /// ### Returns true if both values are finite and they differ by more than the deadband
+    ///
+    /// ### Parameters
+    /// - `last`: Last reported value.
+    /// - `value`: New value.
+    /// - `scaling`: Scaling of the tag, if any (span of `Percent`).
+    ///
+    pub fn exceeded(&self, last: f64, value: f64, scaling: Option<&Scaling>) -> bool {
+        if !last.is_finite() || !value.is_finite() {
+            return last.to_bits() != value.to_bits();
+        }
+        let threshold = match *self {
+            Deadband::Absolute(delta) => delta.abs(),
+            Deadband::Percent(percent) => {
+                let base = match scaling {
+                    Some(scaling) => scaling.eng_high - scaling.eng_low,
+                    None => last,
+                };
+                (base * percent / 100.0).abs()
+            }
+        };
+        (value - last).abs() > threshold
+    }
+
+    /// ### Returns true if the deadband is finite and not negative
+    ///
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Deadband::Absolute(value) | Deadband::Percent(value) => value.is_finite() && value >= 0.0,
+        }
+    }
+}
+
+/// ### Edge of a BOOL tag
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// false -> true
+    Rising,
+    /// true -> false
+    Falling,
+}
+
+/// ### Edge detector with debounce
+///
+/// Turns the levels of a polled bit into edges. A new level is accepted only after it has been
+/// read in `debounce` consecutive samples, so a contact bouncing between two polls doesn't
+/// generate spurious edges.
+///
+/// ### Example
+/// ```rust
+/// use rust7::tag::{Edge, EdgeDetector};
+///
+/// let mut detector = EdgeDetector::new(2);
+/// assert_eq!(detector.update(false), None); // First sample: initial level, no edge
+/// assert_eq!(detector.update(true), None);  // Not yet confirmed
+/// assert_eq!(detector.update(true), Some(Edge::Rising));
+/// assert_eq!(detector.update(false), None); // Bounce, discarded
+/// assert_eq!(detector.update(true), None);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeDetector {
+    debounce: u32,
+    state: Option<bool>,
+    /// Consecutive samples different from the state
+    count: u32,
+}
+
+impl EdgeDetector {
+    /// ### Creates a detector
+    ///
+    /// ### Parameters
+    /// - `debounce`: Consecutive samples required to accept a new level (0 and 1 = no debounce).
+    ///
+    pub fn new(debounce: u32) -> Self {
+        EdgeDetector { debounce: debounce.max(1), state: None, count: 0 }
+    }
+
+    /// ### Processes a sample
+    ///
+    /// ### Returns
+    /// The edge, when a new level is confirmed. The first sample sets the initial level without edge.
+    ///
+    pub fn update(&mut self, level: bool) -> Option<Edge> {
+        let Some(state) = self.state else {
+            self.state = Some(level);
+            return None;
+        };
+        if level == state {
+            self.count = 0;
+            return None;
+        }
+        self.count += 1;
+        if self.count < self.debounce {
+            return None;
+        }
+        self.count = 0;
+        self.state = Some(level);
+        Some(if level { Edge::Rising } else { Edge::Falling })
+    }
+
+    /// ### Returns the debounced level, `None` before the first sample
+    ///
+    pub fn state(&self) -> Option<bool> {
+        self.state
+    }
+
+    /// ### Forgets the level: the next sample is taken as the initial one
+    ///
+    pub fn reset(&mut self) {
+        self.state = None;
+        self.count = 0;
+    }
+}
+
+/// ### Derived values of a tag over a time window
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trend {
+    /// Rate of change (units per second) between the oldest and the newest value of the window,
+    /// 0 with a single value
+    pub rate: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Average of the values (not weighted by time)
+    pub avg: f64,
+    /// Number of values in the window
+    pub samples: usize,
+}
+
+/// ### Sliding time window of the values of a tag
+///
+/// Keeps the values of the last `window` and computes their `Trend`.
+///
+/// ### Example
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use rust7::tag::TrendWindow;
+///
+/// let mut window = TrendWindow::new(Duration::from_secs(10));
+/// let start = Instant::now();
+/// window.update_at(start, 20.0);
+/// let trend = window.update_at(start + Duration::from_secs(4), 22.0);
+/// assert_eq!(trend.rate, 0.5); // 2 units in 4 s
+/// assert_eq!((trend.min, trend.max, trend.avg), (20.0, 22.0, 21.0));
+///
+/// let trend = window.update_at(start + Duration::from_secs(12), 22.0); // 20.0 left the window
+/// assert_eq!(trend.rate, 0.0);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct TrendWindow {
+    window: Duration,
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl TrendWindow {
+    /// ### Creates an empty window
+    ///
+    /// ### Parameters
+    /// - `window`: Age of the oldest value kept.
+    ///
+    pub fn new(window: Duration) -> Self {
+        TrendWindow { window, samples: VecDeque::new() }
+    }
+
+    /// ### Adds a value read now and returns the trend of the window
+    ///
+    pub fn update(&mut self, value: f64) -> Trend {
+        self.update_at(Instant::now(), value)
+    }
+
+    /// ### Adds a value read at `time` and returns the trend of the window
+    ///
+    /// The values older than `window` with respect to `time` are discarded.
+    ///
+    pub fn update_at(&mut self, time: Instant, value: f64) -> Trend {
+        self.samples.push_back((time, value));
+        while self.samples.front().is_some_and(|&(oldest, _)| time.saturating_duration_since(oldest) > self.window) {
+            self.samples.pop_front();
+        }
+        self.compute()
+    }
+
+    /// ### Returns the trend of the window, `None` if empty
+    ///
+    pub fn trend(&self) -> Option<Trend> {
+        (!self.samples.is_empty()).then(|| self.compute())
+    }
+
+    /// ### Discards the values of the window
+    ///
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Trend of a non-empty window
+    fn compute(&self) -> Trend {
+        let (first_time, first) = self.samples[0];
+        let (last_time, last) = self.samples[self.samples.len() - 1];
+        let elapsed = last_time.saturating_duration_since(first_time).as_secs_f64();
+        let rate = if elapsed > 0.0 { (last - first) / elapsed } else { 0.0 };
+        let values = self.samples.iter().map(|&(_, value)| value);
+        Trend {
+            rate,
+            min: values.clone().fold(f64::INFINITY, f64::min),
+            max: values.clone().fold(f64::NEG_INFINITY, f64::max),
+            avg: values.sum::<f64>() / self.samples.len() as f64,
+            samples: self.samples.len(),
+        }
+    }
+}
+
+/// ### PLC Tag
+///
+/// A named PLC variable: where it lives (area, DB, start) and how it is laid out (data type).
+///
+/// With a `scaling`, `read()` returns the engineering value (`S7Value::LReal`) and `write()`
+/// accepts any numeric value in engineering units, converted back to the raw data type.
+///
+/// With an `edge_debounce` (BOOL tags only), a `Subscription` reports the edges of the tag
+/// instead of its level changes. With a `deadband` (REAL, LREAL and scaled tags) it ignores the
+/// changes smaller than the deadband. With a `trend_window_ms` (numeric tags), the updates carry
+/// the rate of change, min, max and average of the values read in the window.
+///
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tag {
+    /// Tag name (e.g. "Line1.Speed")
+    pub name: String,
+    /// S7 memory area constant (e.g., `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_DB`, `S7_AREA_MK`).
+    #[cfg_attr(feature = "serde", serde(with = "crate::tag_config::area_serde"))]
+    pub area: u8,
+    /// DB number (ignored for non-DB areas).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub db_number: u16,
+    /// Starting byte index (bit index, i.e. `byte_num * 8 + bit_idx`, for `S7DataType::Bool`).
+    pub start: u32,
+    /// Type of the variable.
+    pub data_type: S7DataType,
+    /// Engineering-unit scaling (numeric types only).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub scaling: Option<Scaling>,
+    /// Edge detection (BOOL only): consecutive polls required to accept a new level.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub edge_debounce: Option<u32>,
+    /// Deadband of the change notifications (REAL, LREAL and scaled tags).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub deadband: Option<Deadband>,
+    /// Window of the trend reported with the updates (ms, numeric tags).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub trend_window_ms: Option<u64>,
+}
+
+impl Tag {
+    /// ### Creates a new Tag
+    ///
+    /// ### Parameters
+    /// - `name`: Tag name.
+    /// - `area`: S7 memory area constant.
+    /// - `db_number`: DB number (ignored for non-DB areas).
+    /// - `start`: Starting byte index (bit index for `S7DataType::Bool`).
+    /// - `data_type`: Type of the variable.
+    ///
+    pub fn new(name: &str, area: u8, db_number: u16, start: u32, data_type: S7DataType) -> Self {
+        Tag {
+            name: name.to_string(),
+            area,
+            db_number,
+            start,
+            data_type,
+            scaling: None,
+            edge_debounce: None,
+            deadband: None,
+            trend_window_ms: None,
+        }
+    }
+
+    /// ### Creates a Tag at a byte address
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: `S7DataType::Bool`, a bit tag is created with `at_bit()`.
+    ///
+    pub fn at(name: &str, address: impl Into<AreaAddress>, data_type: S7DataType) -> Result<Self, S7Error> {
+        if data_type == S7DataType::Bool {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let address = address.into();
+        Ok(Tag::new(name, address.area, address.db_number, address.byte, data_type))
+    }
+
+    /// ### Creates a BOOL Tag at a bit address
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Bit index > 7 or byte beyond 2 MB.
+    ///
+    pub fn at_bit(name: &str, address: BitAddress) -> Result<Self, S7Error> {
+        let start = address.bit_start().ok_or(S7Error::InvalidFunParameter)?;
+        Ok(Tag::new(name, address.area, address.db_number, start, S7DataType::Bool))
+    }
+
+    /// ### Sets the engineering-unit scaling
+    ///
+    pub fn with_scaling(mut self, scaling: Scaling) -> Self {
+        self.scaling = Some(scaling);
+        self
+    }
+
+    /// ### Enables the edge detection in the subscriptions (BOOL tags only)
+    ///
+    /// ### Parameters
+    /// - `debounce`: Consecutive polls required to accept a new level (0 and 1 = no debounce).
+    ///
+    pub fn with_edge_detection(mut self, debounce: u32) -> Self {
+        self.edge_debounce = Some(debounce);
+        self
+    }
+
+    /// ### Sets the deadband of the change notifications (REAL, LREAL and scaled tags)
+    ///
+    pub fn with_deadband(mut self, deadband: Deadband) -> Self {
+        self.deadband = Some(deadband);
+        self
+    }
+
+    /// ### Enables the trend in the subscription updates (numeric tags)
+    ///
+    /// ### Parameters
+    /// - `window`: Time window of the rate of change, min, max and average (ms resolution).
+    ///
+    pub fn with_trend(mut self, window: Duration) -> Self {
+        self.trend_window_ms = Some(window.as_millis() as u64);
+        self
+    }
+
+    /// ### Returns the type of the values read and written: `LReal` if scaled, otherwise `data_type`
+    ///
+    pub fn value_type(&self) -> S7DataType {
+        if self.scaling.is_some() { S7DataType::LReal } else { self.data_type }
+    }
+
+    /// ### Reads the tag value from the PLC
+    ///
+    /// The value is scaled to engineering units if the tag has a scaling.
+    ///
+    /// For Return and Errors look at `S7Client::read_value()`, plus:
+    /// - `S7Error::InvalidFunParameter`: Invalid scaling or scaling of a non-numeric type.
+    ///
+    pub fn read(&self, client: &mut S7Client) -> Result<S7Value, S7Error> {
+        let value = client.read_value(self.area, self.db_number, self.start, self.data_type)?;
+        self.scale(value)
+    }
+
+    /// ### Writes the tag value to the PLC
+    ///
+    /// If the tag has a scaling, `value` is in engineering units (any numeric variant) and is
+    /// converted to the raw data type (rounded for the integer types).
+    ///
+    /// For Return and Errors look at `S7Client::write_value()`, plus:
+    /// - `S7Error::InvalidFunParameter`: Invalid scaling, non-numeric value or raw value out of the
+    ///   range of the data type.
+    ///
+    pub fn write(&self, client: &mut S7Client, value: &S7Value) -> Result<(), S7Error> {
+        let raw = self.unscale(value)?;
+        client.write_value(self.area, self.db_number, self.start, self.data_type, &raw)
+    }
+
+    /// ### Converts a raw value read from the PLC into the tag value (engineering units if scaled)
+    ///
+    pub fn scale(&self, raw: S7Value) -> Result<S7Value, S7Error> {
+        match &self.scaling {
+            None => Ok(raw),
+            Some(scaling) if scaling.is_valid() => {
+                let raw = raw.as_f64().ok_or(S7Error::InvalidFunParameter)?;
+                Ok(S7Value::LReal(scaling.to_engineering(raw)))
+            }
+            Some(_) => Err(S7Error::InvalidFunParameter),
+        }
+    }
+
+    /// ### Converts a tag value (engineering units if scaled) into the raw value to write
+    ///
+    pub fn unscale(&self, value: &S7Value) -> Result<S7Value, S7Error> {
+        match &self.scaling {
+            None => Ok(value.clone()),
+            Some(scaling) if scaling.is_valid() => {
+                let engineering = value.as_f64().ok_or(S7Error::InvalidFunParameter)?;
+                S7Value::from_f64(scaling.to_raw(engineering), self.data_type)
+            }
+            Some(_) => Err(S7Error::InvalidFunParameter),
+        }
+    }
+}
+
+/// ### Tag value change notification
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagUpdate {
+    /// Tag name
+    pub name: String,
+    /// Type of the value (`LReal` for the scaled tags)
+    pub data_type: S7DataType,
+    /// New value
+    pub value: S7Value,
+    /// Time at which the value was read
+    pub timestamp: SystemTime,
+    /// Edge of a tag with edge detection (`None` for the first value and for the other tags)
+    pub edge: Option<Edge>,
+    /// The value is the last one read and it is no longer fresh (see `Subscription::check_stale()`)
+    pub stale: bool,
+    /// Trend of a tag with a trend window, including the new value (`None` for the other tags and
+    /// for the stale updates)
+    pub trend: Option<Trend>,
+}
+
+/// Name of the pseudo-tag reporting the CPU clock drift (see `Subscription::set_clock_drift_monitor()`)
+pub const CLOCK_DRIFT_TAG: &str = "$ClockDrift";
+
+/// ### Value kept in the history of a subscription
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistorySample {
+    /// Time at which the value was read
+    pub timestamp: SystemTime,
+    pub value: S7Value,
+}
+
+/// ### Tag subscription (poll group)
+///
+/// A set of tags polled at the same scan rate. Each `poll()` reads all the tags and
+/// returns only those whose value changed since the previous poll (change of value).
+/// The tags with edge detection (`Tag::with_edge_detection()`) are reported only on their
+/// debounced edges, with `TagUpdate::edge` set; the tags with a deadband (`Tag::with_deadband()`)
+/// only when they move away from the last reported value by more than the deadband.
+///
+/// The subscription doesn't own the client, so the same connection can serve many
+/// poll groups with different scan rates.
+///
+/// With a stale factor (`set_stale_factor()`), `check_stale()` reports the tags not read
+/// successfully for longer than `factor * interval`, and the next successful poll reports them
+/// again as fresh.
+///
+/// With a history depth (`set_history_depth()`), the last values read of each tag are kept in
+/// memory and returned by `history()`, e.g. for the trend pop-ups of an HMI.
+///
+/// The tags with a trend window (`Tag::with_trend()`) are reported with the rate of change, min,
+/// max and average of their values read in the window (`TagUpdate::trend`, `trend()`).
+///
+/// With a clock drift monitor (`set_clock_drift_monitor()`), the drift of the CPU clock from the
+/// host clock is measured periodically and reported as the `CLOCK_DRIFT_TAG` pseudo-tag.
+///
+/// ### Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use rust7::{S7Client, S7DataType, S7_AREA_DB};
+/// use rust7::tag::{Subscription, Tag};
+///
+/// let mut client = S7Client::new();
+/// client.connect_s71200_1500("192.168.0.100").unwrap();
+///
+/// let mut subscription = Subscription::new(Duration::from_millis(500));
+/// subscription.add_tag(Tag::new("Speed", S7_AREA_DB, 100, 0, S7DataType::Real));
+///
+/// loop {
+///     subscription.wait();
+///     for update in subscription.poll(&mut client).unwrap() {
+///         println!("{} = {}", update.name, update.value);
+///     }
+/// }
+/// ```
+///
+pub struct Subscription {
+    interval: Duration,
+    tags: Vec<Tag>,
+    last_values: Vec<Option<S7Value>>,
+    detectors: Vec<Option<EdgeDetector>>,
+    trends: Vec<Option<TrendWindow>>,
+    next_poll: Instant,
+    stale_factor: Option<f64>,
+    /// Last successful read of each tag (time of adding before the first one)
+    last_reads: Vec<Instant>,
+    stale: Vec<bool>,
+    history_depth: usize,
+    /// Last values read of each tag, oldest first
+    histories: Vec<VecDeque<HistorySample>>,
+    /// Interval of the clock drift measurements (None = disabled)
+    drift_interval: Option<Duration>,
+    next_drift_check: Instant,
+    clock_drift: Option<ClockDrift>,
+    observer: Option<Arc<dyn S7Observer>>,
+}
+
+impl Subscription {
+    /// ### Creates an empty subscription
+    ///
+    /// ### Parameters
+    /// - `interval`: Scan rate of the poll group.
+    ///
+    pub fn new(interval: Duration) -> Self {
+        Subscription {
+            interval,
+            tags: Vec::new(),
+            last_values: Vec::new(),
+            detectors: Vec::new(),
+            trends: Vec::new(),
+            next_poll: Instant::now(),
+            stale_factor: None,
+            last_reads: Vec::new(),
+            stale: Vec::new(),
+            history_depth: 0,
+            histories: Vec::new(),
+            drift_interval: None,
+            next_drift_check: Instant::now(),
+            clock_drift: None,
+            observer: None,
+        }
+    }
+
+    /// ### Adds a tag to the subscription
+    ///
+    /// The first poll after adding a tag always reports its value.
+    ///
+    pub fn add_tag(&mut self, tag: Tag) {
+        self.detectors.push(tag.edge_debounce.map(EdgeDetector::new));
+        self.trends.push(tag.trend_window_ms.map(|window| TrendWindow::new(Duration::from_millis(window))));
+        self.tags.push(tag);
+        self.last_values.push(None);
+        self.last_reads.push(Instant::now());
+        self.stale.push(false);
+        self.histories.push(VecDeque::new());
+    }
+
+    /// ### Returns the subscribed tags
+    ///
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// ### Returns the scan rate of the poll group
+    ///
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// ### Returns the last value read for a tag
+    ///
+    /// `None` if the tag doesn't exist or was never read.
+    ///
+    pub fn value(&self, name: &str) -> Option<&S7Value> {
+        let index = self.tags.iter().position(|tag| tag.name == name)?;
+        self.last_values[index].as_ref()
+    }
+
+    /// ### Returns the current trend of a tag
+    ///
+    /// Updated at each read, also when the value didn't change. `None` if the tag doesn't exist,
+    /// has no trend window or was never read.
+    ///
+    pub fn trend(&self, name: &str) -> Option<Trend> {
+        let index = self.tags.iter().position(|tag| tag.name == name)?;
+        self.trends[index].as_ref()?.trend()
+    }
+
+    /// ### Sets the staleness threshold
+    ///
+    /// ### Parameters
+    /// - `factor`: A tag is stale when it was not read successfully for `factor * interval`
+    ///   (e.g. 3.0 = three missed scans). `None` disables the detection (Default).
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Factor not finite or not greater than 0.
+    ///
+    pub fn set_stale_factor(&mut self, factor: Option<f64>) -> Result<(), S7Error> {
+        if factor.is_some_and(|factor| !factor.is_finite() || factor <= 0.0) {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        self.stale_factor = factor;
+        Ok(())
+    }
+
+    /// ### Returns the staleness threshold
+    ///
+    pub fn stale_factor(&self) -> Option<f64> {
+        self.stale_factor
+    }
+
+    /// ### Returns true if the tag is currently stale
+    ///
+    pub fn is_stale(&self, name: &str) -> bool {
+        self.tags.iter().position(|tag| tag.name == name).is_some_and(|index| self.stale[index])
+    }
+
+    /// ### Marks the tags that stopped being fresh
+    ///
+    /// Call it after each `poll()`, successful or not: a failed poll stops at the first failing
+    /// tag, so neither that tag nor the following ones are refreshed.
+    ///
+    /// ### Returns
+    /// An update with `stale: true` and the last value read for each tag that became stale since
+    /// the previous call (a tag never read is only marked, see `is_stale()`). Empty if the
+    /// detection is disabled.
+    ///
+    /// ### Notes
+    /// When a stale tag is read again, `poll()` reports it even if its value didn't change.
+    ///
+    pub fn check_stale(&mut self) -> Vec<TagUpdate> {
+        let Some(factor) = self.stale_factor else {
+            return Vec::new();
+        };
+        let limit = self.interval.mul_f64(factor);
+        let mut updates = Vec::new();
+        for (index, tag) in self.tags.iter().enumerate() {
+            if self.stale[index] || self.last_reads[index].elapsed() <= limit {
+                continue;
+            }
+            self.stale[index] = true;
+            if let Some(value) = &self.last_values[index] {
+                updates.push(TagUpdate {
+                    name: tag.name.clone(),
+                    data_type: tag.value_type(),
+                    value: value.clone(),
+                    timestamp: SystemTime::now(),
+                    edge: None,
+                    stale: true,
+                    trend: None,
+                });
+            }
+        }
+        updates
+    }
+
+    /// ### Sets the number of values kept in the history of each tag
+    ///
+    /// Each successful read of a tag is kept, changed or not (so the history covers
+    /// `depth * interval`); the oldest values are discarded. 0 disables the history (Default).
+    /// Reducing the depth discards the oldest values already kept.
+    ///
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history_depth = depth;
+        for history in self.histories.iter_mut() {
+            while history.len() > depth {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// ### Returns the number of values kept in the history of each tag
+    ///
+    pub fn history_depth(&self) -> usize {
+        self.history_depth
+    }
+
+    /// ### Returns the values of a tag read since a time
+    ///
+    /// ### Parameters
+    /// - `name`: Tag name.
+    /// - `since`: Oldest time returned (`UNIX_EPOCH` = whole history).
+    ///
+    /// ### Returns
+    /// The values kept, oldest first. Empty if the tag doesn't exist or the history is disabled.
+    ///
+    pub fn history(&self, name: &str, since: SystemTime) -> Vec<HistorySample> {
+        let Some(index) = self.tags.iter().position(|tag| tag.name == name) else {
+            return Vec::new();
+        };
+        self.histories[index].iter().filter(|sample| sample.timestamp >= since).cloned().collect()
+    }
+
+    /// ### Enables the monitoring of the CPU clock drift
+    ///
+    /// Every `interval` a poll reads the CPU clock too, and reports the drift from the host clock
+    /// (ms, positive if the CPU is ahead) as the `DInt` pseudo-tag `CLOCK_DRIFT_TAG`. The drift
+    /// allows correlating the events timestamped by the CPU with the host logs.
+    ///
+    /// ### Parameters
+    /// - `interval`: Time between two measurements (it is rounded up to the poll interval),
+    ///   `None` disables the monitoring (Default).
+    ///
+    /// ### Notes
+    /// A CPU refusing the clock read doesn't fail the poll: the drift is simply not reported.
+    ///
+    pub fn set_clock_drift_monitor(&mut self, interval: Option<Duration>) {
+        self.drift_interval = interval;
+        self.next_drift_check = Instant::now();
+    }
+
+    /// ### Returns the last clock drift measured, `None` if not measured yet
+    ///
+    pub fn clock_drift(&self) -> Option<ClockDrift> {
+        self.clock_drift
+    }
+
+    /// ### Sleeps until the next poll is due
+    ///
+    pub fn wait(&self) {
+        let now = Instant::now();
+        if self.next_poll > now {
+            std::thread::sleep(self.next_poll - now);
+        }
+    }
+
+    /// ### Reads all the tags and returns the changed ones
+    ///
+    /// ### Parameters
+    /// - `client`: A connected client.
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<TagUpdate>>)` containing only the tags whose value changed, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - Reported by `S7Client::read_value()`, the poll stops at the first failing tag.
+    /// - `S7Error::InvalidFunParameter`: Edge detection on a tag that is not BOOL, deadband on a
+    ///   tag that is not REAL, LREAL or scaled, trend window on a CHAR or STRING tag.
+    ///
+    pub fn poll(&mut self, client: &mut S7Client) -> Result<Vec<TagUpdate>, S7Error> {
+        let request = S7Request { operation: "poll", size: self.tags.len() };
+        observe(self.observer.clone(), request, self, |subscription| subscription.poll_tags(client))
+    }
+
+    /// ### Attaches a telemetry observer, notified of each poll cycle
+    ///
+    /// The reads of the tags are notified to the observer of the client.
+    ///
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn S7Observer>>) {
+        self.observer = observer;
+    }
+
+    fn poll_tags(&mut self, client: &mut S7Client) -> Result<Vec<TagUpdate>, S7Error> {
+        self.next_poll = Instant::now() + self.interval;
+        let mut updates = Vec::new();
+
+        if let Some(interval) = self.drift_interval.filter(|_| Instant::now() >= self.next_drift_check) {
+            self.next_drift_check = Instant::now() + interval;
+            match client.measure_clock_drift() {
+                Ok(drift) => {
+                    self.clock_drift = Some(drift);
+                    let drift_ms = drift.drift_ms.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+                    updates.push(TagUpdate {
+                        name: CLOCK_DRIFT_TAG.to_string(),
+                        data_type: S7DataType::DInt,
+                        value: S7Value::DInt(drift_ms),
+                        timestamp: drift.host_time,
+                        edge: None,
+                        stale: false,
+                        trend: None,
+                    });
+                }
+                Err(e) if e.class() == S7ErrorClass::Plc => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        for (index, ((tag, last_value), detector)) in self.tags.iter().zip(self.last_values.iter_mut()).zip(self.detectors.iter_mut()).enumerate() {
+            let mut value = tag.read(client)?;
+            let timestamp = SystemTime::now();
+            self.last_reads[index] = Instant::now();
+            // Back from stale: reported even if unchanged
+            let refreshed = std::mem::replace(&mut self.stale[index], false);
+            let mut edge = None;
+            if let Some(detector) = detector {
+                let S7Value::Bool(level) = value else {
+                    return Err(S7Error::InvalidFunParameter);
+                };
+                edge = detector.update(level);
+                // The debounced level is reported, not the raw one
+                value = S7Value::Bool(detector.state().unwrap_or(level));
+            }
+            let trend = match &mut self.trends[index] {
+                Some(window) => Some(window.update(value.as_f64().ok_or(S7Error::InvalidFunParameter)?)),
+                None => None,
+            };
+            if self.history_depth > 0 {
+                let history = &mut self.histories[index];
+                if history.len() == self.history_depth {
+                    history.pop_front();
+                }
+                history.push_back(HistorySample { timestamp, value: value.clone() });
+            }
+            if let Some(deadband) = &tag.deadband {
+                if !matches!(value, S7Value::Real(_) | S7Value::LReal(_)) {
+                    return Err(S7Error::InvalidFunParameter);
+                }
+                if let Some(last) = last_value.as_ref().and_then(S7Value::as_f64).filter(|_| !refreshed) {
+                    if !deadband.exceeded(last, value.as_f64().unwrap_or(f64::NAN), tag.scaling.as_ref()) {
+                        continue;
+                    }
+                }
+            }
+            if refreshed || last_value.as_ref() != Some(&value) {
+                updates.push(TagUpdate {
+                    name: tag.name.clone(),
+                    data_type: tag.value_type(),
+                    value: value.clone(),
+                    timestamp,
+                    edge,
+                    stale: false,
+                    trend,
+                });
+                *last_value = Some(value);
+            }
+        }
+
+        Ok(updates)
+    }
+}
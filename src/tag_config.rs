@@ -0,0 +1,233 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Tag configuration files (feature `serde`)
+//!
+//! `TagConfig` describes the poll groups of an application (scan rate and tags of each one) and is
+//! saved to / loaded from JSON, so the acquisition configuration can be version-controlled apart
+//! from the code.
+//!
+//! ```text
+//! {
+//!   "poll_groups": [
+//!     {
+//!       "interval_ms": 500,
+//!       "stale_factor": 3.0,
+//!       "tags": [
+//!         { "name": "Line1.Speed", "area": "DB", "db_number": 100, "start": 0, "data_type": "REAL",
+//!           "deadband": { "absolute": 0.5 }, "trend_window_ms": 60000 },
+//!         { "name": "Line1.Running", "area": "M", "start": 80, "data_type": "BOOL", "edge_debounce": 2 },
+//!         { "name": "Line1.Temperature", "area": "I", "start": 256, "data_type": "INT",
+//!           "scaling": { "raw_low": 0, "raw_high": 27648, "eng_low": 0, "eng_high": 150, "clamp": true } }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Areas are written as `DB`, `I` (or `PE`), `Q` (or `PA`), `M` (or `MK`); the data types as
+//! their `Display` name (`INT`, `REAL`, `STRING[20]`...). `db_number` can be omitted for non-DB areas,
+//! `scaling` for the tags without scaling (`offset` and `clamp` are optional too), `edge_debounce`
+//! for the tags reported by level, `deadband` (`absolute` or `percent`) for the tags reported at
+//! every change, `trend_window_ms` for the tags without trend.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::S7Client;
+//! use rust7::tag_config::TagConfig;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! let mut subscriptions = TagConfig::load("tags.json").unwrap().subscriptions();
+//! for subscription in subscriptions.iter_mut() {
+//!     for update in subscription.poll(&mut client).unwrap() {
+//!         println!("{} = {}", update.name, update.value);
+//!     }
+//! }
+//! ```
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::client::{S7Error, S7_AREA_DB, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE};
+use crate::tag::{Subscription, Tag};
+use crate::value::S7DataType;
+
+/// ### Configuration of a poll group (`Subscription`)
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PollGroupConfig {
+    /// Scan rate, in ms
+    pub interval_ms: u64,
+    /// Staleness threshold, in scans (see `Subscription::set_stale_factor()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_factor: Option<f64>,
+    pub tags: Vec<Tag>,
+}
+
+/// ### Tag configuration: the poll groups of an application
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagConfig {
+    pub poll_groups: Vec<PollGroupConfig>,
+}
+
+fn config_error(message: String) -> S7Error {
+    S7Error::Other(format!("tag config: {}", message))
+}
+
+impl TagConfig {
+    /// ### Builds the configuration of existing subscriptions
+    ///
+    pub fn from_subscriptions(subscriptions: &[Subscription]) -> Self {
+        TagConfig {
+            poll_groups: subscriptions.iter().map(Subscription::config).collect(),
+        }
+    }
+
+    /// ### Creates the subscriptions described by the configuration
+    ///
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        self.poll_groups.iter().map(Subscription::from_config).collect()
+    }
+
+    /// ### Serializes the configuration to JSON (pretty printed)
+    ///
+    pub fn to_json(&self) -> String {
+        // Serialization of plain structs with string keys can't fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// ### Parses a JSON configuration
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: Malformed JSON, unknown area or data type, scan rate of 0 ms,
+    ///   invalid stale factor, duplicated tag name in a poll group, invalid scaling, edge detection
+    ///   of a non-BOOL tag, invalid deadband or trend window (the message contains the reason).
+    ///
+    pub fn from_json(json: &str) -> Result<Self, S7Error> {
+        let config: TagConfig = serde_json::from_str(json).map_err(|e| config_error(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// ### Saves the configuration to a JSON file
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The file cannot be written.
+    ///
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), S7Error> {
+        fs::write(path, self.to_json())?;
+        Ok(())
+    }
+
+    /// ### Loads the configuration from a JSON file
+    ///
+    /// ### Errors
+    /// - `S7Error::Io`: The file cannot be read.
+    /// - Reported by `from_json()`
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, S7Error> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), S7Error> {
+        for (index, group) in self.poll_groups.iter().enumerate() {
+            if group.interval_ms == 0 {
+                return Err(config_error(format!("poll group {}: interval_ms must be greater than 0", index)));
+            }
+            if group.stale_factor.is_some_and(|factor| !factor.is_finite() || factor <= 0.0) {
+                return Err(config_error(format!("poll group {}: stale_factor must be greater than 0", index)));
+            }
+            for (position, tag) in group.tags.iter().enumerate() {
+                if group.tags[..position].iter().any(|other| other.name == tag.name) {
+                    return Err(config_error(format!("poll group {}: duplicated tag \"{}\"", index, tag.name)));
+                }
+                if tag.scaling.is_some_and(|scaling| !scaling.is_valid()) {
+                    return Err(config_error(format!("poll group {}: invalid scaling of tag \"{}\"", index, tag.name)));
+                }
+                if tag.edge_debounce.is_some() && (tag.data_type != S7DataType::Bool || tag.scaling.is_some()) {
+                    return Err(config_error(format!("poll group {}: edge detection of non-BOOL tag \"{}\"", index, tag.name)));
+                }
+                if tag.trend_window_ms == Some(0) || (tag.trend_window_ms.is_some() && matches!(tag.data_type, S7DataType::Char | S7DataType::String(_))) {
+                    return Err(config_error(format!("poll group {}: invalid trend window of tag \"{}\"", index, tag.name)));
+                }
+                if let Some(deadband) = tag.deadband {
+                    if !matches!(tag.value_type(), S7DataType::Real | S7DataType::LReal) || !deadband.is_valid() {
+                        return Err(config_error(format!("poll group {}: invalid deadband of tag \"{}\"", index, tag.name)));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Subscription {
+    /// ### Returns the configuration of the subscription (scan rate and tags)
+    ///
+    pub fn config(&self) -> PollGroupConfig {
+        PollGroupConfig {
+            interval_ms: self.interval().as_millis() as u64,
+            stale_factor: self.stale_factor(),
+            tags: self.tags().to_vec(),
+        }
+    }
+
+    /// ### Creates a subscription from its configuration
+    ///
+    pub fn from_config(config: &PollGroupConfig) -> Self {
+        let mut subscription = Subscription::new(Duration::from_millis(config.interval_ms));
+        // An invalid factor (not validated by the caller) disables the detection
+        let _ = subscription.set_stale_factor(config.stale_factor);
+        for tag in &config.tags {
+            subscription.add_tag(tag.clone());
+        }
+        subscription
+    }
+}
+
+/// ### Returns the configuration name of an area (`DB`, `I`, `Q`, `M`)
+///
+/// Unknown areas are written as their hexadecimal code (e.g. `0x1C`).
+///
+pub fn area_name(area: u8) -> String {
+    match area {
+        S7_AREA_DB => "DB".to_string(),
+        S7_AREA_PE => "I".to_string(),
+        S7_AREA_PA => "Q".to_string(),
+        S7_AREA_MK => "M".to_string(),
+        _ => format!("0x{:02X}", area),
+    }
+}
+
+/// ### Parses an area name (case insensitive)
+///
+/// Accepts `DB`, `I`/`E`/`PE`, `Q`/`A`/`PA`, `M`/`MK` and hexadecimal codes (`0x84`).
+///
+pub fn parse_area(name: &str) -> Option<u8> {
+    let name = name.trim().to_ascii_uppercase();
+    match name.as_str() {
+        "DB" => Some(S7_AREA_DB),
+        "I" | "E" | "PE" => Some(S7_AREA_PE),
+        "Q" | "A" | "PA" => Some(S7_AREA_PA),
+        "M" | "MK" => Some(S7_AREA_MK),
+        _ => u8::from_str_radix(name.strip_prefix("0X")?, 16).ok(),
+    }
+}
+
+/// Serde adapter of the `Tag::area` field
+pub(crate) mod area_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(area: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::area_name(*area))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        super::parse_area(&name).ok_or_else(|| serde::de::Error::custom(format!("unknown area \"{}\"", name)))
+    }
+}
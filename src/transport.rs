@@ -0,0 +1,60 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Transport layer
+//!
+//! The client talks ISO-on-TCP over any byte stream implementing `Transport`. By default it is a
+//! `TcpStream`, but it can be replaced (`S7Client::connect_transport()`) or decorated
+//! (`S7Client::set_transport_wrapper()`), e.g. to record the traffic or to go through a tunnel.
+
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::time::Duration;
+
+/// ### Byte stream carrying the ISO-on-TCP telegrams
+///
+pub trait Transport: Read + Write + Send {
+    /// ### Sets the read timeout (None = blocking)
+    ///
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// ### Sets the write timeout (None = blocking)
+    ///
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// ### Shuts down the read, write, or both halves of the stream
+    ///
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()>;
+}
+
+impl Transport for TcpStream {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_write_timeout(self, timeout)
+    }
+
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()> {
+        TcpStream::shutdown(self, how)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        (**self).set_read_timeout(timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        (**self).set_write_timeout(timeout)
+    }
+
+    fn shutdown(&mut self, how: Shutdown) -> io::Result<()> {
+        (**self).shutdown(how)
+    }
+}
+
+/// ### Function decorating the transport created by the connection methods
+///
+pub type TransportWrapper = Box<dyn FnMut(Box<dyn Transport>) -> io::Result<Box<dyn Transport>> + Send>;
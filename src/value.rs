@@ -0,0 +1,677 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::client::S7Error;
+
+/// ### S7 Data types
+///
+/// Describes how a PLC variable is laid out in memory.
+///
+/// All multi-byte values are stored big-endian into the PLC (Motorola format).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum S7DataType {
+    /// 1 bit (transferred as 1 byte: 0x00 or 0x01)
+    Bool,
+    /// 8 bit unsigned
+    Byte,
+    /// 16 bit unsigned
+    Word,
+    /// 32 bit unsigned
+    DWord,
+    /// 64 bit unsigned
+    LWord,
+    /// 8 bit signed
+    SInt,
+    /// 8 bit unsigned
+    USInt,
+    /// 16 bit signed
+    Int,
+    /// 16 bit unsigned
+    UInt,
+    /// 32 bit signed
+    DInt,
+    /// 32 bit unsigned
+    UDInt,
+    /// 64 bit signed
+    LInt,
+    /// 64 bit unsigned
+    ULInt,
+    /// 32 bit IEEE 754 floating point
+    Real,
+    /// 64 bit IEEE 754 floating point
+    LReal,
+    /// 1 byte ASCII character
+    Char,
+    /// S7 String with the given max length (the memory footprint is max length + 2)
+    String(u8),
+    /// IEC Time, 32 bit signed (ms)
+    Time,
+    /// Days since 1990-01-01, 16 bit unsigned
+    Date,
+    /// Milliseconds since midnight, 32 bit unsigned
+    TimeOfDay,
+}
+
+impl S7DataType {
+    /// ### Returns the memory size (bytes) of the data type
+    ///
+    pub fn size(&self) -> usize {
+        match self {
+            S7DataType::Bool | S7DataType::Byte | S7DataType::SInt |
+            S7DataType::USInt | S7DataType::Char => 1,
+            S7DataType::Word | S7DataType::Int | S7DataType::UInt | S7DataType::Date => 2,
+            S7DataType::DWord | S7DataType::DInt | S7DataType::UDInt |
+            S7DataType::Real | S7DataType::Time | S7DataType::TimeOfDay => 4,
+            S7DataType::LWord | S7DataType::LInt | S7DataType::ULInt | S7DataType::LReal => 8,
+            S7DataType::String(max_len) => *max_len as usize + 2,
+        }
+    }
+}
+
+impl fmt::Display for S7DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            S7DataType::Bool => write!(f, "BOOL"),
+            S7DataType::Byte => write!(f, "BYTE"),
+            S7DataType::Word => write!(f, "WORD"),
+            S7DataType::DWord => write!(f, "DWORD"),
+            S7DataType::LWord => write!(f, "LWORD"),
+            S7DataType::SInt => write!(f, "SINT"),
+            S7DataType::USInt => write!(f, "USINT"),
+            S7DataType::Int => write!(f, "INT"),
+            S7DataType::UInt => write!(f, "UINT"),
+            S7DataType::DInt => write!(f, "DINT"),
+            S7DataType::UDInt => write!(f, "UDINT"),
+            S7DataType::LInt => write!(f, "LINT"),
+            S7DataType::ULInt => write!(f, "ULINT"),
+            S7DataType::Real => write!(f, "REAL"),
+            S7DataType::LReal => write!(f, "LREAL"),
+            S7DataType::Char => write!(f, "CHAR"),
+            S7DataType::String(max_len) => write!(f, "STRING[{}]", max_len),
+            S7DataType::Time => write!(f, "TIME"),
+            S7DataType::Date => write!(f, "DATE"),
+            S7DataType::TimeOfDay => write!(f, "TIME_OF_DAY"),
+        }
+    }
+}
+
+impl FromStr for S7DataType {
+    type Err = S7Error;
+
+    /// ### Parses the type name, as written by `Display` (case insensitive)
+    ///
+    /// `STRING` without length is a STRING[254]. `TOD` is accepted for TIME_OF_DAY.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Unknown type name or invalid string length.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_uppercase();
+        let data_type = match name.as_str() {
+            "BOOL" => S7DataType::Bool,
+            "BYTE" => S7DataType::Byte,
+            "WORD" => S7DataType::Word,
+            "DWORD" => S7DataType::DWord,
+            "LWORD" => S7DataType::LWord,
+            "SINT" => S7DataType::SInt,
+            "USINT" => S7DataType::USInt,
+            "INT" => S7DataType::Int,
+            "UINT" => S7DataType::UInt,
+            "DINT" => S7DataType::DInt,
+            "UDINT" => S7DataType::UDInt,
+            "LINT" => S7DataType::LInt,
+            "ULINT" => S7DataType::ULInt,
+            "REAL" => S7DataType::Real,
+            "LREAL" => S7DataType::LReal,
+            "CHAR" => S7DataType::Char,
+            "STRING" => S7DataType::String(254),
+            "TIME" => S7DataType::Time,
+            "DATE" => S7DataType::Date,
+            "TIME_OF_DAY" | "TOD" => S7DataType::TimeOfDay,
+            _ => {
+                let max_len = name.strip_prefix("STRING[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|len| len.trim().parse::<u8>().ok())
+                    .filter(|len| *len <= 254)
+                    .ok_or(S7Error::InvalidFunParameter)?;
+                S7DataType::String(max_len)
+            }
+        };
+        Ok(data_type)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for S7DataType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for S7DataType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(|_| serde::de::Error::custom(format!("unknown data type \"{}\"", name)))
+    }
+}
+
+/// ### Dynamic S7 value
+///
+/// Holds a PLC variable whose type is known only at runtime (e.g. from a configuration file).
+///
+/// Use `S7Value::decode()` to extract a value from a buffer read from the PLC and
+/// `S7Value::encode()` to convert it back into the PLC memory layout.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum S7Value {
+    Bool(bool),
+    Byte(u8),
+    Word(u16),
+    DWord(u32),
+    LWord(u64),
+    SInt(i8),
+    USInt(u8),
+    Int(i16),
+    UInt(u16),
+    DInt(i32),
+    UDInt(u32),
+    LInt(i64),
+    ULInt(u64),
+    Real(f32),
+    LReal(f64),
+    Char(char),
+    String(String),
+    /// IEC Time (ms)
+    Time(i32),
+    /// Days since 1990-01-01
+    Date(u16),
+    /// Milliseconds since midnight
+    TimeOfDay(u32),
+}
+
+impl S7Value {
+    /// ### Decodes a value from a buffer
+    ///
+    /// ### Parameters
+    /// - `data_type`: Type of the variable.
+    /// - `buffer`: Source buffer (PLC memory layout), the value starts at index 0.
+    ///
+    /// ### Returns
+    /// `Ok(<S7Value>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The buffer is smaller than the data type size.
+    ///
+    /// ### Notes
+    /// - `Bool` is considered true if the first byte is != 0 (the same layout returned by a bit read).
+    /// - `Char` and `String` bytes are mapped 1:1 to characters (Latin-1).
+    ///
+    pub fn decode(data_type: S7DataType, buffer: &[u8]) -> Result<S7Value, S7Error> {
+        let size = data_type.size();
+        if buffer.len() < size {
+            return Err(S7Error::InvalidFunParameter);
+        }
+
+        let value = match data_type {
+            S7DataType::Bool => S7Value::Bool(buffer[0] != 0),
+            S7DataType::Byte => S7Value::Byte(buffer[0]),
+            S7DataType::Word => S7Value::Word(u16::from_be_bytes([buffer[0], buffer[1]])),
+            S7DataType::DWord => S7Value::DWord(u32::from_be_bytes(be_array(buffer))),
+            S7DataType::LWord => S7Value::LWord(u64::from_be_bytes(be_array(buffer))),
+            S7DataType::SInt => S7Value::SInt(buffer[0] as i8),
+            S7DataType::USInt => S7Value::USInt(buffer[0]),
+            S7DataType::Int => S7Value::Int(i16::from_be_bytes([buffer[0], buffer[1]])),
+            S7DataType::UInt => S7Value::UInt(u16::from_be_bytes([buffer[0], buffer[1]])),
+            S7DataType::DInt => S7Value::DInt(i32::from_be_bytes(be_array(buffer))),
+            S7DataType::UDInt => S7Value::UDInt(u32::from_be_bytes(be_array(buffer))),
+            S7DataType::LInt => S7Value::LInt(i64::from_be_bytes(be_array(buffer))),
+            S7DataType::ULInt => S7Value::ULInt(u64::from_be_bytes(be_array(buffer))),
+            S7DataType::Real => S7Value::Real(f32::from_be_bytes(be_array(buffer))),
+            S7DataType::LReal => S7Value::LReal(f64::from_be_bytes(be_array(buffer))),
+            S7DataType::Char => S7Value::Char(buffer[0] as char),
+            S7DataType::String(max_len) => {
+                // [0] Max length, [1] Actual length, [2..] Chars
+                let len = buffer[1].min(buffer[0]).min(max_len) as usize;
+                S7Value::String(buffer[2..2 + len].iter().map(|&b| b as char).collect())
+            }
+            S7DataType::Time => S7Value::Time(i32::from_be_bytes(be_array(buffer))),
+            S7DataType::Date => S7Value::Date(u16::from_be_bytes([buffer[0], buffer[1]])),
+            S7DataType::TimeOfDay => S7Value::TimeOfDay(u32::from_be_bytes(be_array(buffer))),
+        };
+
+        Ok(value)
+    }
+
+    /// ### Encodes the value into the PLC memory layout
+    ///
+    /// ### Parameters
+    /// - `data_type`: Type of the destination variable, it must match the value variant.
+    ///
+    /// ### Returns
+    /// `Ok(<Vec<u8>>)` containing exactly `data_type.size()` bytes, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The value doesn't match the data type or the string is too long.
+    ///
+    /// ### Notes
+    /// - Characters outside Latin-1 are replaced with '?'.
+    ///
+    pub fn encode(&self, data_type: S7DataType) -> Result<Vec<u8>, S7Error> {
+        let bytes = match (self, data_type) {
+            (S7Value::Bool(v), S7DataType::Bool) => vec![*v as u8],
+            (S7Value::Byte(v), S7DataType::Byte) => vec![*v],
+            (S7Value::Word(v), S7DataType::Word) => v.to_be_bytes().to_vec(),
+            (S7Value::DWord(v), S7DataType::DWord) => v.to_be_bytes().to_vec(),
+            (S7Value::LWord(v), S7DataType::LWord) => v.to_be_bytes().to_vec(),
+            (S7Value::SInt(v), S7DataType::SInt) => v.to_be_bytes().to_vec(),
+            (S7Value::USInt(v), S7DataType::USInt) => vec![*v],
+            (S7Value::Int(v), S7DataType::Int) => v.to_be_bytes().to_vec(),
+            (S7Value::UInt(v), S7DataType::UInt) => v.to_be_bytes().to_vec(),
+            (S7Value::DInt(v), S7DataType::DInt) => v.to_be_bytes().to_vec(),
+            (S7Value::UDInt(v), S7DataType::UDInt) => v.to_be_bytes().to_vec(),
+            (S7Value::LInt(v), S7DataType::LInt) => v.to_be_bytes().to_vec(),
+            (S7Value::ULInt(v), S7DataType::ULInt) => v.to_be_bytes().to_vec(),
+            (S7Value::Real(v), S7DataType::Real) => v.to_be_bytes().to_vec(),
+            (S7Value::LReal(v), S7DataType::LReal) => v.to_be_bytes().to_vec(),
+            (S7Value::Char(v), S7DataType::Char) => vec![latin1(*v)],
+            (S7Value::String(v), S7DataType::String(max_len)) => {
+                let chars: Vec<u8> = v.chars().map(latin1).collect();
+                if chars.len() > max_len as usize {
+                    return Err(S7Error::InvalidFunParameter);
+                }
+                let mut bytes = vec![0u8; max_len as usize + 2];
+                bytes[0] = max_len;
+                bytes[1] = chars.len() as u8;
+                bytes[2..2 + chars.len()].copy_from_slice(&chars);
+                bytes
+            }
+            (S7Value::Time(v), S7DataType::Time) => v.to_be_bytes().to_vec(),
+            (S7Value::Date(v), S7DataType::Date) => v.to_be_bytes().to_vec(),
+            (S7Value::TimeOfDay(v), S7DataType::TimeOfDay) => v.to_be_bytes().to_vec(),
+            _ => return Err(S7Error::InvalidFunParameter),
+        };
+
+        Ok(bytes)
+    }
+
+    /// ### Returns the value as f64 (if numeric)
+    ///
+    /// Useful for generic processing (scaling, trends, deadbands) where the exact type doesn't matter.
+    /// `Bool` is converted to 0.0 / 1.0, `Char` and `String` return `None`.
+    ///
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            S7Value::Bool(v) => Some(if *v { 1.0 } else { 0.0 }),
+            S7Value::Byte(v) | S7Value::USInt(v) => Some(*v as f64),
+            S7Value::Word(v) | S7Value::UInt(v) | S7Value::Date(v) => Some(*v as f64),
+            S7Value::DWord(v) | S7Value::UDInt(v) | S7Value::TimeOfDay(v) => Some(*v as f64),
+            S7Value::LWord(v) | S7Value::ULInt(v) => Some(*v as f64),
+            S7Value::SInt(v) => Some(*v as f64),
+            S7Value::Int(v) => Some(*v as f64),
+            S7Value::DInt(v) | S7Value::Time(v) => Some(*v as f64),
+            S7Value::LInt(v) => Some(*v as f64),
+            S7Value::Real(v) => Some(*v as f64),
+            S7Value::LReal(v) => Some(*v),
+            S7Value::Char(_) | S7Value::String(_) => None,
+        }
+    }
+
+    /// ### Converts an f64 into a value of the given numeric type
+    ///
+    /// The inverse of `as_f64()`: integers are rounded to the nearest value, `Bool` is true for
+    /// any value other than 0.0.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Non-numeric type (`Char`, `String`), NaN, or value out of
+    ///   the range of an integer type.
+    ///
+    pub fn from_f64(value: f64, data_type: S7DataType) -> Result<S7Value, S7Error> {
+        fn int<T: TryFrom<i128>>(value: f64) -> Result<T, S7Error> {
+            let rounded = value.round();
+            if !rounded.is_finite() || rounded.abs() > 2f64.powi(64) {
+                return Err(S7Error::InvalidFunParameter);
+            }
+            T::try_from(rounded as i128).map_err(|_| S7Error::InvalidFunParameter)
+        }
+
+        if value.is_nan() {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let value = match data_type {
+            S7DataType::Bool => S7Value::Bool(value != 0.0),
+            S7DataType::Byte => S7Value::Byte(int(value)?),
+            S7DataType::Word => S7Value::Word(int(value)?),
+            S7DataType::DWord => S7Value::DWord(int(value)?),
+            S7DataType::LWord => S7Value::LWord(int(value)?),
+            S7DataType::SInt => S7Value::SInt(int(value)?),
+            S7DataType::USInt => S7Value::USInt(int(value)?),
+            S7DataType::Int => S7Value::Int(int(value)?),
+            S7DataType::UInt => S7Value::UInt(int(value)?),
+            S7DataType::DInt => S7Value::DInt(int(value)?),
+            S7DataType::UDInt => S7Value::UDInt(int(value)?),
+            S7DataType::LInt => S7Value::LInt(int(value)?),
+            S7DataType::ULInt => S7Value::ULInt(int(value)?),
+            S7DataType::Real => S7Value::Real(value as f32),
+            S7DataType::LReal => S7Value::LReal(value),
+            S7DataType::Time => S7Value::Time(int(value)?),
+            S7DataType::Date => S7Value::Date(int(value)?),
+            S7DataType::TimeOfDay => S7Value::TimeOfDay(int(value)?),
+            S7DataType::Char | S7DataType::String(_) => return Err(S7Error::InvalidFunParameter),
+        };
+        Ok(value)
+    }
+
+    /// ### Parses a value of the given type from a text
+    ///
+    /// The inverse of `Display`: the integers are decimal or hexadecimal with the `16#` prefix,
+    /// `Bool` is `true`/`false` or `1`/`0` (case insensitive), `String` is the text as is.
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: The text is not a value of the type, or out of its range.
+    ///
+    pub fn parse(text: &str, data_type: S7DataType) -> Result<S7Value, S7Error> {
+        fn int<T: TryFrom<i128>>(text: &str) -> Result<T, S7Error> {
+            let text = text.trim();
+            let value = match text.strip_prefix("16#") {
+                Some(hex) => i128::from_str_radix(&hex.replace('_', ""), 16),
+                None => text.parse::<i128>(),
+            };
+            value.ok().and_then(|value| T::try_from(value).ok()).ok_or(S7Error::InvalidFunParameter)
+        }
+        fn float<T: FromStr>(text: &str) -> Result<T, S7Error> {
+            text.trim().parse().map_err(|_| S7Error::InvalidFunParameter)
+        }
+
+        let value = match data_type {
+            S7DataType::Bool => match text.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => S7Value::Bool(true),
+                "false" | "0" => S7Value::Bool(false),
+                _ => return Err(S7Error::InvalidFunParameter),
+            },
+            S7DataType::Byte => S7Value::Byte(int(text)?),
+            S7DataType::Word => S7Value::Word(int(text)?),
+            S7DataType::DWord => S7Value::DWord(int(text)?),
+            S7DataType::LWord => S7Value::LWord(int(text)?),
+            S7DataType::SInt => S7Value::SInt(int(text)?),
+            S7DataType::USInt => S7Value::USInt(int(text)?),
+            S7DataType::Int => S7Value::Int(int(text)?),
+            S7DataType::UInt => S7Value::UInt(int(text)?),
+            S7DataType::DInt => S7Value::DInt(int(text)?),
+            S7DataType::UDInt => S7Value::UDInt(int(text)?),
+            S7DataType::LInt => S7Value::LInt(int(text)?),
+            S7DataType::ULInt => S7Value::ULInt(int(text)?),
+            S7DataType::Real => S7Value::Real(float(text)?),
+            S7DataType::LReal => S7Value::LReal(float(text)?),
+            S7DataType::Char => {
+                let mut chars = text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => S7Value::Char(c),
+                    _ => return Err(S7Error::InvalidFunParameter),
+                }
+            }
+            S7DataType::String(_) => S7Value::String(text.to_string()),
+            S7DataType::Time => S7Value::Time(int(text)?),
+            S7DataType::Date => S7Value::Date(int(text)?),
+            S7DataType::TimeOfDay => S7Value::TimeOfDay(int(text)?),
+        };
+        Ok(value)
+    }
+}
+
+impl fmt::Display for S7Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            S7Value::Bool(v) => write!(f, "{}", v),
+            S7Value::Byte(v) | S7Value::USInt(v) => write!(f, "{}", v),
+            S7Value::Word(v) => write!(f, "16#{:04X}", v),
+            S7Value::DWord(v) => write!(f, "16#{:08X}", v),
+            S7Value::LWord(v) => write!(f, "16#{:016X}", v),
+            S7Value::SInt(v) => write!(f, "{}", v),
+            S7Value::Int(v) => write!(f, "{}", v),
+            S7Value::UInt(v) | S7Value::Date(v) => write!(f, "{}", v),
+            S7Value::DInt(v) | S7Value::Time(v) => write!(f, "{}", v),
+            S7Value::UDInt(v) | S7Value::TimeOfDay(v) => write!(f, "{}", v),
+            S7Value::LInt(v) => write!(f, "{}", v),
+            S7Value::ULInt(v) => write!(f, "{}", v),
+            S7Value::Real(v) => write!(f, "{}", v),
+            S7Value::LReal(v) => write!(f, "{}", v),
+            S7Value::Char(v) => write!(f, "{}", v),
+            S7Value::String(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// Copies the first N bytes of the buffer into an array (the caller checks the size)
+fn be_array<const N: usize>(buffer: &[u8]) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(&buffer[..N]);
+    bytes
+}
+
+fn latin1(c: char) -> u8 {
+    if (c as u32) < 256 { c as u8 } else { b'?' }
+}
+
+fn from_bcd(byte: u8) -> Option<u64> {
+    let (high, low) = (byte >> 4, byte & 0x0F);
+    if high > 9 || low > 9 { None } else { Some((high * 10 + low) as u64) }
+}
+
+/// ### Decodes an S7 DATE_AND_TIME (8 byte BCD)
+///
+/// ```text
+/// [0] Year (90..99 = 1990..1999, 00..89 = 2000..2089)  [1] Month  [2] Day
+/// [3] Hour  [4] Minute  [5] Second  [6] ms (2 high digits)  [7] ms (low digit) + Weekday
+/// ```
+/// The CPU clock has no time zone: the time is returned as if it were UTC.
+/// `None` if the buffer is shorter than 8 byte or a field is not valid.
+///
+pub(crate) fn date_time_from_bcd(raw: &[u8]) -> Option<SystemTime> {
+    if raw.len() < 8 {
+        return None;
+    }
+    let year = from_bcd(raw[0])?;
+    let year = if year >= 90 { 1900 + year } else { 2000 + year };
+    let (month, day) = (from_bcd(raw[1])?, from_bcd(raw[2])?);
+    let (hour, minute, second) = (from_bcd(raw[3])?, from_bcd(raw[4])?, from_bcd(raw[5])?);
+    let ms = from_bcd(raw[6])? * 10 + from_bcd(raw[7] >> 4)?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    // Days from 1970-01-01 (civil calendar)
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era_days = y / 400 * 146_097 + (y % 400) * 365 + (y % 400) / 4 - (y % 400) / 100 + (153 * m + 2) / 5 + day - 1;
+    let days = era_days - 719_468;
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_millis(ms))
+}
+
+fn to_bcd(value: u64) -> u8 {
+    ((((value / 10) % 10) << 4) | (value % 10)) as u8
+}
+
+/// ### Encodes an S7 DATE_AND_TIME (8 byte BCD), inverse of `date_time_from_bcd()`
+///
+/// The weekday is 1 (Sunday) ..= 7 (Saturday). `None` outside 1990..=2089.
+///
+pub(crate) fn date_time_to_bcd(time: SystemTime) -> Option<[u8; 8]> {
+    let since_epoch = time.duration_since(UNIX_EPOCH).ok()?;
+    let secs = since_epoch.as_secs();
+    let ms = since_epoch.subsec_millis() as u64;
+    let days = secs / 86_400;
+    let second_of_day = secs % 86_400;
+    // Civil date from the days since 1970-01-01
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    if !(1990..=2089).contains(&year) {
+        return None;
+    }
+    let weekday = (days + 4) % 7 + 1; // 1970-01-01 was a Thursday
+    Some([
+        to_bcd(year % 100),
+        to_bcd(month),
+        to_bcd(day),
+        to_bcd(second_of_day / 3600),
+        to_bcd(second_of_day / 60 % 60),
+        to_bcd(second_of_day % 60),
+        to_bcd(ms / 10),
+        (to_bcd(ms % 10) << 4) | weekday as u8,
+    ])
+}
+
+#[cfg(feature = "serde")]
+impl S7Value {
+    /// ### Converts the value into a JSON value
+    ///
+    /// Integers are mapped to JSON numbers without loss (including 64 bit values),
+    /// `Char` and `String` to JSON strings.
+    ///
+    /// ### Notes
+    /// - JSON has no representation for NaN and Infinity, such floating point values are converted to `null`.
+    ///
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+        match self {
+            S7Value::Bool(v) => Value::from(*v),
+            S7Value::Byte(v) | S7Value::USInt(v) => Value::from(*v),
+            S7Value::Word(v) | S7Value::UInt(v) | S7Value::Date(v) => Value::from(*v),
+            S7Value::DWord(v) | S7Value::UDInt(v) | S7Value::TimeOfDay(v) => Value::from(*v),
+            S7Value::LWord(v) | S7Value::ULInt(v) => Value::from(*v),
+            S7Value::SInt(v) => Value::from(*v),
+            S7Value::Int(v) => Value::from(*v),
+            S7Value::DInt(v) | S7Value::Time(v) => Value::from(*v),
+            S7Value::LInt(v) => Value::from(*v),
+            // f32 -> f64 is exact, from_f64() returns None for NaN/Infinity
+            S7Value::Real(v) => serde_json::Number::from_f64(*v as f64).map_or(Value::Null, Value::Number),
+            S7Value::LReal(v) => serde_json::Number::from_f64(*v).map_or(Value::Null, Value::Number),
+            S7Value::Char(v) => Value::from(v.to_string()),
+            S7Value::String(v) => Value::from(v.as_str()),
+        }
+    }
+
+    /// ### Converts a JSON value into an `S7Value` of the given type
+    ///
+    /// The conversion never truncates silently: a JSON value that cannot be represented
+    /// exactly by the destination type is rejected.
+    ///
+    /// ### Parameters
+    /// - `json`: Source JSON value.
+    /// - `data_type`: Type of the destination variable.
+    ///
+    /// ### Returns
+    /// `Ok(<S7Value>)` or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`:
+    /// 1. The JSON value kind doesn't match the data type (e.g. a string for an INT).
+    /// 2. The number is out of range (beyond the f32 range for a `Real`), has a fractional part
+    ///    for an integer type, or is an integer without an exact f64 for an `LReal` (beyond 2^53).
+    /// 3. The string is longer than the max length or contains characters outside Latin-1.
+    ///
+    /// ### Notes
+    /// - For `Real` the number is rounded to the nearest f32 (this is the only accepted precision loss).
+    /// - `Bool` also accepts the numbers 0 and 1.
+    ///
+    pub fn from_json(json: &serde_json::Value, data_type: S7DataType) -> Result<S7Value, S7Error> {
+        use serde_json::Value;
+
+        fn int<T: TryFrom<i64>>(json: &Value) -> Result<T, S7Error> {
+            json.as_i64()
+                .and_then(|v| T::try_from(v).ok())
+                .ok_or(S7Error::InvalidFunParameter)
+        }
+
+        fn uint<T: TryFrom<u64>>(json: &Value) -> Result<T, S7Error> {
+            json.as_u64()
+                .and_then(|v| T::try_from(v).ok())
+                .ok_or(S7Error::InvalidFunParameter)
+        }
+
+        fn real(json: &Value) -> Result<f32, S7Error> {
+            let value = json.as_f64().ok_or(S7Error::InvalidFunParameter)?;
+            let real = value as f32;
+            if real.is_infinite() && value.is_finite() {
+                return Err(S7Error::InvalidFunParameter); // Beyond the f32 range
+            }
+            Ok(real)
+        }
+
+        fn lreal(json: &Value) -> Result<f64, S7Error> {
+            let value = json.as_f64().ok_or(S7Error::InvalidFunParameter)?;
+            // Integers beyond 2^53 don't all have an exact f64
+            let exact = match (json.as_i64(), json.as_u64()) {
+                (Some(integer), _) => value as i128 == integer as i128,
+                (None, Some(integer)) => value as i128 == integer as i128,
+                (None, None) => true,
+            };
+            if !exact {
+                return Err(S7Error::InvalidFunParameter);
+            }
+            Ok(value)
+        }
+
+        let value = match data_type {
+            S7DataType::Bool => match json {
+                Value::Bool(v) => S7Value::Bool(*v),
+                _ => match json.as_u64() {
+                    Some(0) => S7Value::Bool(false),
+                    Some(1) => S7Value::Bool(true),
+                    _ => return Err(S7Error::InvalidFunParameter),
+                },
+            },
+            S7DataType::Byte => S7Value::Byte(uint(json)?),
+            S7DataType::Word => S7Value::Word(uint(json)?),
+            S7DataType::DWord => S7Value::DWord(uint(json)?),
+            S7DataType::LWord => S7Value::LWord(uint(json)?),
+            S7DataType::SInt => S7Value::SInt(int(json)?),
+            S7DataType::USInt => S7Value::USInt(uint(json)?),
+            S7DataType::Int => S7Value::Int(int(json)?),
+            S7DataType::UInt => S7Value::UInt(uint(json)?),
+            S7DataType::DInt => S7Value::DInt(int(json)?),
+            S7DataType::UDInt => S7Value::UDInt(uint(json)?),
+            S7DataType::LInt => S7Value::LInt(int(json)?),
+            S7DataType::ULInt => S7Value::ULInt(uint(json)?),
+            S7DataType::Real => S7Value::Real(real(json)?),
+            S7DataType::LReal => S7Value::LReal(lreal(json)?),
+            S7DataType::Char => {
+                let s = json.as_str().ok_or(S7Error::InvalidFunParameter)?;
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if (c as u32) < 256 => S7Value::Char(c),
+                    _ => return Err(S7Error::InvalidFunParameter),
+                }
+            }
+            S7DataType::String(max_len) => {
+                let s = json.as_str().ok_or(S7Error::InvalidFunParameter)?;
+                if s.chars().count() > max_len as usize || s.chars().any(|c| (c as u32) >= 256) {
+                    return Err(S7Error::InvalidFunParameter);
+                }
+                S7Value::String(s.to_string())
+            }
+            S7DataType::Time => S7Value::Time(int(json)?),
+            S7DataType::Date => S7Value::Date(uint(json)?),
+            S7DataType::TimeOfDay => S7Value::TimeOfDay(uint(json)?),
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<&S7Value> for serde_json::Value {
+    fn from(value: &S7Value) -> Self {
+        value.to_json()
+    }
+}
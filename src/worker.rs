@@ -0,0 +1,487 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Worker-thread client
+//!
+//! `S7Worker` moves an `S7Client` into a background thread that owns the connection and executes
+//! the jobs received through a channel, one at a time. Any number of threads can submit jobs via
+//! cloned `S7WorkerHandle`s, each job returns a `Responder` that delivers its result. No lock has
+//! to be held by the application and the jobs are never interleaved on the wire.
+//!
+//! Each handle has a `JobPriority`: queued jobs with higher priority are executed first, so an
+//! operator command is not delayed by the background polling queued before it.
+//!
+//! A worker created by `spawn_with_aggregation()` combines the small reads (`read_area()` and
+//! `read_value()` of the handles) queued within a short window into multi-variable requests, so
+//! independent components polling a few bytes each share the telegrams without changing their code.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use rust7::{S7Client, S7DataType, S7_AREA_DB};
+//! use rust7::worker::{JobPriority, S7Worker};
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//! let worker = S7Worker::spawn(client);
+//!
+//! let handle = worker.handle();
+//! let poller = std::thread::spawn(move || {
+//!     handle.read_value(S7_AREA_DB, 100, 0, S7DataType::Real).wait()
+//! });
+//!
+//! // Operator command: executed before the queued polling jobs
+//! let operator = worker.handle().with_priority(JobPriority::High);
+//! operator.write_area(S7_AREA_DB, 100, 4, rust7::S7_WL_BYTE, vec![0, 1]).wait().unwrap();
+//! println!("Speed = {}", poller.join().unwrap().unwrap());
+//!
+//! let mut client = worker.stop().unwrap(); // Gets the client back
+//! client.disconnect();
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crate::client::{S7Client, S7Error, S7_WL_BIT, S7_WL_BYTE};
+use crate::multivar::{read_fits, S7DataItem, MAX_VARS};
+use crate::value::{S7DataType, S7Value};
+
+type Job = Box<dyn FnOnce(&mut S7Client) + Send>;
+
+/// Read of a block, that the worker can aggregate with other reads
+struct ReadJob {
+    area: u8,
+    db_number: u16,
+    start: u32,
+    wordlen: u8,
+    size: usize,
+    complete: Box<dyn FnOnce(Result<Vec<u8>, S7Error>) + Send>,
+}
+
+impl ReadJob {
+    fn execute(self, client: &mut S7Client) {
+        let mut buffer = vec![0u8; self.size];
+        let result = client.read_area(self.area, self.db_number, self.start, self.wordlen, &mut buffer);
+        (self.complete)(result.map(|_| buffer));
+    }
+}
+
+enum Task {
+    Job(Job),
+    Read(ReadJob),
+}
+
+enum Command {
+    Task(JobPriority, Task),
+    Stop,
+}
+
+/// ### Priority of the jobs submitted through a handle
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum JobPriority {
+    /// Operator commands, setpoint changes
+    High,
+    /// Default
+    #[default]
+    Normal,
+    /// Bulk background transfers (backups, archiving)
+    Low,
+}
+
+/// Queued jobs, one FIFO per priority
+#[derive(Default)]
+struct JobQueue {
+    queues: [VecDeque<Task>; 3], // Indexed by JobPriority
+    stopping: bool,
+}
+
+impl JobQueue {
+    fn push(&mut self, command: Command) {
+        match command {
+            Command::Task(priority, task) if !self.stopping => self.queues[priority as usize].push_back(task),
+            Command::Task(..) => {} // Queued after stop(): discarded
+            Command::Stop => self.stopping = true,
+        }
+    }
+
+    fn pop(&mut self) -> Option<(usize, Task)> {
+        self.queues.iter_mut().enumerate().find_map(|(priority, queue)| Some((priority, queue.pop_front()?)))
+    }
+
+    /// Pops the next job of the priority if it is a read and no higher priority job is queued
+    fn pop_read(&mut self, priority: usize) -> Option<ReadJob> {
+        if self.queues[..priority].iter().any(|queue| !queue.is_empty()) {
+            return None;
+        }
+        match self.queues[priority].pop_front()? {
+            Task::Read(read) => Some(read),
+            task => {
+                self.queues[priority].push_front(task);
+                None
+            }
+        }
+    }
+
+    /// A job that must not wait for the aggregation is queued
+    fn has_urgent(&self, priority: usize) -> bool {
+        self.stopping
+            || self.queues[..priority].iter().any(|queue| !queue.is_empty())
+            || matches!(self.queues[priority].front(), Some(Task::Job(_)))
+    }
+
+    /// Nothing to do until the next command
+    fn is_idle(&self) -> bool {
+        !self.stopping && self.queues.iter().all(VecDeque::is_empty)
+    }
+}
+
+/// ### Pending result of a job
+///
+pub struct Responder<T> {
+    receiver: Receiver<Result<T, S7Error>>,
+}
+
+impl<T> Responder<T> {
+    /// ### Waits for the result of the job
+    ///
+    /// ### Errors
+    /// - Returned by the job.
+    /// - `S7Error::Other`: The worker stopped before executing the job.
+    ///
+    pub fn wait(self) -> Result<T, S7Error> {
+        self.receiver.recv().unwrap_or_else(|_| Err(worker_stopped()))
+    }
+
+    /// ### Waits for the result of the job, at most `timeout`
+    ///
+    /// ### Returns
+    /// `Some(<result>)`, or `None` if the job is still queued or running.
+    ///
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<Result<T, S7Error>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => Some(result),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(Err(worker_stopped())),
+        }
+    }
+
+    /// ### Returns the result of the job, if available
+    ///
+    /// ### Returns
+    /// `Some(<result>)`, or `None` if the job is still queued or running.
+    ///
+    pub fn try_get(&self) -> Option<Result<T, S7Error>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(worker_stopped())),
+        }
+    }
+}
+
+const WORKER_STOPPED: &str = "S7 worker stopped";
+
+fn worker_stopped() -> S7Error {
+    S7Error::Other(WORKER_STOPPED.to_string())
+}
+
+/// Collects the reads queued, or arriving within the window, behind the first one
+fn gather_reads(first: ReadJob, priority: usize, queue: &mut JobQueue, receiver: &Receiver<Command>, window: Duration) -> Vec<ReadJob> {
+    let deadline = Instant::now() + window;
+    let mut batch = vec![first];
+    loop {
+        while batch.len() < MAX_VARS {
+            match queue.pop_read(priority) {
+                Some(read) => batch.push(read),
+                None => break,
+            }
+        }
+        let now = Instant::now();
+        if batch.len() >= MAX_VARS || queue.has_urgent(priority) || now >= deadline {
+            return batch;
+        }
+        match receiver.recv_timeout(deadline - now) {
+            Ok(command) => queue.push(command),
+            Err(_) => return batch, // Window elapsed or all handles dropped
+        }
+    }
+}
+
+/// Executes the reads, packed into multi-variable requests that fit into the PDU
+fn execute_reads(client: &mut S7Client, reads: Vec<ReadJob>) {
+    if client.read_area_extended() {
+        // The cache, the retries and the error mapping apply only to read_area()
+        reads.into_iter().for_each(|read| read.execute(client));
+        return;
+    }
+    let pdu_length = client.pdu_length as usize;
+    let mut group: Vec<ReadJob> = Vec::new();
+    for read in reads {
+        let mut sizes: Vec<usize> = group.iter().map(|read| read.size).collect();
+        sizes.push(read.size);
+        if !group.is_empty() && (group.len() >= MAX_VARS || !read_fits(&sizes, pdu_length)) {
+            execute_group(client, std::mem::take(&mut group));
+        }
+        if read_fits(&[read.size], pdu_length) {
+            group.push(read);
+        } else {
+            read.execute(client); // Larger than a PDU: split into chunks by read_area()
+        }
+    }
+    execute_group(client, group);
+}
+
+fn execute_group(client: &mut S7Client, group: Vec<ReadJob>) {
+    if group.len() <= 1 {
+        group.into_iter().for_each(|read| read.execute(client));
+        return;
+    }
+    let mut items: Vec<S7DataItem> = group.iter()
+        .map(|read| S7DataItem::new(read.area, read.wordlen, read.db_number, read.start, read.size as u16))
+        .collect();
+    let link_errors = client.traffic_stats().errors;
+    match client.read_multi_vars(&mut items) {
+        Ok(()) => {
+            for (read, item) in group.into_iter().zip(items) {
+                (read.complete)(item.result.map(|_| item.data));
+            }
+        }
+        // Connection lost: every read fails
+        Err(error) if client.traffic_stats().errors > link_errors || matches!(error.root(), S7Error::NotConnected) => {
+            for read in group {
+                (read.complete)(Err(error.clone()));
+            }
+        }
+        // Request refused as a whole (e.g. a CPU without multi-variable support): one by one
+        Err(_) => group.into_iter().for_each(|read| read.execute(client)),
+    }
+}
+
+/// Returns true if the error reports a job not executed because the worker stopped
+pub(crate) fn is_worker_stopped(error: &S7Error) -> bool {
+    matches!(error, S7Error::Other(message) if message == WORKER_STOPPED)
+}
+
+/// ### Handle submitting jobs to an `S7Worker`
+///
+/// Cheap to clone and `Send`: give one to each thread that needs the PLC.
+///
+#[derive(Clone)]
+pub struct S7WorkerHandle {
+    sender: Sender<Command>,
+    priority: JobPriority,
+}
+
+impl S7WorkerHandle {
+    /// ### Returns a handle submitting the jobs with the given priority
+    ///
+    /// ### Notes
+    /// - The priority orders the queued jobs, a running job is never interrupted: split large
+    ///   background transfers into several jobs so that a high-priority job waits at most one of them.
+    /// - Jobs with the same priority are executed in submission order. Lower priorities wait as long
+    ///   as higher-priority jobs are queued.
+    ///
+    pub fn with_priority(&self, priority: JobPriority) -> S7WorkerHandle {
+        S7WorkerHandle {
+            sender: self.sender.clone(),
+            priority,
+        }
+    }
+
+    /// ### Returns the priority of the jobs submitted through this handle
+    ///
+    pub fn priority(&self) -> JobPriority {
+        self.priority
+    }
+
+    /// ### Queues a job executed with exclusive access to the client
+    ///
+    /// The job is queued with the priority of the handle (see `with_priority()`).
+    ///
+    /// ### Parameters
+    /// - `job`: Function receiving the client, its result is delivered by the returned `Responder`.
+    ///
+    /// ### Notes
+    /// The job runs on the worker thread: a long job delays the following ones.
+    ///
+    pub fn execute<T, F>(&self, job: F) -> Responder<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut S7Client) -> Result<T, S7Error> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let job: Job = Box::new(move |client| {
+            let _ = sender.send(job(client));
+        });
+        // If the worker is stopped the job is dropped with its sender, and the responder reports it
+        let _ = self.sender.send(Command::Task(self.priority, Task::Job(job)));
+        Responder { receiver }
+    }
+
+    /// Queues a read, aggregated with the others if the worker does it
+    fn queue_read<T, F>(&self, area: u8, db_number: u16, start: u32, wordlen: u8, size: usize, convert: F) -> Responder<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Vec<u8>) -> Result<T, S7Error> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let read = ReadJob {
+            area,
+            db_number,
+            start,
+            wordlen,
+            size,
+            complete: Box::new(move |result| {
+                let _ = sender.send(result.and_then(convert));
+            }),
+        };
+        let _ = self.sender.send(Command::Task(self.priority, Task::Read(read)));
+        Responder { receiver }
+    }
+
+    /// ### Queues a `read_area()`
+    ///
+    /// ### Returns
+    /// A `Responder` delivering the `size` bytes read.
+    ///
+    /// ### Notes
+    /// With `S7Worker::spawn_with_aggregation()` the read can be combined with other reads into a
+    /// multi-variable request, unless the client applies options that only `read_area()` honors
+    /// (see `S7Worker::spawn_with_aggregation()`).
+    ///
+    pub fn read_area(&self, area: u8, db_number: u16, start: u32, wordlen: u8, size: usize) -> Responder<Vec<u8>> {
+        let size = if wordlen == S7_WL_BIT { size.min(1) } else { size };
+        self.queue_read(area, db_number, start, wordlen, size, Ok)
+    }
+
+    /// ### Queues a `write_area()`
+    ///
+    pub fn write_area(&self, area: u8, db_number: u16, start: u32, wordlen: u8, data: Vec<u8>) -> Responder<()> {
+        self.execute(move |client| client.write_area(area, db_number, start, wordlen, &data))
+    }
+
+    /// ### Queues a `read_value()`
+    ///
+    /// Aggregated as `read_area()`.
+    ///
+    pub fn read_value(&self, area: u8, db_number: u16, start: u32, data_type: S7DataType) -> Responder<S7Value> {
+        let wordlen = if data_type == S7DataType::Bool { S7_WL_BIT } else { S7_WL_BYTE };
+        self.queue_read(area, db_number, start, wordlen, data_type.size(), move |buffer| S7Value::decode(data_type, &buffer))
+    }
+
+    /// ### Queues a `write_value()`
+    ///
+    pub fn write_value(&self, area: u8, db_number: u16, start: u32, data_type: S7DataType, value: S7Value) -> Responder<()> {
+        self.execute(move |client| client.write_value(area, db_number, start, data_type, &value))
+    }
+}
+
+/// ### Background thread owning an `S7Client`
+///
+pub struct S7Worker {
+    handle: S7WorkerHandle,
+    thread: Option<JoinHandle<S7Client>>,
+}
+
+impl S7Worker {
+    /// ### Moves the client into a new worker thread
+    ///
+    /// ### Parameters
+    /// - `client`: The client, usually already configured and connected. Jobs can also (re)connect it.
+    ///
+    pub fn spawn(client: S7Client) -> Self {
+        Self::start(client, None)
+    }
+
+    /// ### Moves the client into a new worker thread that aggregates the small reads
+    ///
+    /// When the worker takes a read (`read_area()` or `read_value()` of a handle), it waits up to
+    /// `window` for other reads and executes them together with `read_multi_vars()`, up to
+    /// `MAX_VARS` per telegram and within the negotiated PDU.
+    ///
+    /// ### Parameters
+    /// - `client`: The client, usually already configured and connected.
+    /// - `window`: Max wait for other reads. `Duration::ZERO` aggregates only the reads already
+    ///   queued, without delaying any of them.
+    ///
+    /// ### Notes
+    /// - Only consecutive reads of the same priority are aggregated: a read never overtakes a job
+    ///   queued before it, and the wait ends as soon as another job or a higher priority job arrives.
+    /// - If the CPU refuses the multi-variable request as a whole the reads are executed one by one;
+    ///   if the connection fails every read of the request reports the error.
+    /// - The jobs submitted with `execute()` are never aggregated.
+    /// - The reads are not aggregated, and are executed one by one with `read_area()`, while the
+    ///   client has the read cache, a retry policy, the error context, the CPU stop verification or
+    ///   the address diagnosis enabled: `read_multi_vars()` doesn't apply them.
+    ///
+    pub fn spawn_with_aggregation(client: S7Client, window: Duration) -> Self {
+        Self::start(client, Some(window))
+    }
+
+    fn start(mut client: S7Client, aggregation: Option<Duration>) -> Self {
+        let (sender, receiver) = mpsc::channel::<Command>();
+        let thread = std::thread::spawn(move || {
+            let mut queue = JobQueue::default();
+            loop {
+                if queue.is_idle() {
+                    match receiver.recv() {
+                        Ok(command) => queue.push(command),
+                        Err(_) => break,
+                    }
+                }
+                while let Ok(command) = receiver.try_recv() {
+                    queue.push(command);
+                }
+                match queue.pop() {
+                    Some((_, Task::Job(job))) => job(&mut client),
+                    Some((priority, Task::Read(read))) => match aggregation {
+                        Some(window) => {
+                            let reads = gather_reads(read, priority, &mut queue, &receiver, window);
+                            execute_reads(&mut client, reads);
+                        }
+                        None => read.execute(&mut client),
+                    },
+                    None if queue.stopping => break,
+                    None => {}
+                }
+            }
+            client
+        });
+        S7Worker {
+            handle: S7WorkerHandle { sender, priority: JobPriority::Normal },
+            thread: Some(thread),
+        }
+    }
+
+    /// ### Returns a new handle to submit jobs (priority `JobPriority::Normal`)
+    ///
+    pub fn handle(&self) -> S7WorkerHandle {
+        self.handle.clone()
+    }
+
+    /// ### Stops the worker and returns the client
+    ///
+    /// The jobs queued before the call are executed, the ones queued after are discarded
+    /// (their `Responder` reports `S7Error::Other`).
+    ///
+    /// ### Errors
+    /// - `S7Error::Other`: The worker thread panicked (e.g. in a job closure), the client is lost.
+    ///
+    pub fn stop(mut self) -> Result<S7Client, S7Error> {
+        self.join().ok_or_else(|| S7Error::Other("S7 worker thread panicked".to_string()))
+    }
+
+    fn join(&mut self) -> Option<S7Client> {
+        let thread = self.thread.take()?;
+        let _ = self.handle.sender.send(Command::Stop);
+        thread.join().ok()
+    }
+}
+
+impl Drop for S7Worker {
+    fn drop(&mut self) {
+        // The client (and its connection) is dropped along with the thread
+        self.join();
+    }
+}
@@ -0,0 +1,321 @@
+// Rust7 - Native Rust S7 client (Snap7‑style) for Siemens PLCs.
+// Copyright 2025 - Davide Nardella
+
+//! ### Coalescing write queue
+//!
+//! `WriteQueue` buffers the writes issued in a short window and sends them together: the writes
+//! to the same address are merged (the latest value wins), contiguous bytes are written as one
+//! variable and the variables are packed into multi-variable telegrams, in the order of the
+//! writes. A UI slider generating dozens of setpoint writes per second produces a single telegram
+//! per window instead of flooding the connection.
+//!
+//! ### Example
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use rust7::{S7Client, S7DataType, S7Value, S7_AREA_DB};
+//! use rust7::write_queue::WriteQueue;
+//!
+//! let mut client = S7Client::new();
+//! client.connect_s71200_1500("192.168.0.100").unwrap();
+//!
+//! let mut queue = WriteQueue::new(Duration::from_millis(100));
+//! for setpoint in 0..50 {
+//!     // Slider moved: only the last value of the window reaches the PLC
+//!     queue.write_value(S7_AREA_DB, 100, 4, S7DataType::Real, &S7Value::Real(setpoint as f32)).unwrap();
+//!     queue.flush_if_due(&mut client).unwrap();
+//!     std::thread::sleep(Duration::from_millis(10));
+//! }
+//! queue.flush(&mut client).unwrap();
+//! ```
+
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Included};
+use std::time::{Duration, Instant};
+use crate::client::{S7Client, S7Error, S7_AREA_DB, S7_AREA_MK, S7_AREA_PA, S7_AREA_PE, S7_WL_BIT, S7_WL_BYTE};
+use crate::frame::{DATA_ITEM_HEADER_LEN, ITEM_SPEC_LEN, S7_JOB_HEADER_LEN};
+use crate::multivar::{S7DataItem, MAX_VARS};
+use crate::value::{S7DataType, S7Value};
+
+/// Address of a pending byte or bit: area, DB number (0 for non-DB areas), byte or bit index
+type Address = (u8, u16, u32);
+
+/// Size of the addressable area (the start of an item spec is a 24-bit bit address)
+const MAX_AREA_BYTES: usize = 1 << 21;
+
+/// ### Buffer of pending writes, merged by address
+///
+/// The writes are sent in the order they were queued: an address written again takes the place
+/// of its latest write.
+///
+pub struct WriteQueue {
+    window: Duration,
+    /// Pending bytes and bits with the sequence number of their latest write
+    bytes: BTreeMap<Address, (u64, u8)>,
+    bits: BTreeMap<Address, (u64, bool)>,
+    sequence: u64,
+    /// Time of the oldest pending write
+    first_pending: Option<Instant>,
+}
+
+fn check_area(area: u8) -> Result<(), S7Error> {
+    match area {
+        S7_AREA_PE | S7_AREA_PA | S7_AREA_MK | S7_AREA_DB => Ok(()),
+        _ => Err(S7Error::InvalidFunParameter),
+    }
+}
+
+fn db_of(area: u8, db_number: u16) -> u16 {
+    if area == S7_AREA_DB { db_number } else { 0 }
+}
+
+/// Size of an item in a Write Var request (fill byte included)
+fn request_size(item: &S7DataItem) -> usize {
+    ITEM_SPEC_LEN + DATA_ITEM_HEADER_LEN + item.data.len() + item.data.len() % 2
+}
+
+impl WriteQueue {
+    /// ### Creates an empty queue
+    ///
+    /// ### Parameters
+    /// - `window`: Max time a write waits in the queue before `flush_if_due()` sends it.
+    ///
+    pub fn new(window: Duration) -> Self {
+        WriteQueue {
+            window,
+            bytes: BTreeMap::new(),
+            bits: BTreeMap::new(),
+            sequence: 0,
+            first_pending: None,
+        }
+    }
+
+    /// ### Queues the write of a block of bytes
+    ///
+    /// ### Parameters
+    /// - `area`: `S7_AREA_PE`, `S7_AREA_PA`, `S7_AREA_MK` or `S7_AREA_DB`.
+    /// - `db_number`: DB number (ignored for non-DB areas).
+    /// - `start`: First byte.
+    /// - `data`: The bytes to write. They replace the pending writes of the same bytes (bits included).
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Invalid area, empty data or block beyond 2 MB.
+    ///
+    pub fn write_area(&mut self, area: u8, db_number: u16, start: u32, data: &[u8]) -> Result<(), S7Error> {
+        check_area(area)?;
+        if data.is_empty() || start as usize + data.len() > MAX_AREA_BYTES {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let db = db_of(area, db_number);
+        let end = start + data.len() as u32;
+        // Bits of the written bytes are superseded
+        let superseded: Vec<Address> = self.bits
+            .range((Included((area, db, start * 8)), Excluded((area, db, end * 8))))
+            .map(|(address, _)| *address)
+            .collect();
+        for address in superseded {
+            self.bits.remove(&address);
+        }
+        let sequence = self.next_sequence();
+        for (offset, byte) in data.iter().enumerate() {
+            self.bytes.insert((area, db, start + offset as u32), (sequence, *byte));
+        }
+        self.first_pending.get_or_insert_with(Instant::now);
+        Ok(())
+    }
+
+    /// ### Queues the write of a bit
+    ///
+    /// ### Parameters
+    /// - `bit_index`: Bit address (`byte_num * 8 + bit_idx`).
+    ///
+    /// ### Errors
+    /// - `S7Error::InvalidFunParameter`: Invalid area or bit beyond 2 MB.
+    ///
+    /// ### Notes
+    /// If a write of the byte containing the bit is pending, the bit is set into it and the byte is
+    /// sent in the place of the bit write.
+    ///
+    pub fn write_bit(&mut self, area: u8, db_number: u16, bit_index: u32, value: bool) -> Result<(), S7Error> {
+        check_area(area)?;
+        if bit_index as usize >= MAX_AREA_BYTES * 8 {
+            return Err(S7Error::InvalidFunParameter);
+        }
+        let db = db_of(area, db_number);
+        let sequence = self.next_sequence();
+        match self.bytes.get_mut(&(area, db, bit_index / 8)) {
+            Some((byte_sequence, byte)) => {
+                *byte_sequence = sequence;
+                if value {
+                    *byte |= 1 << (bit_index % 8);
+                } else {
+                    *byte &= !(1 << (bit_index % 8));
+                }
+            }
+            None => {
+                self.bits.insert((area, db, bit_index), (sequence, value));
+            }
+        }
+        self.first_pending.get_or_insert_with(Instant::now);
+        Ok(())
+    }
+
+    /// ### Queues the write of a typed value (same addressing as `S7Client::write_value()`)
+    ///
+    /// ### Errors
+    /// - Reported by `S7Value::encode()`, `write_area()` and `write_bit()`
+    ///
+    pub fn write_value(&mut self, area: u8, db_number: u16, start: u32, data_type: S7DataType, value: &S7Value) -> Result<(), S7Error> {
+        let buffer = value.encode(data_type)?;
+        if data_type == S7DataType::Bool {
+            self.write_bit(area, db_number, start, buffer[0] != 0)
+        } else {
+            self.write_area(area, db_number, start, &buffer)
+        }
+    }
+
+    /// ### Returns true if no writes are pending
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty() && self.bits.is_empty()
+    }
+
+    /// ### Returns the number of pending addresses (bytes and bits)
+    ///
+    pub fn len(&self) -> usize {
+        self.bytes.len() + self.bits.len()
+    }
+
+    /// ### Discards the pending writes
+    ///
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.bits.clear();
+        self.first_pending = None;
+    }
+
+    /// ### Returns true if the oldest pending write waited at least the window
+    ///
+    pub fn is_due(&self) -> bool {
+        self.first_pending.is_some_and(|time| time.elapsed() >= self.window)
+    }
+
+    /// ### Sends the pending writes if they are due (see `flush()`)
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)` number of write telegrams sent (0 if not due), or `Err(<S7Error>)`
+    ///
+    pub fn flush_if_due(&mut self, client: &mut S7Client) -> Result<usize, S7Error> {
+        if self.is_due() { self.flush(client) } else { Ok(0) }
+    }
+
+    /// ### Sends all the pending writes
+    ///
+    /// The writes are sent in the order they were queued (an address written again in the place of
+    /// its latest write). Contiguous bytes written one after the other are sent as one variable,
+    /// the variables are packed into Write Var telegrams up to `MAX_VARS` and the negotiated PDU.
+    /// A block larger than a PDU is written by `S7Client::write_area()`, split into chunks.
+    ///
+    /// ### Parameters
+    /// - `client`: A connected client.
+    ///
+    /// ### Returns
+    /// `Ok(<usize>)` number of write telegrams sent, or `Err(<S7Error>)`
+    ///
+    /// ### Errors
+    /// - `S7Error::NotConnected`, `S7Error::Io` and the ISO errors: The transfer failed. The writes not
+    ///   confirmed are queued again, so a `flush()` after the reconnection sends them.
+    /// - The first error reported by the PLC for a variable (e.g. `S7Error::S7InvalidAddress`).
+    ///   The other variables are written anyway, the refused ones are discarded.
+    ///
+    pub fn flush(&mut self, client: &mut S7Client) -> Result<usize, S7Error> {
+        let mut items = self.take_items();
+        let overhead = S7_JOB_HEADER_LEN + 2;
+        let pdu_length = client.pdu_length as usize;
+        let mut telegrams = 0;
+        let mut first_error = None;
+
+        let mut index = 0;
+        while index < items.len() {
+            let link_errors = client.traffic_stats().errors;
+            let before = client.traffic_stats().frames_sent;
+            let (end, result) = if overhead + request_size(&items[index]) > pdu_length {
+                let item = &items[index];
+                (index + 1, client.write_area(item.area, item.db, item.start, item.wordlen, &item.data))
+            } else {
+                let mut end = index;
+                let mut size = overhead;
+                while end < items.len() && end - index < MAX_VARS && size + request_size(&items[end]) <= pdu_length {
+                    size += request_size(&items[end]);
+                    end += 1;
+                }
+                (end, client.write_multi_vars(&mut items[index..end]))
+            };
+            telegrams += (client.traffic_stats().frames_sent - before) as usize;
+
+            match result {
+                Ok(()) => {}
+                Err(error) if matches!(error.root(), S7Error::NotConnected) || client.traffic_stats().errors > link_errors => {
+                    self.requeue(&items[index..]);
+                    return Err(error);
+                }
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+            for item in items[index..end].iter_mut() {
+                if let Err(error) = std::mem::replace(&mut item.result, Ok(())) {
+                    first_error.get_or_insert(error);
+                }
+            }
+            index = end;
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(telegrams),
+        }
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence += 1;
+        self.sequence
+    }
+
+    /// Empties the queue and returns the pending writes as variables in the order of the writes,
+    /// contiguous bytes following each other merged
+    fn take_items(&mut self) -> Vec<S7DataItem> {
+        // (sequence, address, wordlen, value)
+        let mut writes: Vec<(u64, Address, u8, u8)> = std::mem::take(&mut self.bytes).into_iter()
+            .map(|(address, (sequence, byte))| (sequence, address, S7_WL_BYTE, byte))
+            .chain(std::mem::take(&mut self.bits).into_iter()
+                .map(|(address, (sequence, value))| (sequence, address, S7_WL_BIT, value as u8)))
+            .collect();
+        writes.sort_unstable();
+
+        let mut items: Vec<S7DataItem> = Vec::new();
+        for (_, (area, db, address), wordlen, value) in writes {
+            match items.last_mut() {
+                Some(item) if wordlen == S7_WL_BYTE && item.wordlen == S7_WL_BYTE && item.amount < u16::MAX
+                    && (item.area, item.db, item.start + item.data.len() as u32) == (area, db, address) => {
+                    item.data.push(value);
+                    item.amount += 1;
+                }
+                _ => items.push(S7DataItem { area, wordlen, db, start: address, amount: 1, data: vec![value], result: Ok(()) }),
+            }
+        }
+        self.first_pending = None;
+        items
+    }
+
+    /// Puts back the writes not sent
+    fn requeue(&mut self, items: &[S7DataItem]) {
+        for item in items {
+            // The addresses were validated when queued
+            let _ = if item.wordlen == S7_WL_BIT {
+                self.write_bit(item.area, item.db, item.start, item.data[0] != 0)
+            } else {
+                self.write_area(item.area, item.db, item.start, &item.data)
+            };
+        }
+    }
+}